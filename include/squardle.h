@@ -0,0 +1,84 @@
+/* The C interface to the squardle generator, implemented in src/ffi.rs.
+   Build the crate with cargo build --release to get libsquardle_rust.so/.dylib/.dll or the static
+   libsquardle_rust.a and link against it.
+
+   Everything returned is owned by the caller until passed back to the matching free function.
+   A function that fails returns null, squardle_last_error then says why. */
+
+#ifndef SQUARDLE_H
+#define SQUARDLE_H
+
+/* Generated by cbindgen from src/ffi.rs, do not edit by hand */
+
+#include <stddef.h>
+#include <stdint.h>
+
+typedef struct SquardleSolutions SquardleSolutions;
+
+typedef struct SquardleSolver SquardleSolver;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/*
+ Makes a solver from the contents of a dictionary file, words separated by commas or new lines.
+  Returns null if the words aren't all the same size or there are none.
+
+  # Safety
+  words must point to at least length readable bytes 
+ */
+SquardleSolver *squardle_solver_new(const uint8_t *words, size_t length);
+
+/*
+ Finds the completions of a partial grid written the way the solve command takes it, like "h...t,,e"
+  with null or "" for an empty grid. A limit of 0 finds every completion, which can take a long time.
+
+  # Safety
+  solver must come from squardle_solver_new and partial_grid must be null or a nul terminated string 
+ */
+SquardleSolutions *squardle_solver_solve(SquardleSolver *solver,
+                                         const char *partial_grid,
+                                         size_t limit);
+
+/*
+ The next solution as its rows separated by commas, or null when there are none left. The
+  string belongs to the solutions and is only valid until the next call. The words aren't checked
+  when the dictionary is loaded, a solution with a nul byte in one can't be a C string so it is
+  skipped with null returned and a message left for squardle_last_error
+
+  # Safety
+  solutions must come from squardle_solver_solve 
+ */
+const char *squardle_solutions_next(SquardleSolutions *solutions);
+
+/*
+ how many solutions squardle_solutions_next has left to give
+
+  # Safety
+  solutions must come from squardle_solver_solve 
+ */
+size_t squardle_solutions_remaining(const SquardleSolutions *solutions);
+
+/*
+ # Safety
+  solutions must come from squardle_solver_solve and not have been freed already, null is ignored 
+ */
+void squardle_solutions_free(SquardleSolutions *solutions);
+
+/*
+ # Safety
+  solver must come from squardle_solver_new and not have been freed already, null is ignored 
+ */
+void squardle_solver_free(SquardleSolver *solver);
+
+/*
+ why the last call on this thread returned null, null if nothing has failed. Valid until the next failure 
+ */
+const char *squardle_last_error(void);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* SQUARDLE_H */
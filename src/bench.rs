@@ -0,0 +1,218 @@
+use std::collections::HashSet;
+use std::fmt;
+use std::fs;
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use std::time::Instant;
+use serde::Serialize;
+use crate::dictionary::dictionary_from_bytes;
+use crate::error::SquardleError;
+use crate::generator_config::GeneratorConfig;
+use crate::engine::solver_engine;
+use crate::prefix_arena::PrefixArena;
+
+/* A dictionary to time the generator over. The bundled lists are all five letter words, so the
+  shorter sizes are the distinct starts of the medium list, which aren't words but make squares
+  the same way and are the same on every machine */
+pub struct Scenario {
+    pub name: &'static str,
+    bytes: &'static [u8],
+    // only the first this many letters of each word, none for the whole word
+    start_length: Option<usize>,
+    // left out unless asked for by name as it takes a long time
+    slow: bool
+}
+
+pub const SCENARIOS: [Scenario; 5] = [
+    Scenario { name: "small", bytes: include_bytes!("../resources/dictionaries/words_small.csv"), start_length: None, slow: false },
+    Scenario { name: "medium-3", bytes: include_bytes!("../resources/dictionaries/words_medium.csv"), start_length: Some(3), slow: false },
+    Scenario { name: "medium-4", bytes: include_bytes!("../resources/dictionaries/words_medium.csv"), start_length: Some(4), slow: false },
+    Scenario { name: "medium", bytes: include_bytes!("../resources/dictionaries/words_medium.csv"), start_length: None, slow: false },
+    Scenario { name: "full", bytes: include_bytes!("../resources/dictionaries/words.csv"), start_length: None, slow: true }
+];
+
+impl Scenario {
+
+    pub fn dictionary(&self) -> Result<Vec<String>, SquardleError> {
+
+        let dictionary = dictionary_from_bytes(self.bytes)?;
+
+        let Some(start_length) = self.start_length else {
+            return Ok(dictionary);
+        };
+
+        let mut starts: Vec<String> = dictionary.iter().map(|word| word.chars().take(start_length).collect()).collect();
+        starts.dedup();
+
+        Ok(starts)
+    }
+}
+
+/* bench with optional --scenario name more than once, --threads n, --json, and any generate flags to time them, --pruning forward-check or --engine naive say */
+#[derive(Debug, PartialEq)]
+pub struct BenchConfig {
+    pub scenarios: Vec<String>,
+    pub threads: usize,
+    pub json: bool,
+    pub generator_args: Vec<String>
+}
+
+impl BenchConfig {
+
+    pub fn build(args: &[String]) -> Result<BenchConfig, SquardleError> {
+
+        let mut scenarios: Vec<String> = Vec::new();
+        let mut threads = 1;
+        let mut json = false;
+        let mut generator_args: Vec<String> = Vec::new();
+        let mut args_iter = args.iter();
+
+        while let Some(arg) = args_iter.next() {
+
+            match arg.as_str() {
+                "--scenario" => match args_iter.next() {
+                    Some(name) if SCENARIOS.iter().any(|scenario| scenario.name == name) => scenarios.push(name.clone()),
+                    _ => return Err(format!("Scenario must be one of {}", SCENARIOS.map(|scenario| scenario.name).join(", ")).as_str().into())
+                },
+                "--threads" => threads = match args_iter.next().map(|value| value.parse::<usize>()) {
+                    Some(Ok(threads)) if threads > 0 => threads,
+                    _ => return Err("Threads must be a number greater than 0".into())
+                },
+                "--json" => json = true,
+                _ => generator_args.push(arg.clone())
+            }
+        }
+
+        if scenarios.is_empty() {
+            scenarios = SCENARIOS.iter().filter(|scenario| !scenario.slow).map(|scenario| scenario.name.to_string()).collect();
+        }
+
+        // checked now rather than after the first scenarios have run
+        generator_config(threads, &generator_args)?;
+
+        Ok(BenchConfig { scenarios, threads, json, generator_args })
+    }
+}
+
+/* the generator counting only so writing solutions out isn't part of what is timed */
+fn generator_config(threads: usize, generator_args: &[String]) -> Result<GeneratorConfig, SquardleError> {
+
+    let threads = threads.to_string();
+    let args: Vec<String> = ["bench", "-", "", "--threads", threads.as_str(), "--count-only"].iter().map(|arg| arg.to_string())
+        .chain(generator_args.iter().cloned())
+        .collect();
+
+    GeneratorConfig::build(&args)
+}
+
+/* how one scenario went, memory is the peak resident size while it ran where the os says */
+#[derive(Debug, Serialize)]
+pub struct BenchResult {
+    pub scenario: String,
+    pub engine: String,
+    pub words: usize,
+    pub word_size: usize,
+    pub seconds: f64,
+    pub seeds: u64,
+    pub solutions: u64,
+    pub seeds_per_second: f64,
+    pub solutions_per_second: f64,
+    pub nodes_expanded: u64,
+    pub peak_memory_kb: Option<u64>
+}
+
+pub fn run_scenario(scenario: &Scenario, config: &BenchConfig) -> Result<BenchResult, SquardleError> {
+
+    let generator_config = generator_config(config.threads, &config.generator_args)?;
+
+    let dictionary = Box::new(scenario.dictionary()?);
+    let word_size = dictionary.first().map_or(0, |word| word.chars().count());
+
+    reset_peak_memory();
+    let started = Instant::now();
+
+    let engine = solver_engine(generator_config.engine);
+
+    let prefix_map = PrefixArena::new(&dictionary)?;
+    let mut pool = engine.start(&generator_config, &dictionary, prefix_map, Arc::new(AtomicBool::new(false)), HashSet::new())?;
+    let stats = pool.join();
+
+    let seconds = started.elapsed().as_secs_f64();
+
+    if let Some(error) = stats.errors.into_iter().next() {
+        return Err(error.into());
+    }
+
+    Ok(BenchResult {
+        scenario: scenario.name.to_string(),
+        engine: engine.name().to_string(),
+        words: dictionary.len(),
+        word_size,
+        seconds,
+        seeds: stats.seeds_processed,
+        solutions: stats.solutions_found,
+        seeds_per_second: stats.seeds_processed as f64 / seconds,
+        solutions_per_second: stats.solutions_found as f64 / seconds,
+        nodes_expanded: stats.nodes_expanded,
+        peak_memory_kb: peak_memory_kb()
+    })
+}
+
+/* linux only, clearing the peak lets each scenario have its own rather than the biggest so far */
+fn reset_peak_memory() {
+    let _ = fs::write("/proc/self/clear_refs", "5");
+}
+
+fn peak_memory_kb() -> Option<u64> {
+
+    let status = fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|line| line.starts_with("VmHWM:"))?;
+
+    line.split_whitespace().nth(1)?.parse().ok()
+}
+
+impl fmt::Display for BenchResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+
+        let memory = self.peak_memory_kb.map_or("-".to_string(), |kb| format!("{:.1}MB", kb as f64 / 1024.0));
+
+        write!(f, "{:<9} {:<10} {:>6} words of {}  {:>8.3}s  {:>10.1} seeds/s  {:>10.1} solutions/s  {:>10} solutions  {:>12} nodes  {:>8}",
+            self.scenario, self.engine, self.words, self.word_size, self.seconds, self.seeds_per_second, self.solutions_per_second, self.solutions, self.nodes_expanded, memory)
+    }
+}
+
+#[test]
+fn build_bench_config() {
+
+    let args: Vec<String> = ["--scenario", "small", "--threads", "2", "--pruning", "forward-check"].iter().map(|arg| arg.to_string()).collect();
+
+    assert_eq!(BenchConfig { scenarios: vec!["small".to_string()], threads: 2, json: false, generator_args: vec!["--pruning".to_string(), "forward-check".to_string()] },
+        BenchConfig::build(&args).unwrap());
+
+    // the full list is only run when asked for
+    assert!(!BenchConfig::build(&[]).unwrap().scenarios.contains(&"full".to_string()));
+
+    assert!(BenchConfig::build(&["--scenario".to_string(), "huge".to_string()]).is_err());
+    assert!(BenchConfig::build(&["--pruning".to_string(), "none".to_string()]).is_err());
+}
+
+#[test]
+fn bench_small_scenario() {
+
+    let config = BenchConfig::build(&["--scenario".to_string(), "small".to_string()]).unwrap();
+    let result = run_scenario(&SCENARIOS[0], &config).unwrap();
+
+    // based,ulcer,diary,genie is short a word so only budge,enter,alien,scant,eerie has a square
+    assert_eq!((10, 5, 10), (result.words, result.word_size, result.seeds));
+    assert_eq!(1, result.solutions);
+    assert_eq!("prefix-map", result.engine);
+}
+
+#[test]
+fn shorter_scenarios_are_distinct_starts() {
+
+    let dictionary = SCENARIOS.iter().find(|scenario| scenario.name == "medium-3").unwrap().dictionary().unwrap();
+
+    assert!(dictionary.iter().all(|word| word.len() == 3));
+    assert!(dictionary.windows(2).all(|pair| pair[0] < pair[1]));
+}
@@ -0,0 +1,242 @@
+use std::io::{BufRead, Read, Write};
+use crate::error::SquardleError;
+use crate::generator_config::{Compression, CsvDialect, OutputFormat};
+use crate::output::{self, OutputWriter};
+use crate::solution::Solution;
+
+const MAGIC: &[u8; 4] = b"SQDL";
+const BINARY_FORMAT_VERSION: u8 = 1;
+
+/* The binary solutions file, little endian throughout:
+    "SQDL"                      magic
+    u8                          format version, 1
+    u32                         word size
+    u32                         number of dictionary words
+    u8 length then the bytes    each dictionary word in sorted order
+    word size u32s              each solution as the indexes of its rows in the dictionary, to the end of the file
+  Scores aren't kept. The same .gz or .zst extensions compress it as they do text output */
+pub fn save_solutions_to_binary<I>(file_path: &str, dictionary: &[String], compression: Compression, solutions: I) -> Result<usize, SquardleError>
+    where I: IntoIterator<Item = Solution> {
+
+    let word_size = dictionary.first().map(|word| word.chars().count()).ok_or(SquardleError::EmptyDictionary)?;
+
+    let mut file = OutputWriter::create(file_path, compression)?;
+
+    file.write_all(MAGIC)?;
+    file.write_all(&[BINARY_FORMAT_VERSION])?;
+    file.write_all(&(word_size as u32).to_le_bytes())?;
+    file.write_all(&(dictionary.len() as u32).to_le_bytes())?;
+
+    for word in dictionary {
+        let length = u8::try_from(word.len()).map_err(|_| SquardleError::from(format!("[{}] is too long for the binary format", word).as_str()))?;
+        file.write_all(&[length])?;
+        file.write_all(word.as_bytes())?;
+    }
+
+    let mut solution_count = 0;
+
+    for solution in solutions {
+
+        for word in solution.rows() {
+            let index = dictionary.binary_search(word).map_err(|_| SquardleError::from(format!("[{}] is not in the dictionary", word).as_str()))?;
+            file.write_all(&(index as u32).to_le_bytes())?;
+        }
+
+        solution_count += 1;
+    }
+
+    file.finish()?;
+
+    Ok(solution_count)
+}
+
+/* reads the solutions back out of a binary solutions file one at a time, decompressing it if its extension says so */
+pub struct BinarySolutionReader {
+    reader: Box<dyn BufRead>,
+    dictionary: Vec<String>,
+    word_size: usize
+}
+
+impl BinarySolutionReader {
+
+    pub fn open(file_path: &str) -> Result<BinarySolutionReader, SquardleError> {
+
+        let mut reader = output::open_solution_file(file_path)?;
+
+        let mut magic = [0u8; 4];
+        let mut version = [0u8; 1];
+
+        if reader.read_exact(&mut magic).is_err() || magic != *MAGIC {
+            return Err(format!("{} is not a binary solutions file", file_path).as_str().into());
+        }
+
+        reader.read_exact(&mut version)?;
+
+        if version[0] != BINARY_FORMAT_VERSION {
+            return Err(format!("binary solutions file version {} is not supported", version[0]).as_str().into());
+        }
+
+        let word_size = read_u32(&mut reader)? as usize;
+        let dictionary_length = read_u32(&mut reader)? as usize;
+        let mut dictionary = Vec::with_capacity(dictionary_length);
+
+        for _ in 0..dictionary_length {
+            let mut length = [0u8; 1];
+            reader.read_exact(&mut length)?;
+
+            let mut word = vec![0u8; length[0] as usize];
+            reader.read_exact(&mut word)?;
+
+            dictionary.push(String::from_utf8(word).map_err(|_| SquardleError::from("a dictionary word in the binary file is not utf-8"))?);
+        }
+
+        Ok(BinarySolutionReader { reader, dictionary, word_size })
+    }
+
+    pub fn dictionary(&self) -> &[String] {
+        &self.dictionary
+    }
+
+    fn read_solution(&mut self) -> Result<Option<Vec<String>>, SquardleError> {
+
+        // the end of the file can only come between solutions
+        if self.reader.fill_buf()?.is_empty() {
+            return Ok(None);
+        }
+
+        let mut solution = Vec::with_capacity(self.word_size);
+
+        for _ in 0..self.word_size {
+            let index = read_u32(&mut self.reader)? as usize;
+            let word = self.dictionary.get(index).ok_or(SquardleError::from("a solution in the binary file has a word index past the end of the dictionary"))?;
+            solution.push(word.clone());
+        }
+
+        Ok(Some(solution))
+    }
+}
+
+impl Iterator for BinarySolutionReader {
+    type Item = Result<Vec<String>, SquardleError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_solution().transpose()
+    }
+}
+
+fn read_u32(reader: &mut impl Read) -> Result<u32, SquardleError> {
+    let mut bytes = [0u8; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+/* writes every solution of a binary file out as text in the output format, returns how many there were */
+pub fn convert_solution_file(source_file_path: &str, dest_file_path: &str, output_format: &OutputFormat) -> Result<usize, SquardleError> {
+
+    let reader = BinarySolutionReader::open(source_file_path)?;
+    let mut dest_file = OutputWriter::create(dest_file_path, Compression::from_file_path(dest_file_path))?;
+    let mut solution_count = 0;
+
+    for solution in reader {
+        writeln!(dest_file, "{}", output::format_solution(&solution?, None, output_format, &CsvDialect::default())?)?;
+        solution_count += 1;
+    }
+
+    dest_file.finish()?;
+
+    Ok(solution_count)
+}
+
+/* convert source_file dest_file with optional --output-format csv|jsonl|grid, picked from the destination's extension otherwise */
+#[derive(Debug, PartialEq)]
+pub struct ConvertConfig {
+    pub source_file_path: String,
+    pub dest_file_path: String,
+    pub output_format: OutputFormat
+}
+
+impl ConvertConfig {
+
+    pub fn build(args: &[String]) -> Result<ConvertConfig, SquardleError> {
+
+        let mut output_format: Option<OutputFormat> = None;
+        let mut positional_args: Vec<String> = Vec::with_capacity(2);
+        let mut args_iter = args.iter();
+
+        while let Some(arg) = args_iter.next() {
+
+            match arg.as_str() {
+                "--output-format" => output_format = match args_iter.next().map(String::as_str) {
+                    Some("csv") => Some(OutputFormat::Csv),
+                    Some("jsonl") => Some(OutputFormat::Jsonl),
+                    Some("grid") => Some(OutputFormat::Grid),
+                    _ => return Err("Output format must be one of: csv, jsonl, grid".into())
+                },
+                _ => positional_args.push(arg.clone())
+            }
+        }
+
+        let [source_file_path, dest_file_path]: [String; 2] = positional_args.try_into()
+            .map_err(|_| SquardleError::from("convert accepts a binary solutions file path, a destination file path and optionally --output-format csv|jsonl|grid"))?;
+
+        let output_format = output_format.unwrap_or_else(|| match dest_file_path.trim_end_matches(".gz").trim_end_matches(".zst") {
+            file_path if file_path.ends_with(".jsonl") => OutputFormat::Jsonl,
+            _ => OutputFormat::Csv
+        });
+
+        Ok(ConvertConfig { source_file_path, dest_file_path, output_format })
+    }
+}
+
+#[test]
+fn binary_round_trip() {
+
+    let dictionary: Vec<String> = ["eat", "era", "net", "ore", "ten", "toe"].iter().map(|word| word.to_string()).collect();
+    let solutions = vec![
+        vec!["ten".to_string(), "ore".to_string(), "eat".to_string()],
+        vec!["toe".to_string(), "era".to_string(), "net".to_string()]
+    ];
+
+    for (file_path, compression) in [("test_binary.sqdl", Compression::None), ("test_binary.sqdl.zst", Compression::Zstd)] {
+
+        assert_eq!(2, save_solutions_to_binary(file_path, &dictionary, compression, solutions.iter().cloned().map(Solution::from)).unwrap());
+
+        let reader = BinarySolutionReader::open(file_path).unwrap();
+        assert_eq!(dictionary, reader.dictionary());
+
+        let read: Vec<Vec<String>> = reader.map(Result::unwrap).collect();
+        std::fs::remove_file(file_path).unwrap();
+
+        assert_eq!(solutions, read);
+    }
+
+    // the header then three u32s a solution
+    save_solutions_to_binary("test_binary_size.sqdl", &dictionary, Compression::None, solutions.into_iter().map(Solution::from)).unwrap();
+    let size = std::fs::metadata("test_binary_size.sqdl").unwrap().len();
+    std::fs::remove_file("test_binary_size.sqdl").unwrap();
+
+    assert_eq!(4 + 1 + 4 + 4 + 6 * 4 + 2 * 3 * 4, size);
+}
+
+#[test]
+fn convert_binary_to_text() {
+
+    let dictionary: Vec<String> = ["eat", "era", "net", "ore", "ten", "toe"].iter().map(|word| word.to_string()).collect();
+    save_solutions_to_binary("test_convert.sqdl", &dictionary, Compression::None, vec![Solution::from(vec!["ten".to_string(), "ore".to_string(), "eat".to_string()])]).unwrap();
+
+    let args: Vec<String> = ["test_convert.sqdl", "test_convert.jsonl"].iter().map(|arg| arg.to_string()).collect();
+    let config = ConvertConfig::build(&args).unwrap();
+    assert_eq!(OutputFormat::Jsonl, config.output_format);
+
+    assert_eq!(1, convert_solution_file("test_convert.sqdl", "test_convert.csv", &OutputFormat::Csv).unwrap());
+    let csv = std::fs::read_to_string("test_convert.csv").unwrap();
+
+    // a text file isn't mistaken for a binary one
+    let not_binary = BinarySolutionReader::open("test_convert.csv").is_err();
+
+    std::fs::remove_file("test_convert.sqdl").unwrap();
+    std::fs::remove_file("test_convert.csv").unwrap();
+
+    assert_eq!("ten,ore,eat\n", csv);
+    assert!(not_binary);
+}
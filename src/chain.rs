@@ -0,0 +1,170 @@
+use crate::error::SquardleError;
+use crate::solution::Solution;
+use crate::solution_generator::{FixedLetters, GridSolver};
+
+/* which way the second square of a pair goes on from the first */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Overlap {
+    // the first square's last row is the second's first row, one grid of 2n-1 rows
+    Down,
+    // the first square's last column is the second's first column, one grid of 2n-1 columns
+    Across
+}
+
+/* chain dictionary_file with optional destination file, stdout without one, --word w at least once,
+  --overlap down|across and --limit n */
+#[derive(Debug, PartialEq)]
+pub struct ChainConfig {
+    pub dictionary_file_path: String,
+    pub destination_file_path: Option<String>,
+    pub words: Vec<String>,
+    pub overlap: Overlap,
+    pub solution_limit: Option<usize>
+}
+
+impl ChainConfig {
+
+    pub fn build(args: &[String]) -> Result<ChainConfig, SquardleError> {
+
+        let mut words: Vec<String> = Vec::new();
+        let mut overlap = Overlap::Down;
+        let mut solution_limit: Option<usize> = None;
+        let mut positional_args: Vec<String> = Vec::with_capacity(2);
+        let mut args_iter = args.iter();
+
+        while let Some(arg) = args_iter.next() {
+
+            match arg.as_str() {
+                "--word" => match args_iter.next() {
+                    Some(word) => words.push(word.to_lowercase()),
+                    None => return Err("Word flag needs a word".into())
+                },
+                "--overlap" => overlap = match args_iter.next().map(String::as_str) {
+                    Some("down") => Overlap::Down,
+                    Some("across") => Overlap::Across,
+                    _ => return Err("Overlap must be down or across".into())
+                },
+                "--limit" => solution_limit = match args_iter.next().map(|limit| limit.parse::<usize>()) {
+                    Some(Ok(limit)) if limit > 0 => Some(limit),
+                    _ => return Err("Limit must be a number greater than 0".into())
+                },
+                _ => positional_args.push(arg.clone())
+            }
+        }
+
+        if positional_args.is_empty() || positional_args.len() > 2 || words.is_empty() {
+            return Err("chain accepts a dictionary file path, optionally a destination file path, --word w at least once and optional flags: --overlap down|across, --limit n".into());
+        }
+
+        let destination_file_path = positional_args.get(1).cloned();
+        let dictionary_file_path = positional_args.swap_remove(0);
+
+        Ok(ChainConfig { dictionary_file_path, destination_file_path, words, overlap, solution_limit })
+    }
+}
+
+/* Pairs of squares overlapping on one of the words, the first square's last row or column being
+  the second's first. The squares are found a shared word at a time with the word fixed as the
+  last row and then as the first, the same search solve does from each seed, and the two lists
+  are paired up as they're handed to on_pair. So only the squares of the word being joined are
+  held, never every square or every pair. Across is the transpose of down, the squares are
+  found the same way and turned. A square isn't paired with itself. Returns the pairs found */
+pub fn for_each_pair<F>(solver: &mut GridSolver, words: &[String], overlap: Overlap, solution_limit: Option<usize>, mut on_pair: F) -> Result<usize, SquardleError>
+    where F: FnMut(&[String], &[String]) -> Result<(), SquardleError> {
+
+    let word_size = solver.word_size();
+    let mut found = 0;
+
+    for word in words {
+
+        if word.chars().count() != word_size {
+            return Err(SquardleError::IncorrectWordSize { word: word.clone(), expected: word_size, found: word.chars().count() });
+        }
+
+        let (ending, _) = solver.solve(word_fixed_at(word, word_size - 1, word_size), None)?;
+
+        if ending.is_empty() {
+            continue;
+        }
+
+        let (starting, _) = solver.solve(word_fixed_at(word, 0, word_size), None)?;
+
+        let turned = |solution: &Solution| -> Vec<String> {
+            match overlap {
+                Overlap::Down => solution.rows().to_vec(),
+                Overlap::Across => solution.columns()
+            }
+        };
+
+        for first in &ending {
+            for second in starting.iter().filter(|second| *second != first) {
+
+                on_pair(&turned(first), &turned(second))?;
+                found += 1;
+
+                if solution_limit.is_some_and(|limit| found >= limit) {
+                    return Ok(found);
+                }
+            }
+        }
+    }
+
+    Ok(found)
+}
+
+fn word_fixed_at(word: &str, row: usize, word_size: usize) -> FixedLetters {
+
+    let mut fixed_letters: FixedLetters = vec![vec![None; word_size]; word_size];
+    fixed_letters[row] = word.chars().map(Some).collect();
+
+    fixed_letters
+}
+
+#[test]
+fn build_chain_config() {
+
+    let args: Vec<String> = ["words.csv", "--word", "Crane", "--overlap", "across", "--word", "slate"].iter().map(|arg| arg.to_string()).collect();
+
+    assert_eq!(ChainConfig { dictionary_file_path: "words.csv".to_string(), destination_file_path: None, words: vec!["crane".to_string(), "slate".to_string()], overlap: Overlap::Across, solution_limit: None },
+        ChainConfig::build(&args).unwrap());
+
+    // there's no word to join on
+    assert!(ChainConfig::build(&["words.csv".to_string()]).is_err());
+    assert!(ChainConfig::build(&["words.csv".to_string(), "--word".to_string(), "crane".to_string(), "--overlap".to_string(), "up".to_string()]).is_err());
+}
+
+#[test]
+fn pairs_overlap_on_the_word() {
+
+    let dictionary: Vec<String> = ["abc", "adc", "adf", "adg", "beb", "bed", "beh", "cba", "cda", "cfi", "deb", "ded", "def", "fda", "ghi"].iter().map(|word| word.to_string()).collect();
+    let prefix_map = crate::prefix_arena::PrefixArena::new(&dictionary).unwrap();
+    let mut solver = GridSolver::new(dictionary, prefix_map).unwrap();
+
+    let words = vec!["abc".to_string()];
+    let mut pairs: Vec<(String, String)> = vec![];
+
+    // two squares end in abc and five start with it, none does both
+    let found = for_each_pair(&mut solver, &words, Overlap::Down, None, |first, second| {
+        pairs.push((first.join(","), second.join(",")));
+        Ok(())
+    }).unwrap();
+
+    assert_eq!(10, found);
+    assert!(pairs.contains(&("cba,ded,abc".to_string(), "abc,def,ghi".to_string())));
+
+    // across the same squares are turned so abc is the last column of the first and the first column of the second
+    let mut across: Vec<(String, String)> = vec![];
+
+    for_each_pair(&mut solver, &words, Overlap::Across, Some(3), |first, second| {
+        across.push((first.join(","), second.join(",")));
+        Ok(())
+    }).unwrap();
+
+    assert_eq!(3, across.len());
+    assert_eq!(("cda,beb,adc".to_string(), "adc,bed,cba".to_string()), across[0]);
+
+    for (first, second) in &across {
+        assert_eq!("abc", first.split(',').filter_map(|row| row.chars().last()).collect::<String>());
+        assert_eq!("abc", second.split(',').filter_map(|row| row.chars().next()).collect::<String>());
+    }
+}
@@ -0,0 +1,159 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use crate::error::SquardleError;
+use crate::generator_config::OutputFormat;
+use crate::output;
+
+/* The state of a long run written out every so often, so an interrupted run can
+  carry on from the seed words it already finished instead of starting again */
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub dictionary_file_path: String,
+    pub completed_seeds: Vec<String>,
+    pub solutions_written: usize,
+    pub output_offset: u64
+}
+
+impl Checkpoint {
+
+    pub fn new(dictionary_file_path: &str) -> Checkpoint {
+
+        Checkpoint {
+            dictionary_file_path: dictionary_file_path.to_string(),
+            completed_seeds: Vec::new(),
+            solutions_written: 0,
+            output_offset: 0
+        }
+    }
+
+    pub fn load(file_path: &str) -> Result<Checkpoint, SquardleError> {
+
+        let file = File::open(file_path)?;
+        let checkpoint = serde_json::from_reader(BufReader::new(file))?;
+
+        Ok(checkpoint)
+    }
+
+    /* writes to a temp file first so a crash mid-save never leaves a half written checkpoint */
+    pub fn save(&self, file_path: &str) -> Result<(), SquardleError> {
+
+        let temp_file_path = format!("{}.tmp", file_path);
+
+        let mut file = BufWriter::new(File::create(&temp_file_path)?);
+        serde_json::to_writer_pretty(&mut file, self)?;
+        file.flush()?;
+        drop(file);
+
+        fs::rename(&temp_file_path, file_path)?;
+
+        Ok(())
+    }
+
+    /* removes a checkpoint and any temp file a save left behind, returns how many files were removed */
+    pub fn remove(file_path: &str) -> Result<usize, SquardleError> {
+
+        let mut removed_count = 0;
+
+        for path in [file_path.to_string(), format!("{}.tmp", file_path)] {
+            match fs::remove_file(&path) {
+                Ok(()) => removed_count += 1,
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => {},
+                Err(err) => return Err(err.into())
+            }
+        }
+
+        Ok(removed_count)
+    }
+
+    pub fn completed_seed_set(&self) -> HashSet<String> {
+        self.completed_seeds.iter().cloned().collect()
+    }
+}
+
+/* Gets the output file ready to be appended to when resuming. Anything past the checkpointed
+  offset is a torn write, and any solution whose first row (its seed word) was not finished
+  will be generated again so is dropped. The checkpoint is updated to match what was kept.
+  Returns the file opened for appending */
+pub fn prepare_output_for_resume(file_path: &str, output_format: &OutputFormat, checkpoint: &mut Checkpoint) -> Result<File, SquardleError> {
+
+    let completed_seeds = checkpoint.completed_seed_set();
+
+    let file = OpenOptions::new().read(true).write(true).create(true).truncate(false).open(file_path)?;
+    file.set_len(checkpoint.output_offset.min(file.metadata()?.len()))?;
+
+    let mut kept_lines: Vec<String> = Vec::new();
+
+    for line in BufReader::new(&file).lines() {
+        let line = line?;
+
+        let seed = output::seed_of_line(&line, output_format).unwrap_or_default();
+
+        if completed_seeds.contains(&seed) {
+            kept_lines.push(line);
+        }
+    }
+
+    let mut file = BufWriter::new(File::create(file_path)?);
+
+    for line in &kept_lines {
+        writeln!(file, "{}", line)?;
+    }
+
+    file.flush()?;
+
+    checkpoint.solutions_written = kept_lines.len();
+    checkpoint.output_offset = file.get_ref().metadata()?.len();
+
+    Ok(OpenOptions::new().append(true).open(file_path)?)
+}
+
+#[test]
+fn checkpoint_round_trip() {
+
+    let file_path = "test_checkpoint.json";
+
+    let mut checkpoint = Checkpoint::new("words.csv");
+    checkpoint.completed_seeds.push("based".to_string());
+    checkpoint.solutions_written = 3;
+    checkpoint.output_offset = 90;
+
+    checkpoint.save(file_path).unwrap();
+    let loaded = Checkpoint::load(file_path).unwrap();
+    fs::remove_file(file_path).unwrap();
+
+    assert_eq!(checkpoint, loaded);
+}
+
+#[test]
+fn resume_drops_unfinished_seeds() {
+
+    let file_path = "test_resume.csv";
+
+    fs::write(file_path, "based,ulcer\nbudge,enter\nbased,unite\ntorn,li").unwrap();
+
+    let mut checkpoint = Checkpoint::new("words.csv");
+    checkpoint.completed_seeds.push("based".to_string());
+    checkpoint.output_offset = 36;
+
+    drop(prepare_output_for_resume(file_path, &OutputFormat::Csv, &mut checkpoint).unwrap());
+    let contents = fs::read_to_string(file_path).unwrap();
+    fs::remove_file(file_path).unwrap();
+
+    assert_eq!("based,ulcer\nbased,unite\n", contents);
+    assert_eq!(2, checkpoint.solutions_written);
+    assert_eq!(24, checkpoint.output_offset);
+}
+
+#[test]
+fn remove_checkpoint_and_temp_file() {
+
+    let file_path = "test_remove_checkpoint.json";
+
+    Checkpoint::new("words.csv").save(file_path).unwrap();
+    fs::write(format!("{}.tmp", file_path), "{").unwrap();
+
+    assert_eq!(2, Checkpoint::remove(file_path).unwrap());
+    assert_eq!(0, Checkpoint::remove(file_path).unwrap());
+}
@@ -0,0 +1,139 @@
+/* The subcommands of the binary. Anything that isn't a subcommand name is taken as the
+  arguments of generate so the original positional interface keeps working */
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Command {
+    Generate,
+    Verify,
+    Solve,
+    Stats,
+    Clean,
+    Play,
+    Daily,
+    Rate,
+    Serve,
+    Merge,
+    Diff,
+    Sample,
+    Coverage,
+    Graph,
+    Estimate,
+    Pangram,
+    Cube,
+    Chain,
+    WordSearch,
+    Bench,
+    Selftest,
+    Dedup,
+    Filter,
+    Unique,
+    Layout,
+    Export,
+    Convert,
+    Index,
+    Query,
+    Help
+}
+
+pub const USAGE: &str = "usage: squardle-rust <subcommand> [args]
+  generate    dictionary file or - for stdin, solution destination file or none for stdout, thread count and flags, the default
+  verify      check every row and column of a solutions file is a word: solutions file path, dictionary file path, --delimiter character|tab
+  estimate    roughly how many solutions generate would find, from random probes: dictionary file path, --probes n, --seed n, --json
+  pangram     the square with the most different letters found in the time: dictionary file path, --letters n to stop at, --seconds n, --json
+  cube        cubes with every row, column and pillar a word: dictionary file path, destination file path or none for stdout, --limit n
+  chain       pairs of squares overlapping on a word, the first's last row or column the second's first: dictionary file path, destination file path or none for stdout, --word w, --overlap down|across, --limit n
+  wordsearch  words spelled by paths of neighbouring cells without reusing one, or each square's bonus words: dictionary file path, grid e.g. cat,are,tea or --solutions file path, --min-length n
+  bench       time generate over the bundled dictionaries for seeds and solutions a second and peak memory: --scenario small|medium-3|medium-4|medium|full, --threads n, --json, any generate flags
+  selftest    check generate against a brute force on random small dictionaries: --rounds n, --seed n, --word-size 3|4, --words n, --letters n
+  solve       every completion of a partial grid: dictionary file path, grid e.g. cra.e,.....,t..er
+  stats       summary statistics of a solutions file: solutions file path, --json, or of the prefix map of a dictionary: --prefix-map dictionary file path, --json
+  coverage    how many solutions each dictionary word is in and the words in none: solutions file path, dictionary file path, --json
+  graph       which words can be the first two rows of a square as an edge list or dot: dictionary file path, destination file path, --graph-format csv|dot
+  clean       remove checkpoint files and their temp files: checkpoint file paths
+  play        guess the words of a hidden square: solutions file path, dictionary file path, --index n, --save file path to carry on later
+  daily       the puzzle for a date as json: solutions file path, dictionary file path, --date YYYY-MM-DD
+  rate        append a difficulty score from 0 to 100: solutions file path, dictionary file path, destination file path, --frequencies file path, --difficulty easy|medium|hard
+  serve       serve solutions, the daily puzzle and guess checking over http: dictionary file path, solutions file path, --address host:port
+  merge       destination file path then the solution files to merge
+  diff        the solutions in one file but not the other, marked < for the first and > for the second: first file path, second file path
+  sample      keep a number of solutions picked at random: solutions file path, destination file path, count, --seed n
+  dedup       drop repeated and transposed solutions: solutions file path, destination file path
+  filter      solution file path, destination file path, --contains word, --exclude word, --row-pattern pattern
+  unique      whether a partial grid has one completion: dictionary file path, grid, or --minimal and the rows of a square for the fewest letters to reveal
+  layout      fill a grid with holes: dictionary file path, layout file path or --square n, --dictionary file path, --limit n
+  export      puzzles as json for a game client: solutions file path, dictionary file path, destination file path, --puzzle-format json, --frequencies file path, --definitions file path, --reveal, --slot-guesses
+  convert     a binary solutions file as text: binary file path, destination file path, --output-format csv|jsonl|grid
+  index       index which solutions have each word for query: solutions file path, index file path
+  query       the solutions with all of the words in a row or column: solutions file path, index file path, words
+  help        print this message";
+
+impl Command {
+
+    pub fn parse(name: &str) -> Option<Command> {
+
+        match name {
+            "generate" => Some(Command::Generate),
+            "verify" => Some(Command::Verify),
+            "solve" => Some(Command::Solve),
+            "stats" => Some(Command::Stats),
+            "clean" => Some(Command::Clean),
+            "play" => Some(Command::Play),
+            "daily" => Some(Command::Daily),
+            "rate" => Some(Command::Rate),
+            "serve" => Some(Command::Serve),
+            "merge" => Some(Command::Merge),
+            "diff" => Some(Command::Diff),
+            "sample" => Some(Command::Sample),
+            "coverage" => Some(Command::Coverage),
+            "graph" => Some(Command::Graph),
+            "estimate" => Some(Command::Estimate),
+            "pangram" => Some(Command::Pangram),
+            "cube" => Some(Command::Cube),
+            "chain" => Some(Command::Chain),
+            "wordsearch" => Some(Command::WordSearch),
+            "bench" => Some(Command::Bench),
+            "selftest" => Some(Command::Selftest),
+            "dedup" => Some(Command::Dedup),
+            "filter" => Some(Command::Filter),
+            "unique" => Some(Command::Unique),
+            "layout" => Some(Command::Layout),
+            "export" => Some(Command::Export),
+            "convert" => Some(Command::Convert),
+            "index" => Some(Command::Index),
+            "query" => Some(Command::Query),
+            "help" | "--help" | "-h" => Some(Command::Help),
+            _ => None
+        }
+    }
+
+    /* splits out the subcommand, the args handed back still start with the executable name
+      so they can go straight to GeneratorConfig::build */
+    pub fn from_args(args: &[String]) -> (Command, Vec<String>) {
+
+        let executable = args.first().cloned().unwrap_or_default();
+
+        match args.get(1).and_then(|name| Command::parse(name)) {
+            Some(command) => (command, [vec![executable], args[2..].to_vec()].concat()),
+            None => (Command::Generate, args.to_vec())
+        }
+    }
+}
+
+#[test]
+fn subcommand_from_args() {
+
+    let args: Vec<String> = ["squardle-rust", "merge", "out.csv", "a.csv"].iter().map(|arg| arg.to_string()).collect();
+
+    assert_eq!((Command::Merge, vec!["squardle-rust".to_string(), "out.csv".to_string(), "a.csv".to_string()]), Command::from_args(&args));
+}
+
+#[test]
+fn no_subcommand_is_generate() {
+
+    let args: Vec<String> = ["squardle-rust", "words.csv", "out.csv", "4"].iter().map(|arg| arg.to_string()).collect();
+
+    assert_eq!((Command::Generate, args.clone()), Command::from_args(&args));
+
+    let args: Vec<String> = ["squardle-rust", "generate", "words.csv"].iter().map(|arg| arg.to_string()).collect();
+
+    assert_eq!((Command::Generate, vec!["squardle-rust".to_string(), "words.csv".to_string()]), Command::from_args(&args));
+}
@@ -0,0 +1,278 @@
+use crate::solution_generator::letter_index;
+
+const EMPTY: u8 = u8::MAX;
+
+/* how much searching a seed word took, added into the run stats by whoever ran it */
+#[derive(Debug, Default, PartialEq)]
+pub struct FillCounts {
+    pub nodes_expanded: u64,
+    pub backtracks: u64
+}
+
+/* Fills a square crossword style, most constrained slot first. Rather than always the next row
+  down, every node works out how many words could still go in each unfilled row and column given
+  the letters already placed across it, backs out straight away if any has none, and otherwise
+  fills whichever has the fewest. For bigger squares that catches a dead end rows before the
+  top to bottom search would reach it. Every row and column is a different word, where the row
+  search only finds a column repeating the last row when validating
+
+  The words that fit a slot are found with a bitset of dictionary indexes per letter per position,
+  so a slot's candidates are the and of the bitsets of its placed letters. Words with anything
+  outside a to z in them are never candidates */
+pub struct ConstrainedFill {
+    dictionary: Vec<String>,
+    word_size: usize,
+    // u64s in a bitset over the dictionary
+    blocks: usize,
+    // position then letter then bitset of the words with that letter there
+    by_letter: Vec<Vec<Vec<u64>>>,
+    // every word that can be a candidate at all
+    all_words: Vec<u64>
+}
+
+/* the grid part way through one seed word's search */
+struct FillState {
+    // letter indexes by cell, row by row
+    cells: Vec<u8>,
+    // rows then columns
+    placed: Vec<bool>,
+    used: Vec<u64>,
+    // the first column has to come after the seed word, as the transposed square is found from the first column's word
+    after_seed: Vec<u64>,
+    counts: FillCounts
+}
+
+impl ConstrainedFill {
+
+    /* the dictionary has to be sorted and every word the same size, the same as the generator takes */
+    pub fn new(dictionary: &[String]) -> ConstrainedFill {
+
+        let word_size = dictionary.first().map_or(0, |word| word.chars().count());
+        let blocks = dictionary.len().div_ceil(64);
+
+        let mut by_letter = vec![vec![vec![0u64; blocks]; 26]; word_size];
+        let mut all_words = vec![0u64; blocks];
+
+        for (index, word) in dictionary.iter().enumerate() {
+
+            let letters: Option<Vec<usize>> = word.chars().map(letter_index).collect();
+
+            let Some(letters) = letters.filter(|letters| letters.len() == word_size) else {
+                continue;
+            };
+
+            for (position, letter) in letters.into_iter().enumerate() {
+                by_letter[position][letter][index / 64] |= 1 << (index % 64);
+            }
+
+            all_words[index / 64] |= 1 << (index % 64);
+        }
+
+        ConstrainedFill { dictionary: dictionary.to_vec(), word_size, blocks, by_letter, all_words }
+    }
+
+    fn cell_of(&self, slot: usize, position: usize) -> usize {
+        match slot < self.word_size {
+            true => slot * self.word_size + position,
+            false => position * self.word_size + (slot - self.word_size)
+        }
+    }
+
+    /* the bitset block of the words that can still go in the slot */
+    fn candidate_block(&self, state: &FillState, slot: usize, block: usize) -> u64 {
+
+        let mut candidates = self.all_words[block] & !state.used[block];
+
+        if slot == self.word_size {
+            candidates &= state.after_seed[block];
+        }
+
+        for position in 0..self.word_size {
+
+            let letter = state.cells[self.cell_of(slot, position)];
+
+            if letter != EMPTY {
+                candidates &= self.by_letter[position][letter as usize][block];
+            }
+
+            if candidates == 0 {
+                break;
+            }
+        }
+
+        candidates
+    }
+
+    fn candidate_count(&self, state: &FillState, slot: usize) -> u32 {
+        (0..self.blocks).map(|block| self.candidate_block(state, slot, block).count_ones()).sum()
+    }
+
+    fn candidates(&self, state: &FillState, slot: usize) -> Vec<usize> {
+
+        let mut candidates = Vec::new();
+
+        for block in 0..self.blocks {
+
+            let mut bits = self.candidate_block(state, slot, block);
+
+            while bits != 0 {
+                candidates.push(block * 64 + bits.trailing_zeros() as usize);
+                bits &= bits - 1;
+            }
+        }
+
+        candidates
+    }
+
+    /* writes the word into the slot, returning the cells it filled that were empty so they can be emptied again */
+    fn place(&self, state: &mut FillState, slot: usize, word_index: usize) -> Vec<usize> {
+
+        let mut filled = Vec::with_capacity(self.word_size);
+
+        for (position, letter) in self.dictionary[word_index].chars().enumerate() {
+
+            let cell = self.cell_of(slot, position);
+
+            if state.cells[cell] == EMPTY {
+                state.cells[cell] = letter_index(letter).expect("only a to z words are candidates") as u8;
+                filled.push(cell);
+            }
+        }
+
+        state.placed[slot] = true;
+        state.used[word_index / 64] |= 1 << (word_index % 64);
+
+        filled
+    }
+
+    fn unplace(&self, state: &mut FillState, slot: usize, word_index: usize, filled: Vec<usize>) {
+
+        for cell in filled {
+            state.cells[cell] = EMPTY;
+        }
+
+        state.placed[slot] = false;
+        state.used[word_index / 64] &= !(1 << (word_index % 64));
+    }
+
+    /* Finds every solution with the seed word as its first row, handing each to on_solution as its rows.
+      Stops early once on_solution returns false or should_stop returns true */
+    pub fn search<F, S>(&self, seed_index: usize, mut on_solution: F, should_stop: S) -> FillCounts
+        where F: FnMut(&[String]) -> bool, S: Fn() -> bool {
+
+        let mut after_seed = vec![u64::MAX; self.blocks];
+
+        for index in 0..=seed_index.min(self.blocks * 64 - 1) {
+            after_seed[index / 64] &= !(1 << (index % 64));
+        }
+
+        let mut state = FillState {
+            cells: vec![EMPTY; self.word_size * self.word_size],
+            placed: vec![false; self.word_size * 2],
+            used: vec![0u64; self.blocks],
+            after_seed,
+            counts: FillCounts::default()
+        };
+
+        // a seed with letters outside a to z can't be placed
+        if self.all_words.get(seed_index / 64).is_some_and(|block| block & (1 << (seed_index % 64)) != 0) {
+            self.place(&mut state, 0, seed_index);
+            self.fill(&mut state, &mut on_solution, &should_stop);
+        }
+
+        state.counts
+    }
+
+    /* false once the search has been told to stop */
+    fn fill<F, S>(&self, state: &mut FillState, on_solution: &mut F, should_stop: &S) -> bool
+        where F: FnMut(&[String]) -> bool, S: Fn() -> bool {
+
+        if should_stop() {
+            return false;
+        }
+
+        state.counts.nodes_expanded += 1;
+
+        let mut most_constrained: Option<(usize, u32)> = None;
+
+        for slot in 0..self.word_size * 2 {
+
+            if state.placed[slot] {
+                continue;
+            }
+
+            let count = self.candidate_count(state, slot);
+
+            if count == 0 {
+                return true;
+            }
+
+            if most_constrained.is_none_or(|(_, fewest)| count < fewest) {
+                most_constrained = Some((slot, count));
+            }
+        }
+
+        let Some((slot, _)) = most_constrained else {
+            let rows: Vec<String> = state.cells.chunks(self.word_size)
+                .map(|row| row.iter().map(|letter| (b'a' + letter) as char).collect())
+                .collect();
+
+            return on_solution(&rows);
+        };
+
+        for word_index in self.candidates(state, slot) {
+
+            let filled = self.place(state, slot, word_index);
+            let carry_on = self.fill(state, on_solution, should_stop);
+            self.unplace(state, slot, word_index, filled);
+
+            state.counts.backtracks += 1;
+
+            if !carry_on {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+#[test]
+fn constrained_fill_finds_each_square_once() {
+
+    let dictionary: Vec<String> = ["eat", "era", "net", "ore", "rat", "ten", "toe", "tor"].iter().map(|word| word.to_string()).collect();
+    let fill = ConstrainedFill::new(&dictionary);
+
+    let mut solutions: Vec<Vec<String>> = Vec::new();
+
+    for seed_index in 0..dictionary.len() {
+        fill.search(seed_index, |rows| {
+            solutions.push(rows.to_vec());
+            true
+        }, || false);
+    }
+
+    // the transposes toe,era,net and tor,era,net start with a word before ten so aren't found again
+    assert_eq!(vec![
+        vec!["ten".to_string(), "ore".to_string(), "eat".to_string()],
+        vec!["ten".to_string(), "ore".to_string(), "rat".to_string()]
+    ], solutions);
+}
+
+#[test]
+fn constrained_fill_stops_when_asked() {
+
+    let dictionary: Vec<String> = ["eat", "era", "net", "ore", "rat", "ten", "toe", "tor"].iter().map(|word| word.to_string()).collect();
+    let fill = ConstrainedFill::new(&dictionary);
+
+    let mut solution_count = 0;
+    let counts = fill.search(5, |_| {
+        solution_count += 1;
+        false
+    }, || false);
+
+    assert_eq!(1, solution_count);
+    assert!(counts.nodes_expanded > 0);
+
+    assert_eq!(FillCounts::default(), fill.search(5, |_| true, || true));
+}
@@ -0,0 +1,821 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::Debug;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use serde::Deserialize;
+use crate::error::SquardleError;
+use crate::solution_generator::{construct_potential_transposed_puzzle, letter_index, letter_mask, FixedLetters};
+
+/* Something a square has to satisfy besides every row and column being a word. The search asks
+  each constraint about every word before checking it against the columns and about every whole
+  square before keeping it, so a constraint that can tell early from a word saves the search
+  going any further down. Both default to allowing everything, a constraint only needs the one
+  it can answer */
+pub trait Constraint: Debug + Send + Sync {
+
+    /* whether the word can be any row or column at all. Words no square can have are taken out of
+      the dictionary before the search starts, so it never gets as far as trying them */
+    fn allows_dictionary_word(&self, _word: &str) -> bool {
+        true
+    }
+
+    /* letters no word can have in the row after the rows of the puzzle so far, as bits with the
+      lowest for a. The search screens out the words with any of them a block at a time, which
+      is much quicker than turning them down one by one with allows_word */
+    fn excluded_letters(&self, _puzzle: &[String]) -> u32 {
+        0
+    }
+
+    /* letters the row after the rows of the puzzle so far has to have, by position. They're screened
+      for the same way, so a row the constraint fixes outright only has its one word tried */
+    fn required_letters(&self, _puzzle: &[String]) -> Vec<(usize, char)> {
+        Vec::new()
+    }
+
+    /* whether the word can go in the row after the rows of the puzzle so far */
+    fn allows_word(&self, _puzzle: &[String], _word: &str) -> bool {
+        true
+    }
+
+    /* whether a finished square is kept, its rows in order */
+    fn allows_solution(&self, _solution: &[String]) -> bool {
+        true
+    }
+
+    /* whether a square and its transpose are always both allowed or both not, so the search only
+      has to find one of them. False keeps both, as a fixed letter in the top row say is in the
+      first column of the transpose */
+    fn is_symmetric(&self) -> bool {
+        false
+    }
+}
+
+/* the letters of a partial grid each row has to have */
+#[derive(Debug)]
+pub struct Pattern {
+    pub fixed_letters: FixedLetters
+}
+
+impl Constraint for Pattern {
+
+    fn required_letters(&self, puzzle: &[String]) -> Vec<(usize, char)> {
+
+        match self.fixed_letters.get(puzzle.len()) {
+            Some(fixed_row) => fixed_row.iter().enumerate().filter_map(|(position, fixed)| fixed.map(|letter| (position, letter))).collect(),
+            None => Vec::new()
+        }
+    }
+
+    fn allows_word(&self, puzzle: &[String], word: &str) -> bool {
+
+        match self.fixed_letters.get(puzzle.len()) {
+            Some(fixed_row) => word.chars().zip(fixed_row).all(|(letter, fixed)| fixed.is_none() || *fixed == Some(letter)),
+            None => true
+        }
+    }
+}
+
+/* a theme word down the diagonal from the top left, each row's letter on it fixed. The transpose
+  has the same diagonal so, unlike other fixed letters, only one of them has to be found */
+#[derive(Debug)]
+pub struct Diagonal {
+    pub letters: Vec<char>
+}
+
+impl Constraint for Diagonal {
+
+    fn required_letters(&self, puzzle: &[String]) -> Vec<(usize, char)> {
+        self.letters.get(puzzle.len()).map_or(Vec::new(), |letter| vec![(puzzle.len(), *letter)])
+    }
+
+    fn allows_word(&self, puzzle: &[String], word: &str) -> bool {
+        self.letters.get(puzzle.len()).is_none_or(|letter| word.chars().nth(puzzle.len()) == Some(*letter))
+    }
+
+    fn allows_solution(&self, solution: &[String]) -> bool {
+        (0..solution.len()).all(|row| self.allows_word(&solution[..row], &solution[row]))
+    }
+
+    fn is_symmetric(&self) -> bool {
+        true
+    }
+}
+
+/* the word has to be one of the rows or columns */
+#[derive(Debug)]
+pub struct RequiredWord {
+    pub word: String
+}
+
+impl Constraint for RequiredWord {
+
+    fn allows_solution(&self, solution: &[String]) -> bool {
+        solution.contains(&self.word) || construct_potential_transposed_puzzle(solution).contains(&self.word)
+    }
+
+    fn is_symmetric(&self) -> bool {
+        true
+    }
+}
+
+/* the most of each letter the whole square can have, each letter counted once per cell */
+#[derive(Debug)]
+pub struct LetterLimits {
+    pub limits: HashMap<char, usize>
+}
+
+impl Constraint for LetterLimits {
+
+    // the rows so far are whole words so going over is known as soon as a word takes it over
+    fn allows_word(&self, puzzle: &[String], word: &str) -> bool {
+
+        self.limits.iter().all(|(limit_letter, limit)| {
+            puzzle.iter().map(String::as_str).chain([word]).flat_map(str::chars).filter(|letter| letter == limit_letter).count() <= *limit
+        })
+    }
+
+    fn is_symmetric(&self) -> bool {
+        true
+    }
+}
+
+/* none of the words can be a row or column */
+#[derive(Debug)]
+pub struct ForbiddenWords {
+    pub words: HashSet<String>
+}
+
+impl Constraint for ForbiddenWords {
+
+    fn allows_dictionary_word(&self, word: &str) -> bool {
+        !self.words.contains(word)
+    }
+
+    fn allows_word(&self, _puzzle: &[String], word: &str) -> bool {
+        !self.words.contains(word)
+    }
+
+    fn allows_solution(&self, solution: &[String]) -> bool {
+        !construct_potential_transposed_puzzle(solution).iter().any(|column| self.words.contains(column))
+    }
+
+    fn is_symmetric(&self) -> bool {
+        true
+    }
+}
+
+/* every one of the letters has to be somewhere in the square */
+#[derive(Debug)]
+pub struct IncludesLetters {
+    pub letters: Vec<char>
+}
+
+impl Constraint for IncludesLetters {
+
+    fn allows_solution(&self, solution: &[String]) -> bool {
+        self.letters.iter().all(|letter| solution.iter().any(|row| row.contains(*letter)))
+    }
+
+    fn is_symmetric(&self) -> bool {
+        true
+    }
+}
+
+pub const VOWELS: [char; 5] = ['a', 'e', 'i', 'o', 'u'];
+
+/* rules each row and column word has to keep to on its own, whatever else is in the square */
+#[derive(Debug, Default)]
+pub struct WordRules {
+    pub min_vowels: usize,
+    // the most times any one letter can be in a word
+    pub max_repeated_letter: Option<usize>,
+    pub forbidden_letters: HashSet<char>
+}
+
+impl WordRules {
+
+    pub fn is_empty(&self) -> bool {
+        self.min_vowels == 0 && self.max_repeated_letter.is_none() && self.forbidden_letters.is_empty()
+    }
+}
+
+impl Constraint for WordRules {
+
+    fn allows_dictionary_word(&self, word: &str) -> bool {
+
+        word.chars().filter(|letter| VOWELS.contains(letter)).count() >= self.min_vowels
+            && !word.chars().any(|letter| self.forbidden_letters.contains(&letter))
+            && self.max_repeated_letter.is_none_or(|max| word.chars().all(|letter| word.chars().filter(|other| *other == letter).count() <= max))
+    }
+
+    fn excluded_letters(&self, _puzzle: &[String]) -> u32 {
+        self.forbidden_letters.iter().filter_map(|letter| letter_index(*letter)).fold(0, |mask, index| mask | 1 << index)
+    }
+
+    // for a dictionary that wasn't filtered first, the columns can only be checked once they're whole
+    fn allows_word(&self, _puzzle: &[String], word: &str) -> bool {
+        self.allows_dictionary_word(word)
+    }
+
+    fn allows_solution(&self, solution: &[String]) -> bool {
+        construct_potential_transposed_puzzle(solution).iter().all(|column| self.allows_dictionary_word(column))
+    }
+
+    fn is_symmetric(&self) -> bool {
+        true
+    }
+}
+
+/* No letter more than once anywhere in the square, a perfect square. The rows are every cell so
+  the letters of the rows so far are the letters already used, and are screened out of the next
+  row as the search goes */
+#[derive(Debug)]
+pub struct AllLettersDifferent;
+
+impl AllLettersDifferent {
+
+    fn rows_letters(puzzle: &[String]) -> u32 {
+        puzzle.iter().fold(0, |mask, row| mask | letter_mask(row))
+    }
+}
+
+impl Constraint for AllLettersDifferent {
+
+    fn allows_dictionary_word(&self, word: &str) -> bool {
+        letter_mask(word).count_ones() as usize == word.chars().count()
+    }
+
+    fn excluded_letters(&self, puzzle: &[String]) -> u32 {
+        Self::rows_letters(puzzle)
+    }
+
+    fn allows_word(&self, puzzle: &[String], word: &str) -> bool {
+        self.allows_dictionary_word(word) && Self::rows_letters(puzzle) & letter_mask(word) == 0
+    }
+
+    // the constrained fill only asks about whole squares
+    fn allows_solution(&self, solution: &[String]) -> bool {
+        Self::rows_letters(solution).count_ones() as usize == solution.iter().map(|row| row.chars().count()).sum::<usize>()
+    }
+
+    fn is_symmetric(&self) -> bool {
+        true
+    }
+}
+
+/* The square reads the same backwards as one string, row after row. So the bottom row is the top row
+  backwards and so on in to the middle, which reads the same both ways, and every cell is the
+  same letter as the one turned half way round onto it. Once the top half is in the rest of the
+  rows are fixed */
+#[derive(Debug)]
+pub struct Palindromic;
+
+impl Palindromic {
+
+    // the row turned half way round onto the next one, none while filling the top half
+    fn mirror_row(puzzle: &[String]) -> Option<&String> {
+        let size = puzzle.first()?.chars().count();
+        (size > puzzle.len() && size - 1 - puzzle.len() < puzzle.len()).then(|| &puzzle[size - 1 - puzzle.len()])
+    }
+}
+
+impl Constraint for Palindromic {
+
+    fn required_letters(&self, puzzle: &[String]) -> Vec<(usize, char)> {
+        Self::mirror_row(puzzle).map_or(Vec::new(), |mirror| mirror.chars().rev().enumerate().collect())
+    }
+
+    fn allows_word(&self, puzzle: &[String], word: &str) -> bool {
+
+        let size = word.chars().count();
+
+        match Self::mirror_row(puzzle) {
+            Some(mirror) => word.chars().eq(mirror.chars().rev()),
+            // the middle row of an odd size
+            None if size == 2 * puzzle.len() + 1 => word.chars().eq(word.chars().rev()),
+            None => true
+        }
+    }
+
+    fn allows_solution(&self, solution: &[String]) -> bool {
+        solution.iter().flat_map(|row| row.chars()).eq(solution.iter().rev().flat_map(|row| row.chars().rev()))
+    }
+
+    // turning the transpose half way round is the transpose turned, so it's palindromic too
+    fn is_symmetric(&self) -> bool {
+        true
+    }
+}
+
+/* The square is the same flipped over the diagonal from its top right to its bottom left, so the
+  last column is the first row backwards, the next column in the second row backwards and so on.
+  The rows so far fix the end of the next row, more of it each row down */
+#[derive(Debug)]
+pub struct AntiDiagonal;
+
+impl Constraint for AntiDiagonal {
+
+    // the cell at row r and column c is the same letter as the one at row size - 1 - c and column size - 1 - r
+    fn required_letters(&self, puzzle: &[String]) -> Vec<(usize, char)> {
+
+        let row = puzzle.len();
+        let size = puzzle.first().map_or(0, |first| first.chars().count());
+
+        (size.saturating_sub(row)..size)
+            .filter_map(|column| puzzle[size - 1 - column].chars().nth(size - 1 - row).map(|letter| (column, letter)))
+            .collect()
+    }
+
+    fn allows_word(&self, puzzle: &[String], word: &str) -> bool {
+        self.required_letters(puzzle).into_iter().all(|(column, letter)| word.chars().nth(column) == Some(letter))
+    }
+
+    fn allows_solution(&self, solution: &[String]) -> bool {
+        (0..solution.len()).all(|row| self.allows_word(&solution[..row], &solution[row]))
+    }
+
+    fn is_symmetric(&self) -> bool {
+        true
+    }
+}
+
+/* Takes out the words the constraints say no square can have, so the prefix map built from what's
+  left doesn't lead the search to them. Returns how many were taken out */
+pub fn filter_dictionary(constraints: &[Arc<dyn Constraint>], dictionary: &mut Vec<String>) -> usize {
+
+    let words = dictionary.len();
+    dictionary.retain(|word| constraints.iter().all(|constraint| constraint.allows_dictionary_word(word)));
+
+    words - dictionary.len()
+}
+
+/* at least this many different letters of the set somewhere in the square, 3 different vowels say */
+#[derive(Debug)]
+pub struct DistinctLetters {
+    pub letters: Vec<char>,
+    pub at_least: usize
+}
+
+impl Constraint for DistinctLetters {
+
+    fn allows_solution(&self, solution: &[String]) -> bool {
+        self.letters.iter().filter(|letter| solution.iter().any(|row| row.contains(**letter))).count() >= self.at_least
+    }
+
+    fn is_symmetric(&self) -> bool {
+        true
+    }
+}
+
+/* at least this many of the rows and columns are words with a theme's tag, the words being every one tagged with it */
+#[derive(Debug)]
+pub struct TaggedWords {
+    pub words: HashSet<String>,
+    pub at_least: usize
+}
+
+impl Constraint for TaggedWords {
+
+    fn allows_solution(&self, solution: &[String]) -> bool {
+
+        let columns = construct_potential_transposed_puzzle(solution);
+
+        solution.iter().chain(columns.iter()).filter(|word| self.words.contains(*word)).count() >= self.at_least
+    }
+
+    fn is_symmetric(&self) -> bool {
+        true
+    }
+}
+
+/* A constraint a square is better for keeping to but can still be had without. Nothing is
+  turned down for breaking one, instead a square's penalty is the weight of every soft
+  constraint it breaks added up, so the squares can be ranked by how close they come */
+#[derive(Debug, Clone)]
+pub struct SoftConstraint {
+    pub constraint: Arc<dyn Constraint>,
+    pub weight: f64
+}
+
+/* The weights of the soft constraints the grid breaks so far. A partial grid is only asked about
+  its rows, a whole square about itself too, so a partial grid's penalty never goes down as
+  it's filled in */
+pub fn penalty(soft_constraints: &[SoftConstraint], puzzle: &[String]) -> f64 {
+
+    // squares so a grid with as many rows as letters in a row is finished
+    let finished = puzzle.first().is_some_and(|row| row.chars().count() == puzzle.len());
+
+    soft_constraints.iter()
+        .filter(|soft| {
+            !(0..puzzle.len()).all(|row| soft.constraint.allows_word(&puzzle[..row], &puzzle[row])) || (finished && !soft.constraint.allows_solution(puzzle))
+        })
+        .map(|soft| soft.weight)
+        .sum()
+}
+
+/* the constraints a square has to satisfy and the ones it's only better for */
+#[derive(Debug, Default)]
+pub struct ConstraintSet {
+    pub hard: Vec<Arc<dyn Constraint>>,
+    pub soft: Vec<SoftConstraint>
+}
+
+/* A constraints file, toml unless it ends in .json, so common requirements can be written down
+  rather than coded. Rows and columns are counted from 0 and their letters all go into the
+  one pattern, row0.starts_with = "qu" and column4.ends_with = "s" say. The grid's rules are
+  about the square as a whole. Each [[soft]] table has a weight and the same rules, which a
+  square is only penalised for breaking */
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct ConstraintsFile {
+    #[serde(flatten)]
+    rules: Rules,
+    soft: Vec<SoftRules>
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct Rules {
+    require_words: Vec<String>,
+    forbid_words: Vec<String>,
+    grid: GridRules,
+    // keyed row0, column3 and so on
+    #[serde(flatten)]
+    lines: HashMap<String, LineRules>
+}
+
+#[derive(Debug, Deserialize)]
+struct SoftRules {
+    weight: f64,
+    #[serde(flatten)]
+    rules: Rules
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct GridRules {
+    must_include_letters: String,
+    max_letters: HashMap<char, usize>,
+    min_distinct_vowels: usize,
+    min_vowels_per_word: usize,
+    max_repeated_letter: Option<usize>,
+    forbid_letters: String,
+    // no letter more than once in the whole square
+    all_letters_different: bool,
+    // a word down the diagonal from the top left
+    diagonal: String
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct LineRules {
+    starts_with: String,
+    ends_with: String,
+    // . for any letter, cra.e say
+    pattern: String
+}
+
+impl LineRules {
+
+    /* the letters the rules fix by where they are in the line */
+    fn fixed_letters(&self, word_size: usize) -> Result<Vec<(usize, char)>, SquardleError> {
+
+        let too_long = |rule: &str| -> SquardleError { format!("[{}] is longer than the word size {}", rule, word_size).as_str().into() };
+
+        let starts_with: Vec<char> = self.starts_with.to_lowercase().chars().collect();
+        let ends_with: Vec<char> = self.ends_with.to_lowercase().chars().collect();
+        let pattern: Vec<char> = self.pattern.to_lowercase().chars().collect();
+
+        for (rule, letters) in [(&self.starts_with, &starts_with), (&self.ends_with, &ends_with), (&self.pattern, &pattern)] {
+            if letters.len() > word_size {
+                return Err(too_long(rule));
+            }
+        }
+
+        let ends_at = word_size - ends_with.len();
+
+        Ok(starts_with.into_iter().enumerate()
+            .chain(ends_with.into_iter().enumerate().map(|(position, letter)| (ends_at + position, letter)))
+            .chain(pattern.into_iter().enumerate().filter(|(_, letter)| *letter != '.'))
+            .collect())
+    }
+}
+
+/* row3 as across and 3, column0 as down and 0 */
+fn parse_line_key(key: &str) -> Option<(bool, usize)> {
+
+    let (across, index) = match (key.strip_prefix("row"), key.strip_prefix("column")) {
+        (Some(index), _) => (true, index),
+        (_, Some(index)) => (false, index),
+        _ => return None
+    };
+
+    index.parse().ok().map(|index| (across, index))
+}
+
+pub fn load_constraints_file(file_path: &str, word_size: usize) -> Result<ConstraintSet, SquardleError> {
+
+    let text = fs::read_to_string(file_path)?;
+    let is_json = Path::new(file_path).extension().is_some_and(|extension| extension == "json");
+
+    parse_constraints(&text, is_json, word_size)
+}
+
+pub fn parse_constraints(text: &str, is_json: bool, word_size: usize) -> Result<ConstraintSet, SquardleError> {
+
+    let file: ConstraintsFile = match is_json {
+        true => serde_json::from_str(text)?,
+        false => toml::from_str(text)?
+    };
+
+    let hard = file.rules.constraints(word_size)?;
+    let mut soft: Vec<SoftConstraint> = Vec::new();
+
+    for soft_rules in file.soft {
+
+        if !soft_rules.weight.is_finite() || soft_rules.weight < 0.0 {
+            return Err(format!("soft constraint weight [{}] has to be a number 0 or more", soft_rules.weight).as_str().into());
+        }
+
+        // each rule is its own soft constraint so a square is penalised for each it breaks
+        soft.extend(soft_rules.rules.constraints(word_size)?.into_iter().map(|constraint| SoftConstraint { constraint, weight: soft_rules.weight }));
+    }
+
+    Ok(ConstraintSet { hard, soft })
+}
+
+impl Rules {
+
+    fn constraints(self, word_size: usize) -> Result<Vec<Arc<dyn Constraint>>, SquardleError> {
+
+        let mut constraints: Vec<Arc<dyn Constraint>> = Vec::new();
+        let mut fixed_letters: FixedLetters = vec![vec![None; word_size]; word_size];
+
+        for (key, rules) in &self.lines {
+
+            let (across, index) = parse_line_key(key)
+                .ok_or_else(|| SquardleError::from(format!("unknown constraint [{}], expected require_words, forbid_words, grid, soft, or a row or column e.g. row0", key).as_str()))?;
+
+            if index >= word_size {
+                return Err(format!("[{}] is outside a square of {}", key, word_size).as_str().into());
+            }
+
+            for (position, letter) in rules.fixed_letters(word_size)? {
+
+                let (row, column) = if across { (index, position) } else { (position, index) };
+
+                match fixed_letters[row][column] {
+                    Some(fixed) if fixed != letter => return Err(format!("row {} column {} can't be both {} and {}", row, column, fixed, letter).as_str().into()),
+                    _ => fixed_letters[row][column] = Some(letter)
+                }
+            }
+        }
+
+        if fixed_letters.iter().flatten().any(Option::is_some) {
+            constraints.push(Arc::new(Pattern { fixed_letters }));
+        }
+
+        for word in &self.require_words {
+            if word.chars().count() != word_size {
+                return Err(SquardleError::IncorrectWordSize { word: word.clone(), expected: word_size, found: word.chars().count() });
+            }
+
+            constraints.push(Arc::new(RequiredWord { word: word.to_lowercase() }));
+        }
+
+        if !self.forbid_words.is_empty() {
+            constraints.push(Arc::new(ForbiddenWords { words: self.forbid_words.iter().map(|word| word.to_lowercase()).collect() }));
+        }
+
+        if !self.grid.must_include_letters.is_empty() {
+            constraints.push(Arc::new(IncludesLetters { letters: self.grid.must_include_letters.to_lowercase().chars().collect() }));
+        }
+
+        if !self.grid.max_letters.is_empty() {
+            constraints.push(Arc::new(LetterLimits { limits: self.grid.max_letters }));
+        }
+
+        if self.grid.min_distinct_vowels > 0 {
+            constraints.push(Arc::new(DistinctLetters { letters: VOWELS.to_vec(), at_least: self.grid.min_distinct_vowels }));
+        }
+
+        if self.grid.all_letters_different {
+            constraints.push(Arc::new(AllLettersDifferent));
+        }
+
+        if !self.grid.diagonal.is_empty() {
+            constraints.push(Arc::new(diagonal(&self.grid.diagonal, word_size)?));
+        }
+
+        let word_rules = WordRules {
+            min_vowels: self.grid.min_vowels_per_word,
+            max_repeated_letter: self.grid.max_repeated_letter,
+            forbidden_letters: self.grid.forbid_letters.to_lowercase().chars().collect()
+        };
+
+        if !word_rules.is_empty() {
+            constraints.push(Arc::new(word_rules));
+        }
+
+        Ok(constraints)
+    }
+}
+
+/* the diagonal constraint for a word of the square's size */
+pub fn diagonal(word: &str, word_size: usize) -> Result<Diagonal, SquardleError> {
+
+    let letters: Vec<char> = word.to_lowercase().chars().collect();
+
+    if letters.len() != word_size {
+        return Err(SquardleError::IncorrectWordSize { word: word.to_string(), expected: word_size, found: letters.len() });
+    }
+
+    Ok(Diagonal { letters })
+}
+
+/* letter:count, the most of a letter a square can have */
+pub fn parse_letter_limit(value: &str) -> Option<(char, usize)> {
+
+    let (letter, count) = value.split_once(':')?;
+    let mut letters = letter.chars();
+
+    match (letters.next(), letters.next(), count.parse::<usize>()) {
+        (Some(letter), None, Ok(count)) => Some((letter, count)),
+        _ => None
+    }
+}
+
+#[test]
+fn built_in_constraints() {
+
+    let rows = |rows: &[&str]| -> Vec<String> { rows.iter().map(|row| row.to_string()).collect() };
+
+    let pattern = Pattern { fixed_letters: crate::solution_generator::parse_partial_grid(".a,..", 2).unwrap() };
+    assert!(pattern.allows_word(&[], "ba") && !pattern.allows_word(&[], "ab"));
+    assert!(pattern.allows_word(&rows(&["ba"]), "ab"));
+
+    let required = RequiredWord { word: "ad".to_string() };
+    assert!(required.allows_solution(&rows(&["ab", "dc"])) && !required.allows_solution(&rows(&["ab", "cd"])));
+
+    let limits = LetterLimits { limits: HashMap::from([('a', 1)]) };
+    assert!(limits.allows_word(&rows(&["ab"]), "cd") && !limits.allows_word(&rows(&["ab"]), "ca"));
+
+    assert_eq!(Some(('e', 3)), parse_letter_limit("e:3"));
+    assert_eq!(None, parse_letter_limit("ee:3"));
+
+    // ab and cd are tagged, bd is a tagged column
+    let tagged = TaggedWords { words: HashSet::from(["ab".to_string(), "bd".to_string(), "cd".to_string()]), at_least: 3 };
+    assert!(tagged.allows_solution(&rows(&["ab", "cd"])) && !tagged.allows_solution(&rows(&["ab", "ce"])));
+}
+
+#[test]
+fn word_rules_filter_the_dictionary() {
+
+    let word_rules = WordRules { min_vowels: 2, max_repeated_letter: Some(1), forbidden_letters: HashSet::from(['q']) };
+
+    // one vowel, a repeated letter and a q
+    let mut dictionary: Vec<String> = ["audio", "crane", "eerie", "plant", "quiet"].iter().map(|word| word.to_string()).collect();
+    let removed = filter_dictionary(&[Arc::new(word_rules)], &mut dictionary);
+
+    assert_eq!(3, removed);
+    assert_eq!(vec!["audio", "crane"], dictionary);
+
+    // the columns of a square from a dictionary that wasn't filtered are checked too
+    let word_rules = WordRules { forbidden_letters: HashSet::from(['z']), ..WordRules::default() };
+    assert!(word_rules.allows_solution(&["ab".to_string(), "cd".to_string()]));
+    assert!(!word_rules.allows_solution(&["az".to_string(), "bc".to_string()]) && !word_rules.allows_word(&[], "az"));
+
+    let constraints = parse_constraints("[grid]\nmin_vowels_per_word = 1\nforbid_letters = \"XZ\"", false, 3).unwrap().hard;
+    assert_eq!(1, constraints.len());
+    assert!(!constraints[0].allows_dictionary_word("zoo") && !constraints[0].allows_dictionary_word("nth"));
+}
+
+#[test]
+fn all_letters_different() {
+
+    let rows = |rows: &[&str]| -> Vec<String> { rows.iter().map(|row| row.to_string()).collect() };
+
+    assert!(!AllLettersDifferent.allows_dictionary_word("eerie") && AllLettersDifferent.allows_dictionary_word("crane"));
+
+    // a, b and c are used so d can come next but not a word with any of them
+    assert_eq!(0b111, AllLettersDifferent.excluded_letters(&rows(&["abc"])));
+    assert!(AllLettersDifferent.allows_word(&rows(&["abc"]), "def") && !AllLettersDifferent.allows_word(&rows(&["abc"]), "dea"));
+
+    assert!(AllLettersDifferent.allows_solution(&rows(&["abc", "def", "ghi"])) && !AllLettersDifferent.allows_solution(&rows(&["abc", "def", "gha"])));
+
+    let constraints = parse_constraints("grid.all_letters_different = true", false, 3).unwrap().hard;
+    assert_eq!(1, constraints.len());
+}
+
+#[test]
+fn symmetric_squares() {
+
+    let rows = |rows: &[&str]| -> Vec<String> { rows.iter().map(|row| row.to_string()).collect() };
+
+    // the bottom row is the top one backwards, the middle row reads the same both ways
+    assert_eq!(vec![(0, 'c'), (1, 'b'), (2, 'a')], Palindromic.required_letters(&rows(&["abc", "ded"])));
+    assert!(Palindromic.required_letters(&rows(&["abc"])).is_empty());
+    assert!(Palindromic.allows_word(&rows(&["abc"]), "ded") && !Palindromic.allows_word(&rows(&["abc"]), "deb"));
+    assert!(Palindromic.allows_solution(&rows(&["abc", "ded", "cba"])) && !Palindromic.allows_solution(&rows(&["abc", "deb", "cba"])));
+
+    // the last column is the top row backwards, then the second row's end is the top row's middle
+    assert_eq!(vec![(2, 'b')], AntiDiagonal.required_letters(&rows(&["abc"])));
+    assert_eq!(vec![(1, 'd'), (2, 'a')], AntiDiagonal.required_letters(&rows(&["abc", "deb"])));
+    assert!(AntiDiagonal.allows_solution(&rows(&["abc", "deb", "fda"])) && !AntiDiagonal.allows_solution(&rows(&["abc", "ded", "fda"])));
+}
+
+#[test]
+fn diagonal_theme_word() {
+
+    let rows = |rows: &[&str]| -> Vec<String> { rows.iter().map(|row| row.to_string()).collect() };
+
+    let diagonal = diagonal("AEI", 3).unwrap();
+
+    assert_eq!(vec![(1, 'e')], diagonal.required_letters(&rows(&["abc"])));
+    assert!(diagonal.allows_word(&rows(&["abc"]), "def") && !diagonal.allows_word(&rows(&["abc"]), "dxf"));
+    assert!(diagonal.allows_solution(&rows(&["abc", "def", "ghi"])) && !diagonal.allows_solution(&rows(&["abc", "def", "gha"])));
+
+    assert!(crate::constraint::diagonal("heart", 3).is_err());
+    assert_eq!(1, parse_constraints("grid.diagonal = \"aei\"", false, 3).unwrap().hard.len());
+}
+
+#[test]
+fn constraints_from_toml_and_json() {
+
+    let rows = |rows: &[&str]| -> Vec<String> { rows.iter().map(|row| row.to_string()).collect() };
+    let allows = |constraints: &[Arc<dyn Constraint>], solution: &[String]| {
+        constraints.iter().all(|constraint| (0..solution.len()).all(|row| constraint.allows_word(&solution[..row], &solution[row])) && constraint.allows_solution(solution))
+    };
+
+    let toml = r#"
+        forbid_words = ["rat"]
+        row0.starts_with = "t"
+        column2.ends_with = "t"
+
+        [grid]
+        must_include_letters = "n"
+    "#;
+
+    let constraints = parse_constraints(toml, false, 3).unwrap().hard;
+
+    // the pattern, forbidden words and included letters
+    assert_eq!(3, constraints.len());
+    assert!(allows(&constraints, &rows(&["ten", "ore", "eat"])));
+    // rat as a row then as a column
+    assert!(!allows(&constraints, &rows(&["ten", "ore", "rat"])));
+    assert!(!allows(&constraints, &rows(&["tor", "era", "net"])));
+    // the last column is e, g, n
+    assert!(!allows(&constraints, &rows(&["tee", "bag", "nun"])));
+
+    let json = r#"{ "row0": { "pattern": ".a." }, "grid": { "max_letters": { "a": 1 } } }"#;
+    let constraints = parse_constraints(json, true, 3).unwrap().hard;
+
+    assert!(allows(&constraints, &rows(&["bat", "ore", "tee"])));
+    assert!(!allows(&constraints, &rows(&["bat", "ore", "tea"])));
+}
+
+#[test]
+fn bad_constraints_files() {
+
+    // not a row or column, outside the square, clashing letters and a typo in a rule
+    assert!(parse_constraints("rows0.starts_with = \"a\"", false, 3).is_err());
+    assert!(parse_constraints("row3.starts_with = \"a\"", false, 3).is_err());
+    assert!(parse_constraints("row0.starts_with = \"a\"\ncolumn0.starts_with = \"b\"", false, 3).is_err());
+    assert!(parse_constraints("row0.start_with = \"a\"", false, 3).is_err());
+    assert!(parse_constraints("row0.ends_with = \"abcd\"", false, 3).is_err());
+
+    // a soft constraint needs a weight that isn't negative
+    assert!(parse_constraints("[[soft]]\nforbid_words = [\"rat\"]", false, 3).is_err());
+    assert!(parse_constraints("[[soft]]\nweight = -1\nforbid_words = [\"rat\"]", false, 3).is_err());
+}
+
+#[test]
+fn soft_constraints_add_up_to_a_penalty() {
+
+    let rows = |rows: &[&str]| -> Vec<String> { rows.iter().map(|row| row.to_string()).collect() };
+
+    let toml = r#"
+        [[soft]]
+        weight = 2
+        grid.min_distinct_vowels = 3
+
+        [[soft]]
+        weight = 0.5
+        forbid_words = ["ten"]
+        row1.starts_with = "e"
+    "#;
+
+    let constraints = parse_constraints(toml, false, 3).unwrap();
+
+    assert!(constraints.hard.is_empty());
+    assert_eq!(vec![2.0, 0.5, 0.5], constraints.soft.iter().map(|soft| soft.weight).collect::<Vec<f64>>());
+
+    // e, o and a but ten is a row and the second row starts with o
+    assert_eq!(1.0, penalty(&constraints.soft, &rows(&["ten", "ore", "eat"])));
+    assert_eq!(0.5, penalty(&constraints.soft, &rows(&["tor", "era", "net"])));
+
+    // the vowels are only counted once the square is finished
+    assert_eq!(0.5, penalty(&constraints.soft, &rows(&["bat", "aaa"])));
+    assert_eq!(2.5, penalty(&constraints.soft, &rows(&["bat", "aaa", "tat"])));
+}
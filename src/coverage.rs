@@ -0,0 +1,103 @@
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::io::BufRead;
+use crate::error::SquardleError;
+use crate::generator_config::ReportFormat;
+use crate::output;
+use crate::solution_generator::construct_potential_transposed_puzzle;
+
+/* How many solutions each dictionary word is in as a row or column, most used first with ties
+  alphabetical, and the words in none of them. A word that is both a row and a column of a
+  solution only counts it once. Words of the solutions that aren't in the dictionary are left
+  for verify to complain about */
+#[derive(Debug, PartialEq, Serialize)]
+pub struct CoverageReport {
+    pub solution_count: u64,
+    pub word_counts: Vec<(String, u64)>,
+    pub unused_words: Vec<String>
+}
+
+impl CoverageReport {
+
+    pub fn render(&self, report_format: &ReportFormat) -> Result<String, SquardleError> {
+
+        match report_format {
+            ReportFormat::Text => Ok(self.to_string()),
+            ReportFormat::Json => Ok(serde_json::to_string_pretty(self)?)
+        }
+    }
+}
+
+pub fn solution_file_coverage(file_path: &str, dictionary: &[String]) -> Result<CoverageReport, SquardleError> {
+
+    let mut counts: HashMap<&str, u64> = dictionary.iter().map(|word| (word.as_str(), 0)).collect();
+    let mut solution_count = 0;
+
+    let reader = output::open_solution_file(file_path)?;
+
+    for line in reader.lines() {
+        let rows = output::rows_of_line(&line?);
+
+        if rows.is_empty() {
+            continue;
+        }
+
+        solution_count += 1;
+
+        let columns = construct_potential_transposed_puzzle(&rows);
+        let words: HashSet<&String> = rows.iter().chain(columns.iter()).collect();
+
+        for word in words {
+            if let Some(count) = counts.get_mut(word.as_str()) {
+                *count += 1;
+            }
+        }
+    }
+
+    let mut unused_words: Vec<String> = counts.iter().filter(|(_, count)| **count == 0).map(|(word, _)| word.to_string()).collect();
+    unused_words.sort();
+
+    let mut word_counts: Vec<(String, u64)> = counts.into_iter().filter(|(_, count)| *count > 0).map(|(word, count)| (word.to_string(), count)).collect();
+    word_counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+    Ok(CoverageReport { solution_count, word_counts, unused_words })
+}
+
+impl fmt::Display for CoverageReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+
+        writeln!(f, "solutions: {}", self.solution_count)?;
+        writeln!(f, "words in solutions: {}", self.word_counts.len())?;
+        writeln!(f, "words in no solution: {}", self.unused_words.len())?;
+
+        writeln!(f, "solutions per word:")?;
+        for (word, count) in &self.word_counts {
+            writeln!(f, "  {}: {}", word, count)?;
+        }
+
+        write!(f, "words in no solution:")?;
+        for word in &self.unused_words {
+            write!(f, "\n  {}", word)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[test]
+fn coverage_of_a_solution_file() {
+
+    let file_path = "test_coverage.csv";
+    std::fs::write(file_path, "ten,ore,eat,1.500\n\ntoe,era,net\n").unwrap();
+
+    let dictionary: Vec<String> = ["eat", "era", "net", "ore", "tan", "ten", "toe"].iter().map(|word| word.to_string()).collect();
+    let coverage = solution_file_coverage(file_path, &dictionary).unwrap();
+    std::fs::remove_file(file_path).unwrap();
+
+    assert_eq!(2, coverage.solution_count);
+    assert_eq!(vec!["tan".to_string()], coverage.unused_words);
+    assert_eq!(("eat".to_string(), 2), coverage.word_counts[0]);
+    assert_eq!(6, coverage.word_counts.len());
+    assert!(coverage.render(&ReportFormat::Json).unwrap().contains("\"unused_words\": [\n    \"tan\"\n  ]"));
+}
@@ -0,0 +1,285 @@
+use crate::error::SquardleError;
+use crate::prefix_arena::PrefixArena;
+use crate::solution_generator::letter_index;
+
+/* cube dictionary_file with optional destination file, stdout without one, and --limit n */
+#[derive(Debug, PartialEq)]
+pub struct CubeConfig {
+    pub dictionary_file_path: String,
+    pub destination_file_path: Option<String>,
+    pub solution_limit: Option<usize>
+}
+
+impl CubeConfig {
+
+    pub fn build(args: &[String]) -> Result<CubeConfig, SquardleError> {
+
+        let mut solution_limit: Option<usize> = None;
+        let mut positional_args: Vec<String> = Vec::with_capacity(2);
+        let mut args_iter = args.iter();
+
+        while let Some(arg) = args_iter.next() {
+
+            match arg.as_str() {
+                "--limit" => solution_limit = match args_iter.next().map(|limit| limit.parse::<usize>()) {
+                    Some(Ok(limit)) if limit > 0 => Some(limit),
+                    _ => return Err("Limit must be a number greater than 0".into())
+                },
+                _ => positional_args.push(arg.clone())
+            }
+        }
+
+        if positional_args.is_empty() || positional_args.len() > 2 {
+            return Err("cube accepts a dictionary file path, optionally a destination file path and --limit n".into());
+        }
+
+        let destination_file_path = positional_args.get(1).cloned();
+        let dictionary_file_path = positional_args.swap_remove(0);
+
+        Ok(CubeConfig { dictionary_file_path, destination_file_path, solution_limit })
+    }
+}
+
+/* A cube of n layers of n rows of n letters where every row across a layer, column down a layer
+  and pillar through the layers is a word. It's filled a row at a time the way a square is, going
+  on to the next layer once one is full, so a row's letters have to keep the column they cross in
+  their own layer and the pillar they cross through the layers above both starting words. The
+  letters that can come next are the same masks the square search looks its columns up in, held
+  by the prefix map.
+  Swapping the axes of a cube around makes another cube, so only the first of the six in
+  reading order is kept the way a square's transpose is left out */
+pub struct CubeSolver {
+    word_size: usize,
+    // the letters words start with, the masks only have starts of a letter or more
+    first_letters: u32,
+    prefix_map: PrefixArena
+}
+
+impl CubeSolver {
+
+    pub fn new(dictionary: &[String], prefix_map: PrefixArena) -> Result<CubeSolver, SquardleError> {
+
+        let word_size = match dictionary.first() {
+            Some(word) => word.chars().count(),
+            None => return Err(SquardleError::EmptyDictionary)
+        };
+
+        let first_letters = dictionary.iter()
+            .filter_map(|word| word.chars().next().and_then(letter_index))
+            .fold(0, |mask, index| mask | 1 << index);
+
+        Ok(CubeSolver { word_size, first_letters, prefix_map })
+    }
+
+    pub fn word_size(&self) -> usize {
+        self.word_size
+    }
+
+    /* Hands each cube found to on_cube as its rows, the first layer's rows then the next layer's
+      and so on, stopping at the limit or the first error on_cube gives. Returns the cubes found */
+    pub fn solve<F>(&self, solution_limit: Option<usize>, on_cube: F) -> Result<usize, SquardleError>
+        where F: FnMut(&[String]) -> Result<(), SquardleError> {
+
+        let size = self.word_size;
+
+        let mut search = CubeSearch {
+            solver: self,
+            columns: vec![String::with_capacity(size); size * size],
+            pillars: vec![String::with_capacity(size); size * size],
+            rows: Vec::with_capacity(size * size),
+            row: String::with_capacity(size),
+            found: 0,
+            solution_limit,
+            on_cube
+        };
+
+        search.fill_row()?;
+
+        Ok(search.found)
+    }
+
+    fn allowed_after(&self, start: &str) -> u32 {
+
+        match start.is_empty() {
+            true => self.first_letters,
+            false => self.prefix_map.next_letters(start)
+        }
+    }
+}
+
+struct CubeSearch<'a, F> {
+    solver: &'a CubeSolver,
+    // the start of each column down a layer so far, by layer then position along the row
+    columns: Vec<String>,
+    // the start of each pillar through the layers so far, by row then position along the row
+    pillars: Vec<String>,
+    rows: Vec<String>,
+    // the row being filled
+    row: String,
+    found: usize,
+    solution_limit: Option<usize>,
+    on_cube: F
+}
+
+impl<F> CubeSearch<'_, F> where F: FnMut(&[String]) -> Result<(), SquardleError> {
+
+    /* false once the limit is reached and the search should stop */
+    fn fill_row(&mut self) -> Result<bool, SquardleError> {
+
+        let size = self.solver.word_size;
+
+        if self.rows.len() < size * size {
+            return self.fill_letter(0);
+        }
+
+        if !first_of_its_axes(&self.rows, size) {
+            return Ok(true);
+        }
+
+        (self.on_cube)(&self.rows)?;
+        self.found += 1;
+
+        Ok(self.solution_limit.is_none_or(|limit| self.found < limit))
+    }
+
+    fn fill_letter(&mut self, position: usize) -> Result<bool, SquardleError> {
+
+        let size = self.solver.word_size;
+
+        if position == size {
+
+            // the next row starts from nothing, this one goes back once its rows below are done
+            let row = std::mem::take(&mut self.row);
+
+            self.rows.push(row);
+            let carry_on = self.fill_row();
+            self.row = self.rows.pop().expect("the row was just pushed");
+
+            return carry_on;
+        }
+
+        let layer = self.rows.len() / size;
+        let row = self.rows.len() % size;
+        let column = layer * size + position;
+        let pillar = row * size + position;
+
+        let allowed = self.solver.allowed_after(&self.row)
+            & self.solver.allowed_after(&self.columns[column])
+            & self.solver.allowed_after(&self.pillars[pillar]);
+
+        for index in (0..26).filter(|index| allowed & 1 << index != 0) {
+
+            let letter = (b'a' + index as u8) as char;
+
+            self.row.push(letter);
+            self.columns[column].push(letter);
+            self.pillars[pillar].push(letter);
+
+            let carry_on = self.fill_letter(position + 1)?;
+
+            self.row.pop();
+            self.columns[column].pop();
+            self.pillars[pillar].pop();
+
+            if !carry_on {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+}
+
+/* whether the cube reads first of the six it makes with its axes swapped around, letter by letter in row order */
+fn first_of_its_axes(rows: &[String], size: usize) -> bool {
+
+    let letters: Vec<char> = rows.iter().flat_map(|row| row.chars()).collect();
+    let letter_at = |coordinates: [usize; 3]| letters[coordinates[0] * size * size + coordinates[1] * size + coordinates[2]];
+
+    const AXES: [[usize; 3]; 5] = [[0, 2, 1], [1, 0, 2], [1, 2, 0], [2, 0, 1], [2, 1, 0]];
+
+    AXES.iter().all(|axes| {
+
+        let swapped = (0..letters.len()).map(|cell| {
+            let position = [cell / (size * size), cell / size % size, cell % size];
+            letter_at([position[axes[0]], position[axes[1]], position[axes[2]]])
+        });
+
+        letters.iter().copied().le(swapped)
+    })
+}
+
+/* every word of a cube, the rows then the columns down each layer then the pillars through them */
+pub fn cube_lines(rows: &[String]) -> Vec<String> {
+
+    let size = rows.first().map_or(0, |row| row.chars().count());
+    let letters: Vec<Vec<char>> = rows.iter().map(|row| row.chars().collect()).collect();
+
+    let columns = (0..size).flat_map(|layer| (0..size).map(move |position| (layer, position)))
+        .map(|(layer, position)| (0..size).map(|row| letters[layer * size + row][position]).collect::<String>());
+
+    let pillars = (0..size).flat_map(|row| (0..size).map(move |position| (row, position)))
+        .map(|(row, position)| (0..size).map(|layer| letters[layer * size + row][position]).collect::<String>());
+
+    rows.iter().cloned().chain(columns).chain(pillars).collect()
+}
+
+#[test]
+fn build_cube_config() {
+
+    let args: Vec<String> = ["words.csv", "cubes.csv", "--limit", "3"].iter().map(|arg| arg.to_string()).collect();
+
+    assert_eq!(CubeConfig { dictionary_file_path: "words.csv".to_string(), destination_file_path: Some("cubes.csv".to_string()), solution_limit: Some(3) },
+        CubeConfig::build(&args).unwrap());
+
+    assert_eq!(None, CubeConfig::build(&["words.csv".to_string()]).unwrap().destination_file_path);
+    assert!(CubeConfig::build(&["words.csv".to_string(), "--limit".to_string(), "0".to_string()]).is_err());
+    assert!(CubeConfig::build(&[]).is_err());
+}
+
+#[test]
+fn every_line_of_a_cube_is_a_word() {
+
+    // the letter at each cell is the one as far along the alphabet as its coordinates add up to, so every line reads on
+    let dictionary: Vec<String> = ["abc", "bcd", "cde", "def", "efg"].iter().map(|word| word.to_string()).collect();
+    let prefix_map = PrefixArena::new(&dictionary).unwrap();
+
+    let solver = CubeSolver::new(&dictionary, prefix_map).unwrap();
+    let mut cubes: Vec<Vec<String>> = vec![];
+
+    let found = solver.solve(None, |rows| {
+        cubes.push(rows.to_vec());
+        Ok(())
+    }).unwrap();
+
+    assert_eq!(found, cubes.len());
+    assert!(cubes.contains(&["abc", "bcd", "cde", "bcd", "cde", "def", "cde", "def", "efg"].iter().map(|word| word.to_string()).collect()));
+
+    for cube in &cubes {
+        assert_eq!(27, cube_lines(cube).len());
+        assert!(cube_lines(cube).iter().all(|line| dictionary.contains(line)), "{:?}", cube);
+        assert!(first_of_its_axes(cube, 3));
+    }
+
+    assert_eq!(1, solver.solve(Some(1), |_| Ok(())).unwrap());
+}
+
+#[test]
+fn a_cube_is_kept_once_of_its_axes() {
+
+    // a cube of one word running one way has the same rows, columns and pillars however its axes go
+    let dictionary: Vec<String> = ["abc", "aaa", "bbb", "ccc"].iter().map(|word| word.to_string()).collect();
+    let prefix_map = PrefixArena::new(&dictionary).unwrap();
+
+    let mut cubes: Vec<String> = vec![];
+
+    CubeSolver::new(&dictionary, prefix_map).unwrap().solve(None, |rows| {
+        cubes.push(rows.join(","));
+        Ok(())
+    }).unwrap();
+
+    // abc running along the rows, columns or pillars is the same cube turned, along the pillars reads first
+    assert!(cubes.contains(&"aaa,aaa,aaa,bbb,bbb,bbb,ccc,ccc,ccc".to_string()));
+    assert!(!cubes.contains(&"aaa,bbb,ccc,aaa,bbb,ccc,aaa,bbb,ccc".to_string()));
+    assert!(!cubes.contains(&"abc,abc,abc,abc,abc,abc,abc,abc,abc".to_string()));
+}
@@ -0,0 +1,96 @@
+use chrono::{Datelike, NaiveDate};
+use serde::Serialize;
+use crate::solution_generator::construct_potential_transposed_puzzle;
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+/* the puzzle for a date along with what is needed to check it was picked fairly */
+#[derive(Debug, PartialEq, Serialize)]
+pub struct DailyPuzzle {
+    pub date: String,
+    pub solution_index: usize,
+    pub solution_count: usize,
+    pub dictionary_hash: String,
+    pub word_size: usize,
+    pub rows: Vec<String>,
+    pub columns: Vec<String>
+}
+
+/* FNV-1a, written out rather than using the std hasher whose output can change between
+  rust versions, which would change every daily puzzle */
+fn fnv1a(bytes: &[u8], hash: u64) -> u64 {
+    bytes.iter().fold(hash, |hash, byte| (hash ^ *byte as u64).wrapping_mul(FNV_PRIME))
+}
+
+/* a hash of the words in the dictionary, a different dictionary gives different puzzles */
+pub fn dictionary_hash(dictionary: &[String]) -> u64 {
+    dictionary.iter().fold(FNV_OFFSET_BASIS, |hash, word| fnv1a(b"\n", fnv1a(word.as_bytes(), hash)))
+}
+
+/* splitmix64, small and fully specified so the same seed picks the same puzzle everywhere */
+struct SplitMix64 {
+    state: u64
+}
+
+impl SplitMix64 {
+
+    fn next(&mut self) -> u64 {
+
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+}
+
+/* Picks the puzzle for a date from the solutions, the rng being seeded from the date and the
+  dictionary hash so everyone with the same solutions file and dictionary gets the same one.
+  The solutions have to be in the same order too, so generate them with --deterministic */
+pub fn pick_daily(solutions: &[Vec<String>], date: NaiveDate, dictionary_hash: u64) -> Option<DailyPuzzle> {
+
+    if solutions.is_empty() {
+        return None;
+    }
+
+    let seed = fnv1a(&date.num_days_from_ce().to_le_bytes(), fnv1a(&dictionary_hash.to_le_bytes(), FNV_OFFSET_BASIS));
+    let solution_index = (SplitMix64 { state: seed }.next() % solutions.len() as u64) as usize;
+
+    let rows = solutions[solution_index].clone();
+
+    Some(DailyPuzzle {
+        date: date.format("%Y-%m-%d").to_string(),
+        solution_index,
+        solution_count: solutions.len(),
+        dictionary_hash: format!("{:016x}", dictionary_hash),
+        word_size: rows.len(),
+        columns: construct_potential_transposed_puzzle(&rows),
+        rows
+    })
+}
+
+#[test]
+fn fnv1a_matches_the_reference() {
+    assert_eq!(0xaf63dc4c8601ec8c, fnv1a(b"a", FNV_OFFSET_BASIS));
+}
+
+#[test]
+fn daily_pick_is_stable() {
+
+    let solutions: Vec<Vec<String>> = (0..100).map(|i| vec![format!("{:02}", i), "xy".to_string()]).collect();
+    let date = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+
+    let puzzle = pick_daily(&solutions, date, 42).unwrap();
+
+    // pinned so a change to the rng or seeding, which would change everyone's puzzles, gets noticed
+    assert_eq!(0, puzzle.solution_index);
+    assert_eq!(84, pick_daily(&solutions, date.succ_opt().unwrap(), 42).unwrap().solution_index);
+    assert_eq!("58100c163f98d177", format!("{:016x}", dictionary_hash(&["ab".to_string(), "cd".to_string()])));
+
+    assert_eq!(vec!["00".to_string(), "xy".to_string()], puzzle.rows);
+    assert_eq!(vec!["0x".to_string(), "0y".to_string()], puzzle.columns);
+    assert_eq!(None, pick_daily(&[], date, 42));
+}
+
@@ -0,0 +1,85 @@
+use std::collections::HashSet;
+use std::io::{BufRead, Write};
+use crate::error::SquardleError;
+use crate::generator_config::Compression;
+use crate::output::{self, OutputWriter};
+use crate::solution_generator::construct_potential_transposed_puzzle;
+
+/* what deduplicating a solutions file found. Transposed duplicates are ones the
+  generator's symmetry breaking should have stopped so any at all means it missed some */
+#[derive(Debug, Default, PartialEq)]
+pub struct DedupCounts {
+    pub kept: usize,
+    pub exact_duplicates: usize,
+    pub transposed_duplicates: usize
+}
+
+/* Writes the solutions of the source file to the dest file keeping only the first of any
+  solutions that are the same or transposes of each other. Lines are kept as they were,
+  score and all, but are compared by their words only */
+pub fn dedup_solution_file(source_file_path: &str, dest_file_path: &str) -> Result<DedupCounts, SquardleError> {
+
+    let reader = output::open_solution_file(source_file_path)?;
+    let mut dest_file = OutputWriter::create(dest_file_path, Compression::from_file_path(dest_file_path))?;
+
+    let mut seen_solutions: HashSet<Vec<String>> = HashSet::new();
+    let mut counts = DedupCounts::default();
+
+    for line in reader.lines() {
+        let line = line?;
+        let rows = output::rows_of_line(&line);
+
+        if rows.is_empty() {
+            continue;
+        }
+
+        if seen_solutions.contains(&rows) {
+            counts.exact_duplicates += 1;
+            continue;
+        }
+
+        let columns = construct_potential_transposed_puzzle(&rows);
+
+        if seen_solutions.contains(&columns) {
+            counts.transposed_duplicates += 1;
+            continue;
+        }
+
+        writeln!(dest_file, "{}", line.trim())?;
+        seen_solutions.insert(rows);
+        counts.kept += 1;
+    }
+
+    dest_file.finish()?;
+
+    Ok(counts)
+}
+
+#[test]
+fn dedup_drops_exact_and_transposed_duplicates() {
+
+    std::fs::write("test_dedup_in.csv", "ab,cd\nac,bd\nab,cd,2.000\nef,gh\n").unwrap();
+
+    let counts = dedup_solution_file("test_dedup_in.csv", "test_dedup_out.csv").unwrap();
+    let deduped = std::fs::read_to_string("test_dedup_out.csv").unwrap();
+
+    for file_path in ["test_dedup_in.csv", "test_dedup_out.csv"] {
+        std::fs::remove_file(file_path).unwrap();
+    }
+
+    assert_eq!(DedupCounts { kept: 2, exact_duplicates: 1, transposed_duplicates: 1 }, counts);
+    assert_eq!("ab,cd\nef,gh\n", deduped);
+}
+
+#[test]
+fn dedup_in_place() {
+
+    std::fs::write("test_dedup_in_place.csv", "ab,cd\nab,cd\nef,gh\n").unwrap();
+
+    let counts = dedup_solution_file("test_dedup_in_place.csv", "test_dedup_in_place.csv").unwrap();
+    let deduped = std::fs::read_to_string("test_dedup_in_place.csv").unwrap();
+    std::fs::remove_file("test_dedup_in_place.csv").unwrap();
+
+    assert_eq!(2, counts.kept);
+    assert_eq!("ab,cd\nef,gh\n", deduped);
+}
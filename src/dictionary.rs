@@ -0,0 +1,537 @@
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{self, BufReader, Read};
+use std::ops::Deref;
+use std::sync::Arc;
+use memmap2::Mmap;
+use crate::constraint::{self, Constraint};
+use crate::error::SquardleError;
+use crate::generator_config::CsvDialect;
+use crate::prefix_arena::PrefixArena;
+use crate::prefix_index::PrefixIndex;
+use crate::word_list::WordList;
+
+// a dictionary file path of - reads the words from stdin instead
+pub const STDIN_FILE_PATH: &str = "-";
+
+/* the tags of each word of a tagged dictionary, animal and nature say */
+pub type WordTags = HashMap<String, Vec<String>>;
+
+pub static ALPHABET: [&str; 26] = [
+    "a", "b", "c", "d", "e", 
+    "f", "g", "h", "i", "j", 
+    "k", "l", "m", "n", "o",
+    "p", "q", "r", "s", "t", 
+    "u", "v", "w", "x", "y", 
+    "z",
+];
+
+/*
+    From the file of the file_path read in a csv file that contains a list of words for a dictionary
+    No Csv Headers 
+    Sorts the dictionary
+*/
+pub fn read_dictionary_from_file(file_path: &str) -> Result<Vec<String>, SquardleError> {
+    read_dictionary_with_dialect(file_path, &CsvDialect::default())
+}
+
+/* Same as read_dictionary_from_file for a csv file written in another dialect. A file is
+  memory mapped rather than read in, so there's no buffer of the whole file held next to the
+  words. The words are still copied out of the mapping into owned strings, the mapping is
+  gone once they're parsed, Dictionary::from_path keeps the mapping for the prefix map to borrow
+  its words from instead. Stdin and anything else that isn't a plain file, a pipe say, is still read in */
+pub fn read_dictionary_with_dialect(file_path: &str, csv_dialect: &CsvDialect) -> Result<Vec<String>, SquardleError> {
+    read_dictionary_bytes(file_path, |bytes| dictionary_from_csv(bytes, csv_dialect))
+}
+
+/* Same as read_dictionary_with_dialect for a tagged dictionary, see tagged_dictionary_from_csv */
+pub fn read_tagged_dictionary(file_path: &str, csv_dialect: &CsvDialect) -> Result<(Vec<String>, WordTags), SquardleError> {
+    read_dictionary_bytes(file_path, |bytes| tagged_dictionary_from_csv(bytes, csv_dialect))
+}
+
+fn read_dictionary_bytes<T, F>(file_path: &str, parse: F) -> Result<T, SquardleError>
+    where F: FnOnce(&[u8]) -> Result<T, SquardleError> {
+
+    let mut bytes: Vec<u8> = Vec::new();
+
+    if file_path == STDIN_FILE_PATH {
+        io::stdin().lock().read_to_end(&mut bytes)?;
+        return parse(&bytes);
+    }
+
+    let file = File::open(file_path)?;
+    let metadata = file.metadata()?;
+
+    if !metadata.is_file() {
+        BufReader::new(file).read_to_end(&mut bytes)?;
+        return parse(&bytes);
+    }
+
+    // an empty file can't be mapped, and has no words anyway
+    if metadata.len() == 0 {
+        return parse(&bytes);
+    }
+
+    // SAFETY: the file changing while it is mapped is undefined behaviour, a dictionary isn't written to while it is being loaded
+    let mapped = unsafe { Mmap::map(&file)? };
+
+    parse(&mapped)
+}
+
+/* The dictionary from the contents of a dictionary file, so it can be loaded without a file system
+  like in a browser. Words are comma separated over any number of lines and come back sorted */
+pub fn dictionary_from_bytes(bytes: &[u8]) -> Result<Vec<String>, SquardleError> {
+    dictionary_from_csv(bytes, &CsvDialect::default())
+}
+
+/* every field of every record is a word, so a list can have one word a line or many, quoted or not */
+pub fn dictionary_from_csv(bytes: &[u8], csv_dialect: &CsvDialect) -> Result<Vec<String>, SquardleError> {
+
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(csv_dialect.delimiter)
+        .has_headers(csv_dialect.dictionary_header)
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(bytes);
+
+    let mut dictionary: Vec<String> = vec![];
+
+    for record in reader.records() {
+        dictionary.extend(record?.iter().filter(|field| !field.is_empty()).map(str::to_lowercase));
+    }
+
+    dictionary.sort();
+
+    Ok(dictionary)
+}
+
+/* A dictionary with a word then its tags on each line e.g. cat,animal,pet, rather than every field
+  being a word. A word with no tags is still a word, and one on more than one line has the tags of
+  all of them. Tags are lowercased like the words */
+pub fn tagged_dictionary_from_csv(bytes: &[u8], csv_dialect: &CsvDialect) -> Result<(Vec<String>, WordTags), SquardleError> {
+
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(csv_dialect.delimiter)
+        .has_headers(csv_dialect.dictionary_header)
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(bytes);
+
+    let mut dictionary: Vec<String> = vec![];
+    let mut word_tags = WordTags::new();
+
+    for record in reader.records() {
+
+        let record = record?;
+        let mut fields = record.iter().filter(|field| !field.is_empty()).map(str::to_lowercase);
+
+        let Some(word) = fields.next() else {
+            continue;
+        };
+
+        for tag in fields {
+
+            let tags = word_tags.entry(word.clone()).or_default();
+
+            if !tags.contains(&tag) {
+                tags.push(tag);
+            }
+        }
+
+        dictionary.push(word);
+    }
+
+    // a word with its tags over more than one line is still one word
+    dictionary.sort();
+    dictionary.dedup();
+
+    Ok((dictionary, word_tags))
+}
+
+/* The sorted words squares are made from, and their tags when the dictionary was a tagged one.
+  Each filter hands back what is left of the dictionary so they chain one after another e.g.
+  Dictionary::from_path(path, &dialect)?.with_length(5).with_charset("abcde"). It derefs to the
+  words so it goes anywhere a slice of words does */
+#[derive(Debug, Clone, Default)]
+pub struct Dictionary {
+    words: Vec<String>,
+    word_tags: WordTags,
+    // only when read from a file, every word it had as spans of the mapped file for the prefix map to be built over
+    file_words: Option<WordList>
+}
+
+impl Dictionary {
+
+    /* The words of a dictionary file, or stdin for a file path of -, each once. The file stays mapped
+      so the prefix map can borrow its words from it rather than copying them again */
+    pub fn from_path(file_path: &str, csv_dialect: &CsvDialect) -> Result<Dictionary, SquardleError> {
+
+        let file_words = WordList::read(file_path, csv_dialect)?;
+
+        Ok(Dictionary { words: file_words.to_words(), word_tags: WordTags::new(), file_words: Some(file_words) })
+    }
+
+    /* same as from_path for a file with a word then its tags on each line */
+    pub fn from_tagged_path(file_path: &str, csv_dialect: &CsvDialect) -> Result<Dictionary, SquardleError> {
+        let (words, word_tags) = read_tagged_dictionary(file_path, csv_dialect)?;
+        Ok(Dictionary { words, word_tags, file_words: None })
+    }
+
+    /* the words of a dictionary read from anywhere, a socket or something unzipped say */
+    pub fn from_reader<R: Read>(mut reader: R, csv_dialect: &CsvDialect) -> Result<Dictionary, SquardleError> {
+
+        let mut bytes: Vec<u8> = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+
+        Ok(Dictionary::from(dictionary_from_csv(&bytes, csv_dialect)?))
+    }
+
+    /* lowercased and sorted the same as the words of a file */
+    pub fn from_words<I, S>(words: I) -> Dictionary
+        where I: IntoIterator<Item = S>, S: AsRef<str> {
+
+        let mut words: Vec<String> = words.into_iter().map(|word| word.as_ref().trim().to_lowercase()).filter(|word| !word.is_empty()).collect();
+        words.sort();
+
+        Dictionary::from(words)
+    }
+
+    /* only the words this many letters long */
+    pub fn with_length(mut self, length: usize) -> Dictionary {
+        self.words.retain(|word| word.chars().count() == length);
+        self
+    }
+
+    /* only the words made of nothing but these letters */
+    pub fn with_charset(mut self, letters: &str) -> Dictionary {
+
+        let letters: HashSet<char> = letters.to_lowercase().chars().collect();
+        self.words.retain(|word| word.chars().all(|letter| letters.contains(&letter)));
+
+        self
+    }
+
+    /* none of the words of the blocklist, rude words say */
+    pub fn without_words<I, S>(mut self, blocklist: I) -> Dictionary
+        where I: IntoIterator<Item = S>, S: AsRef<str> {
+
+        let blocklist: HashSet<String> = blocklist.into_iter().map(|word| word.as_ref().trim().to_lowercase()).collect();
+        self.words.retain(|word| !blocklist.contains(word));
+
+        self
+    }
+
+    /* only the words that could be in a square the constraints allow */
+    pub fn with_constraints(mut self, constraints: &[Arc<dyn Constraint>]) -> Dictionary {
+        constraint::filter_dictionary(constraints, &mut self.words);
+        self
+    }
+
+    pub fn words(&self) -> &[String] {
+        &self.words
+    }
+
+    pub fn word_tags(&self) -> &WordTags {
+        &self.word_tags
+    }
+
+    /* how many letters long the words are going by the first, none for an empty dictionary */
+    pub fn word_size(&self) -> Option<usize> {
+        self.words.first().map(|word| word.chars().count())
+    }
+
+    pub fn contains(&self, word: &str) -> bool {
+        self.words.binary_search_by(|known| known.as_str().cmp(word)).is_ok()
+    }
+
+    /* The words starting with each prefix that the search looks the next row up in. Over the file's
+      words when it was read from one, less any the filters have taken out since */
+    pub fn prefix_map(&self) -> Result<PrefixArena, SquardleError> {
+
+        let Some(file_words) = &self.file_words else {
+            return PrefixArena::new(&self.words);
+        };
+
+        let mut words = file_words.clone();
+        words.retain(|word| self.contains(word));
+
+        PrefixArena::from_word_list(words)
+    }
+
+    /* the words looked up by any start of them, see PrefixIndex */
+    pub fn prefix_index(&self) -> PrefixIndex {
+        PrefixIndex::new(self)
+    }
+
+    /* adds the tags of the words to ones from another dictionary, a word can be tagged in both */
+    pub fn merge_tags_into(&self, word_tags: &mut WordTags) {
+
+        for (word, tags) in &self.word_tags {
+            let known_tags = word_tags.entry(word.clone()).or_default();
+
+            for tag in tags {
+                if !known_tags.contains(tag) {
+                    known_tags.push(tag.clone());
+                }
+            }
+        }
+    }
+
+    pub fn into_words(self) -> Vec<String> {
+        self.words
+    }
+}
+
+/* the same words and tags, wherever they were read from */
+impl PartialEq for Dictionary {
+    fn eq(&self, other: &Dictionary) -> bool {
+        self.words == other.words && self.word_tags == other.word_tags
+    }
+}
+
+impl Eq for Dictionary {}
+
+/* the words are expected sorted like they are coming out of a dictionary file */
+impl From<Vec<String>> for Dictionary {
+    fn from(words: Vec<String>) -> Dictionary {
+        Dictionary { words, word_tags: WordTags::new(), file_words: None }
+    }
+}
+
+impl Deref for Dictionary {
+    type Target = [String];
+
+    fn deref(&self) -> &[String] {
+        &self.words
+    }
+}
+
+/* Generates a hashmap vectors containing words that correspond to a greatest common denomiator of substring.
+  Will do this for all positions in a string besides the first as isnt needed */
+pub fn generate_starts_that_have_words(dictionary: &[String]) -> Result<HashMap<String, Vec<String>>, SquardleError> {
+
+    let word_size = match dictionary.first() {
+        Some(word) => word.chars().count(),
+        None => return Err(SquardleError::EmptyDictionary)
+    };
+
+    // dont need this hashmap this is just used to "seed" the actual hashmap
+    let mut starts_word_map: HashMap<String, Vec<String>> = HashMap::new();
+
+    for letter in ALPHABET {
+        for word in dictionary {
+
+            if word.len() != word_size {
+                return Err(SquardleError::IncorrectWordSize { word: word.clone(), expected: word_size, found: word.len() })
+            }
+
+            if word.starts_with(letter) {
+                let words_that_start_with = starts_word_map.entry(letter.to_owned()).or_default();
+                words_that_start_with.push(word.to_string());
+            }
+        }
+    }
+
+    for i in 1..word_size {
+
+        let mut new_starts_map :HashMap<String, Vec<String>> = HashMap::new();
+
+        // make new key next letter down in words
+        for letter in ALPHABET {
+
+            for prev_start in starts_word_map.keys() {
+
+                //only care about the previously added keys
+                if prev_start.len() != i {
+                    continue;
+                }
+
+                let new_start = format!("{}{}", prev_start, letter);
+
+                let words = starts_word_map.get(prev_start).expect("should always get a value");
+
+                for word in words {
+
+                    if !word.starts_with(&new_start) {
+                        continue;
+                    }
+
+                    let new_start_list = new_starts_map.entry(new_start.clone()).or_default();
+                    new_start_list.push(word.to_string());
+                }
+            }
+        }
+
+        starts_word_map.extend(new_starts_map);
+    }
+
+    for letter in ALPHABET {
+        starts_word_map.remove(letter);
+    }
+
+    Ok(starts_word_map)
+}
+
+/* Each solution as the positions of its rows in the sorted dictionary, a flat row of numbers per
+  solution that analysis tools can hold as an array far more cheaply than the words themselves */
+pub fn solution_word_indices(solutions: &[Vec<String>], dictionary: &[String]) -> Result<Vec<Vec<u32>>, SquardleError> {
+
+    solutions.iter().map(|rows| rows.iter().map(|word| match dictionary.binary_search(word) {
+        Ok(index) => Ok(index as u32),
+        Err(_) => Err(format!("[{}] is not in the dictionary", word).as_str().into())
+    }).collect()).collect()
+}
+
+/* A dictionary small enough to know its squares by heart, for the tests of anything that searches.
+  ten,ore,eat and ten,ore,rat are its only squares, toe,era,net and tor,era,net being their transposes.
+  The prefix map is the arena the search builds from it */
+#[cfg(test)]
+pub fn test_dictionary() -> (Vec<String>, PrefixArena) {
+
+    let dictionary: Vec<String> = ["eat", "era", "net", "ore", "rat", "ten", "toe", "tor"].iter().map(|word| word.to_string()).collect();
+    let prefix_map = PrefixArena::new(&dictionary).unwrap();
+
+    (dictionary, prefix_map)
+}
+
+/* the same words as a dictionary file, for the language bindings that load one from its bytes */
+#[cfg(test)]
+pub fn test_dictionary_file() -> Vec<u8> {
+    test_dictionary().0.join("\n").into_bytes()
+}
+
+#[test]
+fn generate_starts() {
+
+    let dictionary = vec!["based".to_string(), "bases".to_string(), "bassy".to_string(), "baton".to_string(), "belly".to_string(), "elses".to_string() ];
+
+    let starts_generated = generate_starts_that_have_words(&dictionary).unwrap();
+
+    let starts_excpected = HashMap::from([
+        ("ba".to_string(), vec!["based".to_string(), "bases".to_string(), "bassy".to_string(), "baton".to_string()]),
+        ("bas".to_string(), vec!["based".to_string(), "bases".to_string(), "bassy".to_string()]),
+        ("base".to_string(), vec!["based".to_string(), "bases".to_string()]),
+        ("based".to_string(), vec!["based".to_string()]),
+        ("bases".to_string(), vec!["bases".to_string()]),
+        ("bass".to_string(), vec!["bassy".to_string()]),
+        ("bassy".to_string(), vec!["bassy".to_string()]),
+        ("bat".to_string(), vec!["baton".to_string()]),
+        ("bato".to_string(), vec!["baton".to_string()]),
+        ("baton".to_string(), vec!["baton".to_string()]),
+        ("be".to_string(), vec!["belly".to_string()]),
+        ("bel".to_string(), vec!["belly".to_string()]),
+        ("bell".to_string(), vec!["belly".to_string()]),
+        ("belly".to_string(), vec!["belly".to_string()]),
+        ("el".to_string(), vec!["elses".to_string()]),
+        ("els".to_string(), vec!["elses".to_string()]),
+        ("else".to_string(), vec!["elses".to_string()]),
+        ("elses".to_string(), vec!["elses".to_string()]),
+        ]);
+
+    for key in starts_excpected.keys() {
+
+        assert!(starts_excpected.contains_key(key));
+        assert!(starts_generated.contains_key(key));
+
+        let vec1 = starts_excpected.get(key).unwrap();
+        let vec2 = starts_generated.get(key).unwrap();
+
+        assert_eq!(vec1.len(), vec2.len());
+
+        assert_eq!(vec1, vec2);
+    }
+
+}
+
+#[test]
+fn dictionary_loads_from_bytes() {
+
+    let dictionary = dictionary_from_bytes(b"Heart,about\r\n\nzesty,,\nbadge").unwrap();
+
+    assert_eq!(vec!["about", "badge", "heart", "zesty"], dictionary);
+    assert!(dictionary_from_bytes(b"").unwrap().is_empty());
+}
+
+#[test]
+fn dictionary_loads_from_a_mapped_file() {
+
+    let file_path = "test_mapped_dictionary.csv";
+
+    std::fs::write(file_path, "Heart,about\nzesty").unwrap();
+    let dictionary = read_dictionary_from_file(file_path).unwrap();
+
+    std::fs::write(file_path, "").unwrap();
+    let empty_dictionary = read_dictionary_from_file(file_path).unwrap();
+
+    std::fs::remove_file(file_path).unwrap();
+
+    assert_eq!(vec!["about", "heart", "zesty"], dictionary);
+    assert!(empty_dictionary.is_empty());
+}
+
+#[test]
+fn dictionary_loads_in_another_dialect() {
+
+    let csv_dialect = CsvDialect { delimiter: b'\t', dictionary_header: true, output_header: false };
+    let dictionary = dictionary_from_csv(b"word\tnotes\nHeart\t\"about\"\nzesty", &csv_dialect).unwrap();
+
+    assert_eq!(vec!["about", "heart", "zesty"], dictionary);
+}
+
+#[test]
+fn tagged_dictionary_has_a_word_then_its_tags() {
+
+    let (dictionary, word_tags) = tagged_dictionary_from_csv(b"cat,Animal,pet\nbat,animal\nhat\ncat,pet,nature\n", &CsvDialect::default()).unwrap();
+
+    assert_eq!(vec!["bat", "cat", "hat"], dictionary);
+    assert_eq!(Some(&vec!["animal".to_string(), "pet".to_string(), "nature".to_string()]), word_tags.get("cat"));
+    assert_eq!(None, word_tags.get("hat"));
+}
+
+#[test]
+fn generate_starts_empty_dictionary() {
+
+    let dictionary: Vec<String> = Vec::new();
+
+    let starts = generate_starts_that_have_words(&dictionary);
+
+    assert!(matches!(starts.unwrap_err(), SquardleError::EmptyDictionary));
+}
+
+#[test]
+fn generate_starts_has_incorrect_sized_word() {
+
+    let s1:String = "abcdefg".to_string();
+    let s2:String = "hijklmno".to_string();
+
+    let dictionary = vec![s1.clone(), s2.clone()];
+
+    let starts = generate_starts_that_have_words(&dictionary);
+
+    assert_eq!(format!("word [{}] has incorrect size needed {} found {}", s2, s1.len(), s2.len()), starts.unwrap_err().to_string());
+}
+
+#[test]
+fn solutions_as_word_indices() {
+
+    let dictionary = dictionary_from_bytes(b"ten,ore,eat,toe,era,net").unwrap();
+    let solutions = vec![vec!["ten".to_string(), "ore".to_string(), "eat".to_string()]];
+
+    assert_eq!(vec![vec![4, 3, 0]], solution_word_indices(&solutions, &dictionary).unwrap());
+    assert!(solution_word_indices(&[vec!["zzz".to_string()]], &dictionary).is_err());
+}
+
+#[test]
+fn dictionary_filters_chain() {
+
+    let dictionary = Dictionary::from_reader(&b"Heart,about\nzesty,cab\nbadge,abbed,bead"[..], &CsvDialect::default()).unwrap();
+
+    assert_eq!(Some(5), dictionary.word_size());
+    assert!(dictionary.contains("zesty") && !dictionary.contains("Zesty"));
+
+    let filtered = dictionary.with_length(5).with_charset("ABCDEG").without_words(["Abbed"]);
+
+    assert_eq!(["badge"], filtered.words());
+    assert_eq!(Dictionary::from_words(["BADGE"]), filtered);
+    assert!(filtered.with_charset("xyz").is_empty());
+}
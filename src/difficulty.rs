@@ -0,0 +1,247 @@
+use std::io::{BufRead, Write};
+use log::debug;
+use serde::{Deserialize, Serialize};
+use crate::error::SquardleError;
+use crate::generator_config::{Compression, CsvDialect, OutputFormat};
+use crate::output::{self, OutputWriter};
+use crate::prefix_arena::PrefixArena;
+use crate::scoring::Scorer;
+use crate::solution_generator::{construct_potential_transposed_puzzle, letter_index, parse_partial_grid, GridSolver};
+
+// a square that takes the solver this many backtracks (as a power of 10) or more is as hard as it gets
+const MAX_EFFORT_LOG10: f64 = 5.0;
+// about a third of the squares of the full bundled dictionary fall in each band
+const EASY_BELOW: f64 = 70.0;
+const MEDIUM_BELOW: f64 = 74.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard
+}
+
+impl Difficulty {
+
+    pub fn from_score(score: f64) -> Difficulty {
+        if score < EASY_BELOW {
+            Difficulty::Easy
+        } else if score < MEDIUM_BELOW {
+            Difficulty::Medium
+        } else {
+            Difficulty::Hard
+        }
+    }
+}
+
+/* How hard a square is to guess. Every part runs from 0 (easy) to 1 (hard) and the total,
+  from 0 to 100, is their average. Frequency is left out without frequency data */
+#[derive(Debug, Clone, PartialEq)]
+pub struct DifficultyScore {
+    // how uncommon the words are
+    pub frequency: Option<f64>,
+    // how uncommon the letters are, a half being as rare as the average letter of the dictionary
+    pub letter_rarity: f64,
+    // how many words are a letter away from each row and column, ones a guess could be mistaken for
+    pub alternatives: f64,
+    // how much backtracking the solver does to fill the square from its first row
+    pub effort: f64,
+    pub total: f64
+}
+
+impl DifficultyScore {
+    pub fn difficulty(&self) -> Difficulty {
+        Difficulty::from_score(self.total)
+    }
+}
+
+pub struct DifficultyRater {
+    solver: GridSolver,
+    words: Vec<String>,
+    letter_rarity: [f64; 26],
+    // the rarity of the average letter of the dictionary
+    mean_rarity: f64,
+    scorer: Option<Scorer>
+}
+
+impl DifficultyRater {
+
+    pub fn new(dictionary: Vec<String>, prefix_map: PrefixArena, scorer: Option<Scorer>) -> Result<DifficultyRater, SquardleError> {
+
+        let mut letter_counts = [0u64; 26];
+
+        for index in dictionary.iter().flat_map(|word| word.chars()).filter_map(letter_index) {
+            letter_counts[index] += 1;
+        }
+
+        let total_letters = letter_counts.iter().sum::<u64>().max(1) as f64;
+        let mut letter_rarity = [0.0; 26];
+        let mut mean_rarity = 0.0;
+
+        for (rarity, count) in letter_rarity.iter_mut().zip(letter_counts) {
+            if count > 0 {
+                let share = count as f64 / total_letters;
+                *rarity = -share.ln();
+                mean_rarity += share * *rarity;
+            }
+        }
+
+        Ok(DifficultyRater {
+            words: dictionary.clone(),
+            solver: GridSolver::new(dictionary, prefix_map)?,
+            letter_rarity,
+            mean_rarity,
+            scorer
+        })
+    }
+
+    pub fn rate(&mut self, rows: &[String]) -> Result<DifficultyScore, SquardleError> {
+
+        let columns = construct_potential_transposed_puzzle(rows);
+        let word_count = (rows.len() + columns.len()) as f64;
+
+        let frequency = self.scorer.as_ref().map(|scorer| {
+            let max_log_frequency = scorer.max_log_frequency();
+            let mean_log_frequency = rows.iter().chain(columns.iter()).map(|word| scorer.log_frequency(word)).sum::<f64>() / word_count;
+
+            if max_log_frequency > 0.0 { 1.0 - mean_log_frequency / max_log_frequency } else { 0.0 }
+        });
+
+        let letters: Vec<usize> = rows.iter().flat_map(|row| row.chars()).filter_map(letter_index).collect();
+        let square_rarity = letters.iter().map(|index| self.letter_rarity[*index]).sum::<f64>() / letters.len().max(1) as f64;
+        let letter_rarity = if self.mean_rarity > 0.0 { (square_rarity / self.mean_rarity / 2.0).min(1.0) } else { 0.0 };
+
+        let neighbours_per_line = rows.iter().chain(columns.iter()).map(|line| self.neighbour_count(line)).sum::<usize>() as f64 / word_count;
+        let alternatives = neighbours_per_line / (1.0 + neighbours_per_line);
+
+        let first_row = parse_partial_grid(&rows[0], self.solver.word_size())?;
+        let (_, stats) = self.solver.solve(first_row, None)?;
+        let effort = ((stats.backtracks as f64 + 1.0).log10() / MAX_EFFORT_LOG10).min(1.0);
+
+        let parts: Vec<f64> = [frequency, Some(letter_rarity), Some(alternatives), Some(effort)].into_iter().flatten().collect();
+        let total = parts.iter().sum::<f64>() / parts.len() as f64 * 100.0;
+
+        Ok(DifficultyScore { frequency, letter_rarity, alternatives, effort, total })
+    }
+
+    /* the number of dictionary words that differ from the line by exactly one letter */
+    fn neighbour_count(&self, line: &str) -> usize {
+
+        self.words.iter()
+            .filter(|word| word.len() == line.len() && word.chars().zip(line.chars()).filter(|(a, b)| a != b).count() == 1)
+            .count()
+    }
+}
+
+/* rate source_file dictionary_file dest_file with optional --frequencies file and --difficulty easy|medium|hard */
+#[derive(Debug, Default, PartialEq)]
+pub struct RateConfig {
+    pub source_file_path: String,
+    pub dictionary_file_path: String,
+    pub dest_file_path: String,
+    pub frequencies_file_path: Option<String>,
+    pub difficulty: Option<Difficulty>
+}
+
+impl RateConfig {
+
+    pub fn build(args: &[String]) -> Result<RateConfig, SquardleError> {
+
+        let mut config = RateConfig::default();
+        let mut positional_args: Vec<String> = Vec::with_capacity(3);
+        let mut args_iter = args.iter();
+
+        while let Some(arg) = args_iter.next() {
+
+            match arg.as_str() {
+                "--frequencies" => match args_iter.next() {
+                    Some(file_path) => config.frequencies_file_path = Some(file_path.clone()),
+                    None => return Err("Frequencies flag needs a file path".into())
+                },
+                "--difficulty" => config.difficulty = match args_iter.next().map(String::as_str) {
+                    Some("easy") => Some(Difficulty::Easy),
+                    Some("medium") => Some(Difficulty::Medium),
+                    Some("hard") => Some(Difficulty::Hard),
+                    _ => return Err("Difficulty must be one of: easy, medium, hard".into())
+                },
+                _ => positional_args.push(arg.clone())
+            }
+        }
+
+        if positional_args.len() != 3 {
+            return Err("rate accepts a solutions file path, a dictionary file path, a destination file path and optional flags: --frequencies file path, --difficulty easy|medium|hard".into());
+        }
+
+        config.dest_file_path = positional_args.pop().expect("there are three positional args");
+        config.dictionary_file_path = positional_args.pop().expect("there are three positional args");
+        config.source_file_path = positional_args.pop().expect("there are three positional args");
+
+        Ok(config)
+    }
+}
+
+/* Writes each solution of the source file with its difficulty score after it, only the ones
+  of the wanted difficulty if there is one. Returns the number of solutions kept and dropped */
+pub fn rate_solution_file(rater: &mut DifficultyRater, source_file_path: &str, dest_file_path: &str, wanted: Option<Difficulty>) -> Result<(usize, usize), SquardleError> {
+
+    let reader = output::open_solution_file(source_file_path)?;
+    let mut dest_file = OutputWriter::create(dest_file_path, Compression::from_file_path(dest_file_path))?;
+
+    let mut kept_count = 0;
+    let mut dropped_count = 0;
+
+    for line in reader.lines() {
+        let rows = output::rows_of_line(&line?);
+
+        if rows.is_empty() {
+            continue;
+        }
+
+        let score = rater.rate(&rows)?;
+        debug!("{:?} {:?}", rows, score);
+
+        if wanted.is_some_and(|wanted| wanted != score.difficulty()) {
+            dropped_count += 1;
+            continue;
+        }
+
+        writeln!(dest_file, "{}", output::format_solution(&rows, Some(score.total), &OutputFormat::Csv, &CsvDialect::default())?)?;
+        kept_count += 1;
+    }
+
+    dest_file.finish()?;
+
+    Ok((kept_count, dropped_count))
+}
+
+#[test]
+fn rate_a_square() {
+
+    let (dictionary, prefix_map) = crate::dictionary::test_dictionary();
+
+    let mut rater = DifficultyRater::new(dictionary, prefix_map, None).unwrap();
+    let score = rater.rate(&["ten".to_string(), "ore".to_string(), "eat".to_string()]).unwrap();
+
+    // of the six lines only eat and toe have a word a letter away, rat and tor, a third per line scales to a quarter
+    assert!((score.alternatives - 1.0 / 4.0).abs() < 1e-9);
+    assert_eq!(None, score.frequency);
+    assert!(score.effort > 0.0 && score.effort <= 1.0);
+    assert!(score.total > 0.0 && score.total <= 100.0);
+}
+
+#[test]
+fn build_rate_config() {
+
+    let args: Vec<String> = ["in.csv", "--difficulty", "hard", "words.csv", "out.csv"].iter().map(|arg| arg.to_string()).collect();
+
+    let config = RateConfig::build(&args).unwrap();
+
+    assert_eq!("in.csv", config.source_file_path);
+    assert_eq!("words.csv", config.dictionary_file_path);
+    assert_eq!("out.csv", config.dest_file_path);
+    assert_eq!(Some(Difficulty::Hard), config.difficulty);
+
+    assert!(RateConfig::build(&args[..3]).is_err());
+    assert_eq!(Difficulty::Medium, Difficulty::from_score(72.0));
+}
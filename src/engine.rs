@@ -0,0 +1,101 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use crate::error::SquardleError;
+use crate::generator_config::{Engine, GeneratorBackend, GeneratorConfig};
+use crate::prefix_arena::PrefixArena;
+use crate::solution_generator::{GeneratorPool, SolutionGeneratorNaivePool, SolutionGeneratorRayonPool, SolutionGeneratorThreadPool};
+
+/* What generate finds the squares with, picked by --engine. Every engine hands its squares back
+  through a GeneratorPool so the output, checkpoints and run stats are the same whichever runs,
+  and a new one can be timed and checked against the others without changing anything else */
+pub trait SolverEngine: Sync {
+
+    /* the name --engine picks it by */
+    fn name(&self) -> &'static str;
+
+    /* starts looking for the squares of the dictionary, the prefix map being the starts of the column words */
+    fn start(&self, config: &GeneratorConfig, dictionary: &[String], prefix_map: PrefixArena,
+        stop_flag: Arc<AtomicBool>, skipped_seeds: HashSet<String>) -> Result<Box<dyn GeneratorPool>, SquardleError>;
+}
+
+/* the row by row search over the prefix map, on the thread pool or rayon as the backend says */
+pub struct PrefixMapEngine;
+
+impl SolverEngine for PrefixMapEngine {
+
+    fn name(&self) -> &'static str {
+        "prefix-map"
+    }
+
+    fn start(&self, config: &GeneratorConfig, dictionary: &[String], prefix_map: PrefixArena,
+        stop_flag: Arc<AtomicBool>, skipped_seeds: HashSet<String>) -> Result<Box<dyn GeneratorPool>, SquardleError> {
+
+        Ok(match config.backend {
+            GeneratorBackend::ThreadPool => Box::new(SolutionGeneratorThreadPool::new(config, dictionary, prefix_map, stop_flag, skipped_seeds)?),
+            GeneratorBackend::Rayon => Box::new(SolutionGeneratorRayonPool::new(config, dictionary, prefix_map, stop_flag, skipped_seeds)?)
+        })
+    }
+}
+
+/* the brute force, every word in every row, which doesn't need the prefix map */
+pub struct NaiveEngine;
+
+impl SolverEngine for NaiveEngine {
+
+    fn name(&self) -> &'static str {
+        "naive"
+    }
+
+    fn start(&self, config: &GeneratorConfig, dictionary: &[String], _prefix_map: PrefixArena,
+        stop_flag: Arc<AtomicBool>, skipped_seeds: HashSet<String>) -> Result<Box<dyn GeneratorPool>, SquardleError> {
+
+        Ok(Box::new(SolutionGeneratorNaivePool::new(config, dictionary, stop_flag, skipped_seeds)?))
+    }
+}
+
+/* the engine the config picked */
+pub fn solver_engine(engine: Engine) -> &'static dyn SolverEngine {
+
+    match engine {
+        Engine::PrefixMap => &PrefixMapEngine,
+        Engine::Naive => &NaiveEngine
+    }
+}
+
+#[test]
+fn engines_are_picked_by_name() {
+
+    for (name, engine) in [("prefix-map", Engine::PrefixMap), ("naive", Engine::Naive)] {
+
+        let args: Vec<String> = ["exec name", "words.csv", "--engine", name].iter().map(|arg| arg.to_string()).collect();
+
+        assert_eq!(engine, GeneratorConfig::build(&args).unwrap().engine);
+        assert_eq!(name, solver_engine(engine).name());
+    }
+}
+
+#[test]
+fn engines_find_the_same_squares() {
+
+    let dictionary: Vec<String> = ["eat", "era", "net", "ore", "rat", "tea", "ten", "toe", "tor", "tot"].iter().map(|word| word.to_string()).collect();
+    let prefix_map = PrefixArena::new(&dictionary).unwrap();
+
+    let squares_of = |flags: &[&str]| {
+        let args: Vec<String> = ["exec name", "words.csv", "out.csv"].iter().chain(flags).map(|arg| arg.to_string()).collect();
+        let config = GeneratorConfig::build(&args).unwrap();
+
+        let mut pool = solver_engine(config.engine).start(&config, &dictionary, prefix_map.clone(), Arc::new(AtomicBool::new(false)), HashSet::new()).unwrap();
+        let mut squares: Vec<crate::solution::Solution> = pool.solution_receiver().iter().collect();
+        pool.join();
+
+        squares.sort();
+        squares
+    };
+
+    let naive = squares_of(&["--engine", "naive"]);
+
+    assert_eq!(2, naive.len());
+    assert_eq!(naive, squares_of(&[]));
+    assert_eq!(naive, squares_of(&["--backend", "rayon"]));
+}
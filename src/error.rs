@@ -0,0 +1,57 @@
+use std::io;
+use thiserror::Error;
+use crate::solution_generator::GeneratorError;
+
+/* Every error the crate can hand back, from parsing the arguments through loading
+  the dictionary, running the generator and writing out the solutions */
+#[derive(Debug, Error)]
+pub enum SquardleError {
+    #[error("{0}")]
+    InvalidArgument(String),
+
+    #[error("Dictionary is empty")]
+    EmptyDictionary,
+
+    #[error("word [{word}] has incorrect size needed {expected} found {found}")]
+    IncorrectWordSize { word: String, expected: usize, found: usize },
+
+    #[error("Number of threads is less than or equal to 0")]
+    ZeroSizedPool,
+
+    #[error("Prefix map is empty")]
+    EmptyPrefixMap,
+
+    #[error("Could not build the thread pool: {0}")]
+    ThreadPoolBuild(String),
+
+    #[error(transparent)]
+    Generator(#[from] GeneratorError),
+
+    #[error("Checkpoint was made with dictionary {checkpoint_dictionary} not {dictionary}")]
+    CheckpointMismatch { checkpoint_dictionary: String, dictionary: String },
+
+    #[error(transparent)]
+    Io(#[from] io::Error),
+
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+
+    #[error(transparent)]
+    Toml(#[from] toml::de::Error),
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[error(transparent)]
+    Sqlite(#[from] rusqlite::Error),
+
+    #[error(transparent)]
+    Csv(#[from] csv::Error),
+
+    #[error(transparent)]
+    Bincode(#[from] bincode::Error)
+}
+
+impl From<&str> for SquardleError {
+    fn from(message: &str) -> SquardleError {
+        SquardleError::InvalidArgument(message.to_string())
+    }
+}
@@ -0,0 +1,73 @@
+use std::fmt;
+use crate::error::SquardleError;
+use crate::solution_generator::SolutionCountEstimate;
+
+const DEFAULT_PROBES: usize = 10_000;
+
+// about 95% of the time the true count is within this many standard errors of the estimate
+const CONFIDENCE_STANDARD_ERRORS: f64 = 1.96;
+
+/* estimate dictionary_file with optional --probes n, --seed n and --json */
+#[derive(Debug, PartialEq)]
+pub struct EstimateConfig {
+    pub dictionary_file_path: String,
+    pub probes: usize,
+    pub seed: Option<u64>,
+    pub json: bool
+}
+
+impl EstimateConfig {
+
+    pub fn build(args: &[String]) -> Result<EstimateConfig, SquardleError> {
+
+        let mut probes = DEFAULT_PROBES;
+        let mut seed: Option<u64> = None;
+        let mut json = false;
+        let mut positional_args: Vec<String> = Vec::with_capacity(1);
+        let mut args_iter = args.iter();
+
+        while let Some(arg) = args_iter.next() {
+
+            match arg.as_str() {
+                "--probes" => probes = match args_iter.next().map(|value| value.parse::<usize>()) {
+                    Some(Ok(probes)) if probes > 1 => probes,
+                    _ => return Err("Probes must be a number greater than 1".into())
+                },
+                "--seed" => seed = match args_iter.next().map(|value| value.parse::<u64>()) {
+                    Some(Ok(seed)) => Some(seed),
+                    _ => return Err("Seed must be a whole number".into())
+                },
+                "--json" => json = true,
+                _ => positional_args.push(arg.clone())
+            }
+        }
+
+        let [dictionary_file_path]: [String; 1] = positional_args.try_into()
+            .map_err(|_| SquardleError::from("estimate accepts a dictionary file path and optionally --probes n, --seed n and --json"))?;
+
+        Ok(EstimateConfig { dictionary_file_path, probes, seed, json })
+    }
+}
+
+impl fmt::Display for SolutionCountEstimate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+
+        let margin = CONFIDENCE_STANDARD_ERRORS * self.standard_error;
+
+        writeln!(f, "estimated solutions: {:.0}", self.solutions)?;
+        writeln!(f, "95% interval: {:.0} to {:.0}", (self.solutions - margin).max(0.0), self.solutions + margin)?;
+        write!(f, "probes reaching a solution: {} of {}", self.probes_reaching_a_solution, self.probes)
+    }
+}
+
+#[test]
+fn build_estimate_config() {
+
+    let args: Vec<String> = ["words.csv", "--probes", "500", "--seed", "9"].iter().map(|arg| arg.to_string()).collect();
+
+    assert_eq!(EstimateConfig { dictionary_file_path: "words.csv".to_string(), probes: 500, seed: Some(9), json: false }, EstimateConfig::build(&args).unwrap());
+
+    let args: Vec<String> = ["words.csv", "--probes", "1"].iter().map(|arg| arg.to_string()).collect();
+
+    assert!(EstimateConfig::build(&args).is_err());
+}
@@ -0,0 +1,126 @@
+use std::fmt::Write;
+use serde::Serialize;
+
+/* Why a seed word's search found what it did, every candidate tried at each row and what became
+  of it. Rows and columns count from 0 the way hidden cells in an exported puzzle do */
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Explanation {
+    pub seed: String,
+    // a seed the constraints turn down isn't searched at all
+    pub seed_allowed: bool,
+    pub solutions: usize,
+    pub steps: Vec<Step>
+}
+
+/* what happened to one candidate for a row */
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(tag = "outcome", rename_all = "snake_case")]
+pub enum Step {
+    // every column still starts a word so the search goes down to the next row, and backs out again once below is done
+    Fits { row: usize, word: String, solutions: usize, below: Vec<Step> },
+    // the last row with every column a word
+    Solution { row: usize, word: String },
+    // already one of the rows above
+    Used { row: usize, word: String },
+    // a constraint turned the word, or the square it finished, down
+    Constraint { row: usize, word: String },
+    // the column's start isn't the start of any word, and nor is it for the words after this one starting the same way which are skipped
+    BadStart { row: usize, word: String, column: usize, start: String, skipped: usize },
+    // the last row makes a column that isn't a word
+    NotAWord { row: usize, word: String, column: usize, column_word: String },
+    // the last row makes a column that is in the square already, as a row, the last row itself or a column before it
+    ColumnUsed { row: usize, word: String, column: usize, column_word: String },
+    // the first column reads before the first row, so the square is the transpose of one found from another seed
+    Transpose { row: usize, word: String }
+}
+
+impl Step {
+
+    /* the squares this candidate finished, itself or further down */
+    pub fn solutions(&self) -> usize {
+        match self {
+            Step::Fits { solutions, .. } => *solutions,
+            Step::Solution { .. } => 1,
+            _ => 0
+        }
+    }
+
+    fn row(&self) -> usize {
+        match self {
+            Step::Fits { row, .. } | Step::Solution { row, .. } | Step::Used { row, .. } | Step::Constraint { row, .. }
+                | Step::BadStart { row, .. } | Step::NotAWord { row, .. } | Step::ColumnUsed { row, .. } | Step::Transpose { row, .. } => *row
+        }
+    }
+}
+
+/* the steps indented by row, a line each */
+pub fn explanation_as_text(explanation: &Explanation) -> String {
+
+    let mut text = String::new();
+
+    match explanation.seed_allowed {
+        true => writeln!(text, "{} in row 0 makes {} squares", explanation.seed, explanation.solutions),
+        false => writeln!(text, "{} in row 0 is turned down by a constraint so isn't searched", explanation.seed)
+    }.expect("writing to a string");
+
+    write_steps(&mut text, &explanation.steps);
+
+    text
+}
+
+fn write_steps(text: &mut String, steps: &[Step]) {
+
+    for step in steps {
+
+        let indent = "  ".repeat(step.row());
+
+        let line = match step {
+            Step::Fits { row, word, solutions: 0, .. } => format!("{word} in row {row} fits the columns, nothing below so it backs out"),
+            Step::Fits { row, word, solutions, .. } => format!("{word} in row {row} fits the columns, {solutions} squares below"),
+            Step::Solution { row, word } => format!("{word} in row {row} finishes a square"),
+            Step::Used { row, word } => format!("{word} in row {row} is already a row"),
+            Step::Constraint { row, word } => format!("{word} in row {row} is turned down by a constraint"),
+            Step::BadStart { row, word, column, start, skipped: 0 } => format!("{word} in row {row} starts column {column} [{start}] which no word does"),
+            Step::BadStart { row, word, column, start, skipped } => format!("{word} in row {row} starts column {column} [{start}] which no word does, skipping the next {skipped} words starting the same way"),
+            Step::NotAWord { row, word, column, column_word } => format!("{word} in row {row} makes column {column} [{column_word}] which isn't a word"),
+            Step::ColumnUsed { row, word, column, column_word } => format!("{word} in row {row} makes column {column} [{column_word}] which is in the square already"),
+            Step::Transpose { row, word } => format!("{word} in row {row} makes a transpose of a square found from another seed")
+        };
+
+        writeln!(text, "{indent}{line}").expect("writing to a string");
+
+        if let Step::Fits { below, .. } = step {
+            write_steps(text, below);
+        }
+    }
+}
+
+#[test]
+fn explanation_is_indented_by_row() {
+
+    let explanation = Explanation {
+        seed: "abc".to_string(),
+        seed_allowed: true,
+        solutions: 1,
+        steps: vec![
+            Step::Used { row: 1, word: "abc".to_string() },
+            Step::Fits { row: 1, word: "def".to_string(), solutions: 1, below: vec![
+                Step::NotAWord { row: 2, word: "ghe".to_string(), column: 2, column_word: "cfe".to_string() },
+                Step::Solution { row: 2, word: "ghi".to_string() }
+            ]},
+            Step::BadStart { row: 1, word: "fda".to_string(), column: 0, start: "af".to_string(), skipped: 2 }
+        ]
+    };
+
+    assert_eq!("abc in row 0 makes 1 squares
+  abc in row 1 is already a row
+  def in row 1 fits the columns, 1 squares below
+    ghe in row 2 makes column 2 [cfe] which isn't a word
+    ghi in row 2 finishes a square
+  fda in row 1 starts column 0 [af] which no word does, skipping the next 2 words starting the same way
+", explanation_as_text(&explanation));
+
+    let json = serde_json::to_value(&explanation).unwrap();
+    assert_eq!("fits", json["steps"][1]["outcome"]);
+    assert_eq!("solution", json["steps"][1]["below"][1]["outcome"]);
+}
@@ -0,0 +1,170 @@
+use serde::{Deserialize, Serialize};
+use crate::error::SquardleError;
+
+/* the row or column a guess is for, counted from 0 */
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Line {
+    Row(usize),
+    Column(usize)
+}
+
+/* The colour of a letter of a guess following Squardle, best first. For a row guess a letter
+  that is elsewhere in that row is InRow and one that is in the column crossing its cell is
+  InColumn, the other way around for a column guess */
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LetterFeedback {
+    Correct,
+    InRow,
+    InColumn,
+    ElsewhereInGrid,
+    Absent
+}
+
+impl LetterFeedback {
+
+    /* what it is called in json, for the bindings that hand it over as a string */
+    pub fn name(&self) -> &'static str {
+        match self {
+            LetterFeedback::Correct => "correct",
+            LetterFeedback::InRow => "in_row",
+            LetterFeedback::InColumn => "in_column",
+            LetterFeedback::ElsewhereInGrid => "elsewhere_in_grid",
+            LetterFeedback::Absent => "absent"
+        }
+    }
+}
+
+/* Scores a guessed word against a line of the hidden square. Letters in the guessed line are
+  counted like wordle so a letter the line has once is only marked once, anything past that
+  falls through to the crossing line then the rest of the grid */
+pub fn guess_feedback(square: &[String], line: Line, guess: &str) -> Result<Vec<LetterFeedback>, SquardleError> {
+
+    let grid: Vec<Vec<char>> = square.iter().map(|row| row.chars().collect()).collect();
+    let word_size = grid.len();
+    let guess: Vec<char> = guess.chars().collect();
+
+    if grid.iter().any(|row| row.len() != word_size) {
+        return Err("the hidden square is not square".into());
+    }
+
+    if guess.len() != word_size {
+        return Err(format!("guess has {} letters, needs {}", guess.len(), word_size).as_str().into());
+    }
+
+    // the cell of the grid that the ith letter of the guess lands on
+    let cell = |i: usize| match line {
+        Line::Row(row) => (row, i),
+        Line::Column(column) => (i, column)
+    };
+
+    match line {
+        Line::Row(index) | Line::Column(index) if index >= word_size => return Err(format!("there are only {} rows and columns", word_size).as_str().into()),
+        _ => ()
+    }
+
+    let mut feedback = vec![LetterFeedback::Absent; word_size];
+    let mut spare_letters: Vec<char> = Vec::with_capacity(word_size);
+
+    for (i, letter) in guess.iter().enumerate() {
+        let (row, column) = cell(i);
+
+        if grid[row][column] == *letter {
+            feedback[i] = LetterFeedback::Correct;
+        } else {
+            spare_letters.push(grid[row][column]);
+        }
+    }
+
+    for (i, letter) in guess.iter().enumerate() {
+
+        if feedback[i] == LetterFeedback::Correct {
+            continue;
+        }
+
+        if let Some(position) = spare_letters.iter().position(|spare| spare == letter) {
+            spare_letters.swap_remove(position);
+            feedback[i] = match line {
+                Line::Row(_) => LetterFeedback::InRow,
+                Line::Column(_) => LetterFeedback::InColumn
+            };
+            continue;
+        }
+
+        let (row, column) = cell(i);
+
+        feedback[i] = match line {
+            Line::Row(_) if (0..word_size).any(|other_row| other_row != row && grid[other_row][column] == *letter) => LetterFeedback::InColumn,
+            Line::Column(_) if (0..word_size).any(|other_column| other_column != column && grid[row][other_column] == *letter) => LetterFeedback::InRow,
+            _ if grid.iter().flatten().any(|grid_letter| grid_letter == letter) => LetterFeedback::ElsewhereInGrid,
+            _ => LetterFeedback::Absent
+        };
+    }
+
+    Ok(feedback)
+}
+
+#[test]
+fn feedback_for_a_correct_guess() {
+
+    use LetterFeedback::*;
+
+    let square = vec!["budge".to_string(), "enter".to_string(), "alien".to_string(), "scant".to_string(), "eerie".to_string()];
+
+    assert_eq!(vec![Correct; 5], guess_feedback(&square, Line::Row(2), "alien").unwrap());
+    assert_eq!(vec![Correct; 5], guess_feedback(&square, Line::Column(0), "bease").unwrap());
+}
+
+#[test]
+fn feedback_for_a_row_guess() {
+
+    use LetterFeedback::*;
+
+    // columns are bease, unlce, dtiar, geeni, ernte
+    let square = vec!["budge".to_string(), "enter".to_string(), "alien".to_string(), "scant".to_string(), "eerie".to_string()];
+
+    // row 0 is budge: g is in the row, n in the column under it (unlce), t elsewhere, z nowhere
+    assert_eq!(vec![InRow, InColumn, Correct, ElsewhereInGrid, Absent], guess_feedback(&square, Line::Row(0), "gndtz").unwrap());
+}
+
+#[test]
+fn feedback_for_a_column_guess() {
+
+    use LetterFeedback::*;
+
+    let square = vec!["budge".to_string(), "enter".to_string(), "alien".to_string(), "scant".to_string(), "eerie".to_string()];
+
+    // column 1 is unlce: c is in the column, t is in row 1 (enter) crossing it, i elsewhere, x nowhere
+    assert_eq!(vec![Correct, InRow, InColumn, ElsewhereInGrid, Absent], guess_feedback(&square, Line::Column(1), "utcix").unwrap());
+}
+
+#[test]
+fn feedback_counts_repeated_letters() {
+
+    use LetterFeedback::*;
+
+    let square = vec!["budge".to_string(), "enter".to_string(), "alien".to_string(), "scant".to_string(), "eerie".to_string()];
+
+    // budge has one e which the last letter already has, so the others fall through to the crossing columns
+    assert_eq!(vec![InColumn, InColumn, ElsewhereInGrid, InColumn, Correct], guess_feedback(&square, Line::Row(0), "eeeee").unwrap());
+}
+
+#[test]
+fn feedback_rejects_bad_guesses() {
+
+    let square = vec!["budge".to_string(), "enter".to_string(), "alien".to_string(), "scant".to_string(), "eerie".to_string()];
+
+    assert!(guess_feedback(&square, Line::Row(5), "alien").is_err());
+    assert!(guess_feedback(&square, Line::Row(0), "ali").is_err());
+}
+
+#[test]
+fn names_match_the_json() {
+
+    use LetterFeedback::*;
+
+    for letter_feedback in [Correct, InRow, InColumn, ElsewhereInGrid, Absent] {
+        assert_eq!(serde_json::json!(letter_feedback.name()), serde_json::to_value(letter_feedback).unwrap());
+    }
+}
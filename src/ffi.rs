@@ -0,0 +1,212 @@
+use std::cell::RefCell;
+use std::ffi::{c_char, CStr, CString};
+use std::ptr;
+use crate::dictionary::dictionary_from_bytes;
+use crate::error::SquardleError;
+use crate::prefix_arena::PrefixArena;
+use crate::solution::Solution;
+use crate::solution_generator::{parse_partial_grid, GridSolver};
+
+/* The C interface, include/squardle.h is generated from it with cbindgen.
+  Everything handed out is owned by the caller until it is passed back to the matching free.
+  A call that fails returns null and leaves a message for squardle_last_error */
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(err: SquardleError) {
+    let message = CString::new(err.to_string().replace('\0', "")).expect("nul bytes were removed");
+    LAST_ERROR.with(|last_error| *last_error.borrow_mut() = Some(message));
+}
+
+pub struct SquardleSolver {
+    solver: GridSolver
+}
+
+/* the solutions of one solve, handed out one at a time as a line of comma separated rows */
+pub struct SquardleSolutions {
+    solutions: std::vec::IntoIter<Solution>,
+    current: Option<CString>
+}
+
+/** Makes a solver from the contents of a dictionary file, words separated by commas or new lines.
+  Returns null if the words aren't all the same size or there are none.
+
+  # Safety
+  words must point to at least length readable bytes */
+#[no_mangle]
+pub unsafe extern "C" fn squardle_solver_new(words: *const u8, length: usize) -> *mut SquardleSolver {
+
+    if words.is_null() {
+        set_last_error("words is null".into());
+        return ptr::null_mut();
+    }
+
+    let bytes = std::slice::from_raw_parts(words, length);
+
+    let solver = dictionary_from_bytes(bytes)
+        .and_then(|dictionary| PrefixArena::new(&dictionary).and_then(|prefix_map| GridSolver::new(dictionary, prefix_map)));
+
+    match solver {
+        Ok(solver) => Box::into_raw(Box::new(SquardleSolver { solver })),
+        Err(err) => {
+            set_last_error(err);
+            ptr::null_mut()
+        }
+    }
+}
+
+/** Finds the completions of a partial grid written the way the solve command takes it, like "h...t,,e"
+  with null or "" for an empty grid. A limit of 0 finds every completion, which can take a long time.
+
+  # Safety
+  solver must come from squardle_solver_new and partial_grid must be null or a nul terminated string */
+#[no_mangle]
+pub unsafe extern "C" fn squardle_solver_solve(solver: *mut SquardleSolver, partial_grid: *const c_char, limit: usize) -> *mut SquardleSolutions {
+
+    let Some(solver) = solver.as_mut() else {
+        set_last_error("solver is null".into());
+        return ptr::null_mut();
+    };
+
+    let partial_grid = match partial_grid.is_null() {
+        true => "",
+        false => match CStr::from_ptr(partial_grid).to_str() {
+            Ok(partial_grid) => partial_grid,
+            Err(_) => {
+                set_last_error("partial grid is not utf-8".into());
+                return ptr::null_mut();
+            }
+        }
+    };
+
+    let word_size = solver.solver.word_size();
+    let solved = parse_partial_grid(partial_grid, word_size).and_then(|fixed_letters| solver.solver.solve(fixed_letters, (limit > 0).then_some(limit)));
+
+    match solved {
+        Ok((solutions, _)) => Box::into_raw(Box::new(SquardleSolutions { solutions: solutions.into_iter(), current: None })),
+        Err(err) => {
+            set_last_error(err);
+            ptr::null_mut()
+        }
+    }
+}
+
+/** The next solution as its rows separated by commas, or null when there are none left. The
+  string belongs to the solutions and is only valid until the next call. The words aren't checked
+  when the dictionary is loaded, a solution with a nul byte in one can't be a C string so it is
+  skipped with null returned and a message left for squardle_last_error
+
+  # Safety
+  solutions must come from squardle_solver_solve */
+#[no_mangle]
+pub unsafe extern "C" fn squardle_solutions_next(solutions: *mut SquardleSolutions) -> *const c_char {
+
+    let Some(solutions) = solutions.as_mut() else {
+        return ptr::null();
+    };
+
+    solutions.current = None;
+
+    let Some(solution) = solutions.solutions.next() else {
+        return ptr::null();
+    };
+
+    match CString::new(solution.to_string()) {
+        Ok(line) => solutions.current.insert(line).as_ptr(),
+        Err(_) => {
+            set_last_error(format!("solution [{}] has a nul byte in it", solution.to_string().replace('\0', "\\0")).as_str().into());
+            ptr::null()
+        }
+    }
+}
+
+/** how many solutions squardle_solutions_next has left to give
+
+  # Safety
+  solutions must come from squardle_solver_solve */
+#[no_mangle]
+pub unsafe extern "C" fn squardle_solutions_remaining(solutions: *const SquardleSolutions) -> usize {
+    solutions.as_ref().map_or(0, |solutions| solutions.solutions.len())
+}
+
+/** # Safety
+  solutions must come from squardle_solver_solve and not have been freed already, null is ignored */
+#[no_mangle]
+pub unsafe extern "C" fn squardle_solutions_free(solutions: *mut SquardleSolutions) {
+    if !solutions.is_null() {
+        drop(Box::from_raw(solutions));
+    }
+}
+
+/** # Safety
+  solver must come from squardle_solver_new and not have been freed already, null is ignored */
+#[no_mangle]
+pub unsafe extern "C" fn squardle_solver_free(solver: *mut SquardleSolver) {
+    if !solver.is_null() {
+        drop(Box::from_raw(solver));
+    }
+}
+
+/** why the last call on this thread returned null, null if nothing has failed. Valid until the next failure */
+#[no_mangle]
+pub extern "C" fn squardle_last_error() -> *const c_char {
+    LAST_ERROR.with(|last_error| last_error.borrow().as_ref().map_or(ptr::null(), |message| message.as_ptr()))
+}
+
+#[test]
+fn solve_through_the_c_interface() {
+
+    let words = crate::dictionary::test_dictionary_file();
+
+    unsafe {
+        let solver = squardle_solver_new(words.as_ptr(), words.len());
+        assert!(!solver.is_null());
+
+        // ten,ore,eat and ten,ore,rat, their transposes start with to
+        let solutions = squardle_solver_solve(solver, c"te".as_ptr(), 0);
+        assert_eq!(2, squardle_solutions_remaining(solutions));
+        assert_eq!(c"ten,ore,eat", CStr::from_ptr(squardle_solutions_next(solutions)));
+        assert_eq!(c"ten,ore,rat", CStr::from_ptr(squardle_solutions_next(solutions)));
+        assert!(squardle_solutions_next(solutions).is_null());
+        squardle_solutions_free(solutions);
+
+        assert!(squardle_solver_solve(solver, c"abcd".as_ptr(), 0).is_null());
+        assert!(!squardle_last_error().is_null());
+
+        squardle_solver_free(solver);
+
+        let mixed_sizes = b"ab,abc";
+        assert!(squardle_solver_new(mixed_sizes.as_ptr(), mixed_sizes.len()).is_null());
+
+        // a nul byte in a word is an error for that solution rather than a panic across the boundary
+        let with_nul = b"ten,ore,ea\0,toe,era,ne\0";
+        let solver = squardle_solver_new(with_nul.as_ptr(), with_nul.len());
+        let solutions = squardle_solver_solve(solver, c"ten".as_ptr(), 0);
+        assert_eq!(1, squardle_solutions_remaining(solutions));
+        assert!(squardle_solutions_next(solutions).is_null());
+        assert_eq!(c"solution [ten,ore,ea\\0] has a nul byte in it", CStr::from_ptr(squardle_last_error()));
+        squardle_solutions_free(solutions);
+        squardle_solver_free(solver);
+    }
+}
+
+/* the header cbindgen makes from this file as it is now */
+#[cfg(test)]
+fn generated_header() -> String {
+
+    let config = cbindgen::Config::from_file("cbindgen.toml").unwrap();
+    let mut header: Vec<u8> = Vec::new();
+
+    cbindgen::generate_with_config(env!("CARGO_MANIFEST_DIR"), config).unwrap().write(&mut header);
+
+    String::from_utf8(header).unwrap()
+}
+
+#[test]
+fn header_is_generated_from_the_source() {
+
+    // every signature has to match, not just the names, regenerate with cbindgen --config cbindgen.toml --output include/squardle.h
+    assert_eq!(generated_header(), std::fs::read_to_string("include/squardle.h").unwrap(), "include/squardle.h is out of date");
+}
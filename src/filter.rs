@@ -0,0 +1,138 @@
+use std::io::{BufRead, Write};
+use crate::error::SquardleError;
+use crate::generator_config::Compression;
+use crate::output::{self, OutputWriter};
+use crate::solution_generator::construct_potential_transposed_puzzle;
+
+/* which solutions to keep, every condition has to hold. A word is in a solution if it is
+  any of its rows or columns, a row pattern uses . for any letter e.g. h.a.t */
+#[derive(Debug, Default, PartialEq)]
+pub struct SolutionFilter {
+    pub source_file_path: String,
+    pub dest_file_path: String,
+    pub contains: Vec<String>,
+    pub excludes: Vec<String>,
+    pub row_pattern: Option<String>
+}
+
+impl SolutionFilter {
+
+    pub fn build(args: &[String]) -> Result<SolutionFilter, SquardleError> {
+
+        let mut filter = SolutionFilter::default();
+        let mut positional_args: Vec<String> = Vec::with_capacity(2);
+        let mut args_iter = args.iter();
+
+        while let Some(arg) = args_iter.next() {
+
+            match arg.as_str() {
+                "--contains" => match args_iter.next() {
+                    Some(word) => filter.contains.push(word.to_lowercase()),
+                    None => return Err("Contains flag needs a word".into())
+                },
+                "--exclude" => match args_iter.next() {
+                    Some(word) => filter.excludes.push(word.to_lowercase()),
+                    None => return Err("Exclude flag needs a word".into())
+                },
+                "--row-pattern" => match args_iter.next() {
+                    Some(pattern) => filter.row_pattern = Some(pattern.to_lowercase()),
+                    None => return Err("Row pattern flag needs a pattern e.g. h.a.t".into())
+                },
+                _ => positional_args.push(arg.clone())
+            }
+        }
+
+        if positional_args.len() != 2 {
+            return Err("filter accepts a solution file path, a destination file path and optional flags: --contains word, --exclude word, --row-pattern pattern".into());
+        }
+
+        filter.dest_file_path = positional_args.pop().expect("there are two positional args");
+        filter.source_file_path = positional_args.pop().expect("there are two positional args");
+
+        Ok(filter)
+    }
+
+    pub fn matches(&self, rows: &[String]) -> bool {
+
+        let columns = construct_potential_transposed_puzzle(rows);
+        let has_word = |word: &String| rows.contains(word) || columns.contains(word);
+
+        if !self.contains.iter().all(has_word) || self.excludes.iter().any(has_word) {
+            return false;
+        }
+
+        match &self.row_pattern {
+            Some(pattern) => rows.iter().any(|row| matches_pattern(row, pattern)),
+            None => true
+        }
+    }
+
+    /* Reads the solutions file a line at a time, writing out the ones that match.
+      Returns the number of solutions kept and dropped */
+    pub fn filter_solution_file(&self) -> Result<(usize, usize), SquardleError> {
+
+        let reader = output::open_solution_file(&self.source_file_path)?;
+        let mut dest_file = OutputWriter::create(&self.dest_file_path, Compression::from_file_path(&self.dest_file_path))?;
+
+        let mut kept_count = 0;
+        let mut dropped_count = 0;
+
+        for line in reader.lines() {
+            let line = line?;
+            let rows = output::rows_of_line(&line);
+
+            // blank lines and the header
+            if rows.is_empty() {
+                continue;
+            }
+
+            if self.matches(&rows) {
+                writeln!(dest_file, "{}", line)?;
+                kept_count += 1;
+            } else {
+                dropped_count += 1;
+            }
+        }
+
+        dest_file.finish()?;
+
+        Ok((kept_count, dropped_count))
+    }
+}
+
+fn matches_pattern(word: &str, pattern: &str) -> bool {
+    word.chars().count() == pattern.chars().count() && word.chars().zip(pattern.chars()).all(|(letter, wanted)| wanted == '.' || letter == wanted)
+}
+
+#[test]
+fn filter_by_contained_and_excluded_words() {
+
+    let rows = vec!["budge".to_string(), "enter".to_string(), "alien".to_string(), "scant".to_string(), "eerie".to_string()];
+
+    let filter = SolutionFilter { contains: vec!["alien".to_string(), "bease".to_string()], ..SolutionFilter::default() };
+    assert!(filter.matches(&rows));
+
+    let filter = SolutionFilter { excludes: vec!["scant".to_string()], ..SolutionFilter::default() };
+    assert!(!filter.matches(&rows));
+
+    let filter = SolutionFilter { row_pattern: Some("s.a.t".to_string()), ..SolutionFilter::default() };
+    assert!(filter.matches(&rows));
+
+    let filter = SolutionFilter { row_pattern: Some("s.a.".to_string()), ..SolutionFilter::default() };
+    assert!(!filter.matches(&rows));
+}
+
+#[test]
+fn build_filter() {
+
+    let args: Vec<String> = ["in.csv", "--contains", "HEART", "out.csv", "--row-pattern", "h...t"].iter().map(|arg| arg.to_string()).collect();
+
+    let filter = SolutionFilter::build(&args).unwrap();
+
+    assert_eq!("in.csv", filter.source_file_path);
+    assert_eq!("out.csv", filter.dest_file_path);
+    assert_eq!(vec!["heart".to_string()], filter.contains);
+    assert_eq!(Some("h...t".to_string()), filter.row_pattern);
+
+    assert!(SolutionFilter::build(&args[..2]).is_err());
+}
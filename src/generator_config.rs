@@ -1,37 +1,1547 @@
-#[derive(Debug)]
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::thread;
+use log::LevelFilter;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use crate::constraint::{self, AllLettersDifferent, AntiDiagonal, Constraint, LetterLimits, Palindromic, Pattern, RequiredWord, SoftConstraint, TaggedWords, WordRules};
+use crate::dictionary::{WordTags, STDIN_FILE_PATH};
+use crate::error::SquardleError;
+use crate::solution_generator::parse_partial_grid;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum GeneratorBackend {
+    #[serde(rename = "threads")]
+    ThreadPool,
+    Rayon
+}
+
+/* what finds the squares */
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Engine {
+    // the row by row search over the prefix map
+    PrefixMap,
+    // tries every word in every row and checks the grid, only for checking the other on a handful of words
+    Naive
+}
+
+/* the order the solver fills a square in */
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FillOrder {
+    // top to bottom, a row at a time
+    Rows,
+    // whichever row or column has the fewest words that could still go in it
+    Constrained
+}
+
+/* how the row by row search rules out words before trying them */
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Pruning {
+    // skips the words starting the same way as one whose letters couldn't start a column
+    BadStarts,
+    // checks every row still to fill has a word left that fits its columns before going down a row
+    ForwardCheck
+}
+
+/* a symmetry every square has to have besides the row and column words */
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Symmetry {
+    // reads the same backwards as one string row after row, the same turned half way round
+    Palindromic,
+    // the same flipped over the diagonal from the top right corner to the bottom left
+    AntiDiagonal
+}
+
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum OutputFormat {
+    Csv,
+    Jsonl,
+    Grid,
+    Sqlite,
+    Binary
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ReportFormat {
+    Text,
+    Json
+}
+
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ProgressFormat {
+    Text,
+    Json
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ScoreBy {
+    Frequency
+}
+
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Compression {
+    None,
+    Gzip,
+    Zstd
+}
+
+impl Compression {
+
+    /* picks the compression from the file extension, .gz or .zst */
+    pub fn from_file_path(file_path: &str) -> Compression {
+
+        if file_path.ends_with(".gz") {
+            Compression::Gzip
+        } else if file_path.ends_with(".zst") {
+            Compression::Zstd
+        } else {
+            Compression::None
+        }
+    }
+}
+
+/* How csv dictionaries are read and csv solutions are written. Commas and no header rows unless
+  told otherwise, fields with the delimiter or a quote in them are quoted when written */
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
+pub struct CsvDialect {
+    #[serde(with = "delimiter")]
+    pub delimiter: u8,
+    pub dictionary_header: bool,
+    pub output_header: bool
+}
+
+impl Default for CsvDialect {
+    fn default() -> CsvDialect {
+        CsvDialect { delimiter: b',', dictionary_header: false, output_header: false }
+    }
+}
+
+impl CsvDialect {
+
+    /* a single ascii character, or tab or \t for a tab */
+    pub fn parse_delimiter(value: &str) -> Result<u8, SquardleError> {
+
+        match value {
+            "tab" | "\\t" | "\t" => Ok(b'\t'),
+            _ if value.len() == 1 && value.is_ascii() && value != "\"" => Ok(value.as_bytes()[0]),
+            _ => Err("Delimiter must be a single character other than a quote, or tab".into())
+        }
+    }
+}
+
+/* one of count disjoint slices of the seed words, index is 1 based like the --shard 3/16 flag */
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
+pub struct Shard {
+    pub index: usize,
+    pub count: usize
+}
+
+impl Shard {
+
+    pub fn parse(value: &str) -> Result<Shard, SquardleError> {
+
+        let (index, count) = match value.split_once('/') {
+            Some((index, count)) => (index.parse::<usize>(), count.parse::<usize>()),
+            None => return Err("Shard must be written as index/count e.g. 3/16".into())
+        };
+
+        match (index, count) {
+            (Ok(index), Ok(count)) if index >= 1 && index <= count => Ok(Shard { index, count }),
+            _ => Err("Shard index must be between 1 and the shard count".into())
+        }
+    }
+
+    /* seed words are dealt out round robin by their position in the sorted dictionary */
+    pub fn contains(&self, seed_index: usize) -> bool {
+        seed_index % self.count == self.index - 1
+    }
+}
+
+/* what generate takes, printed when the args don't add up */
+const GENERATE_USAGE: &str = "  dictionary source file path
+  solution destination file path
+  optional amount of threads to use
+optional flags:
+  dictionaries: --dictionary file path or - for stdin, --row-dictionary file path or - for stdin, --col-dictionary file path or - for stdin, --tagged-dictionary
+  csv: --delimiter character|tab, --dictionary-header, --output-header
+  search: --backend threads|rayon, --engine prefix-map|naive, --fill-order rows|constrained, --pruning bad-starts|forward-check, --memo capacity, --chunk-prefix length, --beam width, --deterministic
+  queues and threads: --batch-size size, --job-queue capacity, --solution-queue capacity, --threads N|auto|auto/N|N%
+  resuming and sharding: --checkpoint file path, --resume, --shard index/count
+  output: --output-format csv|jsonl|grid|sqlite|binary, --output file path, --compress none|gzip|zstd, --output-shard-size solutions, --worker-output
+  what to keep: --limit count, --count-only, --sample count, --shuffle, --seed n, --first-per-seed, --frequencies file path, --top count, --score-by frequency
+  constraints: --require word, --pattern grid, --max-letter letter:count, --min-vowels-per-word count, --max-repeated-letter count, --forbid-letters letters, --all-letters-different, --require-tag tag, --min-tagged count, --symmetry palindromic|anti-diagonal, --diagonal word, --constraints file path
+  reports: --preflight-only, --report text|json, --explain word, --explain-format text|json, --validate, --progress-format text|json, --progress-file file path
+  logging: -q, -v, -vv";
+
+/* Everything a generate run is told, from its arguments or set by a library user. It goes to and
+  from json for the server, the bindings and anything else that wants to hand a run over whole,
+  with the enums written the way their flags are. Constraints added in code aren't data, so
+  they're left out and a config read back has none. One read back is validated the same as one built from args */
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(remote = "Self")]
 pub struct GeneratorConfig {
     pub dictionary_file_path: String,
+    // the columns' words when they come from another dictionary than the rows
+    pub column_dictionary_file_path: Option<String>,
+    // the dictionaries have a word then its tags on each line
+    pub tagged_dictionary: bool,
+    // the tags of the words, read in along with the dictionary and written out with the solutions as json
+    pub word_tags: WordTags,
     pub num_threads: usize,
-    pub solutions_dest_file_path: Option<String>
+    pub solutions_dest_file_path: Option<String>,
+    pub backend: GeneratorBackend,
+    pub engine: Engine,
+    pub fill_order: FillOrder,
+    pub pruning: Pruning,
+    // remembers up to this many partial grids with no way of finishing them
+    pub memo: Option<usize>,
+    pub chunk_prefix_length: usize,
+    pub solution_batch_size: usize,
+    // how many chunks of seed words can wait for a worker, the thread pool only
+    pub job_queue_capacity: usize,
+    // how many batches of solutions can wait to be written before the workers block on sending more
+    pub solution_queue_capacity: usize,
+    pub checkpoint_file_path: Option<String>,
+    pub resume: bool,
+    pub shard: Option<Shard>,
+    pub output_format: OutputFormat,
+    pub solution_limit: Option<usize>,
+    pub compression: Compression,
+    pub output_shard_size: Option<usize>,
+    pub count_only: bool,
+    // prints the preflight estimate of the search and stops before starting any workers
+    pub preflight_only: bool,
+    // each worker writes its solutions to its own file, put together into the destination at the end
+    pub worker_output: bool,
+    pub report_format: Option<ReportFormat>,
+    pub frequencies_file_path: Option<String>,
+    pub top: Option<usize>,
+    // keeps this many of the solutions picked at random
+    pub sample: Option<usize>,
+    // searches the seed words, and each worker tries words for the other rows, in a random order
+    pub shuffle: bool,
+    // the same seed gives the same sample and search order again
+    pub seed: Option<u64>,
+    // each seed word stops at its first solution, to find which words start any square
+    pub first_per_seed: bool,
+    pub score_by: ScoreBy,
+    pub deterministic: bool,
+    // re-checks every solution the generator finds before it is sent
+    pub validate: bool,
+    #[serde(with = "level_filter")]
+    pub log_level: LevelFilter,
+    pub progress_format: ProgressFormat,
+    pub progress_file_path: Option<String>,
+    pub csv_dialect: CsvDialect,
+    // words each square has to have as a row or column
+    pub required_words: Vec<String>,
+    // a partial grid every square has to fit, only parsed once the word size is known
+    pub pattern: Option<String>,
+    // the most of a letter a square can have
+    pub letter_limits: HashMap<char, usize>,
+    // the fewest vowels each row and column word can have
+    pub min_vowels_per_word: usize,
+    // the most times a letter can be in any one word
+    pub max_repeated_letter: Option<usize>,
+    // letters none of the words can have
+    pub forbidden_letters: HashSet<char>,
+    // no letter more than once in a square
+    pub all_letters_different: bool,
+    pub symmetry: Option<Symmetry>,
+    // a theme word down the diagonal from the top left
+    pub diagonal: Option<String>,
+    // a square needs min_tagged rows and columns tagged with any of these
+    pub required_tags: Vec<String>,
+    pub min_tagged: usize,
+    // a toml or json file of more constraints
+    pub constraints_file_path: Option<String>,
+    // any more a library user adds, checked along with the built in ones
+    #[serde(skip)]
+    pub extra_constraints: Vec<Arc<dyn Constraint>>,
+    // and soft ones, which only add to a square's penalty
+    #[serde(skip)]
+    pub extra_soft_constraints: Vec<SoftConstraint>,
+    // searches a row at a time keeping only this many of the grids with the least penalty
+    pub beam_width: Option<usize>,
+    // writes out every candidate tried searching from this seed word and what became of it, rather than the solutions
+    pub explain: Option<String>,
+    pub explain_format: ReportFormat
 }
 
 impl GeneratorConfig {
 
-    pub fn build(args: &[String]) -> Result<GeneratorConfig, &'static str> {
+    pub fn build(args: &[String]) -> Result<GeneratorConfig, SquardleError> {
+
+        let mut backend = GeneratorBackend::ThreadPool;
+        let mut engine = Engine::PrefixMap;
+        let mut fill_order = FillOrder::Rows;
+        let mut pruning = Pruning::BadStarts;
+        let mut memo: Option<usize> = None;
+        let mut chunk_prefix_length = 2;
+        let mut solution_batch_size = 64;
+        let mut job_queue_capacity = 8;
+        let mut solution_queue_capacity = 1024;
+        let mut threads_arg: Option<String> = None;
+        let mut checkpoint_file_path: Option<String> = None;
+        let mut resume = false;
+        let mut shard: Option<Shard> = None;
+        let mut output_format: Option<OutputFormat> = None;
+        let mut output_file_path: Option<String> = None;
+        let mut compression: Option<Compression> = None;
+        let mut output_shard_size: Option<usize> = None;
+        let mut count_only = false;
+        let mut preflight_only = false;
+        let mut worker_output = false;
+        let mut deterministic = false;
+        let mut validate = false;
+        let mut log_level = LevelFilter::Info;
+        let mut progress_format = ProgressFormat::Text;
+        let mut progress_file_path: Option<String> = None;
+        let mut report_format: Option<ReportFormat> = None;
+        let mut frequencies_file_path: Option<String> = None;
+        let mut top: Option<usize> = None;
+        let mut sample: Option<usize> = None;
+        let mut shuffle = false;
+        let mut first_per_seed = false;
+        let mut seed: Option<u64> = None;
+        let mut score_by = ScoreBy::Frequency;
+        let mut required_words: Vec<String> = Vec::new();
+        let mut pattern: Option<String> = None;
+        let mut letter_limits: HashMap<char, usize> = HashMap::new();
+        let mut min_vowels_per_word = 0;
+        let mut max_repeated_letter: Option<usize> = None;
+        let mut forbidden_letters: HashSet<char> = HashSet::new();
+        let mut all_letters_different = false;
+        let mut symmetry: Option<Symmetry> = None;
+        let mut diagonal: Option<String> = None;
+        let mut constraints_file_path: Option<String> = None;
+        let mut beam_width: Option<usize> = None;
+        let mut explain: Option<String> = None;
+        let mut explain_format: Option<ReportFormat> = None;
+        let mut solution_limit: Option<usize> = None;
+        let mut dictionary_arg: Option<String> = None;
+        let mut column_dictionary_file_path: Option<String> = None;
+        let mut tagged_dictionary = false;
+        let mut required_tags: Vec<String> = Vec::new();
+        let mut min_tagged: Option<usize> = None;
+        let mut csv_dialect = CsvDialect::default();
+
+        // pull out the --flag value pairs, everything else is positional
+        let mut positional_args: Vec<String> = Vec::with_capacity(args.len());
+        let mut args_iter = args.iter();
+
+        while let Some(arg) = args_iter.next() {
+
+            match arg.as_str() {
+                "--backend" => {
+                    backend = match args_iter.next().map(String::as_str) {
+                        Some("threads") => GeneratorBackend::ThreadPool,
+                        Some("rayon") => GeneratorBackend::Rayon,
+                        _ => return Err("Backend must be one of: threads, rayon".into())
+                    };
+                },
+                "--engine" => {
+                    engine = match args_iter.next().map(String::as_str) {
+                        Some("prefix-map") => Engine::PrefixMap,
+                        Some("naive") => Engine::Naive,
+                        _ => return Err("Engine must be one of: prefix-map, naive".into())
+                    };
+                },
+                "--fill-order" => {
+                    fill_order = match args_iter.next().map(String::as_str) {
+                        Some("rows") => FillOrder::Rows,
+                        Some("constrained") => FillOrder::Constrained,
+                        _ => return Err("Fill order must be one of: rows, constrained".into())
+                    };
+                },
+                "--pruning" => {
+                    pruning = match args_iter.next().map(String::as_str) {
+                        Some("bad-starts") => Pruning::BadStarts,
+                        Some("forward-check") => Pruning::ForwardCheck,
+                        _ => return Err("Pruning must be one of: bad-starts, forward-check".into())
+                    };
+                },
+                "--memo" => {
+                    memo = match args_iter.next().map(|value| value.parse::<usize>()) {
+                        Some(Ok(capacity)) if capacity > 0 => Some(capacity),
+                        _ => return Err("Memo must be a number of partial grids greater than 0".into())
+                    };
+                },
+                "--chunk-prefix" => {
+                    chunk_prefix_length = match args_iter.next().map(|value| value.parse::<usize>()) {
+                        Some(Ok(length)) if length > 0 => length,
+                        _ => return Err("Chunk prefix length must be a number greater than 0".into())
+                    };
+                },
+                "--batch-size" => {
+                    solution_batch_size = match args_iter.next().map(|value| value.parse::<usize>()) {
+                        Some(Ok(size)) if size > 0 => size,
+                        _ => return Err("Solution batch size must be a number greater than 0".into())
+                    };
+                },
+                "--job-queue" => {
+                    job_queue_capacity = match args_iter.next().map(|value| value.parse::<usize>()) {
+                        Some(Ok(capacity)) if capacity > 0 => capacity,
+                        _ => return Err("Job queue capacity must be a number greater than 0".into())
+                    };
+                },
+                "--solution-queue" => {
+                    solution_queue_capacity = match args_iter.next().map(|value| value.parse::<usize>()) {
+                        Some(Ok(capacity)) if capacity > 0 => capacity,
+                        _ => return Err("Solution queue capacity must be a number greater than 0".into())
+                    };
+                },
+                "--threads" => {
+                    threads_arg = match args_iter.next() {
+                        Some(value) => Some(value.clone()),
+                        None => return Err("Threads flag needs a value: N, auto, auto/N or N%".into())
+                    };
+                },
+                "--checkpoint" => {
+                    checkpoint_file_path = match args_iter.next() {
+                        Some(value) => Some(value.clone()),
+                        None => return Err("Checkpoint flag needs a file path".into())
+                    };
+                },
+                "--resume" => resume = true,
+                "--count-only" => count_only = true,
+                "--preflight-only" => preflight_only = true,
+                "--worker-output" => worker_output = true,
+                "--deterministic" => deterministic = true,
+                "--validate" => validate = true,
+                "--progress-format" => {
+                    progress_format = match args_iter.next().map(String::as_str) {
+                        Some("text") => ProgressFormat::Text,
+                        Some("json") => ProgressFormat::Json,
+                        _ => return Err("Progress format must be one of: text, json".into())
+                    };
+                },
+                "--progress-file" => {
+                    progress_file_path = match args_iter.next() {
+                        Some(value) => Some(value.clone()),
+                        None => return Err("Progress file flag needs a file path".into())
+                    };
+                },
+                "-q" => log_level = LevelFilter::Warn,
+                "-v" => log_level = LevelFilter::Debug,
+                "-vv" => log_level = LevelFilter::Trace,
+                "--report" => {
+                    report_format = match args_iter.next().map(String::as_str) {
+                        Some("text") => Some(ReportFormat::Text),
+                        Some("json") => Some(ReportFormat::Json),
+                        _ => return Err("Report format must be one of: text, json".into())
+                    };
+                },
+                "--output-format" => {
+                    output_format = match args_iter.next().map(String::as_str) {
+                        Some("csv") => Some(OutputFormat::Csv),
+                        Some("jsonl") => Some(OutputFormat::Jsonl),
+                        Some("grid") => Some(OutputFormat::Grid),
+                        Some("sqlite") => Some(OutputFormat::Sqlite),
+                        Some("binary") => Some(OutputFormat::Binary),
+                        _ => return Err("Output format must be one of: csv, jsonl, grid, sqlite, binary".into())
+                    };
+                },
+                "--compress" => {
+                    compression = match args_iter.next().map(String::as_str) {
+                        Some("none") => Some(Compression::None),
+                        Some("gzip") => Some(Compression::Gzip),
+                        Some("zstd") => Some(Compression::Zstd),
+                        _ => return Err("Compression must be one of: none, gzip, zstd".into())
+                    };
+                },
+                "--output-shard-size" => {
+                    output_shard_size = match args_iter.next().map(|value| value.parse::<usize>()) {
+                        Some(Ok(size)) if size > 0 => Some(size),
+                        _ => return Err("Output shard size must be a number greater than 0".into())
+                    };
+                },
+                "--frequencies" => {
+                    frequencies_file_path = match args_iter.next() {
+                        Some(value) => Some(value.clone()),
+                        None => return Err("Frequencies flag needs a file path".into())
+                    };
+                },
+                "--top" => {
+                    top = match args_iter.next().map(|value| value.parse::<usize>()) {
+                        Some(Ok(k)) if k > 0 => Some(k),
+                        _ => return Err("Top must be a number of solutions greater than 0".into())
+                    };
+                },
+                "--sample" => {
+                    sample = match args_iter.next().map(|value| value.parse::<usize>()) {
+                        Some(Ok(sample_size)) if sample_size > 0 => Some(sample_size),
+                        _ => return Err("Sample must be a number of solutions greater than 0".into())
+                    };
+                },
+                "--shuffle" => shuffle = true,
+                "--first-per-seed" => first_per_seed = true,
+                "--require" => match args_iter.next() {
+                    Some(word) => required_words.push(word.clone()),
+                    None => return Err("Require flag needs a word".into())
+                },
+                "--pattern" => match args_iter.next() {
+                    Some(grid) => pattern = Some(grid.clone()),
+                    None => return Err("Pattern flag needs a grid e.g. cra.e,.....,t..er".into())
+                },
+                "--constraints" => match args_iter.next() {
+                    Some(file_path) => constraints_file_path = Some(file_path.clone()),
+                    None => return Err("Constraints flag needs a file path".into())
+                },
+                "--min-vowels-per-word" => min_vowels_per_word = match args_iter.next().map(|value| value.parse::<usize>()) {
+                    Some(Ok(min_vowels)) => min_vowels,
+                    _ => return Err("Min vowels per word must be a whole number".into())
+                },
+                "--max-repeated-letter" => max_repeated_letter = match args_iter.next().map(|value| value.parse::<usize>()) {
+                    Some(Ok(max)) if max > 0 => Some(max),
+                    _ => return Err("Max repeated letter must be a number greater than 0".into())
+                },
+                "--forbid-letters" => match args_iter.next() {
+                    Some(letters) => forbidden_letters.extend(letters.to_lowercase().chars()),
+                    None => return Err("Forbid letters flag needs the letters e.g. qxz".into())
+                },
+                "--all-letters-different" => all_letters_different = true,
+                "--tagged-dictionary" => tagged_dictionary = true,
+                "--require-tag" => match args_iter.next() {
+                    Some(tag) => required_tags.push(tag.to_lowercase()),
+                    None => return Err("Require tag flag needs a tag e.g. animal".into())
+                },
+                "--min-tagged" => min_tagged = match args_iter.next().map(|value| value.parse::<usize>()) {
+                    Some(Ok(count)) if count > 0 => Some(count),
+                    _ => return Err("Min tagged must be a number greater than 0".into())
+                },
+                "--diagonal" => match args_iter.next() {
+                    Some(word) => diagonal = Some(word.to_lowercase()),
+                    None => return Err("Diagonal flag needs a word e.g. heart".into())
+                },
+                "--symmetry" => symmetry = match args_iter.next().map(String::as_str) {
+                    Some("palindromic") => Some(Symmetry::Palindromic),
+                    Some("anti-diagonal") => Some(Symmetry::AntiDiagonal),
+                    _ => return Err("Symmetry must be one of: palindromic, anti-diagonal".into())
+                },
+                "--beam" => beam_width = match args_iter.next().map(|value| value.parse::<usize>()) {
+                    Some(Ok(width)) if width > 0 => Some(width),
+                    _ => return Err("Beam width must be a number greater than 0".into())
+                },
+                "--explain" => match args_iter.next() {
+                    Some(word) => explain = Some(word.to_lowercase()),
+                    None => return Err("Explain flag needs a seed word".into())
+                },
+                "--explain-format" => explain_format = match args_iter.next().map(String::as_str) {
+                    Some("text") => Some(ReportFormat::Text),
+                    Some("json") => Some(ReportFormat::Json),
+                    _ => return Err("Explain format must be one of: text, json".into())
+                },
+                "--max-letter" => match args_iter.next().and_then(|value| constraint::parse_letter_limit(value)) {
+                    Some((letter, count)) => { letter_limits.insert(letter, count); },
+                    None => return Err("Max letter flag needs a letter and a count e.g. e:3".into())
+                },
+                "--seed" => {
+                    seed = match args_iter.next().map(|value| value.parse::<u64>()) {
+                        Some(Ok(seed)) => Some(seed),
+                        _ => return Err("Seed must be a whole number".into())
+                    };
+                },
+                "--score-by" => {
+                    score_by = match args_iter.next().map(String::as_str) {
+                        Some("frequency") => ScoreBy::Frequency,
+                        _ => return Err("Score by must be one of: frequency".into())
+                    };
+                },
+                "--output" => {
+                    output_file_path = match args_iter.next() {
+                        Some(value) => Some(value.clone()),
+                        None => return Err("Output flag needs a file path".into())
+                    };
+                },
+                "--limit" => {
+                    solution_limit = match args_iter.next().map(|value| value.parse::<usize>()) {
+                        Some(Ok(limit)) if limit > 0 => Some(limit),
+                        _ => return Err("Solution limit must be a number greater than 0".into())
+                    };
+                },
+                "--dictionary" | "--row-dictionary" => {
+                    dictionary_arg = match args_iter.next() {
+                        Some(value) => Some(value.clone()),
+                        None => return Err("Dictionary flag needs a file path or - for stdin".into())
+                    };
+                },
+                "--col-dictionary" => {
+                    column_dictionary_file_path = match args_iter.next() {
+                        Some(value) => Some(value.clone()),
+                        None => return Err("Column dictionary flag needs a file path or - for stdin".into())
+                    };
+                },
+                "--delimiter" => {
+                    csv_dialect.delimiter = match args_iter.next() {
+                        Some(value) => CsvDialect::parse_delimiter(value)?,
+                        None => return Err("Delimiter flag needs a character".into())
+                    };
+                },
+                "--dictionary-header" => csv_dialect.dictionary_header = true,
+                "--output-header" => csv_dialect.output_header = true,
+                "--shard" => {
+                    shard = match args_iter.next() {
+                        Some(value) => Some(Shard::parse(value)?),
+                        None => return Err("Shard flag needs a value e.g. 3/16".into())
+                    };
+                },
+                _ => positional_args.push(arg.clone())
+            }
+        }
+
+        let mut args = positional_args;
+
+        // the flag takes the place of the first positional arg after the executable name
+        if let Some(dictionary_arg) = dictionary_arg {
+            args.insert(args.len().min(1), dictionary_arg);
+        }
 
         // first arg is executable name
         if args.len() < 2 || args.len() > 4 {
 
-            return Err("Inccorect number of args, accepts 2-3 args: \ndictioary source file path\nsolution destination file path\noptional amount of threads to use");
+            return Err(SquardleError::InvalidArgument(format!("Incorrect number of args, accepts 2-3 args:\n{}", GENERATE_USAGE)));
         }
 
         let dictionary_file_path = args[1].clone();
 
-        let solutions_dest_file_path = if args.len() > 2  && args[2] != "" {
+        let solutions_dest_file_path = if output_file_path.is_some() {
+            output_file_path
+        } else if args.len() > 2  && !args[2].is_empty() {
             Some(args[2].clone())
         } else {
             None
         };
 
-        let num_threads = if args.len() == 4 {
-            match args[3].clone().parse::<usize>() {
-                Ok(num) => num,
-                Err(_) => return Err("Could not parse the number of the number of threads argument")
+        // a database destination picks the sqlite format unless told otherwise
+        let output_format = match output_format {
+            Some(output_format) => output_format,
+            None => match &solutions_dest_file_path {
+                Some(file_path) if file_path.ends_with(".db") || file_path.ends_with(".sqlite") => OutputFormat::Sqlite,
+                _ => OutputFormat::Csv
             }
-        } else {
-            1
         };
 
-        Ok(GeneratorConfig { dictionary_file_path, num_threads, solutions_dest_file_path })
+        let compression = match compression {
+            Some(compression) => compression,
+            None => solutions_dest_file_path.as_deref().map(Compression::from_file_path).unwrap_or(Compression::None)
+        };
+
+        // the flag wins over the positional thread count
+        if threads_arg.is_none() && args.len() == 4 {
+            threads_arg = Some(args[3].clone());
+        }
+
+        let num_threads = match threads_arg {
+            Some(threads_arg) => {
+                let available_threads = thread::available_parallelism().map(|num| num.get()).unwrap_or(1);
+                parse_num_threads(&threads_arg, available_threads)?
+            },
+            None => 1
+        };
+
+        if min_tagged.is_some() && required_tags.is_empty() {
+            return Err("Min tagged needs a tag to count with --require-tag".into());
+        }
+
+        let min_tagged = min_tagged.unwrap_or(1);
+
+        if explain_format.is_some() && explain.is_none() {
+            return Err("Explain format needs a seed word to explain with --explain".into());
+        }
+
+        let explain_format = explain_format.unwrap_or(ReportFormat::Text);
+
+        let config = GeneratorConfig { dictionary_file_path, column_dictionary_file_path, tagged_dictionary, word_tags: WordTags::new(), num_threads, solutions_dest_file_path, backend, engine, fill_order, pruning, memo, chunk_prefix_length, solution_batch_size,
+            job_queue_capacity, solution_queue_capacity, checkpoint_file_path, resume, shard, output_format, solution_limit, compression, output_shard_size, count_only, preflight_only, worker_output, report_format, frequencies_file_path, top, sample, shuffle, seed, first_per_seed, score_by, deterministic, validate, log_level, progress_format, progress_file_path, csv_dialect,
+            required_words, pattern, letter_limits, min_vowels_per_word, max_repeated_letter, forbidden_letters, all_letters_different, symmetry, diagonal, required_tags, min_tagged, constraints_file_path, extra_constraints: Vec::new(), extra_soft_constraints: Vec::new(), beam_width, explain, explain_format };
+
+        config.validate()?;
+
+        Ok(config)
+    }
+
+    /* Checks the settings work together, for a config read from json as much as one built from
+      args. Whether a flag was given at all is only known from the args, those are checked in build */
+    pub fn validate(&self) -> Result<(), SquardleError> {
+
+        if self.output_format == OutputFormat::Sqlite && self.solutions_dest_file_path.is_none() {
+            return Err("The sqlite output format needs a solution destination file".into());
+        }
+
+        if self.output_format == OutputFormat::Binary && self.solutions_dest_file_path.is_none() {
+            return Err("The binary output format needs a solution destination file".into());
+        }
+
+        if self.column_dictionary_file_path.as_deref() == Some(STDIN_FILE_PATH) && self.dictionary_file_path == STDIN_FILE_PATH {
+            return Err("Only one of the row and column dictionaries can be read from stdin".into());
+        }
+
+        // the constrained fill and the preflight estimate take their rows and columns from the same words
+        if self.column_dictionary_file_path.is_some() && (self.fill_order == FillOrder::Constrained || self.preflight_only) {
+            return Err("A column dictionary can not be used with the constrained fill order or the preflight estimate".into());
+        }
+
+        if !self.required_tags.is_empty() && !self.tagged_dictionary {
+            return Err("Requiring a tag needs a tagged dictionary".into());
+        }
+
+        if self.resume && self.checkpoint_file_path.is_none() {
+            return Err("Resume needs a checkpoint file to resume from".into());
+        }
+
+        // stdin can't be read again to check a resumed run has the same dictionary
+        if self.checkpoint_file_path.is_some() && self.dictionary_file_path == STDIN_FILE_PATH {
+            return Err("Checkpointing needs a dictionary file rather than stdin".into());
+        }
+
+        if self.checkpoint_file_path.is_some() && self.solutions_dest_file_path.is_none() {
+            return Err("Checkpointing needs a solution destination file".into());
+        }
+
+        if self.compression != Compression::None && (self.output_format == OutputFormat::Sqlite || self.solutions_dest_file_path.is_none()) {
+            return Err("Compression needs a csv, jsonl, grid or binary solution destination file".into());
+        }
+
+        if self.output_shard_size.is_some() && (self.output_format == OutputFormat::Sqlite || self.output_format == OutputFormat::Binary || self.solutions_dest_file_path.is_none()) {
+            return Err("Output shards need a csv, jsonl or grid solution destination file".into());
+        }
+
+        if self.checkpoint_file_path.is_some() && self.output_shard_size.is_some() {
+            return Err("Checkpointing can not be used with sharded output files".into());
+        }
+
+        // only the seed word tallies are written when counting
+        if self.count_only && (self.checkpoint_file_path.is_some() || self.solution_limit.is_some() || self.output_shard_size.is_some()
+            || self.compression != Compression::None || self.output_format != OutputFormat::Csv) {
+            return Err("Counting only can not be used with a checkpoint, solution limit, output shards, compression or an output format".into());
+        }
+
+        if self.top.is_some() && self.score_by == ScoreBy::Frequency && self.frequencies_file_path.is_none() {
+            return Err("Scoring the top solutions by frequency needs a frequencies file".into());
+        }
+
+        if self.top.is_some() && (self.checkpoint_file_path.is_some() || self.solution_limit.is_some() || self.count_only) {
+            return Err("Top solutions can not be used with a checkpoint, solution limit or counting only".into());
+        }
+
+        // the sample is only known once every solution has been seen
+        if self.sample.is_some() && (self.checkpoint_file_path.is_some() || self.top.is_some() || self.count_only) {
+            return Err("Sampling can not be used with a checkpoint, top solutions or counting only".into());
+        }
+
+        if self.seed.is_some() && self.sample.is_none() && !self.shuffle {
+            return Err("Seed needs --sample or --shuffle".into());
+        }
+
+        // each worker tries words in its own order so a seed's solutions don't come out the same way every run
+        if self.shuffle && self.deterministic {
+            return Err("Deterministic output can not be used with a shuffled search order".into());
+        }
+
+        // the constrained fill tries words in dictionary order whichever slot it is filling
+        if self.shuffle && self.fill_order == FillOrder::Constrained {
+            return Err("Shuffling can not be used with the constrained fill order".into());
+        }
+
+        // the constrained fill already gives up as soon as any row or column has no words left
+        if self.pruning != Pruning::BadStarts && self.fill_order == FillOrder::Constrained {
+            return Err("Pruning can only be picked for the rows fill order".into());
+        }
+
+        if self.memo.is_some() && self.fill_order == FillOrder::Constrained {
+            return Err("The memo can only be used with the rows fill order".into());
+        }
+
+        // the letters the symmetry fixes are only screened for by the row by row search, the constrained fill could only check whole squares
+        if self.symmetry.is_some() && self.fill_order == FillOrder::Constrained {
+            return Err("Symmetry can only be used with the rows fill order".into());
+        }
+
+        // the constrained fill only finds one of a square and its transpose, which a pattern can tell apart
+        if self.pattern.is_some() && self.fill_order == FillOrder::Constrained {
+            return Err("A pattern can only be used with the rows fill order".into());
+        }
+
+        // the beam search runs on its own on the main thread and only ever holds the best few grids
+        if self.beam_width.is_some() && (self.checkpoint_file_path.is_some() || self.shard.is_some() || self.count_only || self.worker_output || self.top.is_some() || self.sample.is_some()
+            || self.shuffle || self.fill_order == FillOrder::Constrained) {
+            return Err("A beam search can not be used with a checkpoint, shard, counting only, worker output, top solutions, a sample, a shuffled search or the constrained fill order".into());
+        }
+
+        // the explanation follows the row by row search from the one seed
+        if self.explain.is_some() && (self.beam_width.is_some() || self.fill_order == FillOrder::Constrained) {
+            return Err("Explaining a seed can not be used with a beam search or the constrained fill order".into());
+        }
+
+        // nothing sees every solution go by when the workers write their own, to stop at a limit, score, pick or reorder them
+        if self.worker_output && (self.solutions_dest_file_path.is_none() || self.backend != GeneratorBackend::ThreadPool || self.output_format == OutputFormat::Sqlite
+            || self.output_format == OutputFormat::Binary || self.checkpoint_file_path.is_some() || self.solution_limit.is_some() || self.output_shard_size.is_some()
+            || self.count_only || self.top.is_some() || self.sample.is_some() || self.deterministic || self.frequencies_file_path.is_some()) {
+            return Err("Worker output needs the thread pool backend and a csv, jsonl or grid destination file, and can not be used with a checkpoint, solution limit, output shards, counting only, top solutions, a sample, deterministic output or frequencies".into());
+        }
+
+        // the brute force only knows the one dictionary and tries its words in order on one thread, any constraints are turned down when it starts
+        if self.engine == Engine::Naive && (self.column_dictionary_file_path.is_some() || self.backend != GeneratorBackend::ThreadPool || self.fill_order == FillOrder::Constrained
+            || self.pruning != Pruning::BadStarts || self.memo.is_some() || self.shuffle || self.worker_output || self.beam_width.is_some() || self.explain.is_some()) {
+            return Err("The naive engine can not be used with a column dictionary, the rayon backend, the constrained fill order, pruning, a memo, a shuffled search, worker output, a beam search or explaining a seed".into());
+        }
+
+        // the checkpoint writes solutions as soon as they arrive so can not reorder them
+        if self.deterministic && self.checkpoint_file_path.is_some() {
+            return Err("Deterministic output can not be used with a checkpoint".into());
+        }
+
+        // resuming truncates the output to a byte offset which a compressed stream can not do
+        if self.checkpoint_file_path.is_some() && self.compression != Compression::None {
+            return Err("Checkpointing can not be used with a compressed output file".into());
+        }
+
+        // resuming finds the seed of each line by splitting on commas and has no header to skip
+        if self.checkpoint_file_path.is_some() && (self.csv_dialect.delimiter != b',' || self.csv_dialect.output_header) {
+            return Err("Checkpointing can not be used with a delimiter other than a comma or an output header".into());
+        }
+
+        // a checkpoint has to be able to find every seed in the output and see all of its solutions
+        if self.checkpoint_file_path.is_some() && (self.output_format == OutputFormat::Grid || self.output_format == OutputFormat::Sqlite || self.output_format == OutputFormat::Binary || self.solution_limit.is_some()) {
+            return Err("Checkpointing can not be used with the grid, sqlite or binary output formats or a solution limit".into());
+        }
+
+        Ok(())
+    }
+
+    /* the tags read in with a tagged dictionary, for the required tags to pick words by */
+    pub fn with_word_tags(mut self, word_tags: WordTags) -> GeneratorConfig {
+        self.word_tags = word_tags;
+        self
+    }
+
+    /* for library users, a constraint of their own every square has to satisfy */
+    pub fn with_constraint(mut self, constraint: Arc<dyn Constraint>) -> GeneratorConfig {
+        self.extra_constraints.push(constraint);
+        self
+    }
+
+    /* and one a square is only penalised by the weight for breaking */
+    pub fn with_soft_constraint(mut self, constraint: Arc<dyn Constraint>, weight: f64) -> GeneratorConfig {
+        self.extra_soft_constraints.push(SoftConstraint { constraint, weight });
+        self
+    }
+
+    /* the built in constraints that were asked for and any extra ones, for squares of the word size */
+    pub fn constraints(&self, word_size: usize) -> Result<Vec<Arc<dyn Constraint>>, SquardleError> {
+
+        let mut constraints: Vec<Arc<dyn Constraint>> = Vec::new();
+
+        if let Some(pattern) = &self.pattern {
+            constraints.push(Arc::new(Pattern { fixed_letters: parse_partial_grid(pattern, word_size)? }));
+        }
+
+        for word in &self.required_words {
+            if word.chars().count() != word_size {
+                return Err(SquardleError::IncorrectWordSize { word: word.clone(), expected: word_size, found: word.chars().count() });
+            }
+
+            constraints.push(Arc::new(RequiredWord { word: word.clone() }));
+        }
+
+        if !self.letter_limits.is_empty() {
+            constraints.push(Arc::new(LetterLimits { limits: self.letter_limits.clone() }));
+        }
+
+        if self.all_letters_different {
+            constraints.push(Arc::new(AllLettersDifferent));
+        }
+
+        if let Some(diagonal) = &self.diagonal {
+            constraints.push(Arc::new(constraint::diagonal(diagonal, word_size)?));
+        }
+
+        match self.symmetry {
+            Some(Symmetry::Palindromic) => constraints.push(Arc::new(Palindromic)),
+            Some(Symmetry::AntiDiagonal) => constraints.push(Arc::new(AntiDiagonal)),
+            None => ()
+        }
+
+        if !self.required_tags.is_empty() {
+
+            let words = self.word_tags.iter()
+                .filter(|(_, tags)| tags.iter().any(|tag| self.required_tags.contains(tag)))
+                .map(|(word, _)| word.clone())
+                .collect();
+
+            constraints.push(Arc::new(TaggedWords { words, at_least: self.min_tagged }));
+        }
+
+        let word_rules = WordRules { min_vowels: self.min_vowels_per_word, max_repeated_letter: self.max_repeated_letter, forbidden_letters: self.forbidden_letters.clone() };
+
+        if !word_rules.is_empty() {
+            constraints.push(Arc::new(word_rules));
+        }
+
+        if let Some(constraints_file_path) = &self.constraints_file_path {
+            constraints.extend(constraint::load_constraints_file(constraints_file_path, word_size)?.hard);
+        }
+
+        constraints.extend(self.extra_constraints.iter().cloned());
+
+        // checked here as well for the ones from a file or a library user
+        if self.fill_order == FillOrder::Constrained && constraints.iter().any(|constraint| !constraint.is_symmetric()) {
+            return Err("Only constraints that allow a square and its transpose alike can be used with the constrained fill order".into());
+        }
+
+        Ok(constraints)
+    }
+
+    /* the soft constraints from the constraints file and any extra ones */
+    pub fn soft_constraints(&self, word_size: usize) -> Result<Vec<SoftConstraint>, SquardleError> {
+
+        let mut soft_constraints: Vec<SoftConstraint> = match &self.constraints_file_path {
+            Some(constraints_file_path) => constraint::load_constraints_file(constraints_file_path, word_size)?.soft,
+            None => Vec::new()
+        };
+
+        soft_constraints.extend(self.extra_soft_constraints.iter().cloned());
+
+        Ok(soft_constraints)
+    }
+}
+
+/* parses a thread count that is either an exact number, "auto" for all available threads,
+  "auto/N" for a fraction of them or "N%" for a percentage of them, never going below 1 */
+fn parse_num_threads(value: &str, available_threads: usize) -> Result<usize, SquardleError> {
+
+    let num_threads = if value == "auto" {
+        available_threads
+    } else if let Some(divisor) = value.strip_prefix("auto/") {
+        match divisor.parse::<usize>() {
+            Ok(divisor) if divisor > 0 => available_threads / divisor,
+            _ => return Err("Could not parse the divisor of the auto/N threads argument".into())
+        }
+    } else if let Some(percent) = value.strip_suffix('%') {
+        match percent.parse::<usize>() {
+            Ok(percent) if percent <= 100 => available_threads * percent / 100,
+            _ => return Err("Could not parse the percentage of the N% threads argument".into())
+        }
+    } else {
+        match value.parse::<usize>() {
+            Ok(num) => return Ok(num),
+            Err(_) => return Err("Could not parse the number of the number of threads argument".into())
+        }
+    };
+
+    Ok(num_threads.max(1))
+}
+
+/* the log level as its lowercase name in json, info say */
+/* the derived ones by way of the remote attribute, with the checks build makes on the way in */
+impl Serialize for GeneratorConfig {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        GeneratorConfig::serialize(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for GeneratorConfig {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<GeneratorConfig, D::Error> {
+
+        let config = GeneratorConfig::deserialize(deserializer)?;
+        config.validate().map_err(de::Error::custom)?;
+
+        Ok(config)
+    }
+}
+
+/* the delimiter as the character it is rather than its byte */
+mod delimiter {
+    use serde::{de, Deserialize, Deserializer, Serializer};
+    use super::CsvDialect;
+
+    pub fn serialize<S: Serializer>(delimiter: &u8, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&char::from(*delimiter).to_string())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u8, D::Error> {
+        CsvDialect::parse_delimiter(&String::deserialize(deserializer)?).map_err(de::Error::custom)
+    }
+}
+
+mod level_filter {
+    use log::LevelFilter;
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(log_level: &LevelFilter, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&log_level.as_str().to_lowercase())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<LevelFilter, D::Error> {
+        String::deserialize(deserializer)?.parse().map_err(de::Error::custom)
+    }
+}
+
+#[test]
+fn parse_auto_num_threads() {
+
+    assert_eq!(Some(8), parse_num_threads("8", 4).ok());
+    assert_eq!(Some(4), parse_num_threads("auto", 4).ok());
+    assert_eq!(Some(2), parse_num_threads("auto/2", 4).ok());
+    assert_eq!(Some(1), parse_num_threads("auto/8", 4).ok());
+    assert_eq!(Some(3), parse_num_threads("75%", 4).ok());
+    assert_eq!(Some(1), parse_num_threads("0%", 4).ok());
+    assert!(parse_num_threads("auto/0", 4).is_err());
+    assert!(parse_num_threads("150%", 4).is_err());
+    assert!(parse_num_threads("many", 4).is_err());
+}
+
+#[test]
+fn build_with_threads_flag() {
+
+    let args = vec!["exec name".to_string(), "words.csv".to_string(), "out.csv".to_string(), "2".to_string(), "--threads".to_string(), "3".to_string()];
+
+    assert_eq!(3, GeneratorConfig::build(&args).unwrap().num_threads);
+}
+
+#[test]
+fn build_with_rayon_backend() {
+
+    let args = vec!["exec name".to_string(), "words.csv".to_string(), "--backend".to_string(), "rayon".to_string(), "out.csv".to_string(), "4".to_string()];
+
+    let config = GeneratorConfig::build(&args).unwrap();
+
+    assert_eq!(GeneratorBackend::Rayon, config.backend);
+    assert_eq!(Some("out.csv".to_string()), config.solutions_dest_file_path);
+    assert_eq!(4, config.num_threads);
+}
+
+#[test]
+fn build_with_unknown_backend() {
+
+    let args = vec!["exec name".to_string(), "words.csv".to_string(), "--backend".to_string(), "fibers".to_string()];
+
+    assert!(GeneratorConfig::build(&args).is_err());
+}
+
+#[test]
+fn build_with_chunk_prefix() {
+
+    let args = vec!["exec name".to_string(), "words.csv".to_string(), "--chunk-prefix".to_string(), "3".to_string()];
+
+    assert_eq!(3, GeneratorConfig::build(&args).unwrap().chunk_prefix_length);
+
+    let args = vec!["exec name".to_string(), "words.csv".to_string(), "--chunk-prefix".to_string(), "0".to_string()];
+
+    assert!(GeneratorConfig::build(&args).is_err());
+}
+
+#[test]
+fn build_resume_needs_checkpoint_and_destination() {
+
+    let args = vec!["exec name".to_string(), "words.csv".to_string(), "out.csv".to_string(), "--resume".to_string()];
+
+    assert!(GeneratorConfig::build(&args).is_err());
+
+    let args = vec!["exec name".to_string(), "words.csv".to_string(), "--checkpoint".to_string(), "state.json".to_string()];
+
+    assert!(GeneratorConfig::build(&args).is_err());
+
+    let args = vec!["exec name".to_string(), "words.csv".to_string(), "out.csv".to_string(), "--checkpoint".to_string(), "state.json".to_string(), "--resume".to_string()];
+
+    let config = GeneratorConfig::build(&args).unwrap();
+
+    assert_eq!(Some("state.json".to_string()), config.checkpoint_file_path);
+    assert!(config.resume);
+}
+
+#[test]
+fn parse_shard() {
+
+    assert_eq!(Some(Shard { index: 3, count: 16 }), Shard::parse("3/16").ok());
+    assert!(Shard::parse("0/16").is_err());
+    assert!(Shard::parse("17/16").is_err());
+    assert!(Shard::parse("3").is_err());
+}
+
+#[test]
+fn shards_are_disjoint_and_cover_all_seeds() {
+
+    let shards: Vec<Shard> = (1..=3).map(|index| Shard { index, count: 3 }).collect();
+
+    for seed_index in 0..10 {
+        assert_eq!(1, shards.iter().filter(|shard| shard.contains(seed_index)).count());
     }
 }
+
+#[test]
+fn build_with_output_format() {
+
+    let args = vec!["exec name".to_string(), "words.csv".to_string(), "out.jsonl".to_string(), "--output-format".to_string(), "jsonl".to_string()];
+
+    assert_eq!(OutputFormat::Jsonl, GeneratorConfig::build(&args).unwrap().output_format);
+
+    let args = vec!["exec name".to_string(), "words.csv".to_string(), "--output-format".to_string(), "xml".to_string()];
+
+    assert!(GeneratorConfig::build(&args).is_err());
+
+    let args: Vec<String> = ["exec name", "words.csv", "--output-format", "binary"].iter().map(|arg| arg.to_string()).collect();
+
+    assert!(GeneratorConfig::build(&args).is_err());
+}
+
+#[test]
+fn build_with_grid_and_limit() {
+
+    let args = vec!["exec name".to_string(), "words.csv".to_string(), "--output-format".to_string(), "grid".to_string(), "--limit".to_string(), "5".to_string()];
+
+    let config = GeneratorConfig::build(&args).unwrap();
+
+    assert_eq!(OutputFormat::Grid, config.output_format);
+    assert_eq!(Some(5), config.solution_limit);
+
+    let args = vec!["exec name".to_string(), "words.csv".to_string(), "out.txt".to_string(), "--limit".to_string(), "5".to_string(), "--checkpoint".to_string(), "state.json".to_string()];
+
+    assert!(GeneratorConfig::build(&args).is_err());
+}
+
+#[test]
+fn build_with_sqlite_output() {
+
+    let args = vec!["exec name".to_string(), "words.csv".to_string(), "--output".to_string(), "solutions.db".to_string()];
+
+    let config = GeneratorConfig::build(&args).unwrap();
+
+    assert_eq!(OutputFormat::Sqlite, config.output_format);
+    assert_eq!(Some("solutions.db".to_string()), config.solutions_dest_file_path);
+
+    let args = vec!["exec name".to_string(), "words.csv".to_string(), "--output-format".to_string(), "sqlite".to_string()];
+
+    assert!(GeneratorConfig::build(&args).is_err());
+}
+
+#[test]
+fn build_with_compression() {
+
+    let args = vec!["exec name".to_string(), "words.csv".to_string(), "out.csv.zst".to_string()];
+
+    assert_eq!(Compression::Zstd, GeneratorConfig::build(&args).unwrap().compression);
+
+    let args = vec!["exec name".to_string(), "words.csv".to_string(), "out.zst".to_string(), "--compress".to_string(), "gzip".to_string()];
+
+    assert_eq!(Compression::Gzip, GeneratorConfig::build(&args).unwrap().compression);
+
+    let args = vec!["exec name".to_string(), "words.csv".to_string(), "out.csv.gz".to_string(), "--checkpoint".to_string(), "state.json".to_string()];
+
+    assert!(GeneratorConfig::build(&args).is_err());
+}
+
+#[test]
+fn build_with_output_shard_size() {
+
+    let args = vec!["exec name".to_string(), "words.csv".to_string(), "solutions.csv".to_string(), "--output-shard-size".to_string(), "1000".to_string()];
+
+    assert_eq!(Some(1000), GeneratorConfig::build(&args).unwrap().output_shard_size);
+
+    let args = vec!["exec name".to_string(), "words.csv".to_string(), "--output-shard-size".to_string(), "1000".to_string()];
+
+    assert!(GeneratorConfig::build(&args).is_err());
+}
+
+#[test]
+fn build_count_only() {
+
+    let args = vec!["exec name".to_string(), "words.csv".to_string(), "--count-only".to_string()];
+
+    assert!(GeneratorConfig::build(&args).unwrap().count_only);
+
+    let args = vec!["exec name".to_string(), "words.csv".to_string(), "--count-only".to_string(), "--limit".to_string(), "3".to_string()];
+
+    assert!(GeneratorConfig::build(&args).is_err());
+}
+
+#[test]
+fn build_preflight_only() {
+
+    let args = vec!["exec name".to_string(), "words.csv".to_string(), "--preflight-only".to_string()];
+
+    let config = GeneratorConfig::build(&args).unwrap();
+    assert!(config.preflight_only && !config.count_only);
+}
+
+#[test]
+fn build_with_word_rules() {
+
+    let args: Vec<String> = ["exec name", "words.csv", "--min-vowels-per-word", "2", "--max-repeated-letter", "1", "--forbid-letters", "QX", "--forbid-letters", "z"].iter().map(|arg| arg.to_string()).collect();
+    let config = GeneratorConfig::build(&args).unwrap();
+
+    assert_eq!((2, Some(1)), (config.min_vowels_per_word, config.max_repeated_letter));
+    assert_eq!(HashSet::from(['q', 'x', 'z']), config.forbidden_letters);
+    // the three rules are one constraint
+    assert_eq!(1, config.constraints(5).unwrap().len());
+
+    let args: Vec<String> = ["exec name", "words.csv", "--max-repeated-letter", "0"].iter().map(|arg| arg.to_string()).collect();
+    assert!(GeneratorConfig::build(&args).is_err());
+}
+
+#[test]
+fn build_with_explain() {
+
+    let args: Vec<String> = ["exec name", "words.csv", "--explain", "Crane", "--explain-format", "json"].iter().map(|arg| arg.to_string()).collect();
+    let config = GeneratorConfig::build(&args).unwrap();
+
+    assert_eq!((Some("crane".to_string()), ReportFormat::Json), (config.explain, config.explain_format));
+    assert_eq!(ReportFormat::Text, GeneratorConfig::build(&args[..4]).unwrap().explain_format);
+
+    let args: Vec<String> = ["exec name", "words.csv", "--explain-format", "json"].iter().map(|arg| arg.to_string()).collect();
+    assert!(GeneratorConfig::build(&args).is_err());
+
+    let args: Vec<String> = ["exec name", "words.csv", "--explain", "crane", "--beam", "10"].iter().map(|arg| arg.to_string()).collect();
+    assert!(GeneratorConfig::build(&args).is_err());
+}
+
+#[test]
+fn build_with_beam() {
+
+    let args: Vec<String> = ["exec name", "words.csv", "--beam", "100", "--limit", "10"].iter().map(|arg| arg.to_string()).collect();
+    let config = GeneratorConfig::build(&args).unwrap().with_soft_constraint(Arc::new(RequiredWord { word: "crane".to_string() }), 2.0);
+
+    assert_eq!(Some(100), config.beam_width);
+    assert_eq!(1, config.soft_constraints(5).unwrap().len());
+
+    let args: Vec<String> = ["exec name", "words.csv", "--beam", "100", "--count-only"].iter().map(|arg| arg.to_string()).collect();
+    assert!(GeneratorConfig::build(&args).is_err());
+
+    let args: Vec<String> = ["exec name", "words.csv", "--beam", "0"].iter().map(|arg| arg.to_string()).collect();
+    assert!(GeneratorConfig::build(&args).is_err());
+}
+
+#[test]
+fn build_with_constraints() {
+
+    let args: Vec<String> = ["exec name", "words.csv", "--require", "crane", "--pattern", "c....", "--max-letter", "e:2"].iter().map(|arg| arg.to_string()).collect();
+
+    let config = GeneratorConfig::build(&args).unwrap();
+
+    assert_eq!(3, config.constraints(5).unwrap().len());
+    // crane isn't a three letter word
+    assert!(config.constraints(3).is_err());
+
+    let args: Vec<String> = ["exec name", "words.csv", "--pattern", "c....", "--fill-order", "constrained"].iter().map(|arg| arg.to_string()).collect();
+
+    assert!(GeneratorConfig::build(&args).is_err());
+}
+
+#[test]
+fn build_with_report() {
+
+    let args = vec!["exec name".to_string(), "words.csv".to_string(), "--report".to_string(), "json".to_string()];
+
+    assert_eq!(Some(ReportFormat::Json), GeneratorConfig::build(&args).unwrap().report_format);
+
+    let args = vec!["exec name".to_string(), "words.csv".to_string(), "--report".to_string()];
+
+    assert!(GeneratorConfig::build(&args).is_err());
+}
+
+#[test]
+fn build_with_top() {
+
+    let args = vec!["exec name".to_string(), "words.csv".to_string(), "--top".to_string(), "100".to_string(), "--score-by".to_string(), "frequency".to_string(),
+        "--frequencies".to_string(), "counts.csv".to_string()];
+
+    assert_eq!(Some(100), GeneratorConfig::build(&args).unwrap().top);
+
+    let args = vec!["exec name".to_string(), "words.csv".to_string(), "--top".to_string(), "100".to_string()];
+
+    assert!(GeneratorConfig::build(&args).is_err());
+}
+
+#[test]
+fn build_with_sample() {
+
+    let args = vec!["exec name".to_string(), "words.csv".to_string(), "--sample".to_string(), "1000".to_string(), "--seed".to_string(), "7".to_string()];
+    let config = GeneratorConfig::build(&args).unwrap();
+
+    assert_eq!(Some(1000), config.sample);
+    assert_eq!(Some(7), config.seed);
+
+    let args = vec!["exec name".to_string(), "words.csv".to_string(), "--seed".to_string(), "7".to_string()];
+
+    assert!(GeneratorConfig::build(&args).is_err());
+
+    let args = vec!["exec name".to_string(), "words.csv".to_string(), "--sample".to_string(), "10".to_string(), "--count-only".to_string()];
+
+    assert!(GeneratorConfig::build(&args).is_err());
+}
+
+#[test]
+fn build_with_shuffle() {
+
+    let args = vec!["exec name".to_string(), "words.csv".to_string(), "--shuffle".to_string(), "--seed".to_string(), "42".to_string(), "--limit".to_string(), "100".to_string()];
+    let config = GeneratorConfig::build(&args).unwrap();
+
+    assert!(config.shuffle);
+    assert_eq!(Some(42), config.seed);
+
+    let args = vec!["exec name".to_string(), "words.csv".to_string(), "--shuffle".to_string(), "--deterministic".to_string()];
+
+    assert!(GeneratorConfig::build(&args).is_err());
+}
+
+#[test]
+fn build_with_first_per_seed() {
+
+    let args = vec!["exec name".to_string(), "words.csv".to_string(), "--first-per-seed".to_string(), "--count-only".to_string()];
+    let config = GeneratorConfig::build(&args).unwrap();
+
+    assert!(config.first_per_seed);
+    assert!(config.count_only);
+}
+
+#[test]
+fn build_with_fill_order() {
+
+    let args = vec!["exec name".to_string(), "words.csv".to_string()];
+    assert_eq!(FillOrder::Rows, GeneratorConfig::build(&args).unwrap().fill_order);
+
+    let args = vec!["exec name".to_string(), "words.csv".to_string(), "--fill-order".to_string(), "constrained".to_string()];
+    assert_eq!(FillOrder::Constrained, GeneratorConfig::build(&args).unwrap().fill_order);
+
+    let args = vec!["exec name".to_string(), "words.csv".to_string(), "--fill-order".to_string(), "columns".to_string()];
+    assert!(GeneratorConfig::build(&args).is_err());
+}
+
+#[test]
+fn build_with_pruning() {
+
+    let args = vec!["exec name".to_string(), "words.csv".to_string()];
+    assert_eq!(Pruning::BadStarts, GeneratorConfig::build(&args).unwrap().pruning);
+
+    let args = vec!["exec name".to_string(), "words.csv".to_string(), "--pruning".to_string(), "forward-check".to_string()];
+    assert_eq!(Pruning::ForwardCheck, GeneratorConfig::build(&args).unwrap().pruning);
+
+    let args = vec!["exec name".to_string(), "words.csv".to_string(), "--pruning".to_string(), "forward-check".to_string(), "--fill-order".to_string(), "constrained".to_string()];
+    assert!(GeneratorConfig::build(&args).is_err());
+}
+
+#[test]
+fn build_with_engine() {
+
+    let args = vec!["exec name".to_string(), "words.csv".to_string()];
+    assert_eq!(Engine::PrefixMap, GeneratorConfig::build(&args).unwrap().engine);
+
+    let args: Vec<String> = ["exec name", "words.csv", "out.csv", "--engine", "naive", "--limit", "5"].iter().map(|arg| arg.to_string()).collect();
+    assert_eq!(Engine::Naive, GeneratorConfig::build(&args).unwrap().engine);
+
+    let args: Vec<String> = ["exec name", "words.csv", "--engine", "trie"].iter().map(|arg| arg.to_string()).collect();
+    assert!(GeneratorConfig::build(&args).is_err());
+
+    // the brute force is the one thing left to check the others against so it has none of their options
+    let args: Vec<String> = ["exec name", "words.csv", "--engine", "naive", "--memo", "100"].iter().map(|arg| arg.to_string()).collect();
+    assert!(GeneratorConfig::build(&args).is_err());
+}
+
+#[test]
+fn build_with_symmetry() {
+
+    let args = vec!["exec name".to_string(), "words.csv".to_string(), "--symmetry".to_string(), "anti-diagonal".to_string()];
+    assert_eq!(Some(Symmetry::AntiDiagonal), GeneratorConfig::build(&args).unwrap().symmetry);
+
+    let args = vec!["exec name".to_string(), "words.csv".to_string(), "--symmetry".to_string(), "palindromic".to_string(), "--fill-order".to_string(), "constrained".to_string()];
+    assert!(GeneratorConfig::build(&args).is_err());
+
+    let args = vec!["exec name".to_string(), "words.csv".to_string(), "--symmetry".to_string(), "diagonal".to_string()];
+    assert!(GeneratorConfig::build(&args).is_err());
+}
+
+#[test]
+fn build_with_diagonal() {
+
+    let args = vec!["exec name".to_string(), "words.csv".to_string(), "--diagonal".to_string(), "Heart".to_string(), "--fill-order".to_string(), "constrained".to_string()];
+    let config = GeneratorConfig::build(&args).unwrap();
+
+    // the diagonal is the same in the transpose so the constrained fill can have it
+    assert_eq!(Some("heart".to_string()), config.diagonal);
+    assert_eq!(1, config.constraints(5).unwrap().len());
+    assert!(config.constraints(4).is_err());
+}
+
+#[test]
+fn build_with_memo() {
+
+    let args = vec!["exec name".to_string(), "words.csv".to_string(), "--memo".to_string(), "1000".to_string()];
+    assert_eq!(Some(1000), GeneratorConfig::build(&args).unwrap().memo);
+
+    let args = vec!["exec name".to_string(), "words.csv".to_string(), "--memo".to_string(), "0".to_string()];
+    assert!(GeneratorConfig::build(&args).is_err());
+}
+
+#[test]
+fn build_with_worker_output() {
+
+    let args: Vec<String> = ["exec name", "words.csv", "out.csv.gz", "4", "--worker-output"].iter().map(|arg| arg.to_string()).collect();
+    assert!(GeneratorConfig::build(&args).unwrap().worker_output);
+
+    let args: Vec<String> = ["exec name", "words.csv", "out.csv", "--worker-output", "--backend", "rayon"].iter().map(|arg| arg.to_string()).collect();
+    assert!(GeneratorConfig::build(&args).is_err());
+
+    let args: Vec<String> = ["exec name", "words.csv", "out.csv", "--worker-output", "--limit", "5"].iter().map(|arg| arg.to_string()).collect();
+    assert!(GeneratorConfig::build(&args).is_err());
+}
+
+#[test]
+fn build_with_queue_capacities() {
+
+    let args = vec!["exec name".to_string(), "words.csv".to_string()];
+    let config = GeneratorConfig::build(&args).unwrap();
+    assert_eq!((8, 1024), (config.job_queue_capacity, config.solution_queue_capacity));
+
+    let args: Vec<String> = ["exec name", "words.csv", "--job-queue", "2", "--solution-queue", "16"].iter().map(|arg| arg.to_string()).collect();
+    let config = GeneratorConfig::build(&args).unwrap();
+    assert_eq!((2, 16), (config.job_queue_capacity, config.solution_queue_capacity));
+
+    let args: Vec<String> = ["exec name", "words.csv", "--solution-queue", "0"].iter().map(|arg| arg.to_string()).collect();
+    assert!(GeneratorConfig::build(&args).is_err());
+}
+
+#[test]
+fn build_with_verbosity() {
+
+    let args = vec!["exec name".to_string(), "words.csv".to_string()];
+
+    assert_eq!(LevelFilter::Info, GeneratorConfig::build(&args).unwrap().log_level);
+
+    let args = vec!["exec name".to_string(), "words.csv".to_string(), "-vv".to_string()];
+
+    assert_eq!(LevelFilter::Trace, GeneratorConfig::build(&args).unwrap().log_level);
+
+    let args = vec!["exec name".to_string(), "-q".to_string(), "words.csv".to_string()];
+
+    assert_eq!(LevelFilter::Warn, GeneratorConfig::build(&args).unwrap().log_level);
+}
+
+#[test]
+fn build_with_json_progress() {
+
+    let args = vec!["exec name".to_string(), "words.csv".to_string(), "--progress-format".to_string(), "json".to_string(), "--progress-file".to_string(), "progress.pipe".to_string()];
+
+    let config = GeneratorConfig::build(&args).unwrap();
+
+    assert_eq!(ProgressFormat::Json, config.progress_format);
+    assert_eq!(Some("progress.pipe".to_string()), config.progress_file_path);
+}
+
+#[test]
+fn build_with_validate() {
+
+    let args = vec!["exec name".to_string(), "words.csv".to_string(), "--validate".to_string()];
+
+    assert!(GeneratorConfig::build(&args).unwrap().validate);
+}
+
+#[test]
+fn build_with_dictionary_from_stdin() {
+
+    let args: Vec<String> = ["exec name", "--dictionary", "-", "-q"].iter().map(|arg| arg.to_string()).collect();
+    let config = GeneratorConfig::build(&args).unwrap();
+
+    assert_eq!("-", config.dictionary_file_path);
+    assert_eq!(None, config.solutions_dest_file_path);
+
+    let args: Vec<String> = ["exec name", "out.csv", "--dictionary", "words.csv", "2"].iter().map(|arg| arg.to_string()).collect();
+    let config = GeneratorConfig::build(&args).unwrap();
+
+    assert_eq!("words.csv", config.dictionary_file_path);
+    assert_eq!(Some("out.csv".to_string()), config.solutions_dest_file_path);
+    assert_eq!(2, config.num_threads);
+
+    let args: Vec<String> = ["exec name", "-", "out.csv", "--checkpoint", "out.checkpoint"].iter().map(|arg| arg.to_string()).collect();
+    assert!(GeneratorConfig::build(&args).is_err());
+}
+
+#[test]
+fn build_with_column_dictionary() {
+
+    let args: Vec<String> = ["exec name", "out.csv", "--row-dictionary", "english.csv", "--col-dictionary", "french.csv"].iter().map(|arg| arg.to_string()).collect();
+    let config = GeneratorConfig::build(&args).unwrap();
+
+    assert_eq!("english.csv", config.dictionary_file_path);
+    assert_eq!(Some("french.csv".to_string()), config.column_dictionary_file_path);
+
+    assert_eq!(None, GeneratorConfig::build(&["exec name".to_string(), "words.csv".to_string()]).unwrap().column_dictionary_file_path);
+
+    let args: Vec<String> = ["exec name", "-", "--col-dictionary", "-"].iter().map(|arg| arg.to_string()).collect();
+    assert!(GeneratorConfig::build(&args).is_err());
+
+    let args: Vec<String> = ["exec name", "english.csv", "--col-dictionary", "french.csv", "--fill-order", "constrained"].iter().map(|arg| arg.to_string()).collect();
+    assert!(GeneratorConfig::build(&args).is_err());
+}
+
+#[test]
+fn build_with_required_tags() {
+
+    let args: Vec<String> = ["exec name", "words.csv", "--tagged-dictionary", "--require-tag", "Animal", "--min-tagged", "2"].iter().map(|arg| arg.to_string()).collect();
+
+    let word_tags: WordTags = [("cat".to_string(), vec!["animal".to_string()]), ("elm".to_string(), vec!["tree".to_string()])].into_iter().collect();
+    let config = GeneratorConfig::build(&args).unwrap().with_word_tags(word_tags);
+
+    assert_eq!((vec!["animal".to_string()], 2), (config.required_tags.clone(), config.min_tagged));
+    assert_eq!(1, config.constraints(3).unwrap().len());
+
+    // the tags have to come from somewhere
+    let args: Vec<String> = ["exec name", "words.csv", "--require-tag", "animal"].iter().map(|arg| arg.to_string()).collect();
+    assert!(GeneratorConfig::build(&args).is_err());
+
+    let args: Vec<String> = ["exec name", "words.csv", "--tagged-dictionary", "--min-tagged", "2"].iter().map(|arg| arg.to_string()).collect();
+    assert!(GeneratorConfig::build(&args).is_err());
+}
+
+#[test]
+fn build_with_csv_dialect() {
+
+    let args: Vec<String> = ["exec name", "words.tsv", "out.csv", "--delimiter", "tab", "--dictionary-header", "--output-header"].iter().map(|arg| arg.to_string()).collect();
+    let config = GeneratorConfig::build(&args).unwrap();
+
+    assert_eq!(CsvDialect { delimiter: b'\t', dictionary_header: true, output_header: true }, config.csv_dialect);
+    assert_eq!(b';', CsvDialect::parse_delimiter(";").unwrap());
+    assert!(CsvDialect::parse_delimiter("\"").is_err());
+    assert!(CsvDialect::parse_delimiter("ab").is_err());
+
+    let args: Vec<String> = ["exec name", "words.csv", "out.csv", "--checkpoint", "out.checkpoint", "--output-header"].iter().map(|arg| arg.to_string()).collect();
+    assert!(GeneratorConfig::build(&args).is_err());
+}
+
+#[test]
+fn config_as_json() {
+
+    let args: Vec<String> = ["exec name", "words.csv", "out.jsonl", "2", "--engine", "naive", "--shard", "3/16", "-v", "--require", "heart", "--max-letter", "e:2"].iter().map(|arg| arg.to_string()).collect();
+    let config = GeneratorConfig::build(&args).unwrap();
+
+    let json = serde_json::to_value(&config).unwrap();
+
+    assert_eq!("naive", json["engine"]);
+    assert_eq!("threads", json["backend"]);
+    assert_eq!("debug", json["log_level"]);
+    assert_eq!(serde_json::json!({ "index": 3, "count": 16 }), json["shard"]);
+
+    assert_eq!(",", json["csv_dialect"]["delimiter"]);
+
+    let read_back: GeneratorConfig = serde_json::from_value(json.clone()).unwrap();
+    assert_eq!(json, serde_json::to_value(&read_back).unwrap());
+
+    // checked the same as args, the naive engine only runs on the thread pool
+    let mut rayon_json = json.clone();
+    rayon_json["backend"] = "rayon".into();
+    let err = serde_json::from_value::<GeneratorConfig>(rayon_json).unwrap_err();
+    assert!(err.to_string().contains("The naive engine can not be used"));
+
+    let mut tab_json = json;
+    tab_json["csv_dialect"]["delimiter"] = "tab".into();
+    assert_eq!(b'\t', serde_json::from_value::<GeneratorConfig>(tab_json).unwrap().csv_dialect.delimiter);
+}
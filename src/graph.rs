@@ -0,0 +1,161 @@
+use std::collections::HashSet;
+use std::io::Write;
+use std::rc::Rc;
+use crate::error::SquardleError;
+use crate::generator_config::Compression;
+use crate::output::OutputWriter;
+
+#[derive(Debug, PartialEq)]
+pub enum GraphFormat {
+    // source,target a line
+    Csv,
+    Dot
+}
+
+/* a letter as a bit, letters outside a to z have none so a word with one never connects */
+fn letter_bit(letter: char) -> u32 {
+    match letter {
+        'a'..='z' => 1 << (letter as u32 - 'a' as u32),
+        _ => 0
+    }
+}
+
+/* Every pair of words that can be the first two rows of a square, the two letters of each column
+  then start some dictionary word. It's the check the solver makes on the second row, and a
+  transposed square makes the same pair the first two columns, so a pair with no edge can never
+  open a square together. An edge doesn't promise a square though, the rows further down can
+  still run out of words */
+pub fn adjacent_word_pairs(dictionary: &[String]) -> impl Iterator<Item = (&String, &String)> + '_ {
+
+    let pairs: HashSet<(char, char)> = dictionary.iter().filter_map(|word| {
+        let mut letters = word.chars();
+        Some((letters.next()?, letters.next()?))
+    }).collect();
+
+    // for each word the letters that can go below each of its letters
+    let allowed_below: Vec<Vec<u32>> = dictionary.iter().map(|word| word.chars().map(|above| {
+        pairs.iter().filter(|(first, _)| *first == above).fold(0, |mask, (_, below)| mask | letter_bit(*below))
+    }).collect()).collect();
+
+    let letter_bits: Rc<Vec<Vec<u32>>> = Rc::new(dictionary.iter().map(|word| word.chars().map(letter_bit).collect()).collect());
+
+    (0..dictionary.len()).flat_map(move |above| {
+        let allowed = allowed_below[above].clone();
+        let letter_bits = Rc::clone(&letter_bits);
+
+        (0..dictionary.len())
+            .filter(move |below| *below != above && allowed.iter().zip(&letter_bits[*below]).all(|(allowed, bit)| allowed & bit != 0))
+            .map(move |below| (&dictionary[above], &dictionary[below]))
+    })
+}
+
+/* writes the graph of which words can open a square together, returns how many edges it has */
+pub fn write_word_graph(dictionary: &[String], dest_file_path: &str, graph_format: &GraphFormat) -> Result<usize, SquardleError> {
+
+    let mut dest_file = OutputWriter::create(dest_file_path, Compression::from_file_path(dest_file_path))?;
+    let mut edge_count = 0;
+
+    if *graph_format == GraphFormat::Dot {
+        writeln!(dest_file, "digraph squardle {{")?;
+
+        // every word is a node so the ones with no edges show up too
+        for word in dictionary {
+            writeln!(dest_file, "  \"{}\";", word)?;
+        }
+    }
+
+    for (above, below) in adjacent_word_pairs(dictionary) {
+
+        match graph_format {
+            GraphFormat::Csv => writeln!(dest_file, "{},{}", above, below)?,
+            GraphFormat::Dot => writeln!(dest_file, "  \"{}\" -> \"{}\";", above, below)?
+        }
+
+        edge_count += 1;
+    }
+
+    if *graph_format == GraphFormat::Dot {
+        writeln!(dest_file, "}}")?;
+    }
+
+    dest_file.finish()?;
+
+    Ok(edge_count)
+}
+
+/* graph dictionary_file dest_file with optional --graph-format csv|dot, picked from the destination's extension otherwise */
+#[derive(Debug, PartialEq)]
+pub struct GraphConfig {
+    pub dictionary_file_path: String,
+    pub dest_file_path: String,
+    pub graph_format: GraphFormat
+}
+
+impl GraphConfig {
+
+    pub fn build(args: &[String]) -> Result<GraphConfig, SquardleError> {
+
+        let mut graph_format: Option<GraphFormat> = None;
+        let mut positional_args: Vec<String> = Vec::with_capacity(2);
+        let mut args_iter = args.iter();
+
+        while let Some(arg) = args_iter.next() {
+
+            match arg.as_str() {
+                "--graph-format" => graph_format = match args_iter.next().map(String::as_str) {
+                    Some("csv") => Some(GraphFormat::Csv),
+                    Some("dot") => Some(GraphFormat::Dot),
+                    _ => return Err("Graph format must be one of: csv, dot".into())
+                },
+                _ => positional_args.push(arg.clone())
+            }
+        }
+
+        let [dictionary_file_path, dest_file_path]: [String; 2] = positional_args.try_into()
+            .map_err(|_| SquardleError::from("graph accepts a dictionary file path, a destination file path and optionally --graph-format csv|dot"))?;
+
+        let graph_format = graph_format.unwrap_or_else(|| match dest_file_path.trim_end_matches(".gz").trim_end_matches(".zst") {
+            file_path if file_path.ends_with(".dot") || file_path.ends_with(".gv") => GraphFormat::Dot,
+            _ => GraphFormat::Csv
+        });
+
+        Ok(GraphConfig { dictionary_file_path, dest_file_path, graph_format })
+    }
+}
+
+#[test]
+fn adjacent_words_of_a_dictionary() {
+
+    // the first two rows of ten,ore,eat and toe,era,net
+    let dictionary: Vec<String> = ["eat", "era", "net", "ore", "ten", "toe"].iter().map(|word| word.to_string()).collect();
+    let pairs: Vec<(&String, &String)> = adjacent_word_pairs(&dictionary).collect();
+
+    let has_pair = |above: &str, below: &str| pairs.iter().any(|(a, b)| *a == above && *b == below);
+
+    assert!(has_pair("ten", "ore"));
+    assert!(has_pair("toe", "era"));
+
+    // eat is below ore in the square but no word starts oe, only the first two rows are checked
+    assert!(!has_pair("ore", "eat"));
+
+    // no word starts tn so net can't go below ten
+    assert!(!has_pair("ten", "net"));
+    assert!(pairs.iter().all(|(above, below)| above != below));
+}
+
+#[test]
+fn write_graph_as_dot() {
+
+    let args: Vec<String> = ["words.csv", "test_graph.dot"].iter().map(|arg| arg.to_string()).collect();
+    assert_eq!(GraphFormat::Dot, GraphConfig::build(&args).unwrap().graph_format);
+
+    let dictionary: Vec<String> = ["eat", "era", "net", "ore", "ten", "toe"].iter().map(|word| word.to_string()).collect();
+    let edge_count = write_word_graph(&dictionary, "test_graph.dot", &GraphFormat::Dot).unwrap();
+    let dot = std::fs::read_to_string("test_graph.dot").unwrap();
+    std::fs::remove_file("test_graph.dot").unwrap();
+
+    assert_eq!(edge_count, dot.matches(" -> ").count());
+    assert!(dot.starts_with("digraph squardle {\n  \"eat\";\n"));
+    assert!(dot.contains("  \"ten\" -> \"ore\";\n"));
+    assert!(dot.ends_with("}\n"));
+}
@@ -0,0 +1,112 @@
+use std::collections::HashSet;
+use crate::error::SquardleError;
+use crate::feedback::{self, LetterFeedback, Line};
+use crate::solution_generator::construct_potential_transposed_puzzle;
+
+const VOWELS: [char; 5] = ['a', 'e', 'i', 'o', 'u'];
+
+/* the hints a player can ask for, from the one that gives away the least */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HintKind {
+    Vowels,
+    Line,
+    Letter
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Hint {
+    // cells to reveal as row, column and letter
+    Letters(Vec<(usize, usize, char)>),
+    // a word the player has guessed that is the answer of another line
+    WordLine { word: String, line: Line }
+}
+
+/* Works out a hint of the kind asked for from the hidden square, the cells the player can see
+  and the guesses they've made so far. None when that kind of hint has nothing left to give */
+pub fn hint(square: &[String], revealed: &[Vec<bool>], guesses: &[(Line, String)], kind: HintKind) -> Result<Option<Hint>, SquardleError> {
+
+    let grid: Vec<Vec<char>> = square.iter().map(|row| row.chars().collect()).collect();
+    let hidden_cells: Vec<(usize, usize)> = (0..grid.len())
+        .flat_map(|row| (0..grid.len()).map(move |column| (row, column)))
+        .filter(|(row, column)| !revealed[*row][*column])
+        .collect();
+
+    match kind {
+        HintKind::Vowels => {
+            let vowels: Vec<(usize, usize, char)> = hidden_cells.iter()
+                .map(|(row, column)| (*row, *column, grid[*row][*column]))
+                .filter(|(_, _, letter)| VOWELS.contains(letter))
+                .collect();
+
+            Ok((!vowels.is_empty()).then_some(Hint::Letters(vowels)))
+        },
+        HintKind::Line => {
+            let columns = construct_potential_transposed_puzzle(square);
+            let lines = square.iter().enumerate().map(|(i, row)| (Line::Row(i), row))
+                .chain(columns.iter().enumerate().map(|(i, column)| (Line::Column(i), column)));
+
+            for (line, answer) in lines {
+
+                let solved = (0..grid.len()).all(|i| match line {
+                    Line::Row(row) => revealed[row][i],
+                    Line::Column(column) => revealed[i][column]
+                });
+
+                if !solved && guesses.iter().any(|(guessed_line, word)| *guessed_line != line && word == answer) {
+                    return Ok(Some(Hint::WordLine { word: answer.clone(), line }));
+                }
+            }
+
+            Ok(None)
+        },
+        HintKind::Letter => {
+            // letters the feedback has already shown are somewhere in the square
+            let mut known_letters: HashSet<char> = HashSet::new();
+
+            for (line, word) in guesses {
+                for (letter, letter_feedback) in word.chars().zip(feedback::guess_feedback(square, *line, word)?) {
+                    if letter_feedback != LetterFeedback::Absent {
+                        known_letters.insert(letter);
+                    }
+                }
+            }
+
+            // a letter the player knows nothing about, on the lines closest to being solved
+            let letter_cell = hidden_cells.iter().max_by_key(|(row, column)| {
+                let seen_in_lines = (0..grid.len()).filter(|i| revealed[*row][*i]).count() + (0..grid.len()).filter(|i| revealed[*i][*column]).count();
+                (!known_letters.contains(&grid[*row][*column]), seen_in_lines, usize::MAX - row * grid.len() - column)
+            });
+
+            Ok(letter_cell.map(|(row, column)| Hint::Letters(vec![(*row, *column, grid[*row][*column])])))
+        }
+    }
+}
+
+#[test]
+fn vowel_and_letter_hints() {
+
+    let square = vec!["budge".to_string(), "enter".to_string(), "alien".to_string(), "scant".to_string(), "eerie".to_string()];
+    let mut revealed = vec![vec![false; 5]; 5];
+    revealed[0] = vec![true; 5];
+
+    let vowels = hint(&square, &revealed, &[], HintKind::Vowels).unwrap();
+    assert!(matches!(vowels, Some(Hint::Letters(cells)) if cells.len() == 10 && cells[0] == (1, 0, 'e')));
+
+    // with budge showing every hidden cell is under one revealed letter, the e starting enter is known
+    // from the guess so the hint goes for the n next to it
+    let guesses = vec![(Line::Row(0), "budge".to_string())];
+    assert_eq!(Some(Hint::Letters(vec![(1, 1, 'n')])), hint(&square, &revealed, &guesses, HintKind::Letter).unwrap());
+}
+
+#[test]
+fn line_hints_need_a_guessed_answer() {
+
+    let square = vec!["budge".to_string(), "enter".to_string(), "alien".to_string(), "scant".to_string(), "eerie".to_string()];
+    let revealed = vec![vec![false; 5]; 5];
+
+    assert_eq!(None, hint(&square, &revealed, &[], HintKind::Line).unwrap());
+
+    // alien was guessed for the top row but it is the middle one
+    let guesses = vec![(Line::Row(0), "alien".to_string())];
+    assert_eq!(Some(Hint::WordLine { word: "alien".to_string(), line: Line::Row(2) }), hint(&square, &revealed, &guesses, HintKind::Line).unwrap());
+}
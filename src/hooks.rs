@@ -0,0 +1,171 @@
+use crate::solution::Solution;
+use crate::solution_generator::{GeneratorEvent, GeneratorPool, RunStats};
+
+/* What a library user gets told about a run as it goes, a gui driving its own progress bar say,
+  rather than reading the progress line off stderr. A hook does nothing unless it's overridden.
+  A seed's solutions always come between it starting and finishing, though the seeds of
+  different workers overlap, and when only counting no solutions are sent so none are found */
+pub trait RunObserver {
+
+    fn seed_started(&mut self, _seed: &str) {}
+
+    /* a seed that failed or was stopped part way never finishes */
+    fn seed_finished(&mut self, _seed: &str) {}
+
+    fn solution_found(&mut self, _solution: &Solution) {}
+
+    fn run_complete(&mut self, _stats: &RunStats) {}
+}
+
+/* the hooks as closures, only the ones set are called
+  e.g. RunHooks::new().on_seed_finished(|seed| bar.tick()).on_solution_found(|solution| list.push(solution.clone())) */
+#[derive(Default)]
+pub struct RunHooks<'a> {
+    seed_started: Option<Hook<'a, str>>,
+    seed_finished: Option<Hook<'a, str>>,
+    solution_found: Option<Hook<'a, Solution>>,
+    run_complete: Option<Hook<'a, RunStats>>
+}
+
+type Hook<'a, T> = Box<dyn FnMut(&T) + 'a>;
+
+impl<'a> RunHooks<'a> {
+
+    pub fn new() -> RunHooks<'a> {
+        RunHooks::default()
+    }
+
+    pub fn on_seed_started(mut self, hook: impl FnMut(&str) + 'a) -> RunHooks<'a> {
+        self.seed_started = Some(Box::new(hook));
+        self
+    }
+
+    pub fn on_seed_finished(mut self, hook: impl FnMut(&str) + 'a) -> RunHooks<'a> {
+        self.seed_finished = Some(Box::new(hook));
+        self
+    }
+
+    pub fn on_solution_found(mut self, hook: impl FnMut(&Solution) + 'a) -> RunHooks<'a> {
+        self.solution_found = Some(Box::new(hook));
+        self
+    }
+
+    pub fn on_run_complete(mut self, hook: impl FnMut(&RunStats) + 'a) -> RunHooks<'a> {
+        self.run_complete = Some(Box::new(hook));
+        self
+    }
+}
+
+impl RunObserver for RunHooks<'_> {
+
+    fn seed_started(&mut self, seed: &str) {
+        if let Some(hook) = self.seed_started.as_mut() {
+            hook(seed);
+        }
+    }
+
+    fn seed_finished(&mut self, seed: &str) {
+        if let Some(hook) = self.seed_finished.as_mut() {
+            hook(seed);
+        }
+    }
+
+    fn solution_found(&mut self, solution: &Solution) {
+        if let Some(hook) = self.solution_found.as_mut() {
+            hook(solution);
+        }
+    }
+
+    fn run_complete(&mut self, stats: &RunStats) {
+        if let Some(hook) = self.run_complete.as_mut() {
+            hook(stats);
+        }
+    }
+}
+
+/* Runs the pool to the end, telling the observer about each event as it comes in from the
+  workers then about the run being complete, with the same stats joining the pool gives back.
+  The hooks are called on the thread this is called from so they can hold onto anything */
+pub fn observe_run(pool: &mut dyn GeneratorPool, observer: &mut dyn RunObserver) -> RunStats {
+
+    for event in pool.solution_receiver().events() {
+        match event {
+            GeneratorEvent::SeedStarted(seed) => observer.seed_started(&seed),
+            GeneratorEvent::Solutions(solutions) => solutions.iter().for_each(|solution| observer.solution_found(solution)),
+            GeneratorEvent::SeedFinished(seed) => observer.seed_finished(&seed)
+        }
+    }
+
+    let stats = pool.join();
+    observer.run_complete(&stats);
+
+    stats
+}
+
+#[test]
+fn hooks_see_every_seed_and_solution() {
+
+    use std::collections::HashSet;
+    use std::sync::Arc;
+    use std::sync::atomic::AtomicBool;
+    use crate::engine::solver_engine;
+    use crate::generator_config::GeneratorConfig;
+
+    // the seeds going and a solution from a seed that hasn't started or has already finished
+    #[derive(Default)]
+    struct Recorder {
+        searching: HashSet<String>,
+        finished: usize,
+        solutions: u64,
+        out_of_order: bool
+    }
+
+    impl RunObserver for Recorder {
+
+        fn seed_started(&mut self, seed: &str) {
+            self.searching.insert(seed.to_string());
+        }
+
+        fn seed_finished(&mut self, seed: &str) {
+            self.out_of_order |= !self.searching.remove(seed);
+            self.finished += 1;
+        }
+
+        fn solution_found(&mut self, solution: &Solution) {
+            self.out_of_order |= !self.searching.contains(&solution[0]);
+            self.solutions += 1;
+        }
+    }
+
+    let dictionary: Vec<String> = ["eat", "era", "net", "ore", "rat", "tea", "ten", "toe", "tor", "tot"].iter().map(|word| word.to_string()).collect();
+    let prefix_map = crate::prefix_arena::PrefixArena::new(&dictionary).unwrap();
+
+    for flags in [&["--backend", "threads"], &["--backend", "rayon"], &["--engine", "naive"]] {
+
+        let args: Vec<String> = ["exec name", "words.csv", "out.csv", "2"].iter().chain(flags).map(|arg| arg.to_string()).collect();
+        let config = GeneratorConfig::build(&args).unwrap();
+
+        let mut pool = solver_engine(config.engine).start(&config, &dictionary, prefix_map.clone(), Arc::new(AtomicBool::new(false)), HashSet::new()).unwrap();
+        let mut recorder = Recorder::default();
+        let stats = observe_run(pool.as_mut(), &mut recorder);
+
+        assert!(!recorder.out_of_order && recorder.searching.is_empty());
+        assert_eq!(dictionary.len(), recorder.finished);
+        assert_eq!(stats.solutions_found, recorder.solutions);
+        assert!(recorder.solutions > 0);
+    }
+
+    // and as closures, run complete last with the same stats
+    let args: Vec<String> = ["exec name", "words.csv"].iter().map(|arg| arg.to_string()).collect();
+    let config = GeneratorConfig::build(&args).unwrap();
+
+    let (mut seeds_finished, mut completed_with) = (0, None);
+    let mut pool = solver_engine(config.engine).start(&config, &dictionary, prefix_map, Arc::new(AtomicBool::new(false)), HashSet::new()).unwrap();
+
+    let stats = observe_run(pool.as_mut(), &mut RunHooks::new()
+        .on_seed_finished(|_| seeds_finished += 1)
+        .on_run_complete(|stats| completed_with = Some(stats.seeds_processed)));
+
+    assert_eq!(dictionary.len(), seeds_finished);
+    assert_eq!(Some(stats.seeds_processed), completed_with);
+}
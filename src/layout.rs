@@ -0,0 +1,465 @@
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use serde::{Deserialize, Serialize};
+use crate::error::SquardleError;
+use crate::solution_generator::FixedLetters;
+
+const HOLE: char = '#';
+const BLANK: char = '.';
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Direction {
+    Across,
+    Down
+}
+
+/* a run of cells that has to spell a word, in order, numbered the way a crossword numbers its clues */
+#[derive(Debug, Clone, PartialEq)]
+pub struct Slot {
+    pub number: usize,
+    pub direction: Direction,
+    pub cells: Vec<(usize, usize)>
+}
+
+/* Which cells of a grid exist and which runs of them have to be words. A layout file has a
+  line per row with # for a hole, . for a cell to fill and a letter for a cell that is given.
+  Every run of two or more cells across or down is a word unless the file ends with rows or
+  columns lines, e.g. "rows 0 2 4", that name the only lines that are */
+#[derive(Debug, Clone, PartialEq)]
+pub struct GridLayout {
+    pub cells: Vec<Vec<Option<Option<char>>>>,
+    pub slots: Vec<Slot>
+}
+
+impl GridLayout {
+
+    pub fn read(file_path: &str) -> Result<GridLayout, SquardleError> {
+        GridLayout::parse(&fs::read_to_string(file_path)?)
+    }
+
+    pub fn parse(text: &str) -> Result<GridLayout, SquardleError> {
+
+        let mut cells: Vec<Vec<Option<Option<char>>>> = vec![];
+        let mut word_rows: Option<Vec<usize>> = None;
+        let mut word_columns: Option<Vec<usize>> = None;
+
+        for line in text.lines().map(str::trim).filter(|line| !line.is_empty()) {
+
+            let marked = match line.split_once(' ') {
+                Some(("rows", indices)) => Some((&mut word_rows, indices)),
+                Some(("columns", indices)) => Some((&mut word_columns, indices)),
+                _ => None
+            };
+
+            if let Some((lines, indices)) = marked {
+                let indices: Result<Vec<usize>, _> = indices.split_whitespace().map(str::parse).collect();
+                *lines = Some(indices.map_err(|_| SquardleError::from(format!("layout line [{}] should be indices separated by spaces", line).as_str()))?);
+                continue;
+            }
+
+            if word_rows.is_some() || word_columns.is_some() {
+                return Err("the rows and columns lines have to come after the grid".into());
+            }
+
+            cells.push(line.to_lowercase().chars().map(|cell| match cell {
+                HOLE => None,
+                BLANK => Some(None),
+                letter => Some(Some(letter))
+            }).collect());
+        }
+
+        GridLayout::from_cells(cells, word_rows, word_columns)
+    }
+
+    /* a square with the letters given, every row and column a word */
+    pub fn from_fixed_letters(fixed_letters: &FixedLetters) -> Result<GridLayout, SquardleError> {
+        GridLayout::from_cells(fixed_letters.iter().map(|row| row.iter().map(|letter| Some(*letter)).collect()).collect(), None, None)
+    }
+
+    fn from_cells(cells: Vec<Vec<Option<Option<char>>>>, word_rows: Option<Vec<usize>>, word_columns: Option<Vec<usize>>) -> Result<GridLayout, SquardleError> {
+
+        let width = cells.first().map_or(0, Vec::len);
+
+        if width == 0 || cells.iter().any(|row| row.len() != width) {
+            return Err("every row of the layout needs the same number of cells".into());
+        }
+
+        let height = cells.len();
+        let mut slots = vec![];
+
+        for row in (0..height).filter(|row| word_rows.as_ref().is_none_or(|rows| rows.contains(row))) {
+            slots.extend(runs_of_cells((0..width).map(|column| (row, column)), Direction::Across, &cells));
+        }
+
+        for column in (0..width).filter(|column| word_columns.as_ref().is_none_or(|columns| columns.contains(column))) {
+            slots.extend(runs_of_cells((0..height).map(|row| (row, column)), Direction::Down, &cells));
+        }
+
+        if slots.is_empty() {
+            return Err("the layout has no runs of two or more cells to fill with words".into());
+        }
+
+        // reading the grid row by row, each cell that starts a slot takes the next number
+        let mut starts: Vec<(usize, usize)> = slots.iter().map(|slot| slot.cells[0]).collect();
+        starts.sort();
+        starts.dedup();
+
+        for slot in slots.iter_mut() {
+            slot.number = starts.binary_search(&slot.cells[0]).expect("every start was collected") + 1;
+        }
+
+        Ok(GridLayout { cells, slots })
+    }
+
+    /* a word square is the layout with no holes */
+    pub fn square(word_size: usize) -> GridLayout {
+        GridLayout::parse(&vec![BLANK.to_string().repeat(word_size); word_size].join("\n")).expect("a square has a row and column of each size")
+    }
+
+    /* the word each slot spells in a filled grid */
+    pub fn answers(&self, rows: &[String]) -> Vec<(&Slot, String)> {
+
+        let grid: Vec<Vec<char>> = rows.iter().map(|row| row.chars().collect()).collect();
+
+        self.slots.iter()
+            .map(|slot| (slot, slot.cells.iter().filter_map(|(row, column)| grid.get(*row).and_then(|row| row.get(*column))).collect()))
+            .collect()
+    }
+}
+
+/* layout dictionary_file layout_file with optional --dictionary file, which can be repeated, and --limit n.
+  --square n stands in for the layout file to fill a plain word square */
+#[derive(Debug, Default, PartialEq)]
+pub struct LayoutConfig {
+    pub dictionary_file_paths: Vec<String>,
+    pub layout_file_path: Option<String>,
+    pub square_size: Option<usize>,
+    pub solution_limit: Option<usize>
+}
+
+impl LayoutConfig {
+
+    pub fn build(args: &[String]) -> Result<LayoutConfig, SquardleError> {
+
+        let mut config = LayoutConfig::default();
+        let mut extra_dictionaries: Vec<String> = vec![];
+        let mut positional_args: Vec<String> = Vec::with_capacity(2);
+        let mut args_iter = args.iter();
+
+        while let Some(arg) = args_iter.next() {
+
+            match arg.as_str() {
+                "--dictionary" => match args_iter.next() {
+                    Some(file_path) => extra_dictionaries.push(file_path.clone()),
+                    None => return Err("Dictionary flag needs a file path".into())
+                },
+                "--limit" => match args_iter.next().map(|limit| limit.parse::<usize>()) {
+                    Some(Ok(limit)) => config.solution_limit = Some(limit),
+                    _ => return Err("Limit must be a number".into())
+                },
+                "--square" => match args_iter.next().map(|size| size.parse::<usize>()) {
+                    Some(Ok(size)) if size >= 2 => config.square_size = Some(size),
+                    _ => return Err("Square size must be a number of at least 2".into())
+                },
+                _ => positional_args.push(arg.clone())
+            }
+        }
+
+        let wanted_args = if config.square_size.is_some() { 1 } else { 2 };
+
+        if positional_args.len() != wanted_args {
+            return Err("layout accepts a dictionary file path, a layout file path or --square n and optional flags: --dictionary file path, --limit n".into());
+        }
+
+        if config.square_size.is_none() {
+            config.layout_file_path = positional_args.pop();
+        }
+
+        config.dictionary_file_paths = [positional_args, extra_dictionaries].concat();
+
+        Ok(config)
+    }
+}
+
+/* splits a line of the grid into the runs of two or more cells between its holes */
+fn runs_of_cells(line: impl Iterator<Item = (usize, usize)>, direction: Direction, cells: &[Vec<Option<Option<char>>>]) -> Vec<Slot> {
+
+    let mut slots = vec![];
+    let mut run = vec![];
+
+    for (row, column) in line.chain([(usize::MAX, usize::MAX)]) {
+
+        if cells.get(row).and_then(|cells| cells.get(column)).is_some_and(Option::is_some) {
+            run.push((row, column));
+            continue;
+        }
+
+        if run.len() >= 2 {
+            slots.push(Slot { number: 0, direction, cells: run.clone() });
+        }
+
+        run.clear();
+    }
+
+    slots
+}
+
+/* The words of one length with, for every position and letter, a bitset of the words that
+  have that letter there. Anding the sets of a slot's letters gives the words that fit it */
+struct WordIndex {
+    words: Vec<Vec<char>>,
+    letter_sets: HashMap<(usize, char), Vec<u64>>,
+    used: Vec<u64>
+}
+
+impl WordIndex {
+
+    fn new(words: Vec<Vec<char>>) -> WordIndex {
+
+        let blocks = words.len().div_ceil(64);
+        let mut letter_sets: HashMap<(usize, char), Vec<u64>> = HashMap::new();
+
+        for (index, word) in words.iter().enumerate() {
+            for (position, letter) in word.iter().enumerate() {
+                letter_sets.entry((position, *letter)).or_insert_with(|| vec![0; blocks])[index / 64] |= 1 << (index % 64);
+            }
+        }
+
+        // the bits past the last word start out used so they never fit
+        let mut used = vec![0; blocks];
+        for index in words.len()..blocks * 64 {
+            used[index / 64] |= 1 << (index % 64);
+        }
+
+        WordIndex { used, words, letter_sets }
+    }
+
+    /* the unused words with the letters given, None for a blank */
+    fn fitting(&self, pattern: &[Option<char>]) -> Vec<u64> {
+
+        let mut fitting: Vec<u64> = self.used.iter().map(|used| !used).collect();
+
+        for (position, letter) in pattern.iter().enumerate() {
+            let Some(letter) = letter else { continue };
+
+            match self.letter_sets.get(&(position, *letter)) {
+                Some(letter_set) => fitting.iter_mut().zip(letter_set).for_each(|(block, letter_block)| *block &= letter_block),
+                None => return vec![]
+            }
+        }
+
+        fitting
+    }
+
+    fn set_used(&mut self, index: usize, used: bool) {
+        if used {
+            self.used[index / 64] |= 1 << (index % 64);
+        } else {
+            self.used[index / 64] &= !(1 << (index % 64));
+        }
+    }
+}
+
+/* the indices of the set bits */
+fn set_bits(blocks: &[u64]) -> impl Iterator<Item = usize> + '_ {
+    blocks.iter().enumerate().flat_map(|(block_index, block)| (0..64).filter(move |bit| block & (1 << bit) != 0).map(move |bit| block_index * 64 + bit))
+}
+
+/* Fills the slots of a layout with words of their length, always going for the slot with
+  the fewest words that still fit so dead ends turn up early. No word is used twice. Unlike
+  the generator a square filled this way comes back along with its transpose */
+pub struct LayoutSolver<'a> {
+    layout: &'a GridLayout,
+    indices_by_length: HashMap<usize, WordIndex>,
+    grid: Vec<Vec<Option<char>>>,
+    filled: Vec<bool>,
+    solutions: Vec<Vec<String>>,
+    solution_limit: Option<usize>
+}
+
+impl<'a> LayoutSolver<'a> {
+
+    /* the dictionaries are merged and sorted so fillings come back in the same order every run,
+      words of every length can go in and each slot takes the ones of its length */
+    pub fn new(layout: &'a GridLayout, dictionaries: &[Vec<String>]) -> LayoutSolver<'a> {
+
+        let mut words_by_length: HashMap<usize, Vec<Vec<char>>> = HashMap::new();
+
+        for word in dictionaries.iter().flatten().collect::<BTreeSet<_>>() {
+            let letters: Vec<char> = word.chars().collect();
+            words_by_length.entry(letters.len()).or_default().push(letters);
+        }
+
+        LayoutSolver {
+            layout,
+            indices_by_length: words_by_length.into_iter().map(|(length, words)| (length, WordIndex::new(words))).collect(),
+            grid: layout.cells.iter().map(|row| row.iter().map(|cell| cell.flatten()).collect()).collect(),
+            filled: vec![false; layout.slots.len()],
+            solutions: vec![],
+            solution_limit: None
+        }
+    }
+
+    /* every way of filling the layout, up to the limit, as rows with # for the holes */
+    pub fn solve(mut self, solution_limit: Option<usize>) -> Vec<Vec<String>> {
+
+        self.solution_limit = solution_limit;
+        self.fill_next_slot();
+
+        self.solutions
+    }
+
+    fn fill_next_slot(&mut self) {
+
+        if self.solution_limit.is_some_and(|limit| self.solutions.len() >= limit) {
+            return;
+        }
+
+        let mut next_slot: Option<(usize, Vec<u64>, u32)> = None;
+
+        for index in (0..self.layout.slots.len()).filter(|index| !self.filled[*index]) {
+
+            let fitting = self.fitting_words(&self.layout.slots[index]);
+            let count: u32 = fitting.iter().map(|block| block.count_ones()).sum();
+
+            if count == 0 {
+                return;
+            }
+
+            if next_slot.as_ref().is_none_or(|(_, _, fewest)| count < *fewest) {
+                next_slot = Some((index, fitting, count));
+            }
+        }
+
+        let Some((slot_index, fitting, _)) = next_slot else {
+            self.solutions.push(self.rows());
+            return;
+        };
+
+        let cells = self.layout.slots[slot_index].cells.clone();
+        self.filled[slot_index] = true;
+
+        for word_index in set_bits(&fitting) {
+
+            let word = self.indices_by_length[&cells.len()].words[word_index].clone();
+            let placed: Vec<(usize, usize)> = cells.iter().copied().filter(|(row, column)| self.grid[*row][*column].is_none()).collect();
+
+            for ((row, column), letter) in cells.iter().zip(&word) {
+                self.grid[*row][*column] = Some(*letter);
+            }
+
+            self.set_used(cells.len(), word_index, true);
+            self.fill_next_slot();
+            self.set_used(cells.len(), word_index, false);
+
+            for (row, column) in placed {
+                self.grid[row][column] = None;
+            }
+
+            if self.solution_limit.is_some_and(|limit| self.solutions.len() >= limit) {
+                break;
+            }
+        }
+
+        self.filled[slot_index] = false;
+    }
+
+    fn fitting_words(&self, slot: &Slot) -> Vec<u64> {
+
+        let pattern: Vec<Option<char>> = slot.cells.iter().map(|(row, column)| self.grid[*row][*column]).collect();
+
+        self.indices_by_length.get(&slot.cells.len()).map_or(vec![], |index| index.fitting(&pattern))
+    }
+
+    fn set_used(&mut self, length: usize, word_index: usize, used: bool) {
+        if let Some(index) = self.indices_by_length.get_mut(&length) {
+            index.set_used(word_index, used);
+        }
+    }
+
+    fn rows(&self) -> Vec<String> {
+
+        self.layout.cells.iter().zip(&self.grid)
+            .map(|(cells, letters)| cells.iter().zip(letters).map(|(cell, letter)| match (cell, letter) {
+                (None, _) => HOLE,
+                (Some(_), Some(letter)) => *letter,
+                (Some(_), None) => BLANK
+            }).collect())
+            .collect()
+    }
+}
+
+#[test]
+fn parse_a_waffle_layout() {
+
+    let layout = GridLayout::parse("c.t\n.#.\n...\n").unwrap();
+
+    // the middle row and column are split by the hole so only the outside lines are words
+    assert_eq!(4, layout.slots.len());
+    assert_eq!(Slot { number: 1, direction: Direction::Down, cells: vec![(0, 0), (1, 0), (2, 0)] }, layout.slots[2]);
+    // 1 across and down start in the top left, 2 down in the top right and 3 across on the bottom row
+    assert_eq!(vec![1, 3, 1, 2], layout.slots.iter().map(|slot| slot.number).collect::<Vec<usize>>());
+    assert_eq!(Some(Some('c')), layout.cells[0][0]);
+    assert_eq!(None, layout.cells[1][1]);
+
+    let layout = GridLayout::parse("...\n...\n...\nrows 0 2\ncolumns 1").unwrap();
+    assert_eq!(3, layout.slots.len());
+
+    assert!(GridLayout::parse("...\n..").is_err());
+    assert!(GridLayout::parse(".#.\n###\n.#.").is_err());
+}
+
+#[test]
+fn fill_a_waffle_layout() {
+
+    let dictionaries = vec![["cat", "cow", "tan", "won", "ago", "ant"].iter().map(|word| word.to_string()).collect::<Vec<String>>()];
+    let layout = GridLayout::parse("c.t\n.#.\n...").unwrap();
+
+    // cat across the top, cow and tan down the sides leaves w.n along the bottom
+    let solutions = LayoutSolver::new(&layout, &dictionaries).solve(None);
+    assert_eq!(vec![vec!["cat".to_string(), "o#a".to_string(), "won".to_string()]], solutions);
+
+    let answers: Vec<String> = layout.answers(&solutions[0]).into_iter().map(|(_, word)| word).collect();
+    assert_eq!(vec!["cat", "won", "cow", "tan"], answers);
+
+    let layout = GridLayout::parse("...\n.#.\n...").unwrap();
+    assert_eq!(1, LayoutSolver::new(&layout, &dictionaries).solve(Some(1)).len());
+}
+
+#[test]
+fn build_layout_config() {
+
+    let args: Vec<String> = ["words.csv", "--dictionary", "names.csv", "waffle.txt", "--limit", "3"].iter().map(|arg| arg.to_string()).collect();
+
+    let config = LayoutConfig::build(&args).unwrap();
+
+    assert_eq!(vec!["words.csv".to_string(), "names.csv".to_string()], config.dictionary_file_paths);
+    assert_eq!(Some("waffle.txt".to_string()), config.layout_file_path);
+    assert_eq!(Some(3), config.solution_limit);
+
+    assert!(LayoutConfig::build(&args[..3]).is_err());
+
+    let args: Vec<String> = ["words.csv", "--square", "5"].iter().map(|arg| arg.to_string()).collect();
+
+    assert_eq!(Some(5), LayoutConfig::build(&args).unwrap().square_size);
+    assert!(LayoutConfig::build(&[args, vec!["waffle.txt".to_string()]].concat()).is_err());
+}
+
+#[test]
+fn square_layout_matches_the_solver() {
+
+    use crate::solution_generator::{parse_partial_grid, GridSolver};
+
+    let (dictionary, prefix_map) = crate::dictionary::test_dictionary();
+
+    let (mut expected, _) = GridSolver::new(dictionary.clone(), prefix_map).unwrap().solve(parse_partial_grid("", 3).unwrap(), None).unwrap();
+    expected.sort();
+    assert_eq!(4, expected.len());
+
+    // split across two dictionaries to check they are merged
+    let mut solutions = LayoutSolver::new(&GridLayout::square(3), &[dictionary[..4].to_vec(), dictionary[4..].to_vec()]).solve(None);
+    solutions.sort();
+
+    assert_eq!(expected, solutions);
+}
@@ -0,0 +1,55 @@
+pub mod solution;
+pub mod solution_generator;
+pub mod generator_config;
+pub mod checkpoint;
+pub mod merge;
+pub mod filter;
+pub mod error;
+pub mod output;
+pub mod report;
+pub mod scoring;
+pub mod progress;
+pub mod timing;
+pub mod commands;
+pub mod verify;
+pub mod dedup;
+pub mod play;
+pub mod feedback;
+pub mod hints;
+pub mod daily;
+pub mod difficulty;
+pub mod unique;
+pub mod layout;
+pub mod puzzle;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod server;
+pub mod dictionary;
+pub mod binary;
+pub mod solution_index;
+pub mod sample;
+pub mod coverage;
+pub mod graph;
+pub mod estimate;
+pub mod pangram;
+pub mod cube;
+pub mod chain;
+pub mod explain;
+pub mod engine;
+pub mod hooks;
+pub mod naive;
+pub mod selftest;
+pub mod wordsearch;
+pub mod preflight;
+pub mod bench;
+pub mod constrained_fill;
+pub mod constraint;
+pub mod memo;
+pub mod screen;
+pub mod prefix_arena;
+pub mod prefix_index;
+pub mod word_list;
+pub mod ffi;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+#[cfg(feature = "python")]
+pub mod python;
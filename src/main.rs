@@ -1,251 +1,1669 @@
-mod solution_generator;
-mod generator_config;
+use squardle_rust::{bench, binary, chain, checkpoint, commands, constraint, coverage, cube, daily, dedup, difficulty, engine, estimate, explain, filter, graph, layout, merge, output, pangram, play, preflight, puzzle, report, sample, scoring, selftest, server, solution_generator, solution_index, timing, unique, verify, wordsearch};
+use squardle_rust::dictionary::{read_dictionary_from_file, Dictionary, WordTags};
+use squardle_rust::prefix_arena::PrefixArena;
+use squardle_rust::solution_generator::GeneratorEvent;
+use squardle_rust::checkpoint::Checkpoint;
+use squardle_rust::commands::Command;
+use squardle_rust::constraint::SoftConstraint;
+use squardle_rust::error::SquardleError;
+use squardle_rust::scoring::Scorer;
+use squardle_rust::solution::Solution;
+
+use squardle_rust::generator_config::{GeneratorConfig, OutputFormat, Compression, CsvDialect, ScoreBy, ReportFormat};
+use std::collections::{HashMap, HashSet};
+use std::{process, env};
+use std::time::{Duration, Instant};
+use std::io::{BufRead, BufWriter, ErrorKind, Write};
+use std::fs::File;
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use log::{debug, error, info, warn, LevelFilter};
+use tracing::info_span;
+use tracing_subscriber::layer::SubscriberExt;
+
+const SOLUTIONS_PER_FLUSH: usize = 10_000;
+const CHECKPOINT_INTERVAL: Duration = Duration::from_secs(10);
+
+fn main() {
+
+    let args: Vec<String> = env::args().collect();
+
+    let (command, args) = Command::from_args(&args);
+
+    // generate sets up logging itself once it knows the verbosity
+    if command != Command::Generate {
+        init_logging(LevelFilter::Info);
+    }
+
+    match command {
+        Command::Generate => run_generate(&args),
+        Command::Verify => run_verify(&args[1..]),
+        Command::Solve => run_solve(&args[1..]),
+        Command::Stats => run_stats(&args[1..]),
+        Command::Clean => run_clean(&args[1..]),
+        Command::Merge => run_merge(&args[1..]),
+        Command::Diff => run_diff(&args[1..]),
+        Command::Sample => run_sample(&args[1..]),
+        Command::Coverage => run_coverage(&args[1..]),
+        Command::Graph => run_graph(&args[1..]),
+        Command::Estimate => run_estimate(&args[1..]),
+        Command::Pangram => run_pangram(&args[1..]),
+        Command::Cube => run_cube(&args[1..]),
+        Command::Chain => run_chain(&args[1..]),
+        Command::WordSearch => run_wordsearch(&args[1..]),
+        Command::Bench => run_bench(&args[1..]),
+        Command::Selftest => run_selftest(&args[1..]),
+        Command::Dedup => run_dedup(&args[1..]),
+        Command::Filter => run_filter(&args[1..]),
+        Command::Help => println!("{}", commands::USAGE),
+        Command::Play => run_play(&args[1..]),
+        Command::Daily => run_daily(&args[1..]),
+        Command::Rate => run_rate(&args[1..]),
+        Command::Unique => run_unique(&args[1..]),
+        Command::Layout => run_layout(&args[1..]),
+        Command::Export => run_export(&args[1..]),
+        Command::Serve => run_serve(&args[1..]),
+        Command::Convert => run_convert(&args[1..]),
+        Command::Index => run_index(&args[1..]),
+        Command::Query => run_query(&args[1..])
+    }
+}
+
+/* generate dictionary_file dest_file threads flags... the original interface, the default when no subcommand is given */
+fn run_generate(args: &[String]) {
+
+    let mut config = GeneratorConfig::build(args).unwrap_or_else(|err| {
+        eprintln!("Problem parsing arguments: {err}");
+        process::exit(1);
+    });
+
+    init_logging(config.log_level);
+
+    // the phases are timed by their spans, anything else instrumented is left to other subscribers
+    let phase_timings = timing::PhaseTimings::new();
+
+    if let Err(err) = tracing::subscriber::set_global_default(tracing_subscriber::registry().with(phase_timings.clone())) {
+        warn!("Problem setting up phase timings: {err}");
+    }
+
+    // picked before anything random happens so it can be logged for running the same way again
+    if (config.shuffle || config.sample.is_some()) && config.seed.is_none() {
+        let seed = sample::seed_from_time();
+        info!("Random seed {}, pass --seed {} to pick the same again", seed, seed);
+        config.seed = Some(seed);
+    }
+
+    debug!("{:?}", config);
+
+    let dictionary_span = info_span!(target: timing::PHASE, "dictionary load").entered();
+
+    let mut dictionary = match read_generate_dictionary(&config.dictionary_file_path.clone(), &mut config) {
+
+        Ok(dictionary) => dictionary,
+        Err(err) => {
+            error!("Problem reading dictionary: {err}");
+            process::exit(1)
+        }
+    };
+
+    let word_size = dictionary.word_size().unwrap_or(0);
+
+    // the columns are words from another dictionary when there is one, the prefix map is made of them rather than the rows
+    let mut column_dictionary = config.column_dictionary_file_path.clone().map(|column_dictionary_file_path| {
+        match read_generate_dictionary(&column_dictionary_file_path, &mut config) {
+            Ok(column_dictionary) => column_dictionary,
+            Err(err) => {
+                error!("Problem reading column dictionary: {err}");
+                process::exit(1)
+            }
+        }
+    });
+
+    if let Some(word) = column_dictionary.iter().flat_map(|column_dictionary| column_dictionary.words()).find(|word| word.chars().count() != word_size) {
+        let err = SquardleError::IncorrectWordSize { word: word.clone(), expected: word_size, found: word.chars().count() };
+        error!("Problem reading column dictionary: {err}");
+        process::exit(1)
+    }
+
+    // words no square can have are left out of the prefix map too so the search never tries them
+    match config.constraints(word_size) {
+        Ok(constraints) => {
+            let words = dictionary.len() + column_dictionary.as_ref().map_or(0, |column_dictionary| column_dictionary.len());
+
+            dictionary = dictionary.with_constraints(&constraints);
+            column_dictionary = column_dictionary.map(|column_dictionary| column_dictionary.with_constraints(&constraints));
+
+            let left = dictionary.len() + column_dictionary.as_ref().map_or(0, |column_dictionary| column_dictionary.len());
+
+            if left < words {
+                info!("Left out {} words the constraints rule out, {} are left", words - left, left);
+            }
+        },
+        Err(err) => {
+            error!("Problem with the constraints: {err}");
+            process::exit(1)
+        }
+    }
+
+    if dictionary.is_empty() || column_dictionary.as_ref().is_some_and(|column_dictionary| column_dictionary.is_empty()) {
+        error!("Problem reading dictionary: {}", SquardleError::EmptyDictionary);
+        process::exit(1)
+    }
+
+    drop(dictionary_span);
+    let prefix_map_span = info_span!(target: timing::PHASE, "prefix map build").entered();
+
+    let prefix_map = match column_dictionary.as_ref().unwrap_or(&dictionary).prefix_map() {
+        Ok(prefix_map) => prefix_map,
+        Err(err) => {
+            error!("Could not generate starts from dictionary: {err}");
+            process::exit(1)
+        }
+    };
+
+    drop(prefix_map_span);
+
+    // explaining a seed is wanted most when the preflight would stop a dictionary with no squares
+    if let Some(seed) = &config.explain {
+        run_explain(&config, dictionary.into_words(), prefix_map, seed);
+        return;
+    }
+
+    // the estimate takes the rows and columns from the same words so there isn't one for two dictionaries
+    if column_dictionary.is_none() {
+
+        let preflight = preflight::Preflight::new(&dictionary, &prefix_map);
+
+        if config.preflight_only {
+            println!("{}", preflight);
+            return;
+        }
+
+        info!("Preflight estimate\n{}", preflight);
+
+        if let Some((row, column)) = preflight.dead_cell {
+            error!("No square can be made from this dictionary, no word fits at row {} column {} with the rest of the grid", row + 1, column + 1);
+            process::exit(1)
+        }
+    }
+
+    let soft_constraints = match config.soft_constraints(word_size) {
+        Ok(soft_constraints) => soft_constraints,
+        Err(err) => {
+            error!("Problem reading soft constraints: {err}");
+            process::exit(1)
+        }
+    };
+
+    // the soft constraints' penalties are scored with or without frequencies to add them to
+    let scorer = match &config.frequencies_file_path {
+        Some(frequencies_file_path) => match Scorer::load(frequencies_file_path) {
+            Ok(scorer) => Some(scorer),
+            Err(err) => {
+                error!("Problem reading word frequencies: {err}");
+                process::exit(1)
+            }
+        },
+        None if !soft_constraints.is_empty() => Some(Scorer::default()),
+        None => None
+    }.map(|scorer| scorer.with_soft_constraints(soft_constraints.clone()));
+
+    if config.worker_output && !soft_constraints.is_empty() {
+        warn!("The workers write their own solutions so they aren't scored by the soft constraints");
+    }
+
+    if let Some(beam_width) = config.beam_width {
+        run_beam_search(&config, dictionary.into_words(), prefix_map, beam_width, &soft_constraints, scorer.as_ref());
+        return;
+    }
+
+    let stop_flag = Arc::new(AtomicBool::new(false));
+
+    if let Err(err) = install_interrupt_handler(stop_flag.clone()) {
+        warn!("Problem installing Ctrl+C handler: {err}");
+    }
+
+    let checkpoint = match (&config.checkpoint_file_path, config.resume) {
+        (Some(checkpoint_file_path), true) => match Checkpoint::load(checkpoint_file_path) {
+            Ok(checkpoint) if checkpoint.dictionary_file_path != config.dictionary_file_path => {
+                let err = SquardleError::CheckpointMismatch { checkpoint_dictionary: checkpoint.dictionary_file_path, dictionary: config.dictionary_file_path.clone() };
+                error!("Problem loading checkpoint: {err}");
+                process::exit(1)
+            },
+            Ok(checkpoint) => Some(checkpoint),
+            Err(err) => {
+                error!("Problem loading checkpoint: {err}");
+                process::exit(1)
+            }
+        },
+        (Some(_), false) => Some(Checkpoint::new(&config.dictionary_file_path)),
+        (None, _) => None
+    };
+
+    let mut skipped_seeds = match &checkpoint {
+        Some(checkpoint) => checkpoint.completed_seed_set(),
+        None => HashSet::new()
+    };
+
+    if let Some(shard) = config.shard {
+        skipped_seeds.extend(dictionary.iter().enumerate().filter(|(i, _)| !shard.contains(*i)).map(|(_, word)| word.clone()));
+    }
+
+    // solutions come out in dictionary order of the seed words that are going to be searched
+    let seed_order: Vec<String> = if config.deterministic {
+        dictionary.iter().filter(|word| !skipped_seeds.contains(*word)).cloned().collect()
+    } else {
+        Vec::new()
+    };
+
+    let mut pool = match engine::solver_engine(config.engine).start(&config, &dictionary, prefix_map, stop_flag.clone(), skipped_seeds) {
+        Ok(pool) => pool,
+        Err(err) =>{
+            error!("Problem starting thread pool: {err}");
+            process::exit(1)
+        }
+    };
+
+    let solution_receiver = pool.solution_receiver();
+    let solution_limit = config.solution_limit.unwrap_or(usize::MAX);
+
+    // the top solutions and a sample can only be written once every solution has been seen
+    let solutions = || -> Box<dyn Iterator<Item = Solution> + '_> {
+        let solutions: Box<dyn Iterator<Item = Solution> + '_> = match (config.top, &config.score_by, &scorer) {
+            (Some(k), ScoreBy::Frequency, Some(scorer)) => Box::new(scoring::top_solutions(solution_receiver.iter(), k, scorer).into_iter()),
+            _ if config.deterministic => Box::new(solution_receiver.ordered_iter(seed_order.clone()).take(solution_limit)),
+            _ => Box::new(solution_receiver.iter().take(solution_limit))
+        };
+
+        match (config.sample, config.seed) {
+            (Some(sample_size), Some(seed)) => Box::new(sample::reservoir_sample(solutions, sample_size, &mut sample::SampleRng::new(seed)).into_iter()),
+            _ => solutions
+        }
+    };
+
+    // set when whatever stdout is piped into stops reading
+    let mut output_closed = false;
+
+    // at the same time as generation, solutions are written as they arrive so they never all have to be held in memory
+    let output_span = info_span!(target: timing::PHASE, "output").entered();
+
+    let solution_count = if config.count_only || config.worker_output {
+
+        // nothing but seeds finishing comes through, the tallies are in the run stats and the solutions in the worker files
+        solution_receiver.events().count();
+        0
+
+    } else if let (Some(filename), Some(checkpoint)) = (&config.solutions_dest_file_path, checkpoint) {
+
+        let checkpoint_file_path = config.checkpoint_file_path.as_ref().expect("checkpoint is only made when there is a checkpoint file");
+
+        match save_solutions_with_checkpoint(filename, checkpoint_file_path, checkpoint, config.resume, &config.output_format,
+            Annotations { scorer: scorer.as_ref(), word_tags: &config.word_tags }, solution_receiver.events()) {
+            Ok(solution_count) => solution_count,
+            Err(err) => {
+                error!("Problem writing solutions with checkpoint: {err}");
+                process::exit(1)
+            }
+        }
+
+    } else {
+
+        match write_solutions(&config, &dictionary, scorer.as_ref(), solutions()) {
+            Ok((solution_count, closed)) => {
+                output_closed = closed;
+                solution_count
+            },
+            Err(err) => {
+                error!("Problem writing solutions: {err}");
+                process::exit(1)
+            }
+        }
+    };
+
+    drop(output_span);
+
+    let interrupted = stop_flag.load(Ordering::Relaxed);
+
+    // there are already enough solutions or nobody is reading them so the workers can stop looking
+    if solution_count >= solution_limit || output_closed {
+        stop_flag.store(true, Ordering::Relaxed);
+    }
+
+    let mut run_stats = pool.join();
+
+    // the seed counts or worker files written once the workers are done
+    let finish_output_span = info_span!(target: timing::PHASE, "output finish").entered();
+
+    let solution_count = if config.count_only {
+
+        if let Err(err) = save_seed_counts(config.solutions_dest_file_path.as_deref(), &run_stats.solutions_per_seed) {
+            error!("Problem writing seed word counts: {err}");
+            process::exit(1)
+        }
+
+        run_stats.solutions_found as usize
+
+    } else if let (true, Some(filename)) = (config.worker_output, &config.solutions_dest_file_path) {
+
+        let worker_file_paths: Vec<String> = (0..config.num_threads).map(|worker_number| output::worker_file_path(filename, worker_number)).collect();
+        // the same as the writer on the main thread, a header only once there is a solution under it
+        let header = match config.csv_dialect.output_header && config.output_format == OutputFormat::Csv && run_stats.solutions_found > 0 {
+            true => Some(output::csv_header(word_size, false, &config.csv_dialect)),
+            false => None
+        }.transpose();
+
+        if let Err(err) = header.and_then(|header| output::concatenate_worker_files(filename, config.compression, header.as_deref(), &worker_file_paths)) {
+            error!("Problem putting the worker output files together: {err}");
+            process::exit(1)
+        }
+
+        run_stats.solutions_found as usize
+    } else {
+        solution_count
+    };
+
+    drop(finish_output_span);
+    run_stats.phase_timings = phase_timings.take();
+
+    info!("{}", run_stats);
+
+    if let Some(report_format) = &config.report_format {
+        match report::RunReport::new(&run_stats).render(report_format) {
+            // stdout is taken by the solutions when there is no destination file
+            Ok(report) if config.solutions_dest_file_path.is_none() && !config.count_only => eprintln!("{}", report),
+            Ok(report) => println!("{}", report),
+            Err(err) => error!("Problem rendering the run report: {err}")
+        }
+    }
+
+    for error in &run_stats.errors {
+        error!("{}", error);
+    }
+
+    if interrupted {
+        warn!("Interrupted, kept the {} solutions found so far", solution_count);
+    } else {
+        info!("Found {} solutions", solution_count);
+    }
+
+    // the solutions that were found are still saved but the run is incomplete
+    if !run_stats.errors.is_empty() {
+        process::exit(1);
+    }
+}
+
+/* generate --beam, only the squares a beam search of the width finds, least soft constraint
+  penalty first, rather than every square there is */
+fn run_beam_search(config: &GeneratorConfig, dictionary: Vec<String>, prefix_map: PrefixArena, beam_width: usize,
+    soft_constraints: &[SoftConstraint], scorer: Option<&Scorer>) {
+
+    let word_size = dictionary.first().map_or(0, |word| word.chars().count());
+
+    if soft_constraints.is_empty() {
+        warn!("There are no soft constraints to rank by so the beam keeps the first grids in dictionary order");
+    }
+
+    let searched = config.constraints(word_size)
+        .and_then(|constraints| {
+            let solver = solution_generator::GridSolver::new(dictionary.clone(), prefix_map)?;
+            Ok(constraints.into_iter().fold(solver, |solver, constraint| solver.with_constraint(constraint)))
+        })
+        .and_then(|mut solver| solver.beam_search(beam_width, |puzzle| constraint::penalty(soft_constraints, puzzle)));
+
+    let (solutions, run_stats) = searched.unwrap_or_else(|err| {
+        error!("Problem running the beam search: {err}");
+        process::exit(1);
+    });
+
+    let solution_limit = config.solution_limit.unwrap_or(usize::MAX);
+
+    match write_solutions(config, &dictionary, scorer, solutions.into_iter().take(solution_limit)) {
+        Ok((solution_count, _)) => {
+            info!("{}", run_stats);
+            info!("Found {} solutions", solution_count);
+        },
+        Err(err) => {
+            error!("Problem writing solutions: {err}");
+            process::exit(1)
+        }
+    }
+}
+
+/* writes every candidate the search from the seed word tries and what became of it to stdout, as indented text or json */
+fn run_explain(config: &GeneratorConfig, dictionary: Vec<String>, prefix_map: PrefixArena, seed: &str) {
+
+    let word_size = dictionary.first().map_or(0, |word| word.chars().count());
+
+    let explained = config.constraints(word_size)
+        .and_then(|constraints| {
+            let solver = solution_generator::GridSolver::new(dictionary, prefix_map)?;
+            Ok(constraints.into_iter().fold(solver, |solver, constraint| solver.with_constraint(constraint)))
+        })
+        .and_then(|mut solver| solver.explain(seed));
+
+    let explanation = explained.unwrap_or_else(|err| {
+        error!("Problem explaining seed word [{seed}]: {err}");
+        process::exit(1);
+    });
+
+    match config.explain_format {
+        ReportFormat::Json => match serde_json::to_string_pretty(&explanation) {
+            Ok(json) => println!("{}", json),
+            Err(err) => {
+                error!("Problem rendering the explanation: {err}");
+                process::exit(1)
+            }
+        },
+        ReportFormat::Text => print!("{}", explain::explanation_as_text(&explanation))
+    }
+}
+
+/* merge dest_file source_files... combines shard outputs into one file dropping duplicate solutions */
+fn run_merge(args: &[String]) {
+
+    if args.len() < 2 {
+        error!("merge accepts a destination file path followed by one or more solution files to merge");
+        process::exit(1)
+    }
+
+    match merge::merge_solution_files(&args[0], &args[1..]) {
+        Ok((solution_count, duplicate_count)) => info!("Merged {} solutions, dropped {} duplicates", solution_count, duplicate_count),
+        Err(err) => {
+            error!("Problem merging solution files: {err}");
+            process::exit(1)
+        }
+    }
+}
+
+/* diff first_file second_file prints the solutions only one of them has, like diff does for lines */
+fn run_diff(args: &[String]) {
+
+    let [first_file_path, second_file_path] = args else {
+        error!("diff accepts two solution file paths");
+        process::exit(1)
+    };
+
+    match merge::diff_solution_files(first_file_path, second_file_path) {
+        Ok(diff) => {
+            for solution in &diff.only_in_first {
+                println!("< {}", solution);
+            }
+
+            for solution in &diff.only_in_second {
+                println!("> {}", solution);
+            }
+
+            info!("{} only in {}, {} only in {}, {} in both", diff.only_in_first.len(), first_file_path, diff.only_in_second.len(), second_file_path, diff.in_both_count);
+        },
+        Err(err) => {
+            error!("Problem comparing solution files: {err}");
+            process::exit(1)
+        }
+    }
+}
+
+/* sample source_file dest_file count keeps that many of the solutions picked at random */
+fn run_sample(args: &[String]) {
+
+    let config = sample::SampleConfig::build(args).unwrap_or_else(|err| {
+        error!("Problem parsing arguments: {err}");
+        process::exit(1);
+    });
+
+    let seed = config.seed.unwrap_or_else(sample::seed_from_time);
+
+    match sample::sample_solution_file(&config.source_file_path, &config.dest_file_path, config.sample_size, seed) {
+        Ok(solution_count) => info!("Sampled {} solutions with seed {}", solution_count, seed),
+        Err(err) => {
+            error!("Problem sampling solutions: {err}");
+            process::exit(1)
+        }
+    }
+}
+
+/* verify solutions_file dictionary_file checks every row and column of every solution is a dictionary word,
+  --delimiter for a file generated with one */
+fn run_verify(args: &[String]) {
+
+    let csv_dialect = match args {
+        [_, _] => CsvDialect::default(),
+        [_, _, flag, value] if flag == "--delimiter" => match CsvDialect::parse_delimiter(value) {
+            Ok(delimiter) => CsvDialect { delimiter, ..CsvDialect::default() },
+            Err(err) => {
+                error!("{err}");
+                process::exit(1)
+            }
+        },
+        _ => {
+            error!("verify accepts a solutions file path, a dictionary file path and optional flags: --delimiter character|tab");
+            process::exit(1)
+        }
+    };
+
+    let dictionary: HashSet<String> = match read_dictionary_from_file(&args[1]) {
+        Ok(dictionary) => dictionary.into_iter().collect(),
+        Err(err) => {
+            error!("Problem reading dictionary: {err}");
+            process::exit(1)
+        }
+    };
+
+    let (solution_count, problems) = verify::verify_solution_file(&args[0], &dictionary, &csv_dialect).unwrap_or_else(|err| {
+        error!("Problem reading solutions file: {err}");
+        process::exit(1);
+    });
+
+    for problem in &problems {
+        error!("{}", problem);
+    }
+
+    if problems.is_empty() {
+        info!("Verified {} solutions", solution_count);
+    } else {
+        error!("Found {} problems in {} solutions", problems.len(), solution_count);
+        process::exit(1)
+    }
+}
+
+/* solve dictionary_file grid prints every completion of a partial grid e.g. cra.e,.....,t..er */
+fn run_solve(args: &[String]) {
+
+    if args.len() != 2 {
+        error!("solve accepts a dictionary file path and a grid of comma separated rows with . for blanks e.g. cra.e,.....,t..er");
+        process::exit(1)
+    }
+
+    let (dictionary, prefix_map) = read_dictionary_with_starts(&args[0]);
+
+    let (solutions, _) = solution_generator::GridSolver::new(dictionary, prefix_map)
+        .and_then(|mut solver| {
+            let fixed_letters = solution_generator::parse_partial_grid(&args[1], solver.word_size())?;
+            solver.solve(fixed_letters, None)
+        })
+        .unwrap_or_else(|err| {
+            error!("Problem solving grid: {err}");
+            process::exit(1);
+        });
+
+    for solution in &solutions {
+        println!("{}", solution.join(","));
+    }
+
+    info!("Found {} completions", solutions.len());
+}
+
+/* estimate dictionary_file roughly how many solutions a full generate run would find, without running it */
+fn run_estimate(args: &[String]) {
+
+    let config = estimate::EstimateConfig::build(args).unwrap_or_else(|err| {
+        error!("Problem parsing arguments: {err}");
+        process::exit(1);
+    });
+
+    let (dictionary, prefix_map) = read_dictionary_with_starts(&config.dictionary_file_path);
+
+    let mut solver = solution_generator::GridSolver::new(dictionary, prefix_map).unwrap_or_else(|err| {
+        error!("Problem making solver: {err}");
+        process::exit(1);
+    });
+
+    let seed = config.seed.unwrap_or_else(sample::seed_from_time);
+    info!("Probing with seed {}", seed);
+
+    let estimate = solver.estimate_solution_count(config.probes, &mut sample::SampleRng::new(seed));
+
+    match config.json {
+        true => match serde_json::to_string_pretty(&estimate) {
+            Ok(json) => println!("{}", json),
+            Err(err) => {
+                error!("Problem rendering the estimate: {err}");
+                process::exit(1)
+            }
+        },
+        false => println!("{}", estimate)
+    }
+}
+
+/* pangram dictionary_file the square with the most different letters found within the time */
+fn run_pangram(args: &[String]) {
+
+    let config = pangram::PangramConfig::build(args).unwrap_or_else(|err| {
+        error!("Problem parsing arguments: {err}");
+        process::exit(1);
+    });
+
+    let (dictionary, prefix_map) = read_dictionary_with_starts(&config.dictionary_file_path);
+
+    let coverage = solution_generator::GridSolver::new(dictionary, prefix_map)
+        .and_then(|mut solver| solver.most_letters(config.target_letters, Some(Instant::now() + config.budget)))
+        .unwrap_or_else(|err| {
+            error!("Problem searching for squares: {err}");
+            process::exit(1);
+        });
+
+    match config.json {
+        true => match serde_json::to_string_pretty(&coverage) {
+            Ok(json) => println!("{}", json),
+            Err(err) => {
+                error!("Problem rendering the square: {err}");
+                process::exit(1)
+            }
+        },
+        false => println!("{}", coverage)
+    }
+}
+
+/* cube finds cubes with every row, column and pillar a word, written a line each as their rows layer by layer */
+fn run_cube(args: &[String]) {
+
+    let config = cube::CubeConfig::build(args).unwrap_or_else(|err| {
+        error!("Problem parsing arguments: {err}");
+        process::exit(1);
+    });
+
+    let (dictionary, prefix_map) = read_dictionary_with_starts(&config.dictionary_file_path);
+
+    let solver = cube::CubeSolver::new(&dictionary, prefix_map).unwrap_or_else(|err| {
+        error!("Problem setting up the cube search: {err}");
+        process::exit(1);
+    });
+
+    let started = Instant::now();
+
+    let found = write_to_destination(config.destination_file_path.as_deref(), |out| solver.solve(config.solution_limit, |rows| Ok(writeln!(out, "{}", rows.join(","))?)))
+        .unwrap_or_else(|err| {
+            error!("Problem searching for cubes: {err}");
+            process::exit(1);
+        });
+
+    info!("Found {} cubes of {} letter words in {:.3}s", found, solver.word_size(), started.elapsed().as_secs_f64());
+}
+
+/* wordsearch prints the words a grid spells a line each, or for every square of a solutions file its
+  rows then a ; and its bonus words, the ones that aren't one of its rows or columns */
+fn run_wordsearch(args: &[String]) {
+
+    let config = wordsearch::WordSearchConfig::build(args).unwrap_or_else(|err| {
+        error!("Problem parsing arguments: {err}");
+        process::exit(1);
+    });
+
+    let dictionary = read_dictionary_from_file(&config.dictionary_file_path).unwrap_or_else(|err| {
+        error!("Problem reading dictionary: {err}");
+        process::exit(1);
+    });
+
+    let searcher = wordsearch::WordSearcher::new(&dictionary, config.min_length);
+
+    match &config.source {
+        wordsearch::SearchSource::Grid(rows) => {
+
+            let found = searcher.find(rows);
+
+            for word in &found {
+                println!("{word}");
+            }
+
+            info!("Found {} words of {} letters or more", found.len(), config.min_length);
+        },
+        wordsearch::SearchSource::Solutions(solutions_file_path) => {
+
+            let solutions = read_solutions_from_file(solutions_file_path);
+
+            for rows in &solutions {
+                println!("{};{}", rows.join(","), wordsearch::bonus_words(rows, searcher.find(rows)).join(","));
+            }
+
+            info!("Found the bonus words of {} squares", solutions.len());
+        }
+    }
+}
+
+/* chain finds pairs of squares overlapping on the words asked for, a line each of the first square's rows then a ; and the second's */
+fn run_chain(args: &[String]) {
+
+    let config = chain::ChainConfig::build(args).unwrap_or_else(|err| {
+        error!("Problem parsing arguments: {err}");
+        process::exit(1);
+    });
+
+    let (dictionary, prefix_map) = read_dictionary_with_starts(&config.dictionary_file_path);
+
+    let mut solver = solution_generator::GridSolver::new(dictionary, prefix_map).unwrap_or_else(|err| {
+        error!("Problem setting up the search: {err}");
+        process::exit(1);
+    });
+
+    let found = write_to_destination(config.destination_file_path.as_deref(), |out| chain::for_each_pair(&mut solver, &config.words, config.overlap, config.solution_limit, |first, second| Ok(writeln!(out, "{};{}", first.join(","), second.join(","))?)))
+        .unwrap_or_else(|err| {
+            error!("Problem searching for pairs: {err}");
+            process::exit(1);
+        });
+
+    info!("Found {} pairs overlapping on {} words", found, config.words.len());
+}
+
+/* bench times generate over each bundled scenario in turn, a line each or json once they have all run */
+fn run_bench(args: &[String]) {
+
+    let config = bench::BenchConfig::build(args).unwrap_or_else(|err| {
+        error!("Problem parsing arguments: {err}");
+        process::exit(1);
+    });
+
+    let mut results: Vec<bench::BenchResult> = Vec::new();
+
+    for scenario in bench::SCENARIOS.iter().filter(|scenario| config.scenarios.iter().any(|name| name == scenario.name)) {
+
+        info!("Running scenario {}", scenario.name);
+
+        let result = bench::run_scenario(scenario, &config).unwrap_or_else(|err| {
+            error!("Problem running scenario {}: {err}", scenario.name);
+            process::exit(1);
+        });
+
+        if !config.json {
+            println!("{}", result);
+        }
+
+        results.push(result);
+    }
+
+    if config.json {
+        match serde_json::to_string_pretty(&results) {
+            Ok(json) => println!("{}", json),
+            Err(err) => {
+                error!("Problem rendering the results: {err}");
+                process::exit(1)
+            }
+        }
+    }
+}
+
+/* selftest checks every way of running generate against the brute force on random dictionaries,
+  printing each dictionary where they differ, and exits with 1 if any do */
+fn run_selftest(args: &[String]) {
+
+    let config = selftest::SelftestConfig::build(args).unwrap_or_else(|err| {
+        error!("Problem parsing arguments: {err}");
+        process::exit(1);
+    });
+
+    let seed = config.seed.unwrap_or_else(sample::seed_from_time);
+    info!("Checking with seed {}", seed);
+
+    let summary = selftest::run_selftest(&config, seed).unwrap_or_else(|err| {
+        error!("Problem running the selftest: {err}");
+        process::exit(1);
+    });
+
+    for mismatch in &summary.mismatches {
+        println!("{}", mismatch);
+    }
+
+    info!("Checked {} dictionaries with {} squares against {} variants of generate", summary.rounds, summary.squares, selftest::VARIANTS.len());
+
+    if !summary.mismatches.is_empty() {
+        error!("Found {} mismatches, pass --seed {} to check the same dictionaries again", summary.mismatches.len(), seed);
+        process::exit(1);
+    }
+}
+
+/* unique dictionary_file grid says whether the partial grid has exactly one completion, unique dictionary_file
+  --minimal rows finds letters of the square to reveal that leave it only one completion */
+fn run_unique(args: &[String]) {
+
+    let (dictionary_file_path, grid, minimal) = match args {
+        [dictionary_file_path, grid] => (dictionary_file_path, grid, false),
+        [dictionary_file_path, flag, grid] if flag == "--minimal" => (dictionary_file_path, grid, true),
+        _ => {
+            error!("unique accepts a dictionary file path and a grid with . for blanks e.g. cra.e,.....,t..er, or --minimal and the comma separated rows of a square");
+            process::exit(1)
+        }
+    };
+
+    let dictionary = match read_dictionary_from_file(dictionary_file_path) {
+        Ok(dictionary) => dictionary,
+        Err(err) => {
+            error!("Problem reading dictionary: {err}");
+            process::exit(1)
+        }
+    };
+
+    let word_size = match dictionary.first() {
+        Some(word) => word.chars().count(),
+        None => {
+            error!("Problem reading dictionary: {}", SquardleError::EmptyDictionary);
+            process::exit(1)
+        }
+    };
+
+    let dictionaries = [dictionary];
+
+    if minimal {
+
+        let square: Vec<String> = grid.split(',').map(String::from).collect();
+
+        if square.len() != word_size || square.iter().any(|row| row.len() != word_size || row.contains('.')) {
+            error!("--minimal needs a complete square of {} rows of {} letters", word_size, word_size);
+            process::exit(1)
+        }
+
+        match unique::minimal_reveal_set(&square, &dictionaries) {
+            Ok(fixed_letters) => println!("{}", unique::format_partial_grid(&fixed_letters)),
+            Err(err) => {
+                error!("Problem searching for letters to reveal: {err}");
+                process::exit(1)
+            }
+        }
+
+        return;
+    }
+
+    let (is_unique, solutions) = solution_generator::parse_partial_grid(grid, word_size)
+        .and_then(|fixed_letters| unique::unique_completion(&fixed_letters, &dictionaries))
+        .unwrap_or_else(|err| {
+            error!("Problem solving grid: {err}");
+            process::exit(1);
+        });
+
+    for solution in &solutions {
+        println!("{}", solution.join(","));
+    }
+
+    match (is_unique, solutions.len()) {
+        (true, _) => info!("The grid has a unique completion"),
+        (false, 0) => info!("The grid has no completions"),
+        (false, _) => info!("The grid has more than one completion")
+    }
+}
+
+/* layout dictionary_file layout_file --dictionary file --limit n fills the cells of a layout with words,
+  printing each filling as its rows with # for the holes */
+fn run_layout(args: &[String]) {
+
+    let config = layout::LayoutConfig::build(args).unwrap_or_else(|err| {
+        error!("{err}");
+        process::exit(1);
+    });
+
+    let mut dictionaries = Vec::with_capacity(config.dictionary_file_paths.len());
+
+    for dictionary_file_path in &config.dictionary_file_paths {
+        match read_dictionary_from_file(dictionary_file_path) {
+            Ok(dictionary) => dictionaries.push(dictionary),
+            Err(err) => {
+                error!("Problem reading dictionary {dictionary_file_path}: {err}");
+                process::exit(1)
+            }
+        }
+    }
+
+    let layout = match (&config.layout_file_path, config.square_size) {
+        (Some(layout_file_path), _) => layout::GridLayout::read(layout_file_path).unwrap_or_else(|err| {
+            error!("Problem reading layout: {err}");
+            process::exit(1);
+        }),
+        (None, Some(square_size)) => layout::GridLayout::square(square_size),
+        (None, None) => unreachable!("the config has a layout file or a square size")
+    };
+
+    let solutions = layout::LayoutSolver::new(&layout, &dictionaries).solve(config.solution_limit);
+
+    for solution in &solutions {
+        println!("{}", solution.join(","));
+
+        for (slot, word) in layout.answers(solution) {
+            debug!("{} {:?} {}", slot.number, slot.direction, word);
+        }
+    }
+
+    info!("Found {} fillings of {} slots", solutions.len(), layout.slots.len());
+}
+
+/* play solutions_file dictionary_file --index n hides the letters of a square and takes guesses at its rows
+  and columns, without an index the square is picked from the clock */
+fn run_play(args: &[String]) {
+
+    let config = play::PlayConfig::build(args).unwrap_or_else(|err| {
+        error!("{err}");
+        process::exit(1);
+    });
+
+    let dictionary: HashSet<String> = match read_dictionary_from_file(&config.dictionary_file_path) {
+        Ok(dictionary) => dictionary.into_iter().collect(),
+        Err(err) => {
+            error!("Problem reading dictionary: {err}");
+            process::exit(1)
+        }
+    };
+
+    let solutions = read_solutions_from_file(&config.solutions_file_path);
+
+    if solutions.is_empty() {
+        error!("There are no solutions in {} to play", config.solutions_file_path);
+        process::exit(1)
+    }
+
+    let saved_state = match &config.save_file_path {
+        Some(save_file_path) if Path::new(save_file_path).exists() => Some(play::GameState::load(save_file_path).unwrap_or_else(|err| {
+            error!("Problem loading saved game: {err}");
+            process::exit(1);
+        })),
+        _ => None
+    };
+
+    let game = match saved_state {
+        Some(state) => {
+            let rows = match solutions.iter().find(|rows| puzzle::puzzle_id(rows) == state.puzzle_id) {
+                Some(rows) => rows.clone(),
+                None => {
+                    error!("The saved puzzle {} is not in {}", state.puzzle_id, config.solutions_file_path);
+                    process::exit(1)
+                }
+            };
+
+            info!("Carrying on the saved game");
+            play::Game::from_state(rows, dictionary, state)
+        },
+        None => {
+            let index = config.index.unwrap_or_else(|| {
+                std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).map(|since| since.subsec_nanos() as usize).unwrap_or(0)
+            }) % solutions.len();
+
+            Ok(play::Game::new(solutions[index].clone(), dictionary))
+        }
+    };
+
+    let mut game = game.unwrap_or_else(|err| {
+        error!("Problem loading saved game: {err}");
+        process::exit(1);
+    });
+
+    if let Err(err) = play::play(&mut game, std::io::stdin().lock(), &mut std::io::stdout()) {
+        error!("Problem playing: {err}");
+        process::exit(1)
+    }
+
+    let Some(save_file_path) = &config.save_file_path else {
+        return;
+    };
+
+    // a finished game has nothing to carry on so its save goes, checkpoint removal does the same job
+    let saved = match game.is_over() {
+        true => Checkpoint::remove(save_file_path).map(|_| ()),
+        false => game.state().save(save_file_path)
+    };
+
+    match saved {
+        Ok(()) if !game.is_over() => info!("Saved the game to {}", save_file_path),
+        Ok(()) => (),
+        Err(err) => {
+            error!("Problem saving the game: {err}");
+            process::exit(1)
+        }
+    }
+}
 
-use crate::solution_generator::SolutionGeneratorThreadPool;
+/* daily solutions_file dictionary_file --date YYYY-MM-DD prints the puzzle for the date, today without one */
+fn run_daily(args: &[String]) {
 
-use crate::generator_config::GeneratorConfig;
-use std::collections::HashMap;
-use std::{process, env};
-use std::time::Instant;
-use std::error::Error;
-use std::io::{BufReader, BufRead, BufWriter, Write};
-use std::fs::File;
+    let (solutions_file_path, dictionary_file_path, date) = match args {
+        [solutions_file_path, dictionary_file_path] => (solutions_file_path, dictionary_file_path, chrono::Local::now().date_naive()),
+        [solutions_file_path, dictionary_file_path, flag, date] if flag == "--date" => match chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d") {
+            Ok(date) => (solutions_file_path, dictionary_file_path, date),
+            Err(err) => {
+                error!("Date must be YYYY-MM-DD: {err}");
+                process::exit(1)
+            }
+        },
+        _ => {
+            error!("daily accepts a solutions file path, a dictionary file path and optionally --date YYYY-MM-DD");
+            process::exit(1)
+        }
+    };
+
+    let dictionary = read_dictionary_from_file(dictionary_file_path).unwrap_or_else(|err| {
+        error!("Problem reading dictionary: {err}");
+        process::exit(1);
+    });
 
-static ALPHABET: [&str; 26] = [
-    "a", "b", "c", "d", "e", 
-    "f", "g", "h", "i", "j", 
-    "k", "l", "m", "n", "o",
-    "p", "q", "r", "s", "t", 
-    "u", "v", "w", "x", "y", 
-    "z",
-];
+    let solutions = read_solutions_from_file(solutions_file_path);
+
+    let puzzle = match daily::pick_daily(&solutions, date, daily::dictionary_hash(&dictionary)) {
+        Some(puzzle) => puzzle,
+        None => {
+            error!("There are no solutions in {} to pick from", solutions_file_path);
+            process::exit(1)
+        }
+    };
 
-#[derive(PartialEq)]
-#[derive(Debug)]
-enum DictionaryErrors {
-    InCorrectWordSize(String),
-    Empty
+    match serde_json::to_string_pretty(&puzzle) {
+        Ok(puzzle) => println!("{}", puzzle),
+        Err(err) => {
+            error!("Problem writing the daily puzzle: {err}");
+            process::exit(1)
+        }
+    }
 }
 
-fn main() {
+/* the dictionary file and the prefix map of its words, exiting if either can't be made */
+fn read_dictionary_with_starts(file_path: &str) -> (Vec<String>, PrefixArena) {
 
-    let args: Vec<String> = vec!["exec name".to_string(),"resources/dictionaries/words_medium.csv".to_string(), "test.csv".to_string(), "4".to_string()];//env::args().collect();
+    let dictionary = Dictionary::from_path(file_path, &CsvDialect::default()).unwrap_or_else(|err| {
+        error!("Problem reading dictionary: {err}");
+        process::exit(1);
+    });
 
-    let config = GeneratorConfig::build(&args).unwrap_or_else(|err| {
-        println!("Problem parsing arguments: {err}");
+    let prefix_map = dictionary.prefix_map().unwrap_or_else(|err| {
+        error!("Could not generate starts from dictionary: {err}");
         process::exit(1);
     });
 
-    println!("{:?}", config);
+    (dictionary.into_words(), prefix_map)
+}
 
-    let dictionary = match read_dictionary_from_file(&config.dictionary_file_path){
+/* every solution in a solutions file, exiting if it can't be read */
+fn read_solutions_from_file(file_path: &str) -> Vec<Vec<String>> {
 
-        Ok(dictionary) => Box::new(dictionary),
+    match output::open_solution_file(file_path) {
+        Ok(reader) => reader.lines().map_while(Result::ok).map(|line| output::rows_of_line(&line)).filter(|rows| !rows.is_empty()).collect(),
         Err(err) => {
-            eprintln!("Problem reading dictionary: {err}");
+            error!("Problem reading solutions file: {err}");
             process::exit(1)
         }
-    };
+    }
+}
 
-    let prefix_map = match generate_starts_that_have_words(&dictionary) {
-        Ok(prefix_map) => Box::new(prefix_map),
-        Err(e) => panic!("Could not generate starts from dictionary {:?}", e),
-    };
+/* rate source_file dictionary_file dest_file --frequencies file --difficulty easy|medium|hard
+  writes each solution with its difficulty, keeping only the wanted difficulty if there is one */
+fn run_rate(args: &[String]) {
 
-    let pool: SolutionGeneratorThreadPool = match SolutionGeneratorThreadPool::new(config.num_threads, &dictionary, prefix_map) {
-        Ok(pool) => pool,
-        Err(err) =>{
-            eprintln!("Problem starting thread pool: {err}");
+    let config = difficulty::RateConfig::build(args).unwrap_or_else(|err| {
+        error!("Problem parsing rate arguments: {err}");
+        process::exit(1);
+    });
+
+    let (dictionary, prefix_map) = read_dictionary_with_starts(&config.dictionary_file_path);
+
+    let scorer = config.frequencies_file_path.as_ref().map(|frequencies_file_path| Scorer::load(frequencies_file_path).unwrap_or_else(|err| {
+        error!("Problem reading word frequencies: {err}");
+        process::exit(1);
+    }));
+
+    let rated = difficulty::DifficultyRater::new(dictionary, prefix_map, scorer)
+        .and_then(|mut rater| difficulty::rate_solution_file(&mut rater, &config.source_file_path, &config.dest_file_path, config.difficulty));
+
+    match rated {
+        Ok((kept_count, dropped_count)) => info!("Rated {} solutions, dropped {} of other difficulties", kept_count, dropped_count),
+        Err(err) => {
+            error!("Problem rating solutions: {err}");
             process::exit(1)
         }
-    };
+    }
+}
+
+/* export source_file dictionary_file dest_file --puzzle-format json --frequencies file --reveal writes the
+  solutions as puzzles a game client can load, see puzzle::PuzzleExport for the layout */
+fn run_export(args: &[String]) {
 
-    let now = Instant::now();
+    let config = puzzle::ExportConfig::build(args).unwrap_or_else(|err| {
+        error!("Problem parsing export arguments: {err}");
+        process::exit(1);
+    });
 
-    let mut solutions: Vec<Vec<String>> = Vec::with_capacity(dictionary.len());
+    let (dictionary, prefix_map) = read_dictionary_with_starts(&config.dictionary_file_path);
 
-    for solution in pool.solution_receiver.iter() {
-        //println!("received solution");
-        solutions.push(solution);
-    }
+    let scorer = config.frequencies_file_path.as_ref().map(|frequencies_file_path| Scorer::load(frequencies_file_path).unwrap_or_else(|err| {
+        error!("Problem reading word frequencies: {err}");
+        process::exit(1);
+    }));
 
-    println!("{:#?}", now.elapsed());
+    let definitions = config.definitions_file_path.as_ref().map_or_else(puzzle::Definitions::new, |definitions_file_path| puzzle::load_definitions(definitions_file_path).unwrap_or_else(|err| {
+        error!("Problem reading definitions: {err}");
+        process::exit(1);
+    }));
 
-    if let Some(filename) = config.solutions_dest_file_path {
-        
-        save_solution_to_file(&filename, solutions);
+    let solutions = read_solutions_from_file(&config.source_file_path);
 
-    } else {
+    let exported = difficulty::DifficultyRater::new(dictionary.clone(), prefix_map, scorer)
+        .and_then(|mut rater| puzzle::export_puzzles(&solutions, dictionary, &mut rater, config.reveal, &definitions, config.slot_guesses))
+        .and_then(|export| puzzle::write_puzzles(&export, config.puzzle_format, &config.dest_file_path));
 
-        println!("{:?}",solutions);
+    match exported {
+        Ok(()) => info!("Exported {} puzzles to {}", solutions.len(), config.dest_file_path),
+        Err(err) => {
+            error!("Problem exporting puzzles: {err}");
+            process::exit(1)
+        }
     }
 }
 
-/*
-    From the file of the file_path read in a csv file that contains a list of words for a dictionary
-    No Csv Headers 
-    Sorts the dictionary
-*/
-fn read_dictionary_from_file(file_path: &str) -> Result<Vec<String>, Box<dyn Error>> {
+/* serve dictionary_file solutions_file --address host:port answers http requests for solutions, the daily
+  puzzle and guesses until it is stopped */
+fn run_serve(args: &[String]) {
 
-    let file = File::open(file_path)?;
-    let reader = BufReader::new(file);
-    let mut dictionary: Vec<String> = vec![];
+    let config = server::ServeConfig::build(args).unwrap_or_else(|err| {
+        error!("{err}");
+        process::exit(1);
+    });
 
-    for line in reader.lines() {
-        let mut words: Vec<String> = match line {
-            Ok(line) => line.split(",").map(str::to_lowercase).filter(|s| s != "").collect(),
-            Err(_) => panic!("could not read line")
+    let dictionary = read_dictionary_from_file(&config.dictionary_file_path).unwrap_or_else(|err| {
+        error!("Problem reading dictionary: {err}");
+        process::exit(1);
+    });
+
+    let mut words_by_size: HashMap<usize, Vec<String>> = HashMap::new();
+    for word in &dictionary {
+        words_by_size.entry(word.chars().count()).or_default().push(word.clone());
+    }
+
+    let mut solvers = HashMap::with_capacity(words_by_size.len());
+
+    for (word_size, words) in words_by_size {
+
+        let solver = PrefixArena::new(&words).and_then(|prefix_map| solution_generator::GridSolver::new(words, prefix_map));
+
+        match solver {
+            Ok(solver) => solvers.insert(word_size, solver),
+            Err(err) => {
+                error!("Problem setting up the solver for {} letter words: {err}", word_size);
+                process::exit(1)
+            }
         };
+    }
+
+    let solutions = read_solutions_from_file(&config.solutions_file_path);
+    let api = server::PuzzleApi::new(dictionary, solvers, solutions);
 
-        dictionary.append(&mut words);
+    if let Err(err) = server::serve(api, &config.address) {
+        error!("Problem serving: {err}");
+        process::exit(1)
     }
+}
 
-    dictionary.sort();
+/* stats solutions_file --json prints the letter and word counts of a solutions file */
+fn run_stats(args: &[String]) {
 
-    Ok(dictionary)
+    let (file_path, report_format, prefix_map) = match args {
+        [file_path] => (file_path, ReportFormat::Text, false),
+        [file_path, flag] if flag == "--json" => (file_path, ReportFormat::Json, false),
+        [flag, file_path] if flag == "--prefix-map" => (file_path, ReportFormat::Text, true),
+        [flag, file_path, json] if flag == "--prefix-map" && json == "--json" => (file_path, ReportFormat::Json, true),
+        _ => {
+            error!("stats accepts a solutions file path, or --prefix-map and a dictionary file path, and optionally --json");
+            process::exit(1)
+        }
+    };
+
+    if prefix_map {
+        return run_prefix_map_stats(file_path, &report_format);
+    }
+
+    let run_stats = report::solution_file_stats(file_path).unwrap_or_else(|err| {
+        error!("Problem reading solutions file: {err}");
+        process::exit(1);
+    });
+
+    match report::RunReport::new(&run_stats).render(&report_format) {
+        Ok(report) => println!("{}", report),
+        Err(err) => {
+            error!("Problem rendering the stats: {err}");
+            process::exit(1)
+        }
+    }
 }
 
-/* Generates a hashmap vectors containing words that correspond to a greatest common denomiator of substring.
-  Will do this for all positions in a string besides the first as isnt needed */
-fn generate_starts_that_have_words(dictionary: &Vec<String>) -> Result<HashMap<String, Vec<String>>, DictionaryErrors> {
+/* stats --prefix-map dictionary_file what the prefix map built from a dictionary looks like */
+fn run_prefix_map_stats(dictionary_file_path: &str, report_format: &ReportFormat) {
 
-    let word_size = match dictionary.get(0) {
-        Some(word) => word.chars().count(),
-        None => return Err(DictionaryErrors::Empty)
+    let dictionary = read_dictionary_from_file(dictionary_file_path).unwrap_or_else(|err| {
+        error!("Problem reading dictionary: {err}");
+        process::exit(1);
+    });
+
+    let rendered = report::PrefixMapReport::new(&dictionary).and_then(|report| report.render(report_format));
+
+    match rendered {
+        Ok(report) => println!("{}", report),
+        Err(err) => {
+            error!("Problem rendering the prefix map stats: {err}");
+            process::exit(1)
+        }
+    }
+}
+
+/* coverage solutions_file dictionary_file how many solutions each dictionary word is in and which are in none */
+fn run_coverage(args: &[String]) {
+
+    let (solutions_file_path, dictionary_file_path, report_format) = match args {
+        [solutions_file_path, dictionary_file_path] => (solutions_file_path, dictionary_file_path, ReportFormat::Text),
+        [solutions_file_path, dictionary_file_path, flag] if flag == "--json" => (solutions_file_path, dictionary_file_path, ReportFormat::Json),
+        _ => {
+            error!("coverage accepts a solutions file path, a dictionary file path and optionally --json");
+            process::exit(1)
+        }
     };
 
-    // dont need this hashmap this is just used to "seed" the actual hashmap
-    let mut starts_word_map: HashMap<String, Vec<String>> = HashMap::new();
+    let dictionary = read_dictionary_from_file(dictionary_file_path).unwrap_or_else(|err| {
+        error!("Problem reading dictionary: {err}");
+        process::exit(1);
+    });
+
+    let coverage = coverage::solution_file_coverage(solutions_file_path, &dictionary).unwrap_or_else(|err| {
+        error!("Problem reading solutions file: {err}");
+        process::exit(1);
+    });
+
+    match coverage.render(&report_format) {
+        Ok(report) => println!("{}", report),
+        Err(err) => {
+            error!("Problem rendering the coverage: {err}");
+            process::exit(1)
+        }
+    }
+}
+
+/* graph dictionary_file dest_file writes which words can open a square together for analysing elsewhere */
+fn run_graph(args: &[String]) {
+
+    let config = graph::GraphConfig::build(args).unwrap_or_else(|err| {
+        error!("Problem parsing arguments: {err}");
+        process::exit(1);
+    });
+
+    let dictionary = read_dictionary_from_file(&config.dictionary_file_path).unwrap_or_else(|err| {
+        error!("Problem reading dictionary: {err}");
+        process::exit(1);
+    });
+
+    match graph::write_word_graph(&dictionary, &config.dest_file_path, &config.graph_format) {
+        Ok(edge_count) => info!("Wrote {} words and {} edges", dictionary.len(), edge_count),
+        Err(err) => {
+            error!("Problem writing graph: {err}");
+            process::exit(1)
+        }
+    }
+}
+
+/* clean checkpoint_files... removes checkpoints along with any temp files left by an interrupted save */
+fn run_clean(args: &[String]) {
 
-    for letter in ALPHABET {
-        for word in dictionary {
+    if args.is_empty() {
+        error!("clean accepts one or more checkpoint file paths");
+        process::exit(1)
+    }
 
-            if word.len() != word_size {
-                return Err(DictionaryErrors::InCorrectWordSize(format!("word [{}] has incorrect size needed {} found {}", word, word_size, word.len())))
+    for file_path in args {
+        match Checkpoint::remove(file_path) {
+            Ok(0) => warn!("No checkpoint at {}", file_path),
+            Ok(removed_count) => info!("Removed {} files for checkpoint {}", removed_count, file_path),
+            Err(err) => {
+                error!("Problem removing checkpoint {}: {err}", file_path);
+                process::exit(1)
             }
+        }
+    }
+}
+
+/* dedup source_file dest_file drops repeated solutions including transposes of earlier ones */
+fn run_dedup(args: &[String]) {
+
+    if args.len() != 2 {
+        error!("dedup accepts a solutions file path and a destination file path");
+        process::exit(1)
+    }
+
+    match dedup::dedup_solution_file(&args[0], &args[1]) {
+        Ok(counts) => {
+            info!("Kept {} solutions, dropped {} exact duplicates and {} transposed duplicates", counts.kept, counts.exact_duplicates, counts.transposed_duplicates);
 
-            if word.starts_with(letter) {
-                let words_that_start_with = starts_word_map.entry(letter.to_owned()).or_insert_with(|| Vec::<String>::new());
-                words_that_start_with.push(word.to_string());
+            if counts.transposed_duplicates > 0 {
+                warn!("The symmetry breaking missed {} transposed solutions", counts.transposed_duplicates);
             }
+        },
+        Err(err) => {
+            error!("Problem deduplicating solution file: {err}");
+            process::exit(1)
         }
     }
+}
+
+/* convert source_file dest_file --output-format csv|jsonl|grid turns a binary solutions file back into text */
+fn run_convert(args: &[String]) {
 
-    for i in 1..word_size {
+    let config = binary::ConvertConfig::build(args).unwrap_or_else(|err| {
+        error!("Problem parsing arguments: {err}");
+        process::exit(1);
+    });
 
-        let mut new_starts_map :HashMap<String, Vec<String>> = HashMap::new();
+    match binary::convert_solution_file(&config.source_file_path, &config.dest_file_path, &config.output_format) {
+        Ok(solution_count) => info!("Converted {} solutions", solution_count),
+        Err(err) => {
+            error!("Problem converting solutions file: {err}");
+            process::exit(1)
+        }
+    }
+}
 
-        // make new key next letter down in words
-        for letter in ALPHABET {
+/* index solutions_file index_file builds the word index that query reads */
+fn run_index(args: &[String]) {
 
-            for prev_start in starts_word_map.keys() {
+    let [solutions_file_path, index_file_path] = args else {
+        error!("index accepts a solutions file path and an index file path");
+        process::exit(1)
+    };
 
-                //only care about the previously added keys
-                if prev_start.len() != i {
-                    continue;
-                }
+    let index = solution_index::SolutionIndex::build(solutions_file_path).unwrap_or_else(|err| {
+        error!("Problem indexing solutions file: {err}");
+        process::exit(1);
+    });
+
+    if let Err(err) = index.save(index_file_path) {
+        error!("Problem saving index: {err}");
+        process::exit(1)
+    }
 
-                let new_start = format!("{}{}", prev_start, letter);
+    info!("Indexed {} solutions", index.solution_count());
+}
 
-                let words = starts_word_map.get(prev_start).expect("should always get a value");
+/* query solutions_file index_file words... prints the solutions that have every word as a row or column */
+fn run_query(args: &[String]) {
 
-                for word in words {
+    let [solutions_file_path, index_file_path, words @ ..] = args else {
+        error!("query accepts a solutions file path, an index file path and the words to look for");
+        process::exit(1)
+    };
 
-                    if !word.starts_with(&new_start) {
-                        continue;
-                    }
+    if words.is_empty() {
+        error!("query needs at least one word to look for");
+        process::exit(1)
+    }
 
-                    let new_start_list = new_starts_map.entry(new_start.clone()).or_insert_with(|| Vec::<String>::new());
-                    new_start_list.push(word.to_string());
-                }
+    let index = solution_index::SolutionIndex::load(index_file_path).unwrap_or_else(|err| {
+        error!("Problem loading index: {err}");
+        process::exit(1);
+    });
+
+    let words: Vec<String> = words.iter().map(|word| word.to_lowercase()).collect();
+    let ids = index.solutions_with(&words);
+
+    match index.read_solutions(solutions_file_path, &ids) {
+        Ok(lines) => {
+            for line in &lines {
+                println!("{}", line);
             }
-        }
 
-        starts_word_map.extend(new_starts_map);
+            info!("Found {} solutions", lines.len());
+        },
+        Err(err) => {
+            error!("Problem reading solutions: {err}");
+            process::exit(1)
+        }
     }
+}
 
-    for letter in ALPHABET {
-        starts_word_map.remove(letter);
+/* filter source_file dest_file --contains word --exclude word --row-pattern h.a.t keeps the solutions that match */
+fn run_filter(args: &[String]) {
+
+    let filter = filter::SolutionFilter::build(args).unwrap_or_else(|err| {
+        error!("Problem parsing filter arguments: {err}");
+        process::exit(1);
+    });
+
+    match filter.filter_solution_file() {
+        Ok((kept_count, dropped_count)) => info!("Kept {} solutions, dropped {}", kept_count, dropped_count),
+        Err(err) => {
+            error!("Problem filtering solution file: {err}");
+            process::exit(1)
+        }
     }
+}
 
-    Ok(starts_word_map)
+/* diagnostics go to stderr through the log facade so stdout only ever has solutions and reports on it,
+  RUST_LOG still overrides the level picked by -q, -v and -vv */
+fn init_logging(log_level: LevelFilter) {
+
+    env_logger::Builder::new()
+        .filter_level(log_level)
+        .format_timestamp(None)
+        .format_target(false)
+        .parse_default_env()
+        .init();
 }
 
-fn save_solution_to_file(file_path: &String, solutions: Vec<Vec<String>>) {
+/* the first Ctrl+C asks the workers to stop so the solutions found so far still get written,
+  a second one exits straight away */
+fn install_interrupt_handler(stop_flag: Arc<AtomicBool>) -> Result<(), ctrlc::Error> {
+
+    ctrlc::set_handler(move || {
+
+        if stop_flag.swap(true, Ordering::Relaxed) {
+            process::exit(130);
+        }
+
+        warn!("Stopping, press Ctrl+C again to exit without saving");
+    })
+}
+
+/* what's written along with each solution's words, its score and the tags of its words */
+#[derive(Clone, Copy)]
+struct Annotations<'a> {
+    scorer: Option<&'a Scorer>,
+    word_tags: &'a WordTags
+}
+
+impl Annotations<'_> {
+
+    fn format_solution(&self, solution: &[String], output_format: &OutputFormat, csv_dialect: &CsvDialect) -> Result<String, SquardleError> {
+        output::format_tagged_solution(solution, self.scorer.map(|scorer| scorer.score(solution)), self.word_tags, output_format, csv_dialect)
+    }
+}
+
+/* writes each solution as a line as soon as it is received, through an encoder if the output is
+  compressed, flushing every so often so a long run always has most of its solutions on disk.
+  With a shard size the output moves on to the next numbered file once one is full.
+  Returns the number written */
+fn save_solution_to_file<I>(file_path: &str, output_format: &OutputFormat, csv_dialect: &CsvDialect, annotations: Annotations, compression: Compression, shard_size: Option<usize>,
+    solutions: I) -> Result<usize, SquardleError>
+    where I: IntoIterator<Item = Solution> {
+
+    let mut shard_number = 1;
+
+    let shard_file_path = |shard_number| match shard_size {
+        Some(_) => output::output_shard_file_path(file_path, shard_number),
+        None => file_path.to_string()
+    };
+
+    let mut file = output::OutputWriter::create(&shard_file_path(shard_number), compression)?;
+    let mut solution_count = 0;
 
-    let file = File::create(file_path).unwrap();
-    let mut file = BufWriter::new(file);
     for solution in solutions {
-        let merged: String = solution.join(",");
-        writeln!(file, "{}", merged).unwrap();
+
+        if let Some(shard_size) = shard_size {
+            if solution_count > 0 && solution_count % shard_size == 0 {
+                file.finish()?;
+                shard_number += 1;
+                file = output::OutputWriter::create(&shard_file_path(shard_number), compression)?;
+            }
+        }
+
+        // every shard starts with the header so each can be read on its own
+        if csv_dialect.output_header && *output_format == OutputFormat::Csv && shard_size.map_or(solution_count == 0, |shard_size| solution_count % shard_size == 0) {
+            writeln!(file, "{}", output::csv_header(solution.len(), annotations.scorer.is_some(), csv_dialect)?)?;
+        }
+
+        let line = annotations.format_solution(&solution, output_format, csv_dialect)?;
+        writeln!(file, "{}", line)?;
+
+        solution_count += 1;
+
+        if solution_count % SOLUTIONS_PER_FLUSH == 0 {
+            file.flush()?;
+        }
     }
+
+    file.finish()?;
+
+    Ok(solution_count)
 }
 
-#[test]
-fn generate_starts() {
+/* reads a dictionary for generate in its csv dialect, keeping the tags of a tagged dictionary in the config */
+fn read_generate_dictionary(file_path: &str, config: &mut GeneratorConfig) -> Result<Dictionary, SquardleError> {
+
+    if !config.tagged_dictionary {
+        return Dictionary::from_path(file_path, &config.csv_dialect);
+    }
+
+    let dictionary = Dictionary::from_tagged_path(file_path, &config.csv_dialect)?;
+
+    // the row and column dictionaries can tag the same word
+    dictionary.merge_tags_into(&mut config.word_tags);
+
+    Ok(dictionary)
+}
+
+/* writes the solutions to the destination file in its output format or to stdout when there isn't
+  one, returning how many were written and whether whatever was reading stdout went away */
+fn write_solutions<I>(config: &GeneratorConfig, dictionary: &[String], scorer: Option<&Scorer>, solutions: I) -> Result<(usize, bool), SquardleError>
+    where I: Iterator<Item = Solution> {
+
+    let Some(filename) = &config.solutions_dest_file_path else {
+        return write_solutions_to_stdout(&config.output_format, &config.csv_dialect, Annotations { scorer, word_tags: &config.word_tags }, solutions);
+    };
 
-    let dictionary = vec!["based".to_string(), "bases".to_string(), "bassy".to_string(), "baton".to_string(), "belly".to_string(), "elses".to_string() ];
+    let saved = match config.output_format {
+        OutputFormat::Sqlite => output::save_solutions_to_sqlite(filename, SOLUTIONS_PER_FLUSH, scorer, solutions),
+        OutputFormat::Binary => binary::save_solutions_to_binary(filename, dictionary, config.compression, solutions),
+        _ => save_solution_to_file(filename, &config.output_format, &config.csv_dialect, Annotations { scorer, word_tags: &config.word_tags }, config.compression, config.output_shard_size, solutions)
+    };
 
-    let starts_generated = generate_starts_that_have_words(&dictionary).unwrap();
+    saved.map(|solution_count| (solution_count, false))
+}
 
-    let starts_excpected = HashMap::from([
-        ("ba".to_string(), vec!["based".to_string(), "bases".to_string(), "bassy".to_string(), "baton".to_string()]),
-        ("bas".to_string(), vec!["based".to_string(), "bases".to_string(), "bassy".to_string()]),
-        ("base".to_string(), vec!["based".to_string(), "bases".to_string()]),
-        ("based".to_string(), vec!["based".to_string()]),
-        ("bases".to_string(), vec!["bases".to_string()]),
-        ("bass".to_string(), vec!["bassy".to_string()]),
-        ("bassy".to_string(), vec!["bassy".to_string()]),
-        ("bat".to_string(), vec!["baton".to_string()]),
-        ("bato".to_string(), vec!["baton".to_string()]),
-        ("baton".to_string(), vec!["baton".to_string()]),
-        ("be".to_string(), vec!["belly".to_string()]),
-        ("bel".to_string(), vec!["belly".to_string()]),
-        ("bell".to_string(), vec!["belly".to_string()]),
-        ("belly".to_string(), vec!["belly".to_string()]),
-        ("el".to_string(), vec!["elses".to_string()]),
-        ("els".to_string(), vec!["elses".to_string()]),
-        ("else".to_string(), vec!["elses".to_string()]),
-        ("elses".to_string(), vec!["elses".to_string()]),
-        ]);
+/* Writes the solutions to stdout in the output format so they can be piped into other tools. A
+  reader that stops early, like head, closes the pipe which just ends the output, returned along
+  with how many solutions were written */
+fn write_solutions_to_stdout<I>(output_format: &OutputFormat, csv_dialect: &CsvDialect, annotations: Annotations, solutions: I) -> Result<(usize, bool), SquardleError>
+    where I: Iterator<Item = Solution> {
 
-    for key in starts_excpected.keys() {
+    let mut out = BufWriter::new(std::io::stdout().lock());
+    let mut solution_count = 0;
 
-        assert!(starts_excpected.contains_key(key));
-        assert!(starts_generated.contains_key(key));
+    for solution in solutions {
 
-        let vec1 = starts_excpected.get(key).unwrap();
-        let vec2 = starts_generated.get(key).unwrap();
+        let mut line = annotations.format_solution(&solution, output_format, csv_dialect)?;
 
-        assert_eq!(vec1.len(), vec2.len());
+        if solution_count == 0 && csv_dialect.output_header && *output_format == OutputFormat::Csv {
+            line = format!("{}\n{}", output::csv_header(solution.len(), annotations.scorer.is_some(), csv_dialect)?, line);
+        }
 
-        assert_eq!(vec1, vec2);
+        match writeln!(out, "{}", line) {
+            Ok(()) => solution_count += 1,
+            Err(err) if err.kind() == ErrorKind::BrokenPipe => return Ok((solution_count, true)),
+            Err(err) => return Err(err.into())
+        }
     }
 
+    match out.flush() {
+        Ok(()) => Ok((solution_count, false)),
+        Err(err) if err.kind() == ErrorKind::BrokenPipe => Ok((solution_count, true)),
+        Err(err) => Err(err.into())
+    }
 }
 
-#[test]
-fn generate_starts_empty_dictionary() {
+/* writes how many solutions each seed word had as seed,count lines sorted by seed word,
+  to the file if there is one otherwise printed */
+fn save_seed_counts(file_path: Option<&str>, solutions_per_seed: &HashMap<String, u64>) -> Result<(), SquardleError> {
+
+    let mut seed_counts: Vec<(&String, &u64)> = solutions_per_seed.iter().collect();
+    seed_counts.sort();
 
-    let dictionary: Vec<String> = Vec::new();
+    write_to_destination(file_path, |out| {
 
-    let starts = generate_starts_that_have_words(&dictionary);
+        for (seed, count) in seed_counts {
+            writeln!(out, "{},{}", seed, count)?;
+        }
 
-    assert_eq!(DictionaryErrors::Empty, starts.unwrap_err());
+        Ok(())
+    })
 }
 
-#[test]
-fn generate_starts_has_incorrect_sized_word() {
+/* Hands the writing over to the destination file, or stdout when there isn't one. The file goes
+  through an output writer so it's only replaced once everything has been written, and is
+  compressed if its name ends .gz or .zst */
+fn write_to_destination<T, F>(file_path: Option<&str>, write: F) -> Result<T, SquardleError>
+    where F: FnOnce(&mut dyn Write) -> Result<T, SquardleError> {
+
+    match file_path {
+        Some(file_path) => {
+            let mut file = output::OutputWriter::create(file_path, Compression::from_file_path(file_path))?;
+            let written = write(&mut file)?;
+            file.finish()?;
+
+            Ok(written)
+        },
+        None => {
+            let mut out = BufWriter::new(std::io::stdout().lock());
+            let written = write(&mut out)?;
+            out.flush()?;
+
+            Ok(written)
+        }
+    }
+}
+
+/* same as saving to a file but keeps the checkpoint up to date as seed words finish, only
+  recording a seed once its solutions are flushed to the file. Returns the number written this run */
+fn save_solutions_with_checkpoint<I>(file_path: &str, checkpoint_file_path: &str, mut checkpoint: Checkpoint, resume: bool,
+    output_format: &OutputFormat, annotations: Annotations, events: I) -> Result<usize, SquardleError>
+    where I: IntoIterator<Item = GeneratorEvent> {
+
+    let file = if resume {
+        checkpoint::prepare_output_for_resume(file_path, output_format, &mut checkpoint)?
+    } else {
+        File::create(file_path)?
+    };
+
+    let mut file = BufWriter::new(file);
+    let mut solution_count = 0;
+    let mut last_saved = Instant::now();
+    let previously_written = checkpoint.solutions_written;
+
+    for event in events {
+        match event {
+            GeneratorEvent::SeedStarted(_) => (),
+            GeneratorEvent::Solutions(solutions) => {
+                for solution in solutions {
+                    // checkpointing is only allowed with the default dialect
+                    writeln!(file, "{}", annotations.format_solution(&solution, output_format, &CsvDialect::default())?)?;
+                    solution_count += 1;
+                }
+            },
+            GeneratorEvent::SeedFinished(seed) => {
+                checkpoint.completed_seeds.push(seed);
+
+                if last_saved.elapsed() >= CHECKPOINT_INTERVAL {
+                    save_checkpoint(&mut file, &mut checkpoint, checkpoint_file_path, previously_written + solution_count)?;
+                    last_saved = Instant::now();
+                }
+            }
+        }
+    }
+
+    save_checkpoint(&mut file, &mut checkpoint, checkpoint_file_path, previously_written + solution_count)?;
 
-    let s1:String = "abcdefg".to_string();
-    let s2:String = "hijklmno".to_string();
+    Ok(solution_count)
+}
 
-    let dictionary = vec![s1.clone(), s2.clone()];
+fn save_checkpoint(file: &mut BufWriter<File>, checkpoint: &mut Checkpoint, checkpoint_file_path: &str, solutions_written: usize) -> Result<(), SquardleError> {
 
-    let starts = generate_starts_that_have_words(&dictionary);
+    file.flush()?;
 
-    assert_eq!(DictionaryErrors::InCorrectWordSize( format!("word [{}] has incorrect size needed {} found {}", s2, s1.len(), s2.len())), starts.unwrap_err());
+    checkpoint.output_offset = file.get_ref().metadata()?.len();
+    checkpoint.solutions_written = solutions_written;
+    checkpoint.save(checkpoint_file_path)
 }
 
 #[test]
@@ -255,15 +1673,15 @@ fn write_one_solution() {
     let solution_copy = solutions.clone();
 
     let file_path = "test.csv".to_string();
-    save_solution_to_file(&file_path, solutions);
+    assert_eq!(1, save_solution_to_file(&file_path, &OutputFormat::Csv, &CsvDialect::default(), Annotations { scorer: None, word_tags: &WordTags::new() }, Compression::None, None, solutions.into_iter().map(Solution::from)).unwrap());
 
     let file = File::open(&file_path).unwrap();
-    let reader = BufReader::new(file);
+    let reader = std::io::BufReader::new(file);
     let mut solutions_read: Vec<Vec<String>> = vec![];
 
     for line in reader.lines() {
         let words: Vec<String> = match line {
-            Ok(line) => line.split(",").map(str::to_lowercase).filter(|s| s != "").collect(),
+            Ok(line) => line.split(",").map(str::to_lowercase).filter(|s| !s.is_empty()).collect(),
             Err(_) => panic!("could not read line")
         };
 
@@ -271,4 +1689,49 @@ fn write_one_solution() {
     }
     
     assert_eq!(solution_copy, solutions_read);
-}
\ No newline at end of file
+}
+#[test]
+fn write_sharded_solutions() {
+
+    let solutions: Vec<Vec<String>> = vec![vec!["ab".to_string(), "cd".to_string()], vec!["ef".to_string(), "gh".to_string()], vec!["ij".to_string(), "kl".to_string()]];
+
+    assert_eq!(3, save_solution_to_file("test_shard.csv", &OutputFormat::Csv, &CsvDialect::default(), Annotations { scorer: None, word_tags: &WordTags::new() }, Compression::None, Some(2), solutions.into_iter().map(Solution::from)).unwrap());
+
+    let first_shard = std::fs::read_to_string("test_shard_0001.csv").unwrap();
+    let second_shard = std::fs::read_to_string("test_shard_0002.csv").unwrap();
+    std::fs::remove_file("test_shard_0001.csv").unwrap();
+    std::fs::remove_file("test_shard_0002.csv").unwrap();
+
+    assert_eq!("ab,cd\nef,gh\n", first_shard);
+    assert_eq!("ij,kl\n", second_shard);
+}
+
+#[test]
+fn write_sharded_solutions_with_headers() {
+
+    let solutions: Vec<Vec<String>> = vec![vec!["ab".to_string(), "cd".to_string()], vec!["ef".to_string(), "gh".to_string()], vec!["ij".to_string(), "kl".to_string()]];
+    let csv_dialect = CsvDialect { delimiter: b'\t', dictionary_header: false, output_header: true };
+
+    assert_eq!(3, save_solution_to_file("test_header_shard.csv", &OutputFormat::Csv, &csv_dialect, Annotations { scorer: None, word_tags: &WordTags::new() }, Compression::None, Some(2), solutions.into_iter().map(Solution::from)).unwrap());
+
+    let first_shard = std::fs::read_to_string("test_header_shard_0001.csv").unwrap();
+    let second_shard = std::fs::read_to_string("test_header_shard_0002.csv").unwrap();
+    std::fs::remove_file("test_header_shard_0001.csv").unwrap();
+    std::fs::remove_file("test_header_shard_0002.csv").unwrap();
+
+    assert_eq!("row_1\trow_2\nab\tcd\nef\tgh\n", first_shard);
+    assert_eq!("row_1\trow_2\nij\tkl\n", second_shard);
+}
+
+#[test]
+fn write_seed_counts() {
+
+    let solutions_per_seed = HashMap::from([("budge".to_string(), 2), ("based".to_string(), 5)]);
+
+    save_seed_counts(Some("test_seed_counts.csv"), &solutions_per_seed).unwrap();
+
+    let contents = std::fs::read_to_string("test_seed_counts.csv").unwrap();
+    std::fs::remove_file("test_seed_counts.csv").unwrap();
+
+    assert_eq!("based,5\nbudge,2\n", contents);
+}
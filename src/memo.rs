@@ -0,0 +1,97 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use crate::prefix_arena::PrefixArena;
+
+/* Column starts grouped by how they can end. Two grids whose columns start differently can still
+  have exactly the same ways of finishing every column, "bas" and "cas" both only ending in e or t
+  say, and then the rows left to fill either fit both or neither. So each start is given the id of
+  the set of endings it has and a partial grid's key is the id of each of its columns */
+pub struct PrefixClasses {
+    classes: HashMap<String, u32>
+}
+
+impl PrefixClasses {
+
+    pub fn new(prefix_map: &PrefixArena) -> PrefixClasses {
+
+        let mut class_ids: HashMap<Vec<&str>, u32> = HashMap::new();
+        let mut classes: HashMap<String, u32> = HashMap::with_capacity(prefix_map.len());
+
+        for (prefix, words) in prefix_map.iter() {
+
+            let mut endings: Vec<&str> = words.iter().map(|word| &word[prefix.len()..]).collect();
+            endings.sort_unstable();
+
+            let next_id = class_ids.len() as u32;
+            let class_id = *class_ids.entry(endings).or_insert(next_id);
+
+            classes.insert(prefix.to_string(), class_id);
+        }
+
+        PrefixClasses { classes }
+    }
+
+    /* none when a column has no words starting like it, the search never gets as far as asking then */
+    pub fn key(&self, columns: &[String]) -> Option<Vec<u32>> {
+        columns.iter().map(|column| self.classes.get(column).copied()).collect()
+    }
+}
+
+/* The keys of partial grids known to have no way of filling the rest of their rows. Bounded, once
+  it holds capacity keys it is emptied and starts again, a depth first search mostly comes back to
+  grids it has just been near so the keys from long ago are the least use */
+pub struct FailureMemo {
+    prefix_classes: Arc<PrefixClasses>,
+    failed: Mutex<HashSet<Vec<u32>>>,
+    capacity: usize
+}
+
+impl FailureMemo {
+
+    pub fn new(prefix_classes: Arc<PrefixClasses>, capacity: usize) -> FailureMemo {
+        FailureMemo { prefix_classes, failed: Mutex::new(HashSet::new()), capacity }
+    }
+
+    pub fn key(&self, columns: &[String]) -> Option<Vec<u32>> {
+        self.prefix_classes.key(columns)
+    }
+
+    pub fn has_failed(&self, key: &[u32]) -> bool {
+        self.failed.lock().map(|failed| failed.contains(key)).unwrap_or(false)
+    }
+
+    pub fn record_failure(&self, key: Vec<u32>) {
+
+        // a poisoned memo is only a missed shortcut
+        let Ok(mut failed) = self.failed.lock() else {
+            return;
+        };
+
+        if failed.len() >= self.capacity {
+            failed.clear();
+        }
+
+        failed.insert(key);
+    }
+}
+
+#[test]
+fn prefixes_with_the_same_endings_share_a_class() {
+
+    let words = |words: &[&str]| -> Vec<String> { words.iter().map(|word| word.to_string()).collect() };
+
+    let memo = FailureMemo::new(Arc::new(PrefixClasses::new(&PrefixArena::new(&words(&["bae", "bat", "cae", "cat", "dab"])).unwrap())), 1);
+
+    let key = memo.key(&words(&["ba", "da"])).unwrap();
+    assert_eq!(key, memo.key(&words(&["ca", "da"])).unwrap());
+    assert_ne!(key, memo.key(&words(&["ba", "ca"])).unwrap());
+    assert_eq!(None, memo.key(&words(&["ba", "ea"])));
+
+    memo.record_failure(key.clone());
+    assert!(memo.has_failed(&key));
+
+    // full so the next failure empties it first
+    memo.record_failure(vec![0, 0]);
+    assert!(!memo.has_failed(&key));
+    assert!(memo.has_failed(&[0, 0]));
+}
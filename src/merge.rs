@@ -0,0 +1,138 @@
+use std::collections::HashSet;
+use std::io::{BufRead, Write};
+use crate::error::SquardleError;
+use crate::generator_config::Compression;
+use crate::output::{self, OutputWriter};
+
+/* a solution is known by its words, so a scored and an unscored copy of the same square are the same solution */
+fn solution_key(line: &str) -> Option<String> {
+    let rows = output::rows_of_line(line);
+    (!rows.is_empty()).then(|| rows.join(","))
+}
+
+/* Combines the solution files of several runs or shards into one, keeping the first
+  occurrence of every solution. Returns the number of solutions written and duplicates dropped */
+pub fn merge_solution_files(dest_file_path: &str, source_file_paths: &[String]) -> Result<(usize, usize), SquardleError> {
+
+    let mut seen_solutions: HashSet<String> = HashSet::new();
+    let mut duplicate_count = 0;
+
+    let mut dest_file = OutputWriter::create(dest_file_path, Compression::from_file_path(dest_file_path))?;
+
+    for source_file_path in source_file_paths {
+
+        let reader = output::open_solution_file(source_file_path)?;
+
+        for line in reader.lines() {
+            let line = line?;
+
+            let Some(solution) = solution_key(&line) else {
+                continue;
+            };
+
+            if seen_solutions.contains(&solution) {
+                duplicate_count += 1;
+                continue;
+            }
+
+            writeln!(dest_file, "{}", line.trim())?;
+            seen_solutions.insert(solution);
+        }
+    }
+
+    dest_file.finish()?;
+
+    Ok((seen_solutions.len(), duplicate_count))
+}
+
+/* the solutions only in the first file and those only in the second, each in the order of its file */
+#[derive(Debug, PartialEq)]
+pub struct SolutionDiff {
+    pub only_in_first: Vec<String>,
+    pub only_in_second: Vec<String>,
+    pub in_both_count: usize
+}
+
+fn solution_keys_of_file(file_path: &str) -> Result<Vec<String>, SquardleError> {
+
+    let mut keys = Vec::new();
+
+    for line in output::open_solution_file(file_path)?.lines() {
+        keys.extend(solution_key(&line?));
+    }
+
+    Ok(keys)
+}
+
+/* compares the squares two solution files hold, ignoring order, scores and repeats within a file */
+pub fn diff_solution_files(first_file_path: &str, second_file_path: &str) -> Result<SolutionDiff, SquardleError> {
+
+    let first = solution_keys_of_file(first_file_path)?;
+    let second = solution_keys_of_file(second_file_path)?;
+
+    let first_set: HashSet<&String> = first.iter().collect();
+    let second_set: HashSet<&String> = second.iter().collect();
+
+    let only_in = |solutions: &Vec<String>, other: &HashSet<&String>| {
+        let mut seen: HashSet<&String> = HashSet::new();
+        solutions.iter().filter(|solution| !other.contains(solution) && seen.insert(solution)).cloned().collect::<Vec<String>>()
+    };
+
+    Ok(SolutionDiff {
+        only_in_first: only_in(&first, &second_set),
+        only_in_second: only_in(&second, &first_set),
+        in_both_count: first_set.intersection(&second_set).count()
+    })
+}
+
+#[test]
+fn merge_drops_duplicates() {
+
+    std::fs::write("test_merge_1.csv", "based,ulcer\nbudge,enter\n").unwrap();
+    std::fs::write("test_merge_2.csv", "budge,enter\ncases,ulnae\n").unwrap();
+
+    let counts = merge_solution_files("test_merge.csv", &["test_merge_1.csv".to_string(), "test_merge_2.csv".to_string()]).unwrap();
+    let merged = std::fs::read_to_string("test_merge.csv").unwrap();
+
+    for file_path in ["test_merge_1.csv", "test_merge_2.csv", "test_merge.csv"] {
+        std::fs::remove_file(file_path).unwrap();
+    }
+
+    assert_eq!((3, 1), counts);
+    assert_eq!("based,ulcer\nbudge,enter\ncases,ulnae\n", merged);
+}
+
+#[test]
+fn merge_treats_scored_copies_as_duplicates() {
+
+    std::fs::write("test_merge_scored_1.csv", "ten,ore,eat,1.500\n").unwrap();
+    std::fs::write("test_merge_scored_2.csv", "ten,ore,eat\ntoe,era,net\n").unwrap();
+
+    let counts = merge_solution_files("test_merge_scored.csv", &["test_merge_scored_1.csv".to_string(), "test_merge_scored_2.csv".to_string()]).unwrap();
+    let merged = std::fs::read_to_string("test_merge_scored.csv").unwrap();
+
+    for file_path in ["test_merge_scored_1.csv", "test_merge_scored_2.csv", "test_merge_scored.csv"] {
+        std::fs::remove_file(file_path).unwrap();
+    }
+
+    assert_eq!((2, 1), counts);
+    assert_eq!("ten,ore,eat,1.500\ntoe,era,net\n", merged);
+}
+
+#[test]
+fn diff_finds_solutions_in_one_file_only() {
+
+    std::fs::write("test_diff_1.csv", "ten,ore,eat\ntoe,era,net\nten,ore,eat\n").unwrap();
+    std::fs::write("test_diff_2.csv", "tot,ore,ten\ntoe,era,net,2.000\n").unwrap();
+
+    let diff = diff_solution_files("test_diff_1.csv", "test_diff_2.csv").unwrap();
+
+    std::fs::remove_file("test_diff_1.csv").unwrap();
+    std::fs::remove_file("test_diff_2.csv").unwrap();
+
+    assert_eq!(SolutionDiff {
+        only_in_first: vec!["ten,ore,eat".to_string()],
+        only_in_second: vec!["tot,ore,ten".to_string()],
+        in_both_count: 1
+    }, diff);
+}
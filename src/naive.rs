@@ -0,0 +1,92 @@
+use std::collections::HashSet;
+use crate::solution::Solution;
+use crate::solution_generator::construct_potential_transposed_puzzle;
+
+/* Every square of a dictionary found by trying every word in every row and checking what comes
+  out, with no pruning at all so there's nothing to get wrong, only for a handful of words as it
+  goes through dictionary size to the power of the word size grids. A square has a word for
+  every row and column and no word twice, and of a square and its transpose only the one whose
+  first row reads before its first column is kept, the same as generate. In dictionary order */
+pub fn brute_force_squares(dictionary: &[String]) -> Vec<Solution> {
+
+    let words = unique_words(dictionary);
+
+    words.iter().flat_map(|seed| squares_from(&words, seed)).collect()
+}
+
+/* the squares brute_force_squares finds with the seed word as the first row, for going a seed at a time */
+pub fn brute_force_squares_from(dictionary: &[String], seed: &str) -> Vec<Solution> {
+    squares_from(&unique_words(dictionary), seed)
+}
+
+fn unique_words(dictionary: &[String]) -> Vec<String> {
+
+    let mut words: Vec<String> = dictionary.to_vec();
+    words.sort();
+    words.dedup();
+
+    words
+}
+
+fn squares_from(words: &[String], seed: &str) -> Vec<Solution> {
+
+    let word_size = seed.chars().count();
+
+    let is_word: HashSet<&str> = words.iter().map(String::as_str).collect();
+    let mut squares: Vec<Solution> = Vec::new();
+
+    if word_size < 2 || words.is_empty() {
+        return squares;
+    }
+
+    // which word each row below the seed has, counted up like an odometer with the last row turning fastest
+    let mut row_words = vec![0; word_size - 1];
+
+    loop {
+
+        let rows: Vec<String> = [seed.to_string()].into_iter().chain(row_words.iter().map(|index| words[*index].clone())).collect();
+
+        if is_square(&rows, &is_word) {
+            squares.push(Solution::new(rows));
+        }
+
+        let Some(row) = row_words.iter().rposition(|index| index + 1 < words.len()) else {
+            return squares;
+        };
+
+        row_words[row] += 1;
+        row_words[row + 1..].fill(0);
+    }
+}
+
+fn is_square(rows: &[String], is_word: &HashSet<&str>) -> bool {
+
+    let columns = construct_potential_transposed_puzzle(rows);
+
+    let mut seen: HashSet<&String> = HashSet::with_capacity(rows.len() * 2);
+    let all_different_words = rows.iter().chain(&columns).all(|word| is_word.contains(word.as_str()) && seen.insert(word));
+
+    all_different_words && rows[0] < columns[0]
+}
+
+#[test]
+fn brute_force_finds_every_square_once() {
+
+    let dictionary: Vec<String> = ["abc", "adc", "adf", "adg", "beb", "bed", "beh", "cba", "cda", "cfi", "deb", "ded", "def", "fda", "ghi"].iter().map(|word| word.to_string()).collect();
+
+    let squares = brute_force_squares(&dictionary);
+
+    let abc_def_ghi = Solution::from(vec!["abc".to_string(), "def".to_string(), "ghi".to_string()]);
+    assert!(squares.contains(&abc_def_ghi));
+
+    // its transpose has adg for its first row, which reads after its first column abc
+    assert!(!squares.contains(&abc_def_ghi.transpose()));
+    assert!(squares.iter().all(|square| square[0] < square.columns()[0]));
+
+    // a word twice in the dictionary doesn't make its squares twice
+    assert_eq!(squares, brute_force_squares(&[dictionary.clone(), dictionary.clone()].concat()));
+
+    let from_abc = brute_force_squares_from(&dictionary, "abc");
+    assert!(!from_abc.is_empty() && from_abc.iter().all(|square| square[0] == "abc"));
+    assert_eq!(from_abc, squares.iter().filter(|square| square[0] == "abc").cloned().collect::<Vec<Solution>>());
+}
@@ -0,0 +1,609 @@
+use serde::Serialize;
+use std::collections::BTreeMap;
+#[cfg(not(target_arch = "wasm32"))]
+use rusqlite::{params, Connection};
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::Path;
+use flate2::{read::MultiGzDecoder, write::GzEncoder};
+use crate::dictionary::WordTags;
+use crate::error::SquardleError;
+use crate::generator_config::{OutputFormat, Compression, CsvDialect};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::scoring::Scorer;
+use crate::solution::Solution;
+use crate::solution_generator::construct_potential_transposed_puzzle;
+
+/* what a solution looks like as a json line, the columns are included so consumers
+  do not have to transpose the rows themselves */
+#[derive(Debug, Serialize)]
+struct SolutionRecord<'a> {
+    rows: &'a [String],
+    columns: &'a [String],
+    word_size: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    score: Option<f64>,
+    // the tags of the rows and columns from a tagged dictionary that have any
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    tags: BTreeMap<&'a str, &'a [String]>
+}
+
+/* turns a solution into a single line of the output file, without the newline. A score
+  goes after the words, as the last field of a csv line */
+pub fn format_solution(solution: &[String], score: Option<f64>, output_format: &OutputFormat, csv_dialect: &CsvDialect) -> Result<String, SquardleError> {
+    format_tagged_solution(solution, score, &WordTags::new(), output_format, csv_dialect)
+}
+
+/* Same as format_solution with the tags of its words from a tagged dictionary, which only
+  json has anywhere to put so it's the format to curate themed squares from */
+pub fn format_tagged_solution(solution: &[String], score: Option<f64>, word_tags: &WordTags, output_format: &OutputFormat, csv_dialect: &CsvDialect) -> Result<String, SquardleError> {
+
+    match output_format {
+        OutputFormat::Csv => match score {
+            Some(score) => csv_line(solution.iter().map(String::as_str).chain([format!("{:.3}", score).as_str()]), csv_dialect),
+            None => csv_line(solution.iter().map(String::as_str), csv_dialect)
+        },
+        OutputFormat::Jsonl => {
+
+            let columns = construct_potential_transposed_puzzle(solution);
+
+            let tags = solution.iter().chain(columns.iter())
+                .filter_map(|word| word_tags.get(word).map(|tags| (word.as_str(), tags.as_slice())))
+                .collect();
+
+            let record = SolutionRecord {
+                rows: solution,
+                columns: &columns,
+                word_size: solution.first().map(|word| word.chars().count()).unwrap_or(0),
+                score,
+                tags
+            };
+
+            Ok(serde_json::to_string(&record)?)
+        },
+        OutputFormat::Grid => match score {
+            Some(score) => Ok(format!("{}score: {:.3}\n", format_grid(solution), score)),
+            None => Ok(format_grid(solution))
+        },
+        OutputFormat::Sqlite | OutputFormat::Binary => Err(SquardleError::InvalidArgument("Sqlite and binary solutions are not written as lines".to_string()))
+    }
+}
+
+/* the header row of a csv solutions file, a column for each row of the square and the score if there is one */
+pub fn csv_header(word_size: usize, scored: bool, csv_dialect: &CsvDialect) -> Result<String, SquardleError> {
+
+    let mut fields: Vec<String> = (1..=word_size).map(|row| format!("row_{}", row)).collect();
+
+    if scored {
+        fields.push("score".to_string());
+    }
+
+    csv_line(fields.iter().map(String::as_str), csv_dialect)
+}
+
+/* one record through the csv writer so anything with the delimiter or a quote in it gets quoted */
+fn csv_line<'a, I>(fields: I, csv_dialect: &CsvDialect) -> Result<String, SquardleError>
+    where I: IntoIterator<Item = &'a str> {
+
+    let mut writer = csv::WriterBuilder::new()
+        .delimiter(csv_dialect.delimiter)
+        .terminator(csv::Terminator::Any(b'\n'))
+        .buffer_capacity(128)
+        .from_writer(Vec::with_capacity(64));
+
+    writer.write_record(fields)?;
+
+    let mut line = writer.into_inner().map_err(|err| SquardleError::from(err.into_error()))?;
+    line.pop();
+
+    Ok(String::from_utf8(line).expect("the fields were all strings"))
+}
+
+/* draws the solution as a boxed grid of letters with a blank line after it so consecutive
+  solutions are easy to tell apart when eyeballing them */
+fn format_grid(solution: &[String]) -> String {
+
+    let word_size = solution.first().map(|word| word.chars().count()).unwrap_or(0);
+    let separator = format!("+{}", "---+".repeat(word_size));
+
+    let mut grid = separator.clone();
+
+    for word in solution {
+        grid.push_str("\n|");
+
+        for letter in word.chars() {
+            grid.push_str(&format!(" {} |", letter));
+        }
+
+        grid.push('\n');
+        grid.push_str(&separator);
+    }
+
+    grid.push('\n');
+
+    grid
+}
+
+/* the seed word of a solution is its first row, pulled back out of a line of the output file */
+pub fn seed_of_line(line: &str, output_format: &OutputFormat) -> Option<String> {
+
+    match output_format {
+        // checkpointing is only allowed with the default dialect
+        OutputFormat::Csv => rows_of_line(line).into_iter().next(),
+        OutputFormat::Jsonl => {
+            let record: serde_json::Value = serde_json::from_str(line).ok()?;
+            record["rows"][0].as_str().map(str::to_string)
+        },
+        // a grid is spread over several lines so there is no seed on any one of them
+        OutputFormat::Grid | OutputFormat::Sqlite | OutputFormat::Binary => None
+    }
+}
+
+/* the words of a csv solution line written with the default dialect, a trailing score is not one of the words */
+pub fn rows_of_line(line: &str) -> Vec<String> {
+    rows_of_csv_line(line, &CsvDialect::default())
+}
+
+/* Same as rows_of_line for a file written with another delimiter. The line goes through the csv
+  reader so quoted fields come back as they were written, and a header row written by
+  --output-header has no words in it, the same as a blank line */
+pub fn rows_of_csv_line(line: &str, csv_dialect: &CsvDialect) -> Vec<String> {
+
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(csv_dialect.delimiter)
+        .has_headers(false)
+        .flexible(true)
+        .buffer_capacity(128)
+        .from_reader(line.trim().as_bytes());
+
+    let fields: Vec<String> = match reader.records().next() {
+        Some(Ok(record)) => record.iter().map(|field| field.trim().to_string()).collect(),
+        _ => return Vec::new()
+    };
+
+    if is_csv_header(&fields) {
+        return Vec::new();
+    }
+
+    let mut fields: Vec<String> = fields.into_iter().filter(|field| !field.is_empty()).collect();
+
+    // a square has as many rows as a row has letters, a number in the field after them is the
+    // score. Going by position so a word like nan or inf is never taken for one
+    let word_size = fields.first().map_or(0, |seed| seed.chars().count());
+
+    if fields.len() == word_size + 1 && fields.last().is_some_and(|score| score.parse::<f64>().is_ok()) {
+        fields.pop();
+    }
+
+    fields
+}
+
+/* the fields csv_header writes, row_1 to row_n and maybe a score */
+fn is_csv_header(fields: &[String]) -> bool {
+    !fields.is_empty() && fields.iter().all(|field| field == "score" || field.strip_prefix("row_").is_some_and(|row| row.parse::<usize>().is_ok()))
+}
+
+/* zstd is C underneath so it isn't in a wasm32 build, gzip is pure rust and still works */
+#[cfg(target_arch = "wasm32")]
+const ZSTD_UNAVAILABLE: &str = "zstd compression is not available in a wasm build";
+
+/* the file being written to, plain or through a streaming encoder */
+enum OutputEncoder {
+    Plain(BufWriter<File>),
+    Gzip(GzEncoder<BufWriter<File>>),
+    #[cfg(not(target_arch = "wasm32"))]
+    Zstd(zstd::Encoder<'static, BufWriter<File>>)
+}
+
+/* A solution file being written. The solutions go into a temp file next to the destination which
+  only replaces it once finished, so a run that dies part way never leaves a truncated file behind
+  and whatever was there before is kept. Has to be finished so a compressed stream gets its end written */
+pub struct OutputWriter {
+    encoder: Option<OutputEncoder>,
+    file_path: String,
+    temp_file_path: String
+}
+
+impl OutputWriter {
+
+    pub fn create(file_path: &str, compression: Compression) -> Result<OutputWriter, SquardleError> {
+
+        let temp_file_path = format!("{}.tmp", file_path);
+        let file = BufWriter::new(File::create(&temp_file_path)?);
+
+        let encoder = match compression {
+            Compression::None => OutputEncoder::Plain(file),
+            Compression::Gzip => OutputEncoder::Gzip(GzEncoder::new(file, flate2::Compression::default())),
+            #[cfg(not(target_arch = "wasm32"))]
+            Compression::Zstd => OutputEncoder::Zstd(zstd::Encoder::new(file, zstd::DEFAULT_COMPRESSION_LEVEL)?),
+            #[cfg(target_arch = "wasm32")]
+            Compression::Zstd => return Err(ZSTD_UNAVAILABLE.into())
+        };
+
+        Ok(OutputWriter { encoder: Some(encoder), file_path: file_path.to_string(), temp_file_path })
+    }
+
+    pub fn finish(mut self) -> Result<(), SquardleError> {
+
+        let mut file = match self.encoder.take().expect("the encoder is only taken when finishing") {
+            OutputEncoder::Plain(file) => file,
+            OutputEncoder::Gzip(encoder) => encoder.finish()?,
+            #[cfg(not(target_arch = "wasm32"))]
+            OutputEncoder::Zstd(encoder) => encoder.finish()?
+        };
+
+        file.flush()?;
+        file.get_ref().sync_all()?;
+        drop(file);
+
+        fs::rename(&self.temp_file_path, &self.file_path)?;
+
+        Ok(())
+    }
+
+    fn encoder(&mut self) -> &mut OutputEncoder {
+        self.encoder.as_mut().expect("the encoder is only taken when finishing")
+    }
+}
+
+/* a writer that never got finished was abandoned on an error, its temp file is of no use */
+impl Drop for OutputWriter {
+    fn drop(&mut self) {
+        if self.encoder.take().is_some() {
+            let _ = fs::remove_file(&self.temp_file_path);
+        }
+    }
+}
+
+impl Write for OutputWriter {
+
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self.encoder() {
+            OutputEncoder::Plain(file) => file.write(buf),
+            OutputEncoder::Gzip(encoder) => encoder.write(buf),
+            #[cfg(not(target_arch = "wasm32"))]
+            OutputEncoder::Zstd(encoder) => encoder.write(buf)
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self.encoder() {
+            OutputEncoder::Plain(file) => file.flush(),
+            OutputEncoder::Gzip(encoder) => encoder.flush(),
+            #[cfg(not(target_arch = "wasm32"))]
+            OutputEncoder::Zstd(encoder) => encoder.flush()
+        }
+    }
+}
+
+/* the file path of one of the numbered output shards, solutions.csv.gz becomes solutions_0002.csv.gz */
+pub fn output_shard_file_path(file_path: &str, shard_number: usize) -> String {
+
+    let file_name_start = file_path.rfind('/').map(|i| i + 1).unwrap_or(0);
+
+    match file_path[file_name_start..].find('.') {
+        Some(i) => format!("{}_{:04}{}", &file_path[..file_name_start + i], shard_number, &file_path[file_name_start + i..]),
+        None => format!("{}_{:04}", file_path, shard_number)
+    }
+}
+
+/* where one worker writes its solutions when every worker has its own file, next to the solutions file */
+pub fn worker_file_path(file_path: &str, worker_number: usize) -> String {
+    format!("{}.worker_{:04}", file_path, worker_number)
+}
+
+/* One worker's own solutions file. Only its worker writes to it so the workers never wait on
+  each other or on the one channel to the main thread, the files are put together once they
+  have all finished. Always plain lines, compressing happens when they are put together */
+pub struct WorkerFile {
+    file: BufWriter<File>,
+    output_format: OutputFormat,
+    csv_dialect: CsvDialect
+}
+
+impl WorkerFile {
+
+    pub fn create(file_path: &str, output_format: OutputFormat, csv_dialect: CsvDialect) -> Result<WorkerFile, SquardleError> {
+        Ok(WorkerFile { file: BufWriter::new(File::create(file_path)?), output_format, csv_dialect })
+    }
+
+    pub fn write_solutions(&mut self, solutions: &[Solution]) -> Result<(), SquardleError> {
+
+        for solution in solutions {
+            writeln!(self.file, "{}", format_solution(solution, None, &self.output_format, &self.csv_dialect)?)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn finish(&mut self) -> Result<(), SquardleError> {
+        Ok(self.file.flush()?)
+    }
+}
+
+/* Puts the workers' files together into the solutions file, in worker order after the header
+  if there is one, then removes them */
+pub fn concatenate_worker_files(file_path: &str, compression: Compression, header: Option<&str>, worker_file_paths: &[String]) -> Result<(), SquardleError> {
+
+    let mut file = OutputWriter::create(file_path, compression)?;
+
+    if let Some(header) = header {
+        writeln!(file, "{}", header)?;
+    }
+
+    for worker_file_path in worker_file_paths {
+        io::copy(&mut File::open(worker_file_path)?, &mut file)?;
+    }
+
+    file.finish()?;
+
+    for worker_file_path in worker_file_paths {
+        fs::remove_file(worker_file_path)?;
+    }
+
+    Ok(())
+}
+
+/* opens a solution file for reading, decompressing it if its extension says it is compressed */
+pub fn open_solution_file(file_path: &str) -> Result<Box<dyn BufRead>, SquardleError> {
+
+    let file = File::open(file_path)?;
+
+    Ok(match Compression::from_file_path(file_path) {
+        Compression::None => Box::new(BufReader::new(file)),
+        Compression::Gzip => Box::new(BufReader::new(MultiGzDecoder::new(file))),
+        #[cfg(not(target_arch = "wasm32"))]
+        Compression::Zstd => Box::new(BufReader::new(zstd::Decoder::new(file)?)),
+        #[cfg(target_arch = "wasm32")]
+        Compression::Zstd => return Err(ZSTD_UNAVAILABLE.into())
+    })
+}
+
+/* Writes the solutions into a sqlite database replacing any that is already there. Every row
+  and column word is also kept in its own table with an index so finding all the squares with
+  a given word in them is a quick lookup. Committed every solutions_per_commit so a long run
+  keeps most of what it found. Returns the number written */
+#[cfg(not(target_arch = "wasm32"))]
+pub fn save_solutions_to_sqlite<I>(file_path: &str, solutions_per_commit: usize, scorer: Option<&Scorer>, solutions: I) -> Result<usize, SquardleError>
+    where I: IntoIterator<Item = Solution> {
+
+    if Path::new(file_path).exists() {
+        fs::remove_file(file_path)?;
+    }
+
+    let mut connection = Connection::open(file_path)?;
+
+    connection.execute_batch(
+        "CREATE TABLE solutions (
+            id INTEGER PRIMARY KEY,
+            word_size INTEGER NOT NULL,
+            rows TEXT NOT NULL,
+            score REAL
+        );
+        CREATE TABLE solution_words (
+            solution_id INTEGER NOT NULL REFERENCES solutions(id),
+            direction TEXT NOT NULL,
+            position INTEGER NOT NULL,
+            word TEXT NOT NULL
+        );
+        CREATE INDEX solution_words_word ON solution_words(word);")?;
+
+    let mut solution_count = 0;
+    let mut transaction = connection.transaction()?;
+
+    for solution in solutions {
+
+        let word_size = solution.first().map(|word| word.chars().count()).unwrap_or(0);
+
+        let score = scorer.map(|scorer| scorer.score(&solution));
+
+        transaction.execute("INSERT INTO solutions (word_size, rows, score) VALUES (?1, ?2, ?3)", params![word_size, solution.join(","), score])?;
+        let solution_id = transaction.last_insert_rowid();
+
+        let columns = solution.columns();
+
+        for (direction, words) in [("row", solution.rows()), ("column", columns.as_slice())] {
+            for (position, word) in words.iter().enumerate() {
+                transaction.execute("INSERT INTO solution_words (solution_id, direction, position, word) VALUES (?1, ?2, ?3, ?4)",
+                    params![solution_id, direction, position, word])?;
+            }
+        }
+
+        solution_count += 1;
+
+        if solution_count % solutions_per_commit == 0 {
+            transaction.commit()?;
+            transaction = connection.transaction()?;
+        }
+    }
+
+    transaction.commit()?;
+
+    Ok(solution_count)
+}
+
+#[test]
+fn format_solution_as_jsonl() {
+
+    let solution = vec!["ab".to_string(), "cd".to_string()];
+
+    let line = format_solution(&solution, None, &OutputFormat::Jsonl, &CsvDialect::default()).unwrap();
+
+    assert_eq!(r#"{"rows":["ab","cd"],"columns":["ac","bd"],"word_size":2}"#, line);
+    assert_eq!(Some("ab".to_string()), seed_of_line(&line, &OutputFormat::Jsonl));
+}
+
+#[test]
+fn tagged_solution_as_jsonl() {
+
+    let solution = vec!["ab".to_string(), "cd".to_string()];
+    let word_tags: WordTags = [("ac".to_string(), vec!["animal".to_string()]), ("zz".to_string(), vec!["sleep".to_string()])].into_iter().collect();
+
+    let line = format_tagged_solution(&solution, None, &word_tags, &OutputFormat::Jsonl, &CsvDialect::default()).unwrap();
+    assert_eq!(r#"{"rows":["ab","cd"],"columns":["ac","bd"],"word_size":2,"tags":{"ac":["animal"]}}"#, line);
+
+    // csv has nowhere for them
+    assert_eq!("ab,cd", format_tagged_solution(&solution, None, &word_tags, &OutputFormat::Csv, &CsvDialect::default()).unwrap());
+}
+
+#[test]
+fn format_solution_as_csv() {
+
+    let solution = vec!["ab".to_string(), "cd".to_string()];
+
+    let line = format_solution(&solution, None, &OutputFormat::Csv, &CsvDialect::default()).unwrap();
+
+    assert_eq!("ab,cd", line);
+    assert_eq!(Some("ab".to_string()), seed_of_line(&line, &OutputFormat::Csv));
+}
+
+#[test]
+fn read_csv_lines_back() {
+
+    let csv_dialect = CsvDialect { delimiter: b'\t', dictionary_header: false, output_header: true };
+    let solution = vec!["ab".to_string(), "c d".to_string()];
+
+    let line = format_solution(&solution, Some(1.5), &OutputFormat::Csv, &csv_dialect).unwrap();
+    assert_eq!(solution, rows_of_csv_line(&line, &csv_dialect));
+
+    // quoted because of the delimiter
+    let line = format_solution(&["a,b".to_string(), "cd".to_string()], None, &OutputFormat::Csv, &CsvDialect::default()).unwrap();
+    assert_eq!(vec!["a,b".to_string(), "cd".to_string()], rows_of_line(&line));
+
+    // the header has no words
+    assert!(rows_of_csv_line(&csv_header(2, true, &csv_dialect).unwrap(), &csv_dialect).is_empty());
+    assert!(rows_of_line(&csv_header(5, false, &CsvDialect::default()).unwrap()).is_empty());
+    assert_eq!(None, seed_of_line("row_1,row_2", &OutputFormat::Csv));
+}
+
+#[test]
+fn words_that_parse_as_numbers_are_not_scores() {
+
+    assert_eq!(vec!["inf", "nan", "fan"], rows_of_line("inf,nan,fan"));
+    assert_eq!(vec!["inf", "nan", "fan"], rows_of_line("inf,nan,fan,2.500"));
+    assert_eq!(vec!["nan", "ago", "not"], rows_of_line("nan,ago,not,NaN"));
+}
+
+#[test]
+fn format_solution_as_grid() {
+
+    let solution = vec!["ab".to_string(), "cd".to_string()];
+
+    let grid = format_solution(&solution, None, &OutputFormat::Grid, &CsvDialect::default()).unwrap();
+
+    assert_eq!("+---+---+\n| a | b |\n+---+---+\n| c | d |\n+---+---+\n", grid);
+}
+
+#[test]
+fn save_solutions_to_sqlite_indexes_words() {
+
+    let file_path = "test_solutions.db";
+
+    let solutions = vec![vec!["ab".to_string(), "cd".to_string()], vec!["ef".to_string(), "gh".to_string()]];
+
+    assert_eq!(2, save_solutions_to_sqlite(file_path, 1, None, solutions.into_iter().map(Solution::from)).unwrap());
+
+    let connection = Connection::open(file_path).unwrap();
+    let rows: String = connection.query_row(
+        "SELECT rows FROM solutions JOIN solution_words ON solutions.id = solution_words.solution_id WHERE word = 'bd'",
+        [], |row| row.get(0)).unwrap();
+    drop(connection);
+
+    fs::remove_file(file_path).unwrap();
+
+    assert_eq!("ab,cd", rows);
+}
+
+#[test]
+fn compressed_output_round_trip() {
+
+    for (file_path, compression) in [("test_output.csv.gz", Compression::Gzip), ("test_output.csv.zst", Compression::Zstd)] {
+
+        let mut writer = OutputWriter::create(file_path, compression).unwrap();
+        writeln!(writer, "ab,cd").unwrap();
+        writer.flush().unwrap();
+        writeln!(writer, "ef,gh").unwrap();
+        writer.finish().unwrap();
+
+        let lines: Vec<String> = open_solution_file(file_path).unwrap().lines().map(Result::unwrap).collect();
+        fs::remove_file(file_path).unwrap();
+
+        assert_eq!(vec!["ab,cd".to_string(), "ef,gh".to_string()], lines);
+    }
+}
+
+#[test]
+fn output_only_replaces_the_file_once_finished() {
+
+    let file_path = "test_output_atomic.csv";
+    fs::write(file_path, "ab,cd\n").unwrap();
+
+    let mut writer = OutputWriter::create(file_path, Compression::None).unwrap();
+    writeln!(writer, "ef,gh").unwrap();
+    writer.flush().unwrap();
+
+    // the old file is untouched while writing and again when the writer is abandoned
+    assert_eq!("ab,cd\n", fs::read_to_string(file_path).unwrap());
+    drop(writer);
+    assert_eq!("ab,cd\n", fs::read_to_string(file_path).unwrap());
+    assert!(!Path::new("test_output_atomic.csv.tmp").exists());
+
+    let mut writer = OutputWriter::create(file_path, Compression::None).unwrap();
+    writeln!(writer, "ef,gh").unwrap();
+    writer.finish().unwrap();
+
+    let contents = fs::read_to_string(file_path).unwrap();
+    fs::remove_file(file_path).unwrap();
+
+    assert_eq!("ef,gh\n", contents);
+    assert!(!Path::new("test_output_atomic.csv.tmp").exists());
+}
+
+#[test]
+fn worker_files_are_put_together_in_order() {
+
+    let file_path = "test_worker_output.csv";
+    let worker_file_paths: Vec<String> = (0..2).map(|worker_number| worker_file_path(file_path, worker_number)).collect();
+
+    for (worker_file_path, solution) in worker_file_paths.iter().zip(["ab,cd", "ef,gh"]) {
+        let mut worker_file = WorkerFile::create(worker_file_path, OutputFormat::Csv, CsvDialect::default()).unwrap();
+        worker_file.write_solutions(&[Solution::new(solution.split(',').map(str::to_string).collect())]).unwrap();
+        worker_file.finish().unwrap();
+    }
+
+    concatenate_worker_files(file_path, Compression::None, Some("row_1,row_2"), &worker_file_paths).unwrap();
+
+    let contents = fs::read_to_string(file_path).unwrap();
+    fs::remove_file(file_path).unwrap();
+
+    assert_eq!("row_1,row_2\nab,cd\nef,gh\n", contents);
+    assert!(!Path::new("test_worker_output.csv.worker_0000").exists());
+}
+
+#[test]
+fn format_solution_in_another_dialect() {
+
+    let solution = vec!["ab".to_string(), "c;d".to_string()];
+    let csv_dialect = CsvDialect { delimiter: b';', dictionary_header: false, output_header: true };
+
+    assert_eq!("ab;\"c;d\";1.500", format_solution(&solution, Some(1.5), &OutputFormat::Csv, &csv_dialect).unwrap());
+    assert_eq!("row_1;row_2;score", csv_header(2, true, &csv_dialect).unwrap());
+}
+
+#[test]
+fn shard_file_paths() {
+
+    assert_eq!("solutions_0001.csv", output_shard_file_path("solutions.csv", 1));
+    assert_eq!("out/solutions_0012.csv.gz", output_shard_file_path("out/solutions.csv.gz", 12));
+    assert_eq!("./runs/solutions_0003", output_shard_file_path("./runs/solutions", 3));
+}
+
+#[test]
+fn format_scored_solution() {
+
+    let solution = vec!["ab".to_string(), "cd".to_string()];
+
+    assert_eq!("ab,cd,1.500", format_solution(&solution, Some(1.5), &OutputFormat::Csv, &CsvDialect::default()).unwrap());
+    assert_eq!(r#"{"rows":["ab","cd"],"columns":["ac","bd"],"word_size":2,"score":1.5}"#, format_solution(&solution, Some(1.5), &OutputFormat::Jsonl, &CsvDialect::default()).unwrap());
+}
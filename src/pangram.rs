@@ -0,0 +1,76 @@
+use std::fmt;
+use std::time::Duration;
+use crate::error::SquardleError;
+use crate::solution_generator::{letter_mask, LetterCoverage};
+
+const DEFAULT_BUDGET: Duration = Duration::from_secs(60);
+
+/* pangram dictionary_file with optional --letters n to stop at, --seconds n to search for and --json */
+#[derive(Debug, PartialEq)]
+pub struct PangramConfig {
+    pub dictionary_file_path: String,
+    pub target_letters: Option<usize>,
+    pub budget: Duration,
+    pub json: bool
+}
+
+impl PangramConfig {
+
+    pub fn build(args: &[String]) -> Result<PangramConfig, SquardleError> {
+
+        let mut target_letters: Option<usize> = None;
+        let mut budget = DEFAULT_BUDGET;
+        let mut json = false;
+        let mut positional_args: Vec<String> = Vec::with_capacity(1);
+        let mut args_iter = args.iter();
+
+        while let Some(arg) = args_iter.next() {
+
+            match arg.as_str() {
+                "--letters" => target_letters = match args_iter.next().map(|value| value.parse::<usize>()) {
+                    Some(Ok(letters)) if (1..=26).contains(&letters) => Some(letters),
+                    _ => return Err("Letters must be a number from 1 to 26".into())
+                },
+                "--seconds" => budget = match args_iter.next().map(|value| value.parse::<u64>()) {
+                    Some(Ok(seconds)) if seconds > 0 => Duration::from_secs(seconds),
+                    _ => return Err("Seconds must be a number greater than 0".into())
+                },
+                "--json" => json = true,
+                _ => positional_args.push(arg.clone())
+            }
+        }
+
+        let [dictionary_file_path]: [String; 1] = positional_args.try_into()
+            .map_err(|_| SquardleError::from("pangram accepts a dictionary file path and optionally --letters n, --seconds n and --json"))?;
+
+        Ok(PangramConfig { dictionary_file_path, target_letters, budget, json })
+    }
+}
+
+impl fmt::Display for LetterCoverage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+
+        let Some(square) = &self.square else {
+            return write!(f, "no square found{}", if self.timed_out { " before running out of time" } else { "" });
+        };
+
+        let letters = square.iter().fold(0, |mask, row| mask | letter_mask(row));
+        let missing: String = (0..26).filter(|index| letters & 1 << index == 0).map(|index| (b'a' + index as u8) as char).collect();
+
+        writeln!(f, "{}", square.join(","))?;
+        writeln!(f, "letters: {}, missing {}", self.letters, missing)?;
+        write!(f, "{}", if self.timed_out { "ran out of time, there could be a square with more" } else { "searched every square" })
+    }
+}
+
+#[test]
+fn build_pangram_config() {
+
+    let args: Vec<String> = ["words.csv", "--letters", "20", "--seconds", "5"].iter().map(|arg| arg.to_string()).collect();
+
+    assert_eq!(PangramConfig { dictionary_file_path: "words.csv".to_string(), target_letters: Some(20), budget: Duration::from_secs(5), json: false },
+        PangramConfig::build(&args).unwrap());
+
+    assert!(PangramConfig::build(&["words.csv".to_string(), "--letters".to_string(), "27".to_string()]).is_err());
+    assert!(PangramConfig::build(&[]).is_err());
+}
@@ -0,0 +1,392 @@
+use std::collections::HashSet;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use serde::{Deserialize, Serialize};
+use crate::error::SquardleError;
+use crate::feedback::{self, LetterFeedback, Line};
+use crate::hints::{self, Hint, HintKind};
+use crate::puzzle;
+
+const GUESSES_PER_LETTER_ROW: usize = 4;
+
+const GREEN: &str = "\x1b[42;30m";
+const YELLOW: &str = "\x1b[43;30m";
+const ORANGE: &str = "\x1b[48;5;208;30m";
+const WHITE: &str = "\x1b[47;30m";
+const GREY: &str = "\x1b[100;37m";
+const RESET: &str = "\x1b[0m";
+
+/* A square with its letters hidden, guessing a row or column word reveals the letters
+  that were in the right place. Won once every letter is showing */
+pub struct Game {
+    rows: Vec<String>,
+    revealed: Vec<Vec<bool>>,
+    dictionary: HashSet<String>,
+    guesses: Vec<(Line, String)>,
+    pub guesses_left: usize
+}
+
+/* A game part way through, enough to pick it up again with the same solutions file. The
+  puzzle id is the one the export gives the square so a frontend can match them up */
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct GameState {
+    pub puzzle_id: String,
+    pub guesses: Vec<SavedGuess>,
+    pub revealed: Vec<Vec<bool>>,
+    pub guesses_left: usize
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedGuess {
+    pub line: Line,
+    pub word: String
+}
+
+impl GameState {
+
+    pub fn load(file_path: &str) -> Result<GameState, SquardleError> {
+
+        let file = File::open(file_path)?;
+        let state = serde_json::from_reader(BufReader::new(file))?;
+
+        Ok(state)
+    }
+
+    /* writes to a temp file first so quitting mid-save never loses the game */
+    pub fn save(&self, file_path: &str) -> Result<(), SquardleError> {
+
+        let temp_file_path = format!("{}.tmp", file_path);
+
+        let mut file = BufWriter::new(File::create(&temp_file_path)?);
+        serde_json::to_writer_pretty(&mut file, self)?;
+        file.flush()?;
+        drop(file);
+
+        fs::rename(&temp_file_path, file_path)?;
+
+        Ok(())
+    }
+}
+
+impl Game {
+
+    pub fn new(rows: Vec<String>, dictionary: HashSet<String>) -> Game {
+
+        let word_size = rows.len();
+
+        Game {
+            rows,
+            revealed: vec![vec![false; word_size]; word_size],
+            dictionary,
+            guesses: vec![],
+            guesses_left: word_size * GUESSES_PER_LETTER_ROW
+        }
+    }
+
+    /* carries on a saved game, the rows have to be the square it was saved from */
+    pub fn from_state(rows: Vec<String>, dictionary: HashSet<String>, state: GameState) -> Result<Game, SquardleError> {
+
+        if puzzle::puzzle_id(&rows) != state.puzzle_id {
+            return Err(format!("the saved game is for puzzle {} not {}", state.puzzle_id, puzzle::puzzle_id(&rows)).as_str().into());
+        }
+
+        if state.revealed.len() != rows.len() || state.revealed.iter().any(|row| row.len() != rows.len()) {
+            return Err("the saved game doesn't match the size of its square".into());
+        }
+
+        Ok(Game {
+            rows,
+            revealed: state.revealed,
+            dictionary,
+            guesses: state.guesses.into_iter().map(|guess| (guess.line, guess.word)).collect(),
+            guesses_left: state.guesses_left
+        })
+    }
+
+    pub fn state(&self) -> GameState {
+
+        GameState {
+            puzzle_id: puzzle::puzzle_id(&self.rows),
+            guesses: self.guesses.iter().map(|(line, word)| SavedGuess { line: *line, word: word.clone() }).collect(),
+            revealed: self.revealed.clone(),
+            guesses_left: self.guesses_left
+        }
+    }
+
+    pub fn guess(&mut self, line: Line, word: &str) -> Result<Vec<LetterFeedback>, SquardleError> {
+
+        let word = word.to_lowercase();
+        let feedback = feedback::guess_feedback(&self.rows, line, &word)?;
+
+        if !self.dictionary.contains(&word) {
+            return Err(format!("[{}] is not in the dictionary", word).as_str().into());
+        }
+
+        for (i, letter_feedback) in feedback.iter().enumerate() {
+            if *letter_feedback == LetterFeedback::Correct {
+                match line {
+                    Line::Row(row) => self.revealed[row][i] = true,
+                    Line::Column(column) => self.revealed[i][column] = true
+                }
+            }
+        }
+
+        self.guesses.push((line, word));
+        self.guesses_left -= 1;
+
+        Ok(feedback)
+    }
+
+    /* a hint of the kind asked for, revealing any letters it gives */
+    pub fn hint(&mut self, kind: HintKind) -> Result<Option<Hint>, SquardleError> {
+
+        let hint = hints::hint(&self.rows, &self.revealed, &self.guesses, kind)?;
+
+        if let Some(Hint::Letters(cells)) = &hint {
+            for (row, column, _) in cells {
+                self.revealed[*row][*column] = true;
+            }
+        }
+
+        Ok(hint)
+    }
+
+    pub fn is_won(&self) -> bool {
+        self.revealed.iter().all(|row| row.iter().all(|revealed| *revealed))
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.is_won() || self.guesses_left == 0
+    }
+
+    pub fn answer(&self) -> String {
+        self.rows.join("\n")
+    }
+}
+
+impl fmt::Display for Game {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+
+        write!(f, "  ")?;
+        for i in 0..self.rows.len() {
+            write!(f, " c{}", i + 1)?;
+        }
+
+        for (i, row) in self.rows.iter().enumerate() {
+
+            write!(f, "\nr{} ", i + 1)?;
+
+            for (j, letter) in row.chars().enumerate() {
+                if self.revealed[i][j] {
+                    write!(f, "{} {} {}", GREEN, letter, RESET)?;
+                } else {
+                    write!(f, " _ ")?;
+                }
+            }
+        }
+
+        write!(f, "\n{} guesses left", self.guesses_left)
+    }
+}
+
+/* a row or column as players write them, r1 or c3 counting from 1 */
+pub fn parse_line(line: &str) -> Option<Line> {
+
+    let (kind, number) = line.split_at_checked(1)?;
+    let index = number.parse::<usize>().ok()?.checked_sub(1)?;
+
+    match kind {
+        "r" | "R" => Some(Line::Row(index)),
+        "c" | "C" => Some(Line::Column(index)),
+        _ => None
+    }
+}
+
+fn parse_hint_kind(kind: &str) -> Option<HintKind> {
+    match kind {
+        "vowels" => Some(HintKind::Vowels),
+        "line" => Some(HintKind::Line),
+        "letter" => Some(HintKind::Letter),
+        _ => None
+    }
+}
+
+fn render_feedback(word: &str, feedback: &[LetterFeedback]) -> String {
+
+    word.chars().zip(feedback).map(|(letter, letter_feedback)| {
+        let colour = match letter_feedback {
+            LetterFeedback::Correct => GREEN,
+            LetterFeedback::InRow => YELLOW,
+            LetterFeedback::InColumn => ORANGE,
+            LetterFeedback::ElsewhereInGrid => WHITE,
+            LetterFeedback::Absent => GREY
+        };
+        format!("{} {} {}", colour, letter.to_ascii_uppercase(), RESET)
+    })
+    .collect()
+}
+
+/* Plays a game reading guesses like r2 heart or c1 bases a line at a time until it is won,
+  out of guesses, or the input ends. Returns whether it was won, the game is left as it was
+  stopped so it can be saved */
+pub fn play<R: BufRead, W: Write>(game: &mut Game, input: R, out: &mut W) -> Result<bool, SquardleError> {
+
+    writeln!(out, "guess a row or column word, e.g. r1 heart or c3 bases, hint vowels, hint line or hint letter for help, quit to give up")?;
+    writeln!(out, "{}green{} right place, {}yellow{} in the row, {}orange{} in the column, {}white{} elsewhere in the square\n{}",
+        GREEN, RESET, YELLOW, RESET, ORANGE, RESET, WHITE, RESET, game)?;
+
+    let mut lines = input.lines();
+
+    while !game.is_over() {
+
+        write!(out, "> ")?;
+        out.flush()?;
+
+        let input_line = match lines.next() {
+            Some(input_line) => input_line?,
+            None => break
+        };
+
+        let mut parts = input_line.split_whitespace();
+
+        let (line, word) = match (parts.next(), parts.next()) {
+            (Some("quit"), _) => break,
+            (Some("hint"), kind) => {
+                match kind.and_then(parse_hint_kind).map(|kind| game.hint(kind)).transpose()? {
+                    Some(Some(Hint::Letters(_))) => writeln!(out, "{}", game)?,
+                    Some(Some(Hint::WordLine { word, line: Line::Row(row) })) => writeln!(out, "{} is row {}", word, row + 1)?,
+                    Some(Some(Hint::WordLine { word, line: Line::Column(column) })) => writeln!(out, "{} is column {}", word, column + 1)?,
+                    Some(None) => writeln!(out, "no hint of that kind left")?,
+                    None => writeln!(out, "ask for hint vowels, hint line or hint letter")?
+                }
+                continue;
+            },
+            (Some(line), Some(word)) => match parse_line(line) {
+                Some(line) => (line, word),
+                None => {
+                    writeln!(out, "pick a row or column like r1 or c3")?;
+                    continue;
+                }
+            },
+            _ => {
+                writeln!(out, "guess a row or column word, e.g. r1 heart")?;
+                continue;
+            }
+        };
+
+        match game.guess(line, word) {
+            Ok(feedback) => writeln!(out, "{}\n{}", render_feedback(word, &feedback), game)?,
+            Err(err) => writeln!(out, "{}", err)?
+        }
+    }
+
+    if game.is_won() {
+        writeln!(out, "solved with {} guesses left", game.guesses_left)?;
+    } else if game.is_over() {
+        writeln!(out, "the square was\n{}", game.answer())?;
+    }
+
+    Ok(game.is_won())
+}
+
+/* play solutions_file dictionary_file with optional --index n and --save file */
+#[derive(Debug, Default, PartialEq)]
+pub struct PlayConfig {
+    pub solutions_file_path: String,
+    pub dictionary_file_path: String,
+    pub index: Option<usize>,
+    pub save_file_path: Option<String>
+}
+
+impl PlayConfig {
+
+    pub fn build(args: &[String]) -> Result<PlayConfig, SquardleError> {
+
+        let mut config = PlayConfig::default();
+        let mut positional_args: Vec<String> = Vec::with_capacity(2);
+        let mut args_iter = args.iter();
+
+        while let Some(arg) = args_iter.next() {
+
+            match arg.as_str() {
+                "--index" => match args_iter.next().map(|index| index.parse::<usize>()) {
+                    Some(Ok(index)) => config.index = Some(index),
+                    _ => return Err("Index must be a number".into())
+                },
+                "--save" => match args_iter.next() {
+                    Some(file_path) => config.save_file_path = Some(file_path.clone()),
+                    None => return Err("Save flag needs a file path".into())
+                },
+                _ => positional_args.push(arg.clone())
+            }
+        }
+
+        if positional_args.len() != 2 {
+            return Err("play accepts a solutions file path, a dictionary file path and optional flags: --index n, --save file path".into());
+        }
+
+        config.dictionary_file_path = positional_args.pop().expect("there are two positional args");
+        config.solutions_file_path = positional_args.pop().expect("there are two positional args");
+
+        Ok(config)
+    }
+}
+
+#[test]
+fn play_until_solved() {
+
+    let dictionary: HashSet<String> = ["ab", "cd", "ac", "bd"].iter().map(|word| word.to_string()).collect();
+    let mut game = Game::new(vec!["ab".to_string(), "cd".to_string()], dictionary);
+
+    let input = "r1 ba\nr9 ab\nhint line\nr1 ab\nc2 bd\nc1 ac\n".as_bytes();
+    let mut out: Vec<u8> = Vec::new();
+
+    assert!(play(&mut game, input, &mut out).unwrap());
+
+    let out = String::from_utf8(out).unwrap();
+    assert!(out.contains("[ba] is not in the dictionary"));
+    assert!(out.contains("there are only 2 rows and columns"));
+    assert!(out.contains("no hint of that kind left"));
+    assert!(out.contains("solved with 5 guesses left"));
+}
+
+#[test]
+fn save_and_resume_a_game() {
+
+    let dictionary: HashSet<String> = ["ab", "cd", "ac", "bd"].iter().map(|word| word.to_string()).collect();
+    let rows = vec!["ab".to_string(), "cd".to_string()];
+    let mut game = Game::new(rows.clone(), dictionary.clone());
+
+    play(&mut game, "r1 ab\nquit\n".as_bytes(), &mut Vec::new()).unwrap();
+
+    let file_path = "test_game_state.json";
+    game.state().save(file_path).unwrap();
+
+    let state = GameState::load(file_path).unwrap();
+    fs::remove_file(file_path).unwrap();
+
+    assert_eq!(vec![SavedGuess { line: Line::Row(0), word: "ab".to_string() }], state.guesses);
+    assert_eq!(7, state.guesses_left);
+    assert!(Game::from_state(vec!["ac".to_string(), "bd".to_string()], dictionary.clone(), game.state()).is_err());
+
+    let mut game = Game::from_state(rows, dictionary, state).unwrap();
+
+    assert!(play(&mut game, "r2 cd\n".as_bytes(), &mut Vec::new()).unwrap());
+    assert_eq!(6, game.guesses_left);
+}
+
+#[test]
+fn build_play_config() {
+
+    let args: Vec<String> = ["squares.csv", "--save", "game.json", "words.csv", "--index", "3"].iter().map(|arg| arg.to_string()).collect();
+
+    let config = PlayConfig::build(&args).unwrap();
+
+    assert_eq!("squares.csv", config.solutions_file_path);
+    assert_eq!("words.csv", config.dictionary_file_path);
+    assert_eq!(Some(3), config.index);
+    assert_eq!(Some("game.json".to_string()), config.save_file_path);
+
+    assert!(PlayConfig::build(&args[..3]).is_err());
+}
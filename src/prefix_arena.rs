@@ -0,0 +1,191 @@
+use std::hash::{BuildHasher, RandomState};
+use std::mem;
+use hashbrown::HashTable;
+use crate::error::SquardleError;
+use crate::solution_generator::letter_index;
+use crate::word_list::{WordList, Words};
+
+/* The starts of the words, of two letters up to whole words, each with the words starting that way.
+  The sorted words are kept once and a start is only the run of them it has and how many letters
+  it is, which is always a run as sorted words starting the same way are all together. A start's
+  letters are the first letters of the first word of its run, so nothing but the words is held as
+  text, and that can be the dictionary file mapped, see WordList. Looked up by start to the same
+  words as the prefix map generate_starts_that_have_words makes */
+#[derive(Clone)]
+pub struct PrefixArena {
+    words: WordList,
+    runs: HashTable<PrefixRun>,
+    hasher: RandomState,
+    // by first letter the letters that can come second, the starts of a single letter aren't runs
+    second_letters: [u32; 26]
+}
+
+/* the words of a start by where they are in the sorted words, how many letters the start is and the letters that can come next */
+#[derive(Clone, Copy)]
+struct PrefixRun {
+    start: u32,
+    end: u32,
+    length: u32,
+    next_letters: u32
+}
+
+impl PrefixArena {
+
+    /* the words all have to be the same size, like generate_starts_that_have_words only starts of a to z are looked up */
+    pub fn new(dictionary: &[String]) -> Result<PrefixArena, SquardleError> {
+        PrefixArena::from_word_list(WordList::from_words(dictionary))
+    }
+
+    /* the same over words that are already a list, their text isn't copied */
+    pub fn from_word_list(words: WordList) -> Result<PrefixArena, SquardleError> {
+
+        if words.is_empty() {
+            return Err(SquardleError::EmptyDictionary)
+        }
+
+        // the list is sorted so the first word isn't the first in the file, any word will do to check the rest against
+        let word_size = words.get(0).chars().count();
+
+        if let Some(word) = words.iter().find(|word| word.len() != word_size) {
+            return Err(SquardleError::IncorrectWordSize { word: word.to_string(), expected: word_size, found: word.len() })
+        }
+
+        let hasher = RandomState::new();
+        let mut runs: HashTable<PrefixRun> = HashTable::new();
+        let mut second_letters = [0; 26];
+
+        for length in 2..=word_size {
+
+            let mut start = 0;
+
+            while start < words.len() {
+
+                // by bytes as a word with a letter outside a to z can have a start ending part way through one
+                let prefix = &words.get(start).as_bytes()[..length];
+                let end = (start + 1..words.len()).find(|index| !words.get(*index).as_bytes().starts_with(prefix)).unwrap_or(words.len());
+
+                if prefix.iter().all(u8::is_ascii_lowercase) {
+
+                    // a word's letter after the start is one a to z or it can't be in a square, whole words have none after
+                    let next_letters = (start..end)
+                        .filter_map(|index| words.get(index)[length..].chars().next().and_then(letter_index))
+                        .fold(0, |mask, index| mask | 1 << index);
+
+                    if length == 2 {
+                        second_letters[(prefix[0] - b'a') as usize] |= 1 << (prefix[1] - b'a');
+                    }
+
+                    let run = PrefixRun { start: start as u32, end: end as u32, length: length as u32, next_letters };
+                    runs.insert_unique(hasher.hash_one(run_prefix(&words, &run)), run, |run| hasher.hash_one(run_prefix(&words, run)));
+                }
+
+                start = end;
+            }
+        }
+
+        Ok(PrefixArena { words, runs, hasher, second_letters })
+    }
+
+    pub fn len(&self) -> usize {
+        self.runs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.runs.is_empty()
+    }
+
+    fn run(&self, prefix: &str) -> Option<&PrefixRun> {
+        self.runs.find(self.hasher.hash_one(prefix), |run| run_prefix(&self.words, run) == prefix)
+    }
+
+    pub fn contains_key(&self, prefix: &str) -> bool {
+        self.run(prefix).is_some()
+    }
+
+    /* every word once, sorted */
+    pub fn words(&self) -> Words<'_> {
+        self.words.words()
+    }
+
+    /* The letters that can come after a column start, a bit each, none for a start no word has. From
+      the words the start leads to, so a row before the last can only screen out a letter no start has
+      next and the last row one no word ends in */
+    pub fn next_letters(&self, start: &str) -> u32 {
+
+        match start.len() {
+            1 => start.chars().next().and_then(letter_index).map_or(0, |index| self.second_letters[index]),
+            _ => self.run(start).map_or(0, |run| run.next_letters)
+        }
+    }
+
+    pub fn get(&self, prefix: &str) -> Option<Words<'_>> {
+        self.run(prefix).map(|run| self.words.slice(run.start as usize, run.end as usize))
+    }
+
+    /* roughly the heap it takes up, the words and the runs with a byte of hash table control for each slot */
+    pub fn estimated_bytes(&self) -> usize {
+        self.words.estimated_bytes() + self.runs.capacity() * (mem::size_of::<PrefixRun>() + 1)
+    }
+
+    /* every start with its words, in no particular order */
+    pub fn iter(&self) -> impl Iterator<Item = (&str, Words<'_>)> + '_ {
+        self.runs.iter().map(|run| (run_prefix(&self.words, run), self.words.slice(run.start as usize, run.end as usize)))
+    }
+}
+
+fn run_prefix<'a>(words: &'a WordList, run: &PrefixRun) -> &'a str {
+    &words.get(run.start as usize)[..run.length as usize]
+}
+
+#[test]
+fn arena_looks_up_like_the_prefix_map() {
+
+    let dictionary: Vec<String> = ["bad", "bat", "cab", "cat", "tab"].iter().map(|word| word.to_string()).collect();
+    let prefix_map = crate::dictionary::generate_starts_that_have_words(&dictionary).unwrap();
+
+    let arena = PrefixArena::new(&dictionary).unwrap();
+
+    assert_eq!(prefix_map.len(), arena.len());
+    assert_eq!(dictionary, arena.words.to_words());
+
+    for (prefix, words) in &prefix_map {
+        assert_eq!(Some(words.clone()), arena.get(prefix).map(|words| words.iter().map(str::to_string).collect()));
+    }
+
+    assert!(arena.contains_key("ca"));
+    assert_eq!(Some(vec!["tab"]), arena.get("ta").map(|words| words.iter().collect()));
+    assert!(arena.get("da").is_none());
+    assert!(arena.get("t").is_none());
+}
+
+#[test]
+fn next_letters_come_from_starts_and_words() {
+
+    let words: Vec<String> = ["abc", "abd", "axe"].iter().map(|word| word.to_string()).collect();
+    let arena = PrefixArena::new(&words).unwrap();
+
+    assert_eq!(1 << 1 | 1 << 23, arena.next_letters("a"));
+    assert_eq!(1 << 2 | 1 << 3, arena.next_letters("ab"));
+    assert_eq!(0, arena.next_letters("abc"));
+    assert_eq!(0, arena.next_letters("b"));
+    assert_eq!(0, arena.next_letters(""));
+}
+
+#[test]
+fn arena_only_has_starts_of_a_to_z() {
+
+    let dictionary: Vec<String> = ["ab-d", "abcd", "zz'z"].iter().map(|word| word.to_string()).collect();
+    let prefix_map = crate::dictionary::generate_starts_that_have_words(&dictionary).unwrap();
+
+    let arena = PrefixArena::new(&dictionary).unwrap();
+
+    let mut starts: Vec<&str> = arena.iter().map(|(prefix, _)| prefix).collect();
+    starts.sort_unstable();
+
+    let mut expected: Vec<&str> = prefix_map.keys().map(String::as_str).collect();
+    expected.sort_unstable();
+
+    assert_eq!(expected, starts);
+    assert_eq!(Some(vec!["ab-d", "abcd"]), arena.get("ab").map(|words| words.iter().collect()));
+    assert!(matches!(PrefixArena::new(&["abc".to_string(), "ab".to_string()]), Err(SquardleError::IncorrectWordSize { .. })));
+}
@@ -0,0 +1,110 @@
+use std::collections::HashMap;
+use crate::dictionary::{generate_starts_that_have_words, Dictionary};
+use crate::error::SquardleError;
+
+/* The words of a dictionary looked up by how they start, for anything that wants to know what a
+  partly typed word could still become, hints and autocomplete say. The words are kept sorted so
+  the ones starting the same way are always a run of them, found with a binary search for any
+  prefix at all, unlike the prefix map which only has starts of two letters or more */
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PrefixIndex {
+    words: Vec<String>
+}
+
+impl PrefixIndex {
+
+    pub fn new(dictionary: &Dictionary) -> PrefixIndex {
+        PrefixIndex::from_words(dictionary.words())
+    }
+
+    /* the words don't have to be sorted or lowercase, the same word twice is kept once */
+    pub fn from_words(words: &[String]) -> PrefixIndex {
+
+        let mut words: Vec<String> = words.iter().map(|word| word.to_lowercase()).collect();
+        words.sort_unstable();
+        words.dedup();
+
+        PrefixIndex { words }
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    /* every word once, sorted */
+    pub fn words(&self) -> &[String] {
+        &self.words
+    }
+
+    pub fn contains_word(&self, word: &str) -> bool {
+        self.words.binary_search_by(|known| known.as_str().cmp(word)).is_ok()
+    }
+
+    /* whether any word starts this way, every word starts with the empty prefix */
+    pub fn contains_prefix(&self, prefix: &str) -> bool {
+        !self.words_with_prefix(prefix).is_empty()
+    }
+
+    /* the words starting this way, sorted */
+    pub fn words_with_prefix(&self, prefix: &str) -> &[String] {
+
+        let start = self.words.partition_point(|word| word.as_str() < prefix);
+        let end = start + self.words[start..].partition_point(|word| word.starts_with(prefix));
+
+        &self.words[start..end]
+    }
+
+    /* the letters that can come next after the prefix and still be the start of a word, in
+      alphabetical order. Empty for a prefix that is a whole word and the start of nothing longer */
+    pub fn extendable_letters(&self, prefix: &str) -> Vec<char> {
+
+        let mut letters: Vec<char> = self.words_with_prefix(prefix).iter()
+            .filter_map(|word| word[prefix.len()..].chars().next())
+            .collect();
+
+        // the words are sorted so the same next letter is always together
+        letters.dedup();
+
+        letters
+    }
+
+    /* the prefix map the search looks the next row up in, all the words have to be the same length */
+    pub fn prefix_map(&self) -> Result<HashMap<String, Vec<String>>, SquardleError> {
+        generate_starts_that_have_words(&self.words)
+    }
+}
+
+impl From<&Dictionary> for PrefixIndex {
+    fn from(dictionary: &Dictionary) -> PrefixIndex {
+        PrefixIndex::new(dictionary)
+    }
+}
+
+#[test]
+fn prefix_queries() {
+
+    let index = PrefixIndex::new(&Dictionary::from_words(["bad", "bat", "Cab", "cat", "tab", "ta"]));
+
+    assert!(index.contains_prefix("ca") && index.contains_prefix("") && !index.contains_prefix("da"));
+    assert!(index.contains_word("cab") && !index.contains_word("ca"));
+
+    assert_eq!(["bad", "bat"], index.words_with_prefix("ba"));
+    assert_eq!(["ta", "tab"], index.words_with_prefix("ta"));
+    assert!(index.words_with_prefix("z").is_empty());
+
+    assert_eq!(vec!['b', 'c', 't'], index.extendable_letters(""));
+    assert_eq!(vec!['d', 't'], index.extendable_letters("ba"));
+    assert!(index.extendable_letters("bad").is_empty());
+}
+
+#[test]
+fn index_makes_the_prefix_map() {
+
+    let dictionary = Dictionary::from_words(["bad", "bat", "cab", "cat", "tab"]);
+
+    assert_eq!(generate_starts_that_have_words(&dictionary).unwrap(), PrefixIndex::from(&dictionary).prefix_map().unwrap());
+}
@@ -0,0 +1,239 @@
+use std::collections::HashSet;
+use std::fmt;
+use crate::prefix_arena::PrefixArena;
+use crate::solution_generator::letter_index;
+
+/* Cheap statistics about the search a dictionary makes, worked out before any workers start so
+  a configuration with no hope of finishing, or of finding anything, can be given up on early.
+  The estimates treat the letters of a grid as picked independently by how often each letter
+  is at each position, so they're only good for an order of magnitude */
+#[derive(Debug)]
+pub struct Preflight {
+    pub words: usize,
+    pub word_size: usize,
+    // indexed by letter, a to z
+    pub words_per_first_letter: [usize; 26],
+    // the average number of letters that can come after a start, for starts of one letter up
+    pub branching_factors: Vec<f64>,
+    // how many grids of one row, two rows and so on have every column still starting a word, the last is whole squares
+    pub expected_grids_per_depth: Vec<f64>,
+    // row then column of the first cell found that no letter can go in, when there's no square at all
+    pub dead_cell: Option<(usize, usize)>
+}
+
+impl Preflight {
+
+    pub fn new(dictionary: &[String], prefix_map: &PrefixArena) -> Preflight {
+
+        let words = dictionary.len();
+        let word_size = dictionary.first().map_or(0, |word| word.chars().count());
+
+        let mut words_per_first_letter = [0; 26];
+
+        for index in dictionary.iter().filter_map(|word| word.chars().next().and_then(letter_index)) {
+            words_per_first_letter[index] += 1;
+        }
+
+        // the prefix map has the starts of two letters up, the single letters are counted here
+        let mut starts_per_length = vec![0usize; word_size + 1];
+        starts_per_length[1] = dictionary.iter().filter_map(|word| word.chars().next()).collect::<HashSet<char>>().len();
+
+        for (prefix, _) in prefix_map.iter() {
+            if let Some(count) = starts_per_length.get_mut(prefix.chars().count()) {
+                *count += 1;
+            }
+        }
+
+        let branching_factors = (1..word_size)
+            .map(|length| starts_per_length[length + 1] as f64 / starts_per_length[length].max(1) as f64)
+            .collect();
+
+        let expected_grids_per_depth = expected_grids_per_depth(dictionary, word_size);
+        let dead_cell = first_dead_cell(dictionary, word_size);
+
+        Preflight { words, word_size, words_per_first_letter, branching_factors, expected_grids_per_depth, dead_cell }
+    }
+
+    /* every partial grid the search could go through, the whole squares aren't searched past */
+    pub fn expected_partial_grids(&self) -> f64 {
+        self.expected_grids_per_depth.iter().rev().skip(1).sum()
+    }
+
+    pub fn expected_solutions(&self) -> f64 {
+        self.expected_grids_per_depth.last().copied().unwrap_or(0.0)
+    }
+}
+
+/* With rows picked at random from the dictionary the letter a column gets from each row is as
+  likely as that letter is at the column's position in the words. So a column is a start of
+  depth letters as often as the chance of each start summed, and the grids of that depth are
+  every choice of rows times the chance every column is a start */
+fn expected_grids_per_depth(dictionary: &[String], word_size: usize) -> Vec<f64> {
+
+    let words = dictionary.len() as f64;
+
+    // by position then letter
+    let mut frequencies = vec![[0.0f64; 26]; word_size];
+
+    for word in dictionary {
+        for (position, index) in word.chars().take(word_size).enumerate().filter_map(|(position, letter)| letter_index(letter).map(|index| (position, index))) {
+            frequencies[position][index] += 1.0 / words;
+        }
+    }
+
+    (1..=word_size).map(|depth| {
+
+        let starts: HashSet<&str> = dictionary.iter()
+            .filter_map(|word| word.char_indices().nth(depth).map_or(Some(word.as_str()), |(end, _)| word.get(..end)))
+            .collect();
+
+        let columns_start_words: f64 = frequencies.iter().map(|column_frequencies| {
+
+            starts.iter().map(|start| start.chars()
+                .map(|letter| letter_index(letter).map_or(0.0, |index| column_frequencies[index]))
+                .product::<f64>())
+            .sum::<f64>()
+        }).product();
+
+        words.powi(depth as i32) * columns_start_words
+
+    }).collect()
+}
+
+/* Narrows down the letters each cell of the square could have until nothing changes. A cell
+  starts with the letters words have at its column's position as a row and its row's position
+  as a column, then a row or column keeps only the letters of words that fit every one of its
+  cells. A letter is only dropped when no square can have it there, so an empty cell means
+  there are no squares, found from the words alone without searching */
+fn first_dead_cell(dictionary: &[String], word_size: usize) -> Option<(usize, usize)> {
+
+    let words: Vec<Vec<char>> = dictionary.iter().map(|word| word.chars().collect::<Vec<char>>()).filter(|word| word.len() == word_size).collect();
+
+    let letters_at: Vec<HashSet<char>> = (0..word_size).map(|position| words.iter().map(|word| word[position]).collect()).collect();
+
+    let mut cells: Vec<Vec<HashSet<char>>> = (0..word_size)
+        .map(|row| (0..word_size).map(|column| letters_at[column].intersection(&letters_at[row]).copied().collect()).collect())
+        .collect();
+
+    let mut changed = true;
+
+    while changed {
+
+        changed = false;
+
+        // each row then each column, as the cells of line down or across
+        for (line, across) in (0..word_size).flat_map(|line| [(line, true), (line, false)]) {
+
+            let cell = |position: usize| -> (usize, usize) {
+                if across { (line, position) } else { (position, line) }
+            };
+
+            let mut supported: Vec<HashSet<char>> = vec![HashSet::new(); word_size];
+
+            for word in &words {
+
+                let fits = word.iter().enumerate().all(|(position, letter)| {
+                    let (row, column) = cell(position);
+                    cells[row][column].contains(letter)
+                });
+
+                if fits {
+                    for (position, letter) in word.iter().enumerate() {
+                        supported[position].insert(*letter);
+                    }
+                }
+            }
+
+            for (position, letters) in supported.into_iter().enumerate() {
+
+                let (row, column) = cell(position);
+
+                if letters.len() == cells[row][column].len() {
+                    continue;
+                }
+
+                if letters.is_empty() {
+                    return Some((row, column));
+                }
+
+                cells[row][column] = letters;
+                changed = true;
+            }
+        }
+    }
+
+    None
+}
+
+impl fmt::Display for Preflight {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+
+        writeln!(f, "words: {} of {} letters", self.words, self.word_size)?;
+
+        let first_letters: Vec<String> = self.words_per_first_letter.iter().enumerate()
+            .filter(|(_, count)| **count > 0)
+            .map(|(index, count)| format!("{} {}", (b'a' + index as u8) as char, count))
+            .collect();
+
+        writeln!(f, "words per first letter: {}", first_letters.join(", "))?;
+
+        let branching_factors: Vec<String> = self.branching_factors.iter().enumerate()
+            .map(|(length, factor)| format!("{} {:.2}", length + 1, factor))
+            .collect();
+
+        writeln!(f, "letters after a start by its length: {}", branching_factors.join(", "))?;
+
+        if let Some((row, column)) = self.dead_cell {
+            writeln!(f, "no squares: nothing can go in row {} column {}", row + 1, column + 1)?;
+        }
+
+        writeln!(f, "expected partial grids: {:.3e}", self.expected_partial_grids())?;
+        write!(f, "expected solutions: {:.1}", self.expected_solutions())
+    }
+}
+
+#[test]
+fn preflight_of_a_tiny_dictionary() {
+
+    let dictionary: Vec<String> = ["ab", "ba", "bb"].iter().map(|word| word.to_string()).collect();
+    let prefix_map = PrefixArena::new(&dictionary).unwrap();
+
+    let preflight = Preflight::new(&dictionary, &prefix_map);
+
+    assert_eq!((3, 2), (preflight.words, preflight.word_size));
+    assert_eq!([1, 2], preflight.words_per_first_letter[..2]);
+
+    // a then b, b then a or b
+    assert_eq!(vec![1.5], preflight.branching_factors);
+
+    // a first row is always fine, then each column is a word unless it's aa, 8/9 of the time, near the 7 of 9 squares there really are
+    let expected = [3.0, 9.0 * 8.0 / 9.0 * 8.0 / 9.0];
+
+    for (expected, found) in expected.iter().zip(&preflight.expected_grids_per_depth) {
+        assert!((expected - found).abs() < 1e-9, "expected {} found {}", expected, found);
+    }
+}
+
+#[test]
+fn dead_cells_are_found_without_searching() {
+
+    let words = |words: &[&str]| -> Vec<String> { words.iter().map(|word| word.to_string()).collect() };
+
+    // the top row needs a t in the middle to start the second column and an a at the end for the third, no word does
+    assert_eq!(Some((0, 0)), first_dead_cell(&words(&["ate", "tea"]), 3));
+
+    // every cell has a letter to start with, it takes a few rounds of narrowing down to empty one
+    assert!(first_dead_cell(&words(&["era", "rat", "tar"]), 3).is_some());
+
+    assert_eq!(None, first_dead_cell(&words(&["ab", "ba"]), 2));
+    assert_eq!(None, first_dead_cell(&crate::dictionary::dictionary_from_bytes(include_bytes!("../resources/dictionaries/words_small.csv")).unwrap(), 5));
+}
+
+#[test]
+fn long_words_from_a_short_list_expect_nothing() {
+
+    let dictionary: Vec<String> = ["abandon", "balance", "cabinet", "dolphin", "example"].iter().map(|word| word.to_string()).collect();
+    let prefix_map = PrefixArena::new(&dictionary).unwrap();
+
+    assert!(Preflight::new(&dictionary, &prefix_map).expected_solutions() < 0.01);
+}
@@ -0,0 +1,185 @@
+use serde::Serialize;
+use std::fs::OpenOptions;
+use std::io::{self, IsTerminal, Write};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use log::warn;
+use crate::generator_config::ProgressFormat;
+
+const REDRAW_INTERVAL: Duration = Duration::from_millis(250);
+const RECORD_INTERVAL: Duration = Duration::from_secs(1);
+
+/* one line of the json progress, the last one written has finished set */
+#[derive(Debug, Serialize)]
+struct ProgressRecord {
+    timestamp: f64,
+    seeds_done: u64,
+    seeds_total: u64,
+    solutions_found: u64,
+    seeds_per_second: f64,
+    elapsed_seconds: f64,
+    finished: bool
+}
+
+/* Counters the workers bump as seed words finish and solutions are found, so the progress
+  line shows work actually done rather than work handed out */
+#[derive(Debug)]
+pub struct Progress {
+    seeds_total: u64,
+    seeds_done: AtomicU64,
+    solutions_found: AtomicU64,
+    finished: AtomicBool,
+    started: Instant
+}
+
+impl Progress {
+
+    pub fn new(seeds_total: u64) -> Progress {
+
+        Progress {
+            seeds_total,
+            seeds_done: AtomicU64::new(0),
+            solutions_found: AtomicU64::new(0),
+            finished: AtomicBool::new(false),
+            started: Instant::now()
+        }
+    }
+
+    /* a seed word that was searched to the end or failed, either way there is no more to do for it */
+    pub fn seed_done(&self) {
+        self.seeds_done.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn solution_found(&self) {
+        self.solutions_found.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /* Writes progress until finish is called. As text it redraws a line on stderr, only when
+      stderr is a terminal, or appends lines to the progress file. As json it writes a record
+      a second to stderr or the progress file (which can be a named pipe) whatever the verbosity */
+    pub fn spawn_reporter(progress: Arc<Progress>, progress_format: ProgressFormat, progress_file_path: Option<&str>) -> Option<JoinHandle<()>> {
+
+        let out: Box<dyn Write + Send> = match progress_file_path {
+            Some(file_path) => match OpenOptions::new().append(true).create(true).open(file_path) {
+                Ok(file) => Box::new(file),
+                Err(err) => {
+                    warn!("Problem opening progress file {}: {err}", file_path);
+                    return None;
+                }
+            },
+            None => {
+                // quiet runs and anything piped get no progress line
+                if progress_format == ProgressFormat::Text && (!io::stderr().is_terminal() || !log::log_enabled!(log::Level::Info)) {
+                    return None;
+                }
+
+                Box::new(io::stderr())
+            }
+        };
+
+        let redraw = progress_file_path.is_none();
+
+        Some(thread::spawn(move || progress.report(out, progress_format, redraw)))
+    }
+
+    fn report(&self, mut out: Box<dyn Write + Send>, progress_format: ProgressFormat, redraw: bool) {
+
+        let interval = match progress_format {
+            ProgressFormat::Text if redraw => REDRAW_INTERVAL,
+            _ => RECORD_INTERVAL
+        };
+
+        loop {
+            let finished = self.finished.load(Ordering::Relaxed);
+
+            // a reader hanging up on the pipe is not worth stopping the run for
+            let _ = match progress_format {
+                ProgressFormat::Text if redraw && !finished => write!(out, "\r{}", self.line()),
+                ProgressFormat::Text if redraw => writeln!(out, "\r{}", self.line()),
+                ProgressFormat::Text => writeln!(out, "{}", self.line()),
+                ProgressFormat::Json => writeln!(out, "{}", self.json_record(finished))
+            };
+            let _ = out.flush();
+
+            if finished {
+                return;
+            }
+
+            // sleeps in short steps so finishing is not held up by a whole interval
+            let slept = Instant::now();
+            while slept.elapsed() < interval && !self.finished.load(Ordering::Relaxed) {
+                thread::sleep(Duration::from_millis(50));
+            }
+        }
+    }
+
+    fn json_record(&self, finished: bool) -> String {
+
+        let elapsed = self.started.elapsed();
+        let seeds_done = self.seeds_done.load(Ordering::Relaxed);
+
+        let record = ProgressRecord {
+            timestamp: SystemTime::now().duration_since(UNIX_EPOCH).map(|since| since.as_secs_f64()).unwrap_or(0.0),
+            seeds_done,
+            seeds_total: self.seeds_total,
+            solutions_found: self.solutions_found.load(Ordering::Relaxed),
+            seeds_per_second: seeds_done as f64 / elapsed.as_secs_f64().max(0.001),
+            elapsed_seconds: elapsed.as_secs_f64(),
+            finished
+        };
+
+        serde_json::to_string(&record).unwrap_or_default()
+    }
+
+    pub fn finish(&self) {
+        self.finished.store(true, Ordering::Relaxed);
+    }
+
+    fn line(&self) -> String {
+        format_line(self.seeds_done.load(Ordering::Relaxed), self.seeds_total, self.solutions_found.load(Ordering::Relaxed), self.started.elapsed())
+    }
+}
+
+fn format_line(seeds_done: u64, seeds_total: u64, solutions_found: u64, elapsed: Duration) -> String {
+
+    let percent = if seeds_total == 0 { 100.0 } else { seeds_done as f64 * 100.0 / seeds_total as f64 };
+    let rate = seeds_done as f64 / elapsed.as_secs_f64().max(0.001);
+
+    let eta = if seeds_done == 0 {
+        "--:--".to_string()
+    } else {
+        let remaining_seconds = (seeds_total.saturating_sub(seeds_done) as f64 / rate).round() as u64;
+        format!("{:02}:{:02}", remaining_seconds / 60, remaining_seconds % 60)
+    };
+
+    format!("{}/{} seeds ({:.1}%) | {} solutions | {:.1} seeds/s | ETA {}   ", seeds_done, seeds_total, percent, solutions_found, rate, eta)
+}
+
+#[test]
+fn progress_line_has_rate_and_eta() {
+
+    assert_eq!("50/200 seeds (25.0%) | 7 solutions | 5.0 seeds/s | ETA 00:30   ", format_line(50, 200, 7, Duration::from_secs(10)));
+    assert_eq!("0/200 seeds (0.0%) | 0 solutions | 0.0 seeds/s | ETA --:--   ", format_line(0, 200, 0, Duration::from_secs(1)));
+}
+
+#[test]
+fn progress_records_to_a_file() {
+
+    let file_path = "test_progress.jsonl";
+    let progress = Arc::new(Progress::new(4));
+
+    let reporter = Progress::spawn_reporter(progress.clone(), ProgressFormat::Json, Some(file_path)).unwrap();
+    progress.seed_done();
+    progress.finish();
+    reporter.join().unwrap();
+
+    let contents = std::fs::read_to_string(file_path).unwrap();
+    std::fs::remove_file(file_path).unwrap();
+
+    let last_record: serde_json::Value = serde_json::from_str(contents.lines().last().unwrap()).unwrap();
+
+    assert_eq!(true, last_record["finished"]);
+    assert_eq!(4, last_record["seeds_total"]);
+}
@@ -0,0 +1,329 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fs;
+use serde::{Deserialize, Serialize};
+use crate::daily;
+use crate::difficulty::{Difficulty, DifficultyRater};
+use crate::error::SquardleError;
+use crate::generator_config::Compression;
+use crate::layout::{Direction, GridLayout, Slot};
+use crate::output::OutputWriter;
+use crate::unique;
+
+const PUZZLE_FORMAT_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PuzzleFormat {
+    Json
+}
+
+/* What a game client loads, version 1:
+  {
+    "format_version": 1,
+    "dictionary_hash": "58100c163f98d177",   the dictionary the puzzles were made from, as daily gives it
+    "valid_guesses": ["aback", ...],         every word a guess can be, sorted
+    "puzzles": [{
+      "id": "9f0c...",                       stable for the same rows so progress can be saved against it
+      "word_size": 5,
+      "grid": ["budge", "enter", ...],       the rows of the solved square
+      "hidden": [[0, 1], [0, 2], ...],       [row, column] of the cells the player starts without, row by row
+      "answers": [{"number": 1, "direction": "across", "word": "budge", "clue": "move slightly",
+                   "guesses": ["badge", "budge", ...]}, ...],
+      "difficulty": {"score": 71.3, "band": "medium"}
+    }]
+  }
+  An answer only has a clue when its word is in the definitions file given, so a client has to
+  cope without one. Answers only have guesses, every word that fits the slot's given letters,
+  when they're asked for, and then valid_guesses is only the words that are a guess somewhere
+  rather than the whole dictionary. Any change that would break a client that reads version 1 bumps the version */
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct PuzzleExport {
+    pub format_version: u32,
+    pub dictionary_hash: String,
+    pub valid_guesses: Vec<String>,
+    pub puzzles: Vec<Puzzle>
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Puzzle {
+    pub id: String,
+    pub word_size: usize,
+    pub grid: Vec<String>,
+    pub hidden: Vec<[usize; 2]>,
+    pub answers: Vec<Answer>,
+    pub difficulty: PuzzleDifficulty
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Answer {
+    pub number: usize,
+    pub direction: Direction,
+    pub word: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub clue: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub guesses: Option<Vec<String>>
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct PuzzleDifficulty {
+    pub score: f64,
+    pub band: Difficulty
+}
+
+/* the rows hashed the way a dictionary is, so the same square always gets the same id */
+pub fn puzzle_id(rows: &[String]) -> String {
+    format!("{:016x}", daily::dictionary_hash(rows))
+}
+
+/* word to its gloss, the clue an answer is given */
+pub type Definitions = HashMap<String, String>;
+
+/* a word then its gloss on each line, split at the first tab or, on a line without one, the first comma */
+pub fn load_definitions(file_path: &str) -> Result<Definitions, SquardleError> {
+    Ok(definitions_from_str(&fs::read_to_string(file_path)?))
+}
+
+/* lines without a gloss are skipped, a word defined more than once keeps its first gloss */
+pub fn definitions_from_str(text: &str) -> Definitions {
+
+    let mut definitions = Definitions::new();
+
+    for line in text.lines() {
+
+        let (word, gloss) = match line.split_once('\t').or_else(|| line.split_once(',')) {
+            Some((word, gloss)) => (word.trim().to_lowercase(), gloss.trim()),
+            None => continue
+        };
+
+        if !word.is_empty() && !gloss.is_empty() {
+            definitions.entry(word).or_insert_with(|| gloss.to_string());
+        }
+    }
+
+    definitions
+}
+
+/* Builds a puzzle for each solution. Every cell starts hidden unless reveal is set, then a few
+  letters are given that leave the square only one way to be completed. Answers with a word in
+  definitions get its gloss as their clue, and with slot_guesses set the words that fit */
+pub fn export_puzzles(solutions: &[Vec<String>], dictionary: Vec<String>, rater: &mut DifficultyRater, reveal: bool, definitions: &Definitions, slot_guesses: bool) -> Result<PuzzleExport, SquardleError> {
+
+    let dictionaries = [dictionary];
+
+    // every guess of every slot when there are slot guesses
+    let mut guessable: BTreeSet<String> = BTreeSet::new();
+    let mut puzzles = Vec::with_capacity(solutions.len());
+
+    for rows in solutions {
+
+        let word_size = rows.len();
+        let score = rater.rate(rows)?;
+
+        let hidden: Vec<[usize; 2]> = match reveal {
+            true => unique::minimal_reveal_set(rows, &dictionaries)?.iter().enumerate()
+                .flat_map(|(row, letters)| letters.iter().enumerate().filter(|(_, letter)| letter.is_none()).map(move |(column, _)| [row, column]))
+                .collect(),
+            false => (0..word_size).flat_map(|row| (0..word_size).map(move |column| [row, column])).collect()
+        };
+
+        let hidden_cells: HashSet<[usize; 2]> = hidden.iter().copied().collect();
+
+        let answers = GridLayout::square(word_size).answers(rows).into_iter()
+            .map(|(slot, word)| {
+
+                let guesses = slot_guesses.then(|| guesses_for_slot(slot, rows, &hidden_cells, &dictionaries[0]));
+                guessable.extend(guesses.iter().flatten().cloned());
+
+                Answer { number: slot.number, direction: slot.direction, clue: definitions.get(&word).cloned(), word, guesses }
+            })
+            .collect();
+
+        puzzles.push(Puzzle {
+            id: puzzle_id(rows),
+            word_size,
+            grid: rows.clone(),
+            hidden,
+            answers,
+            difficulty: PuzzleDifficulty { score: score.total, band: score.difficulty() }
+        });
+    }
+
+    let [dictionary] = dictionaries;
+    let dictionary_hash = format!("{:016x}", daily::dictionary_hash(&dictionary));
+
+    Ok(PuzzleExport {
+        format_version: PUZZLE_FORMAT_VERSION,
+        dictionary_hash,
+        valid_guesses: match slot_guesses {
+            true => guessable.into_iter().collect(),
+            false => dictionary
+        },
+        puzzles
+    })
+}
+
+/* the dictionary words as long as the slot with the letters it starts with given, in dictionary order */
+fn guesses_for_slot(slot: &Slot, rows: &[String], hidden: &HashSet<[usize; 2]>, dictionary: &[String]) -> Vec<String> {
+
+    let pattern: Vec<Option<char>> = slot.cells.iter()
+        .map(|&(row, column)| match hidden.contains(&[row, column]) {
+            true => None,
+            false => rows[row].chars().nth(column)
+        })
+        .collect();
+
+    dictionary.iter()
+        .filter(|word| word.chars().count() == pattern.len() && word.chars().zip(&pattern).all(|(letter, given)| given.is_none_or(|given| given == letter)))
+        .cloned()
+        .collect()
+}
+
+pub fn write_puzzles(export: &PuzzleExport, puzzle_format: PuzzleFormat, dest_file_path: &str) -> Result<(), SquardleError> {
+
+    let mut dest_file = OutputWriter::create(dest_file_path, Compression::from_file_path(dest_file_path))?;
+
+    match puzzle_format {
+        PuzzleFormat::Json => serde_json::to_writer_pretty(&mut dest_file, export)?
+    }
+
+    dest_file.finish()?;
+
+    Ok(())
+}
+
+/* export source_file dictionary_file dest_file with optional --puzzle-format json, --frequencies file, --definitions file,
+  --reveal and --slot-guesses */
+#[derive(Debug, PartialEq)]
+pub struct ExportConfig {
+    pub source_file_path: String,
+    pub dictionary_file_path: String,
+    pub dest_file_path: String,
+    pub puzzle_format: PuzzleFormat,
+    pub frequencies_file_path: Option<String>,
+    pub definitions_file_path: Option<String>,
+    pub reveal: bool,
+    pub slot_guesses: bool
+}
+
+impl ExportConfig {
+
+    pub fn build(args: &[String]) -> Result<ExportConfig, SquardleError> {
+
+        let mut puzzle_format = PuzzleFormat::Json;
+        let mut frequencies_file_path: Option<String> = None;
+        let mut definitions_file_path: Option<String> = None;
+        let mut reveal = false;
+        let mut slot_guesses = false;
+        let mut positional_args: Vec<String> = Vec::with_capacity(3);
+        let mut args_iter = args.iter();
+
+        while let Some(arg) = args_iter.next() {
+
+            match arg.as_str() {
+                "--puzzle-format" => puzzle_format = match args_iter.next().map(String::as_str) {
+                    Some("json") => PuzzleFormat::Json,
+                    _ => return Err("Puzzle format must be one of: json".into())
+                },
+                "--frequencies" => match args_iter.next() {
+                    Some(file_path) => frequencies_file_path = Some(file_path.clone()),
+                    None => return Err("Frequencies flag needs a file path".into())
+                },
+                "--definitions" => match args_iter.next() {
+                    Some(file_path) => definitions_file_path = Some(file_path.clone()),
+                    None => return Err("Definitions flag needs a file path".into())
+                },
+                "--reveal" => reveal = true,
+                "--slot-guesses" => slot_guesses = true,
+                _ => positional_args.push(arg.clone())
+            }
+        }
+
+        let [source_file_path, dictionary_file_path, dest_file_path]: [String; 3] = positional_args.try_into()
+            .map_err(|_| SquardleError::from("export accepts a solutions file path, a dictionary file path, a destination file path and optional flags: --puzzle-format json, --frequencies file path, --definitions file path, --reveal, --slot-guesses"))?;
+
+        Ok(ExportConfig { source_file_path, dictionary_file_path, dest_file_path, puzzle_format, frequencies_file_path, definitions_file_path, reveal, slot_guesses })
+    }
+}
+
+#[test]
+fn export_a_puzzle() {
+
+    let (dictionary, prefix_map) = crate::dictionary::test_dictionary();
+
+    let mut rater = DifficultyRater::new(dictionary.clone(), prefix_map, None).unwrap();
+    let solutions = vec![vec!["ten".to_string(), "ore".to_string(), "eat".to_string()]];
+
+    let export = export_puzzles(&solutions, dictionary.clone(), &mut rater, false, &Definitions::new(), false).unwrap();
+    let puzzle = &export.puzzles[0];
+
+    let every_cell: Vec<[usize; 2]> = (0..3).flat_map(|row| (0..3).map(move |column| [row, column])).collect();
+
+    assert_eq!(every_cell, puzzle.hidden);
+    assert_eq!(vec!["ten", "ore", "eat", "toe", "era", "net"], puzzle.answers.iter().map(|answer| answer.word.as_str()).collect::<Vec<&str>>());
+    assert_eq!(dictionary, export.valid_guesses);
+
+    // only the e of eat needs to be given, the same as unique finds
+    let export = export_puzzles(&solutions, dictionary.clone(), &mut rater, true, &Definitions::new(), false).unwrap();
+    assert_eq!(every_cell.iter().filter(|cell| **cell != [2, 0]).copied().collect::<Vec<[usize; 2]>>(), export.puzzles[0].hidden);
+
+    let json = serde_json::to_value(&export).unwrap();
+    assert_eq!(1, json["format_version"]);
+    assert_eq!("down", json["puzzles"][0]["answers"][3]["direction"]);
+    assert_eq!(puzzle_id(&solutions[0]), json["puzzles"][0]["id"]);
+
+    // without definitions there are no clues in the json at all
+    assert!(json["puzzles"][0]["answers"][0].get("clue").is_none());
+    assert!(json["puzzles"][0]["answers"][0].get("guesses").is_none());
+
+    // with the e given the last row has to start with it and the first column end with it
+    let export = export_puzzles(&solutions, dictionary.clone(), &mut rater, true, &Definitions::new(), true).unwrap();
+    let guesses: Vec<Vec<String>> = export.puzzles[0].answers.iter().map(|answer| answer.guesses.clone().unwrap()).collect();
+
+    let words = |words: &[&str]| -> Vec<String> { words.iter().map(|word| word.to_string()).collect() };
+    assert_eq!(vec![dictionary.clone(), dictionary.clone(), words(&["eat", "era"]), words(&["ore", "toe"]), dictionary.clone(), dictionary.clone()], guesses);
+    assert_eq!(dictionary, export.valid_guesses);
+}
+
+#[test]
+fn answers_are_clued_from_definitions() {
+
+    let definitions = definitions_from_str("TEN\tnine, plus one\nore,a rock\nore,not this one\neat\n\ntoe\t  \n");
+
+    // the first tab or comma splits the word from its gloss, the rest is the gloss
+    assert_eq!(2, definitions.len());
+    assert_eq!(Some("nine, plus one"), definitions.get("ten").map(String::as_str));
+    assert_eq!(Some("a rock"), definitions.get("ore").map(String::as_str));
+
+    let (dictionary, prefix_map) = crate::dictionary::test_dictionary();
+
+    let mut rater = DifficultyRater::new(dictionary.clone(), prefix_map, None).unwrap();
+    let export = export_puzzles(&[vec!["ten".to_string(), "ore".to_string(), "eat".to_string()]], dictionary, &mut rater, false, &definitions, false).unwrap();
+
+    let clues: Vec<Option<&str>> = export.puzzles[0].answers.iter().map(|answer| answer.clue.as_deref()).collect();
+    assert_eq!(vec![Some("nine, plus one"), Some("a rock"), None, None, None, None], clues);
+
+    let json = serde_json::to_value(&export).unwrap();
+    assert_eq!("a rock", json["puzzles"][0]["answers"][1]["clue"]);
+    assert!(json["puzzles"][0]["answers"][2].get("clue").is_none());
+
+    // and a client reading it back gets the same puzzles, an answer without a clue still has none
+    assert_eq!(export, serde_json::from_value::<PuzzleExport>(json).unwrap());
+}
+
+#[test]
+fn build_export_config() {
+
+    let args: Vec<String> = ["in.csv", "words.csv", "--puzzle-format", "json", "--reveal", "out.json"].iter().map(|arg| arg.to_string()).collect();
+
+    let config = ExportConfig::build(&args).unwrap();
+
+    assert_eq!("out.json", config.dest_file_path);
+    assert_eq!(PuzzleFormat::Json, config.puzzle_format);
+    assert!(config.reveal);
+    assert_eq!(None, config.definitions_file_path);
+    assert!(!config.slot_guesses);
+    assert_eq!(Some("clues.tsv".to_string()), ExportConfig::build(&[args.clone(), vec!["--definitions".to_string(), "clues.tsv".to_string()]].concat()).unwrap().definitions_file_path);
+
+    assert!(ExportConfig::build(&args[..4]).is_err());
+    assert!(ExportConfig::build(&[args, vec!["--puzzle-format".to_string(), "xml".to_string()]].concat()).is_err());
+}
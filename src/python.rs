@@ -0,0 +1,178 @@
+use numpy::ndarray::Array2;
+use numpy::{IntoPyArray, PyArray2};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use crate::dictionary::{self, Dictionary};
+use crate::error::SquardleError;
+use crate::feedback;
+use crate::generator_config::CsvDialect;
+use crate::play::parse_line;
+use crate::solution_generator::{parse_partial_grid, GridSolver};
+
+/* The python module, built with --features python by maturin or the like. Solutions come back as
+  lists of rows, or as a numpy array of the rows' positions in the sorted dictionary which is far
+  cheaper to hold for analysis. Anything that fails raises a ValueError with the same message the
+  command line would print. The /// comments are what help() shows */
+
+impl From<SquardleError> for PyErr {
+    fn from(err: SquardleError) -> PyErr {
+        PyValueError::new_err(err.to_string())
+    }
+}
+
+/// The sorted, lowercase words squares are made from.
+#[pyclass(name = "Dictionary", module = "squardle_rust")]
+#[derive(Clone)]
+pub struct PyDictionary {
+    dictionary: Dictionary
+}
+
+#[pymethods]
+impl PyDictionary {
+
+    /// A dictionary of the words, lowercased and sorted the same as the words of a file.
+    #[new]
+    fn new(words: Vec<String>) -> PyDictionary {
+        PyDictionary { dictionary: Dictionary::from_words(words) }
+    }
+
+    /// The words of a dictionary file, separated by commas or new lines.
+    #[staticmethod]
+    fn from_path(file_path: &str) -> PyResult<PyDictionary> {
+        Ok(PyDictionary { dictionary: Dictionary::from_path(file_path, &CsvDialect::default())? })
+    }
+
+    /// The words of the contents of a dictionary file, separated by commas or new lines.
+    #[staticmethod]
+    fn from_bytes(bytes: &[u8]) -> PyResult<PyDictionary> {
+        Ok(PyDictionary { dictionary: Dictionary::from(dictionary::dictionary_from_bytes(bytes)?) })
+    }
+
+    /// Only the words this many letters long.
+    fn with_length(&self, length: usize) -> PyDictionary {
+        PyDictionary { dictionary: self.dictionary.clone().with_length(length) }
+    }
+
+    /// Only the words made of nothing but these letters.
+    fn with_charset(&self, letters: &str) -> PyDictionary {
+        PyDictionary { dictionary: self.dictionary.clone().with_charset(letters) }
+    }
+
+    /// None of the words of the blocklist.
+    fn without_words(&self, blocklist: Vec<String>) -> PyDictionary {
+        PyDictionary { dictionary: self.dictionary.clone().without_words(blocklist) }
+    }
+
+    /// Every word, sorted.
+    fn words(&self) -> Vec<String> {
+        self.dictionary.words().to_vec()
+    }
+
+    /// How many letters long the words are going by the first, None when there are no words.
+    fn word_size(&self) -> Option<usize> {
+        self.dictionary.word_size()
+    }
+
+    fn __len__(&self) -> usize {
+        self.dictionary.words().len()
+    }
+
+    fn __contains__(&self, word: &str) -> bool {
+        self.dictionary.contains(word)
+    }
+
+    /// A solver for the words, they all have to be the same size.
+    fn solver(&self) -> PyResult<PySolver> {
+
+        let solver = GridSolver::new(self.dictionary.words().to_vec(), self.dictionary.prefix_map()?)?;
+
+        Ok(PySolver { solver, words: self.dictionary.words().to_vec() })
+    }
+}
+
+/// Completes partial grids from the words of the dictionary it was made from.
+// GridSolver holds the constraints it checks as trait objects that needn't be Send, so it stays on the thread that made it
+#[pyclass(name = "Solver", module = "squardle_rust", unsendable)]
+pub struct PySolver {
+    solver: GridSolver,
+    // sorted, the index arrays are positions in it
+    words: Vec<String>
+}
+
+impl PySolver {
+
+    fn solutions(&mut self, partial_grid: &str, limit: usize) -> Result<Vec<Vec<String>>, SquardleError> {
+
+        let fixed_letters = parse_partial_grid(partial_grid, self.solver.word_size())?;
+        let (solutions, _) = self.solver.solve(fixed_letters, (limit > 0).then_some(limit))?;
+
+        Ok(solutions.into_iter().map(|solution| solution.rows().to_vec()).collect())
+    }
+}
+
+#[pymethods]
+impl PySolver {
+
+    /// The completions of a partial grid written the way the solve command takes it, like "h...t,,e",
+    /// each a list of its rows. A limit of 0 finds every completion, which can take a long time.
+    #[pyo3(signature = (partial_grid = "", limit = 0))]
+    fn solve(&mut self, partial_grid: &str, limit: usize) -> PyResult<Vec<Vec<String>>> {
+        Ok(self.solutions(partial_grid, limit)?)
+    }
+
+    /// The same completions as solve, as a numpy array with a row per solution holding where each
+    /// of its rows is in the dictionary's words.
+    #[pyo3(signature = (partial_grid = "", limit = 0))]
+    fn solve_indices<'py>(&mut self, py: Python<'py>, partial_grid: &str, limit: usize) -> PyResult<Bound<'py, PyArray2<u32>>> {
+
+        // numpy panics making the array when it isn't installed, this raises the ImportError instead
+        py.import("numpy")?;
+
+        let solutions = self.solutions(partial_grid, limit)?;
+        let indices = dictionary::solution_word_indices(&solutions, &self.words)?;
+
+        let shape = (indices.len(), self.solver.word_size());
+        let indices = Array2::from_shape_vec(shape, indices.concat()).expect("every solution has a row per letter of a word");
+
+        Ok(indices.into_pyarray(py))
+    }
+}
+
+/// The feedback for each letter of a guess at a row or column of the hidden square, r1 or c3 say,
+/// as correct, in_row, in_column, elsewhere_in_grid or absent.
+#[pyfunction]
+fn guess_feedback(square: Vec<String>, line: &str, guess: &str) -> PyResult<Vec<String>> {
+
+    let line = parse_line(line).ok_or_else(|| PyValueError::new_err(format!("[{}] is not a line, use r1 or c3 say", line)))?;
+    let feedback = feedback::guess_feedback(&square, line, &guess.to_lowercase())?;
+
+    Ok(feedback.iter().map(|letter_feedback| letter_feedback.name().to_string()).collect())
+}
+
+#[pymodule]
+fn squardle_rust(module: &Bound<'_, PyModule>) -> PyResult<()> {
+
+    module.add_class::<PyDictionary>()?;
+    module.add_class::<PySolver>()?;
+    module.add_function(wrap_pyfunction!(guess_feedback, module)?)?;
+
+    Ok(())
+}
+
+#[test]
+fn solve_through_the_python_module() {
+
+    let dictionary = PyDictionary::from_bytes(&crate::dictionary::test_dictionary_file()).unwrap();
+    let mut solver = dictionary.solver().unwrap();
+
+    assert_eq!(vec![vec!["toe", "era", "net"]], solver.solve("toe", 0).unwrap());
+    assert!(solver.solve("abcd", 0).is_err());
+
+    // what solve_indices hands numpy, numpy isn't needed to check it
+    let solutions = solver.solutions("toe", 0).unwrap();
+    assert_eq!(dictionary.words(), solver.words);
+    assert_eq!(vec![vec![6, 1, 2]], dictionary::solution_word_indices(&solutions, &solver.words).unwrap());
+
+    assert_eq!(vec!["correct", "elsewhere_in_grid", "in_row"], guess_feedback(vec!["ten".into(), "ore".into(), "eat".into()], "r1", "toe").unwrap());
+    assert!(guess_feedback(Vec::new(), "x1", "toe").is_err());
+}
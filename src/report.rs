@@ -0,0 +1,281 @@
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::io::BufRead;
+use std::mem;
+use crate::dictionary::generate_starts_that_have_words;
+use crate::error::SquardleError;
+use crate::generator_config::ReportFormat;
+use crate::output;
+use crate::prefix_arena::PrefixArena;
+use crate::solution_generator::RunStats;
+
+const MOST_FREQUENT_WORD_COUNT: usize = 10;
+const LARGEST_BUCKET_COUNT: usize = 10;
+
+/* the summary of a finished run, worked out from the stats the workers collected */
+#[derive(Debug, Serialize)]
+pub struct RunReport {
+    pub solutions_found: u64,
+    pub solutions_per_letter: BTreeMap<char, u64>,
+    pub most_frequent_words: Vec<(String, u64)>,
+    pub letter_histogram: BTreeMap<char, u64>,
+    pub average_branching_factor: f64,
+    pub search: SearchCounts,
+    pub phase_seconds: Vec<(String, f64)>
+}
+
+/* how much work the search did, to tell whether a change to the pruning pays for itself */
+#[derive(Debug, PartialEq, Serialize)]
+pub struct SearchCounts {
+    pub nodes_expanded: u64,
+    pub candidate_checks: u64,
+    pub prefix_lookups: u64,
+    pub bad_start_prunes: u64,
+    pub bad_start_skips: u64,
+    pub forward_check_prunes: u64,
+    pub memo_hits: u64,
+    pub backtracks: u64
+}
+
+impl RunReport {
+
+    pub fn new(run_stats: &RunStats) -> RunReport {
+
+        let mut most_frequent_words: Vec<(String, u64)> = run_stats.word_counts.iter().map(|(word, count)| (word.clone(), *count)).collect();
+        // most frequent first, ties alphabetical so the report is the same every run
+        most_frequent_words.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        most_frequent_words.truncate(MOST_FREQUENT_WORD_COUNT);
+
+        let average_branching_factor = if run_stats.nodes_expanded == 0 {
+            0.0
+        } else {
+            run_stats.backtracks as f64 / run_stats.nodes_expanded as f64
+        };
+
+        RunReport {
+            solutions_found: run_stats.solutions_found,
+            solutions_per_letter: by_letter(&run_stats.solutions_per_letter),
+            most_frequent_words,
+            letter_histogram: by_letter(&run_stats.letter_counts),
+            average_branching_factor,
+            search: SearchCounts {
+                nodes_expanded: run_stats.nodes_expanded,
+                candidate_checks: run_stats.candidate_checks,
+                prefix_lookups: run_stats.prefix_lookups,
+                bad_start_prunes: run_stats.bad_start_prunes,
+                bad_start_skips: run_stats.bad_start_skips,
+                forward_check_prunes: run_stats.forward_check_prunes,
+                memo_hits: run_stats.memo_hits,
+                backtracks: run_stats.backtracks
+            },
+            phase_seconds: run_stats.phase_timings.iter().map(|(phase, elapsed)| (phase.clone(), elapsed.as_secs_f64())).collect()
+        }
+    }
+
+    pub fn render(&self, report_format: &ReportFormat) -> Result<String, SquardleError> {
+
+        match report_format {
+            ReportFormat::Text => Ok(self.to_string()),
+            ReportFormat::Json => Ok(serde_json::to_string_pretty(self)?)
+        }
+    }
+}
+
+/* the stats of an existing csv solutions file, only what can be worked out from the solutions
+  themselves so the search counts and timings stay empty */
+pub fn solution_file_stats(file_path: &str) -> Result<RunStats, SquardleError> {
+
+    let reader = output::open_solution_file(file_path)?;
+    let mut run_stats = RunStats::default();
+
+    for line in reader.lines() {
+        let rows = output::rows_of_line(&line?);
+
+        if let Some((last_word, puzzle)) = rows.split_last() {
+            run_stats.record_solution(puzzle, last_word);
+            run_stats.solutions_found += 1;
+        }
+    }
+
+    Ok(run_stats)
+}
+
+/* what generate_starts_that_have_words made of a dictionary, for seeing how a dictionary
+  changes the starts the search has to go through and how much they take to hold */
+#[derive(Debug, Serialize)]
+pub struct PrefixMapReport {
+    pub prefixes: usize,
+    pub prefixes_per_length: BTreeMap<usize, usize>,
+    // the starts with the most words, most first
+    pub largest_buckets: Vec<(String, usize)>,
+    // every word under every start, the words the map holds copies of
+    pub bucket_entries: usize,
+    pub prefix_map_bytes: usize,
+    // the generator holds the map as an arena of the words, each once
+    pub prefix_arena_bytes: usize
+}
+
+impl PrefixMapReport {
+
+    pub fn new(dictionary: &[String]) -> Result<PrefixMapReport, SquardleError> {
+
+        let prefix_map = generate_starts_that_have_words(dictionary)?;
+
+        let mut prefixes_per_length: BTreeMap<usize, usize> = BTreeMap::new();
+
+        for prefix in prefix_map.keys() {
+            *prefixes_per_length.entry(prefix.chars().count()).or_insert(0) += 1;
+        }
+
+        let mut largest_buckets: Vec<(String, usize)> = prefix_map.iter().map(|(prefix, words)| (prefix.clone(), words.len())).collect();
+        // biggest first, ties alphabetical so the report is the same every run
+        largest_buckets.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        largest_buckets.truncate(LARGEST_BUCKET_COUNT);
+
+        let bucket_entries = prefix_map.values().map(|words| words.len()).sum();
+        let prefix_map_bytes = prefix_map_bytes(&prefix_map);
+        let prefixes = prefix_map.len();
+
+        let prefix_arena_bytes = PrefixArena::new(dictionary)?.estimated_bytes();
+
+        Ok(PrefixMapReport { prefixes, prefixes_per_length, largest_buckets, bucket_entries, prefix_map_bytes, prefix_arena_bytes })
+    }
+
+    pub fn render(&self, report_format: &ReportFormat) -> Result<String, SquardleError> {
+
+        match report_format {
+            ReportFormat::Text => Ok(self.to_string()),
+            ReportFormat::Json => Ok(serde_json::to_string_pretty(self)?)
+        }
+    }
+}
+
+/* roughly the heap the map takes up, the starts, their lists and the copies of the words in
+  them and a byte of hash table control for each slot */
+fn prefix_map_bytes(prefix_map: &HashMap<String, Vec<String>>) -> usize {
+
+    let slots = prefix_map.capacity() * (mem::size_of::<(String, Vec<String>)>() + 1);
+
+    let entries: usize = prefix_map.iter()
+        .map(|(prefix, words)| prefix.capacity() + words.capacity() * mem::size_of::<String>() + words.iter().map(|word| word.capacity()).sum::<usize>())
+        .sum();
+
+    slots + entries
+}
+
+/* letters that never came up are left out */
+fn by_letter(counts: &[u64; 26]) -> BTreeMap<char, u64> {
+    ('a'..='z').zip(counts.iter()).filter(|(_, count)| **count > 0).map(|(letter, count)| (letter, *count)).collect()
+}
+
+impl fmt::Display for RunReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+
+        writeln!(f, "solutions found: {}", self.solutions_found)?;
+        writeln!(f, "average branching factor: {:.3}", self.average_branching_factor)?;
+
+        writeln!(f, "search counts:")?;
+        writeln!(f, "  nodes expanded: {}", self.search.nodes_expanded)?;
+        writeln!(f, "  candidates checked: {}", self.search.candidate_checks)?;
+        writeln!(f, "  prefix lookups: {}", self.search.prefix_lookups)?;
+        writeln!(f, "  bad starts pruned: {}", self.search.bad_start_prunes)?;
+        writeln!(f, "  words skipped by bad starts: {}", self.search.bad_start_skips)?;
+        writeln!(f, "  forward check prunes: {}", self.search.forward_check_prunes)?;
+        writeln!(f, "  memo hits: {}", self.search.memo_hits)?;
+        writeln!(f, "  backtracks: {}", self.search.backtracks)?;
+
+        writeln!(f, "solutions per starting letter:")?;
+        for (letter, count) in &self.solutions_per_letter {
+            writeln!(f, "  {}: {}", letter, count)?;
+        }
+
+        writeln!(f, "most frequent words:")?;
+        for (word, count) in &self.most_frequent_words {
+            writeln!(f, "  {}: {}", word, count)?;
+        }
+
+        writeln!(f, "letter histogram:")?;
+        for (letter, count) in &self.letter_histogram {
+            writeln!(f, "  {}: {}", letter, count)?;
+        }
+
+        write!(f, "phase timings:")?;
+        for (phase, seconds) in &self.phase_seconds {
+            write!(f, "\n  {}: {:.3}s", phase, seconds)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for PrefixMapReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+
+        writeln!(f, "prefixes: {}", self.prefixes)?;
+
+        writeln!(f, "prefixes per length:")?;
+        for (length, count) in &self.prefixes_per_length {
+            writeln!(f, "  {}: {}", length, count)?;
+        }
+
+        writeln!(f, "largest buckets:")?;
+        for (prefix, count) in &self.largest_buckets {
+            writeln!(f, "  {}: {}", prefix, count)?;
+        }
+
+        writeln!(f, "words across all buckets: {}", self.bucket_entries)?;
+        writeln!(f, "estimated prefix map size: {:.1}KB", self.prefix_map_bytes as f64 / 1024.0)?;
+        write!(f, "estimated prefix arena size: {:.1}KB", self.prefix_arena_bytes as f64 / 1024.0)
+    }
+}
+
+#[test]
+fn report_from_run_stats() {
+
+    let mut run_stats = RunStats { solutions_found: 2, backtracks: 6, nodes_expanded: 4, candidate_checks: 9, bad_start_prunes: 3, ..RunStats::default() };
+    run_stats.solutions_per_letter[0] = 2;
+    run_stats.letter_counts[1] = 3;
+    run_stats.word_counts.insert("ab".to_string(), 2);
+    run_stats.word_counts.insert("ba".to_string(), 2);
+    run_stats.word_counts.insert("cd".to_string(), 1);
+
+    let report = RunReport::new(&run_stats);
+
+    assert_eq!(1.5, report.average_branching_factor);
+    assert_eq!(BTreeMap::from([('a', 2)]), report.solutions_per_letter);
+    assert_eq!(BTreeMap::from([('b', 3)]), report.letter_histogram);
+    assert_eq!(vec![("ab".to_string(), 2), ("ba".to_string(), 2), ("cd".to_string(), 1)], report.most_frequent_words);
+    assert!(report.render(&ReportFormat::Json).unwrap().contains("\"average_branching_factor\": 1.5"));
+    assert_eq!((9, 3, 6), (report.search.candidate_checks, report.search.bad_start_prunes, report.search.backtracks));
+    assert!(report.to_string().contains("  candidates checked: 9\n"));
+}
+
+#[test]
+fn stats_of_a_solution_file() {
+
+    let file_path = "test_stats.csv";
+    std::fs::write(file_path, "ab,cd,1.500\n\nab,ba\n").unwrap();
+
+    let run_stats = solution_file_stats(file_path).unwrap();
+    std::fs::remove_file(file_path).unwrap();
+
+    assert_eq!(2, run_stats.solutions_found);
+    assert_eq!(2, run_stats.solutions_per_letter[0]);
+    assert_eq!(Some(&2), run_stats.word_counts.get("ab"));
+    assert_eq!(None, run_stats.word_counts.get("1.500"));
+}
+
+#[test]
+fn report_of_a_prefix_map() {
+
+    let dictionary: Vec<String> = ["bad", "bat", "cab", "cat", "tab"].iter().map(|word| word.to_string()).collect();
+    let report = PrefixMapReport::new(&dictionary).unwrap();
+
+    // ba ca ta, then every word
+    assert_eq!(BTreeMap::from([(2, 3), (3, 5)]), report.prefixes_per_length);
+    assert_eq!(vec![("ba".to_string(), 2), ("ca".to_string(), 2), ("bad".to_string(), 1)], report.largest_buckets[..3]);
+    // each word under its two and three letter starts
+    assert_eq!(10, report.bucket_entries);
+    assert!(report.prefix_arena_bytes < report.prefix_map_bytes);
+}
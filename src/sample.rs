@@ -0,0 +1,206 @@
+use std::io::{BufRead, Write};
+use std::time::{SystemTime, UNIX_EPOCH};
+use crate::error::SquardleError;
+use crate::generator_config::Compression;
+use crate::output::{self, OutputWriter};
+
+/* splitmix64, written out like the daily puzzle's hash so the same seed picks the same
+  sample from the same file in every version rather than whatever a random crate does now */
+pub struct SampleRng {
+    state: u64
+}
+
+impl SampleRng {
+
+    pub fn new(seed: u64) -> SampleRng {
+        SampleRng { state: seed }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    /* a number from 0 up to but not including bound, the bias of scaling a u64 down is far too small to matter here */
+    pub fn below(&mut self, bound: u64) -> u64 {
+        ((self.next_u64() as u128 * bound as u128) >> 64) as u64
+    }
+
+    /* Fisher-Yates, every order is as likely as any other */
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = self.below(i as u64 + 1) as usize;
+            items.swap(i, j);
+        }
+    }
+}
+
+/* a seed for when none was given, logged by the callers so it can be picked again */
+pub fn seed_from_time() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|time| time.as_nanos() as u64).unwrap_or(0)
+}
+
+/* Keeps sample_size of the items picked uniformly at random without ever holding more than that
+  many, every item has the same chance of being kept however many there are. Algorithm R: the
+  nth item replaces a random kept one with chance sample_size/n. The kept items are given back in
+  the order they came in */
+pub fn reservoir_sample<T, I>(items: I, sample_size: usize, rng: &mut SampleRng) -> Vec<T>
+    where I: IntoIterator<Item = T> {
+
+    let mut reservoir: Vec<(usize, T)> = Vec::with_capacity(sample_size.min(1 << 20));
+
+    for (position, item) in items.into_iter().enumerate() {
+
+        if reservoir.len() < sample_size {
+            reservoir.push((position, item));
+            continue;
+        }
+
+        let replaced = rng.below(position as u64 + 1) as usize;
+
+        if replaced < sample_size {
+            reservoir[replaced] = (position, item);
+        }
+    }
+
+    reservoir.sort_unstable_by_key(|(position, _)| *position);
+    reservoir.into_iter().map(|(_, item)| item).collect()
+}
+
+/* writes a random sample of the solution lines of the source file to the dest file, returns how many were written */
+pub fn sample_solution_file(source_file_path: &str, dest_file_path: &str, sample_size: usize, seed: u64) -> Result<usize, SquardleError> {
+
+    let reader = output::open_solution_file(source_file_path)?;
+
+    let mut read_error: Option<std::io::Error> = None;
+
+    // stops at the first read error which is then returned instead of sampling what came before it
+    let solution_lines = reader.lines().map_while(|line| match line {
+        Ok(line) => Some(line),
+        Err(err) => {
+            read_error = Some(err);
+            None
+        }
+    }).filter(|line| !output::rows_of_line(line).is_empty());
+
+    let lines = reservoir_sample(solution_lines, sample_size, &mut SampleRng::new(seed));
+
+    if let Some(err) = read_error {
+        return Err(err.into());
+    }
+
+    let mut dest_file = OutputWriter::create(dest_file_path, Compression::from_file_path(dest_file_path))?;
+
+    for line in &lines {
+        writeln!(dest_file, "{}", line.trim())?;
+    }
+
+    dest_file.finish()?;
+
+    Ok(lines.len())
+}
+
+/* sample source_file dest_file count with optional --seed n to pick the same sample again */
+#[derive(Debug, PartialEq)]
+pub struct SampleConfig {
+    pub source_file_path: String,
+    pub dest_file_path: String,
+    pub sample_size: usize,
+    pub seed: Option<u64>
+}
+
+impl SampleConfig {
+
+    pub fn build(args: &[String]) -> Result<SampleConfig, SquardleError> {
+
+        let mut seed: Option<u64> = None;
+        let mut positional_args: Vec<String> = Vec::with_capacity(3);
+        let mut args_iter = args.iter();
+
+        while let Some(arg) = args_iter.next() {
+
+            match arg.as_str() {
+                "--seed" => seed = match args_iter.next().map(|value| value.parse::<u64>()) {
+                    Some(Ok(seed)) => Some(seed),
+                    _ => return Err("Seed must be a whole number".into())
+                },
+                _ => positional_args.push(arg.clone())
+            }
+        }
+
+        let [source_file_path, dest_file_path, sample_size]: [String; 3] = positional_args.try_into()
+            .map_err(|_| SquardleError::from("sample accepts a solutions file path, a destination file path, the number of solutions to keep and optionally --seed n"))?;
+
+        let sample_size = match sample_size.parse::<usize>() {
+            Ok(sample_size) if sample_size > 0 => sample_size,
+            _ => return Err("Sample size must be a number of solutions greater than 0".into())
+        };
+
+        Ok(SampleConfig { source_file_path, dest_file_path, sample_size, seed })
+    }
+}
+
+#[test]
+fn reservoir_sample_is_uniform() {
+
+    let mut rng = SampleRng::new(7);
+
+    // fewer items than the sample size keeps them all
+    assert_eq!(vec![1, 2, 3], reservoir_sample(vec![1, 2, 3], 5, &mut rng));
+
+    // every one of ten items should be kept about half the time when keeping five
+    let mut kept_counts = [0usize; 10];
+
+    for _ in 0..10_000 {
+        let sample = reservoir_sample(0..10, 5, &mut rng);
+        assert_eq!(5, sample.len());
+        assert!(sample.windows(2).all(|pair| pair[0] < pair[1]));
+
+        for item in sample {
+            kept_counts[item] += 1;
+        }
+    }
+
+    assert!(kept_counts.iter().all(|count| (4_700..5_300).contains(count)), "{:?}", kept_counts);
+}
+
+#[test]
+fn shuffle_is_a_permutation() {
+
+    let mut items: Vec<usize> = (0..100).collect();
+    SampleRng::new(42).shuffle(&mut items);
+
+    let mut again: Vec<usize> = (0..100).collect();
+    SampleRng::new(42).shuffle(&mut again);
+
+    assert_eq!(items, again);
+    assert_ne!((0..100).collect::<Vec<usize>>(), items);
+
+    items.sort();
+    assert_eq!((0..100).collect::<Vec<usize>>(), items);
+}
+
+#[test]
+fn sample_solution_file_with_a_seed() {
+
+    std::fs::write("test_sample.csv", "based,ulcer\nbudge,enter\n\ncases,ulnae\ntoe,era,net\n").unwrap();
+
+    let args: Vec<String> = ["test_sample.csv", "test_sample_out.csv", "2", "--seed", "42"].iter().map(|arg| arg.to_string()).collect();
+    let config = SampleConfig::build(&args).unwrap();
+    assert_eq!(Some(42), config.seed);
+
+    assert_eq!(2, sample_solution_file("test_sample.csv", "test_sample_out.csv", 2, 42).unwrap());
+    let first = std::fs::read_to_string("test_sample_out.csv").unwrap();
+
+    sample_solution_file("test_sample.csv", "test_sample_out.csv", 2, 42).unwrap();
+    let second = std::fs::read_to_string("test_sample_out.csv").unwrap();
+
+    std::fs::remove_file("test_sample.csv").unwrap();
+    std::fs::remove_file("test_sample_out.csv").unwrap();
+
+    assert_eq!(first, second);
+    assert_eq!(2, first.lines().count());
+}
@@ -0,0 +1,159 @@
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashMap};
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use crate::constraint::{self, SoftConstraint};
+use crate::error::SquardleError;
+use crate::solution::Solution;
+use crate::solution_generator::construct_potential_transposed_puzzle;
+
+/* Scores solutions by how common their words are, the sum of the log frequency of every
+  row and column word so squares made of everyday words score higher. A word missing
+  from the frequency data counts as having been seen once, adding nothing. Any soft constraints
+  a square breaks take their weights off, so a scorer with no frequencies ranks by penalty alone */
+#[derive(Debug, Default)]
+pub struct Scorer {
+    log_frequencies: HashMap<String, f64>,
+    soft_constraints: Vec<SoftConstraint>
+}
+
+impl Scorer {
+
+    /* reads word,count lines, anything without a count after the word (like a header) is skipped */
+    pub fn load(file_path: &str) -> Result<Scorer, SquardleError> {
+
+        let reader = BufReader::new(File::open(file_path)?);
+        let mut log_frequencies: HashMap<String, f64> = HashMap::new();
+
+        for line in reader.lines() {
+            let line = line?;
+
+            let (word, count) = match line.split_once(',') {
+                Some((word, count)) => (word.trim().to_lowercase(), count.trim().parse::<f64>()),
+                None => continue
+            };
+
+            if let Ok(count) = count {
+                if count >= 1.0 {
+                    log_frequencies.insert(word, count.ln());
+                }
+            }
+        }
+
+        Ok(Scorer { log_frequencies, soft_constraints: Vec::new() })
+    }
+
+    pub fn with_soft_constraints(mut self, soft_constraints: Vec<SoftConstraint>) -> Scorer {
+        self.soft_constraints = soft_constraints;
+        self
+    }
+
+    pub fn score(&self, solution: &[String]) -> f64 {
+
+        let columns = construct_potential_transposed_puzzle(solution);
+
+        let frequencies: f64 = solution.iter().chain(columns.iter())
+            .map(|word| self.log_frequency(word))
+            .sum();
+
+        frequencies - constraint::penalty(&self.soft_constraints, solution)
+    }
+
+    pub fn log_frequency(&self, word: &str) -> f64 {
+        self.log_frequencies.get(word).copied().unwrap_or(0.0)
+    }
+
+    /* the log frequency of the most common word, 0 when there is no frequency data */
+    pub fn max_log_frequency(&self) -> f64 {
+        self.log_frequencies.values().copied().fold(0.0, f64::max)
+    }
+}
+
+/* a solution in the top k heap, ordered by score then by its words so ties always
+  come out the same way */
+struct ScoredSolution {
+    score: f64,
+    solution: Solution
+}
+
+impl Ord for ScoredSolution {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.score.total_cmp(&other.score).then_with(|| other.solution.cmp(&self.solution))
+    }
+}
+
+impl PartialOrd for ScoredSolution {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for ScoredSolution {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for ScoredSolution {}
+
+/* Keeps only the k highest scoring solutions in a min heap so memory stays fixed however
+  many solutions go by. Returns them best first */
+pub fn top_solutions<I>(solutions: I, k: usize, scorer: &Scorer) -> Vec<Solution>
+    where I: IntoIterator<Item = Solution> {
+
+    let mut heap: BinaryHeap<Reverse<ScoredSolution>> = BinaryHeap::with_capacity(k + 1);
+
+    for solution in solutions {
+
+        heap.push(Reverse(ScoredSolution { score: scorer.score(&solution), solution }));
+
+        if heap.len() > k {
+            heap.pop();
+        }
+    }
+
+    heap.into_sorted_vec().into_iter().map(|Reverse(scored)| scored.solution).collect()
+}
+
+#[test]
+fn score_sums_row_and_column_log_frequencies() {
+
+    std::fs::write("test_frequencies.csv", "word,count\nab,10\ncd,100\nac,1000\nbd,x\n").unwrap();
+    let scorer = Scorer::load("test_frequencies.csv").unwrap();
+    std::fs::remove_file("test_frequencies.csv").unwrap();
+
+    let score = scorer.score(&["ab".to_string(), "cd".to_string()]);
+
+    assert!((score - (10f64.ln() + 100f64.ln() + 1000f64.ln())).abs() < 1e-9);
+}
+
+#[test]
+fn top_solutions_keeps_the_highest_scores() {
+
+    let scorer = Scorer { log_frequencies: HashMap::from([("ab".to_string(), 3.0), ("ef".to_string(), 1.0), ("ij".to_string(), 2.0)]), soft_constraints: Vec::new() };
+
+    let solutions = vec![
+        vec!["ab".to_string(), "cd".to_string()],
+        vec!["ef".to_string(), "gh".to_string()],
+        vec!["ij".to_string(), "kl".to_string()]
+    ];
+
+    assert_eq!(vec![vec!["ab".to_string(), "cd".to_string()], vec!["ij".to_string(), "kl".to_string()]], top_solutions(solutions.into_iter().map(Solution::from), 2, &scorer));
+}
+
+#[test]
+fn soft_constraints_come_off_the_score() {
+
+    use std::sync::Arc;
+
+    let soft_constraints = vec![
+        SoftConstraint { constraint: Arc::new(constraint::RequiredWord { word: "bd".to_string() }), weight: 2.0 },
+        SoftConstraint { constraint: Arc::new(constraint::IncludesLetters { letters: vec!['z'] }), weight: 0.5 }
+    ];
+
+    let scorer = Scorer { log_frequencies: HashMap::from([("ab".to_string(), 3.0)]), soft_constraints: Vec::new() }.with_soft_constraints(soft_constraints);
+
+    // bd is the second column so only the z is missing
+    assert_eq!(2.5, scorer.score(&["ab".to_string(), "cd".to_string()]));
+    assert_eq!(0.5, scorer.score(&["ab".to_string(), "ef".to_string()]));
+}
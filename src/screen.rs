@@ -0,0 +1,154 @@
+use crate::solution_generator::letter_index;
+
+/* how many candidates are screened at once, one byte each in a 128 bit register */
+pub const BLOCK_SIZE: usize = 16;
+
+// never allowed in any column, for padding and letters outside a to z
+const NOT_A_LETTER: u8 = u8::MAX;
+
+/* The letters each column allows for the row being filled, as a bitmask and as the two 16 byte
+  lookup tables the shuffle instruction wants, a to p in one and q to z in the other */
+pub struct AllowedLetters {
+    masks: Vec<u32>,
+    #[cfg_attr(not(target_arch = "x86_64"), allow(dead_code))]
+    low_tables: Vec<[u8; 16]>,
+    #[cfg_attr(not(target_arch = "x86_64"), allow(dead_code))]
+    high_tables: Vec<[u8; 16]>
+}
+
+impl AllowedLetters {
+
+    pub fn new(masks: Vec<u32>) -> AllowedLetters {
+
+        let table = |mask: u32, first_letter: usize| -> [u8; 16] {
+            std::array::from_fn(|offset| if first_letter + offset < 26 && mask & (1 << (first_letter + offset)) != 0 { u8::MAX } else { 0 })
+        };
+
+        let low_tables = masks.iter().map(|mask| table(*mask, 0)).collect();
+        let high_tables = masks.iter().map(|mask| table(*mask, 16)).collect();
+
+        AllowedLetters { masks, low_tables, high_tables }
+    }
+}
+
+/* The candidates' letters laid out position by position, so the letters at one position of a
+  block of candidates are next to each other and can be checked against a column in one go.
+  A block's screen is a bit per candidate, set when every letter is one its column allows. It
+  only rules words out, whatever it lets through still needs checking properly */
+pub struct CandidateLetters {
+    // position then candidate, padded with NOT_A_LETTER to whole blocks
+    by_position: Vec<Vec<u8>>,
+    // by block, the candidates with letters outside a to z, never screened out as the prefix map could still have them
+    unscreened: Vec<u16>,
+    ssse3: bool
+}
+
+impl CandidateLetters {
+
+    pub fn new(candidates: &[String], word_size: usize) -> CandidateLetters {
+
+        let padded_len = candidates.len().div_ceil(BLOCK_SIZE) * BLOCK_SIZE;
+        let mut by_position = vec![vec![NOT_A_LETTER; padded_len]; word_size];
+        let mut unscreened = vec![0u16; padded_len / BLOCK_SIZE];
+
+        for (index, candidate) in candidates.iter().enumerate() {
+
+            for (position, letter) in candidate.chars().take(word_size).enumerate() {
+                match letter_index(letter) {
+                    Some(letter) => by_position[position][index] = letter as u8,
+                    None => unscreened[index / BLOCK_SIZE] |= 1 << (index % BLOCK_SIZE)
+                }
+            }
+        }
+
+        CandidateLetters { by_position, unscreened, ssse3: has_ssse3() }
+    }
+
+    /* a bit for each candidate in the block that could fit, the lowest for the first candidate */
+    pub fn screen_block(&self, block: usize, allowed: &AllowedLetters) -> u16 {
+
+        let Some(unscreened) = self.unscreened.get(block) else {
+            return 0;
+        };
+
+        let fits = match self.ssse3 {
+            // SAFETY: only picked when the cpu has been seen to support ssse3
+            #[cfg(target_arch = "x86_64")]
+            true => unsafe { screen_block_ssse3(&self.by_position, block * BLOCK_SIZE, allowed) },
+            _ => screen_block_scalar(&self.by_position, block * BLOCK_SIZE, allowed)
+        };
+
+        fits | unscreened
+    }
+}
+
+fn has_ssse3() -> bool {
+
+    #[cfg(target_arch = "x86_64")]
+    return is_x86_feature_detected!("ssse3");
+
+    #[cfg(not(target_arch = "x86_64"))]
+    return false;
+}
+
+fn screen_block_scalar(by_position: &[Vec<u8>], start: usize, allowed: &AllowedLetters) -> u16 {
+
+    (0..BLOCK_SIZE).fold(0, |fits, offset| {
+
+        let fit = by_position.iter().zip(&allowed.masks)
+            .all(|(letters, mask)| letters[start + offset] < 26 && mask & (1 << letters[start + offset]) != 0);
+
+        fits | (fit as u16) << offset
+    })
+}
+
+/* Looks every letter of the block up in its column's tables with one shuffle per table. A
+  shuffle gives zero for an index with the high bit set, which is used to keep q to z out of
+  the a to p table, a to p out of the q to z table once 16 is taken off, and padding out of both */
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "ssse3")]
+fn screen_block_ssse3(by_position: &[Vec<u8>], start: usize, allowed: &AllowedLetters) -> u16 {
+
+    use std::arch::x86_64::*;
+
+    let load = |bytes: &[u8]| -> __m128i {
+        let bytes: &[u8; 16] = bytes.try_into().expect("a block is 16 bytes");
+        // SAFETY: an unaligned load of exactly the 16 bytes borrowed
+        unsafe { _mm_loadu_si128(bytes.as_ptr() as *const __m128i) }
+    };
+
+    let mut fits = _mm_set1_epi8(-1);
+
+    for ((letters, low_table), high_table) in by_position.iter().zip(&allowed.low_tables).zip(&allowed.high_tables) {
+
+        let letters = load(&letters[start..start + BLOCK_SIZE]);
+
+        // padding is -1 as a signed byte so isn't counted as q to z
+        let is_high = _mm_cmpgt_epi8(letters, _mm_set1_epi8(15));
+        let low_fits = _mm_shuffle_epi8(load(low_table), _mm_or_si128(letters, _mm_and_si128(is_high, _mm_set1_epi8(i8::MIN))));
+        let high_fits = _mm_shuffle_epi8(load(high_table), _mm_sub_epi8(letters, _mm_set1_epi8(16)));
+
+        fits = _mm_and_si128(fits, _mm_or_si128(low_fits, high_fits));
+    }
+
+    _mm_movemask_epi8(fits) as u16
+}
+
+#[test]
+fn screened_blocks_only_let_through_allowed_letters() {
+
+    let candidates: Vec<String> = ["ab", "az", "ba", "qa", "zz", "éa", "pq"].iter().map(|word| word.to_string()).collect();
+    let letters = CandidateLetters::new(&candidates, 2);
+
+    let bit = |letter: char| 1u32 << letter_index(letter).unwrap();
+
+    // a, p or z first and a or z second
+    let allowed = AllowedLetters::new(vec![bit('a') | bit('p') | bit('z'), bit('a') | bit('z')]);
+
+    // az and zz, and éa as it isn't screened at all
+    let expected = 0b0110010;
+    assert_eq!(expected, letters.screen_block(0, &allowed));
+    assert_eq!(expected, screen_block_scalar(&letters.by_position, 0, &allowed) | letters.unscreened[0]);
+
+    assert_eq!(0, letters.screen_block(1, &allowed));
+}
@@ -0,0 +1,224 @@
+use std::collections::HashSet;
+use std::fmt;
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use crate::engine::solver_engine;
+use crate::error::SquardleError;
+use crate::generator_config::GeneratorConfig;
+use crate::naive::brute_force_squares;
+use crate::prefix_arena::PrefixArena;
+use crate::sample::SampleRng;
+use crate::solution::Solution;
+
+/* the ways of running generate checked against the brute force, each as the flags that pick it */
+pub const VARIANTS: [&[&str]; 6] = [
+    &[],
+    &["--pruning", "forward-check"],
+    &["--memo", "4096"],
+    &["--fill-order", "constrained"],
+    &["--backend", "rayon", "--threads", "2"],
+    &["--shuffle", "--seed", "1"]
+];
+
+const DEFAULT_ROUNDS: usize = 50;
+const DEFAULT_WORD_SIZE: usize = 3;
+const DEFAULT_WORDS: usize = 24;
+const DEFAULT_LETTERS: usize = 5;
+
+/* selftest with optional --rounds n, --seed n, --word-size 3|4, --words n and --letters n */
+#[derive(Debug, PartialEq)]
+pub struct SelftestConfig {
+    pub rounds: usize,
+    pub seed: Option<u64>,
+    pub word_size: usize,
+    pub words: usize,
+    // words are made of the first this many letters of the alphabet
+    pub letters: usize
+}
+
+impl SelftestConfig {
+
+    pub fn build(args: &[String]) -> Result<SelftestConfig, SquardleError> {
+
+        let mut rounds = DEFAULT_ROUNDS;
+        let mut seed: Option<u64> = None;
+        let mut word_size = DEFAULT_WORD_SIZE;
+        let mut words = DEFAULT_WORDS;
+        let mut letters = DEFAULT_LETTERS;
+        let mut args_iter = args.iter();
+
+        let number = |value: Option<&String>| value.and_then(|value| value.parse::<usize>().ok());
+
+        while let Some(arg) = args_iter.next() {
+
+            match arg.as_str() {
+                "--rounds" => rounds = match number(args_iter.next()) {
+                    Some(rounds) if rounds > 0 => rounds,
+                    _ => return Err("Rounds must be a number greater than 0".into())
+                },
+                "--seed" => seed = match args_iter.next().map(|value| value.parse::<u64>()) {
+                    Some(Ok(seed)) => Some(seed),
+                    _ => return Err("Seed must be a whole number".into())
+                },
+                // the brute force tries every word in every row, any bigger takes too long
+                "--word-size" => word_size = match number(args_iter.next()) {
+                    Some(word_size) if (3..=4).contains(&word_size) => word_size,
+                    _ => return Err("Word size must be 3 or 4".into())
+                },
+                "--words" => words = match number(args_iter.next()) {
+                    Some(words) if words > 0 => words,
+                    _ => return Err("Words must be a number greater than 0".into())
+                },
+                "--letters" => letters = match number(args_iter.next()) {
+                    Some(letters) if (1..=26).contains(&letters) => letters,
+                    _ => return Err("Letters must be a number from 1 to 26".into())
+                },
+                _ => return Err("selftest accepts optional flags: --rounds n, --seed n, --word-size 3|4, --words n, --letters n".into())
+            }
+        }
+
+        Ok(SelftestConfig { rounds, seed, word_size, words, letters })
+    }
+}
+
+/* a variant of generate that didn't find the same squares as the brute force for a dictionary */
+#[derive(Debug, PartialEq)]
+pub struct Mismatch {
+    pub flags: String,
+    pub dictionary: Vec<String>,
+    // found by the brute force but not generate
+    pub missing: Vec<Solution>,
+    // found by generate but not the brute force
+    pub extra: Vec<Solution>
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+
+        let squares = |squares: &[Solution]| squares.iter().map(Solution::to_string).collect::<Vec<String>>().join(" ");
+
+        writeln!(f, "generate with [{}] on the dictionary {}", self.flags, self.dictionary.join(","))?;
+        writeln!(f, "  missing: {}", squares(&self.missing))?;
+        write!(f, "  extra: {}", squares(&self.extra))
+    }
+}
+
+#[derive(Debug)]
+pub struct SelftestSummary {
+    pub rounds: usize,
+    // the squares the brute force found over every round
+    pub squares: usize,
+    pub mismatches: Vec<Mismatch>
+}
+
+/* words of the first few letters only so they share starts and make squares, a word picked twice is only kept once */
+pub fn random_dictionary(rng: &mut SampleRng, word_size: usize, words: usize, letters: usize) -> Vec<String> {
+
+    let mut dictionary: Vec<String> = (0..words)
+        .map(|_| (0..word_size).map(|_| (b'a' + rng.below(letters as u64) as u8) as char).collect())
+        .collect();
+
+    dictionary.sort();
+    dictionary.dedup();
+
+    dictionary
+}
+
+/* what generate finds with the flags, sorted */
+pub fn generated_squares(dictionary: &[String], flags: &[&str]) -> Result<Vec<Solution>, SquardleError> {
+
+    let args: Vec<String> = ["selftest", "-", ""].iter().chain(flags).map(|arg| arg.to_string()).collect();
+    let config = GeneratorConfig::build(&args)?;
+
+    let dictionary = dictionary.to_vec();
+    let prefix_map = PrefixArena::new(&dictionary)?;
+    let stop_flag = Arc::new(AtomicBool::new(false));
+
+    let mut pool = solver_engine(config.engine).start(&config, &dictionary, prefix_map, stop_flag, HashSet::new())?;
+
+    let mut squares: Vec<Solution> = pool.solution_receiver().iter().collect();
+    let stats = pool.join();
+
+    if let Some(error) = stats.errors.into_iter().next() {
+        return Err(error.into());
+    }
+
+    squares.sort();
+
+    Ok(squares)
+}
+
+/* every variant of generate against the brute force on the one dictionary, returns the squares there are and where they differ */
+pub fn check_dictionary(dictionary: &[String]) -> Result<(usize, Vec<Mismatch>), SquardleError> {
+
+    let expected = brute_force_squares(dictionary);
+    let mut mismatches: Vec<Mismatch> = Vec::new();
+
+    for flags in VARIANTS {
+
+        let found = generated_squares(dictionary, flags)?;
+
+        if found != expected {
+            mismatches.push(Mismatch {
+                flags: flags.join(" "),
+                dictionary: dictionary.to_vec(),
+                missing: expected.iter().filter(|square| found.binary_search(square).is_err()).cloned().collect(),
+                extra: found.iter().filter(|square| expected.binary_search(square).is_err()).cloned().collect()
+            });
+        }
+    }
+
+    Ok((expected.len(), mismatches))
+}
+
+/* checks a random dictionary a round, the same seed makes the same dictionaries */
+pub fn run_selftest(config: &SelftestConfig, seed: u64) -> Result<SelftestSummary, SquardleError> {
+
+    let mut rng = SampleRng::new(seed);
+    let mut summary = SelftestSummary { rounds: config.rounds, squares: 0, mismatches: Vec::new() };
+
+    for _ in 0..config.rounds {
+
+        let dictionary = random_dictionary(&mut rng, config.word_size, config.words, config.letters);
+        let (squares, mismatches) = check_dictionary(&dictionary)?;
+
+        summary.squares += squares;
+        summary.mismatches.extend(mismatches);
+    }
+
+    Ok(summary)
+}
+
+#[test]
+fn build_selftest_config() {
+
+    let args: Vec<String> = ["--rounds", "5", "--seed", "7", "--word-size", "4"].iter().map(|arg| arg.to_string()).collect();
+
+    assert_eq!(SelftestConfig { rounds: 5, seed: Some(7), word_size: 4, words: DEFAULT_WORDS, letters: DEFAULT_LETTERS }, SelftestConfig::build(&args).unwrap());
+
+    assert!(SelftestConfig::build(&["--word-size".to_string(), "5".to_string()]).is_err());
+    assert!(SelftestConfig::build(&["words.csv".to_string()]).is_err());
+}
+
+#[test]
+fn generate_agrees_with_the_brute_force() {
+
+    let config = SelftestConfig::build(&["--rounds".to_string(), "10".to_string()]).unwrap();
+    let summary = run_selftest(&config, 11).unwrap();
+
+    assert!(summary.squares > 0);
+    assert!(summary.mismatches.is_empty(), "{}", summary.mismatches.iter().map(Mismatch::to_string).collect::<Vec<String>>().join("\n"));
+}
+
+#[test]
+fn last_row_making_itself_doesnt_skip_its_start() {
+
+    // aca in the last row under aab,cbd makes aca down the first column, which mustn't skip acb after it
+    let dictionary: Vec<String> = ["aab", "abc", "aca", "acb", "bdb", "cbd"].iter().map(|word| word.to_string()).collect();
+
+    let (squares, mismatches) = check_dictionary(&dictionary).unwrap();
+
+    assert!(squares > 0);
+    assert!(mismatches.is_empty(), "{}", mismatches.iter().map(Mismatch::to_string).collect::<Vec<String>>().join("\n"));
+    assert!(generated_squares(&dictionary, &[]).unwrap().contains(&Solution::from(vec!["aab".to_string(), "cbd".to_string(), "acb".to_string()])));
+}
@@ -0,0 +1,557 @@
+use std::collections::{HashMap, HashSet};
+use std::io::{self, Write};
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use chrono::NaiveDate;
+use crossbeam_channel::{Receiver, Sender};
+use log::{error, info};
+use serde_json::{json, Value};
+use tiny_http::{Header, Method, Request, Response, Server};
+use crate::daily;
+use crate::error::SquardleError;
+use crate::feedback::{self, LetterFeedback};
+use crate::play;
+use crate::puzzle;
+use crate::solution::Solution;
+use crate::solution_generator::{parse_partial_grid, GridSolver};
+
+// the most solutions one request can ask the generator for
+const MAX_SOLUTIONS_PER_REQUEST: usize = 500;
+// a stream can ask for more as the client isn't waiting for all of them
+const MAX_STREAMED_SOLUTIONS: usize = 20_000;
+// any job only generates for so long, a word size with few squares can take a long time to find even a handful
+const GENERATE_TIME_LIMIT: Duration = Duration::from_secs(60);
+const DEFAULT_ADDRESS: &str = "127.0.0.1:8080";
+
+#[derive(Debug)]
+pub struct ApiResponse {
+    pub status: u16,
+    pub body: Value
+}
+
+impl ApiResponse {
+
+    fn ok(body: Value) -> ApiResponse {
+        ApiResponse { status: 200, body }
+    }
+
+    fn error(status: u16, message: &str) -> ApiResponse {
+        ApiResponse { status, body: json!({ "error": message }) }
+    }
+}
+
+/* A request for solutions handed to the generation worker, which replies once they are cached or
+  the time limit is reached. A streamed job is replied to as soon as it starts and each solution is
+  sent down the stream as it is found, as a json line, with a last line saying how it finished */
+struct GenerateJob {
+    word_size: usize,
+    count: usize,
+    reply: Sender<Result<(), String>>,
+    stream: Option<Sender<Value>>
+}
+
+/* The solutions generated so far for a word size. The solver always finds them in the same
+  order so a smaller request is the start of a bigger one, exhausted once it found them all */
+#[derive(Default)]
+struct CachedSolutions {
+    solutions: Vec<Vec<String>>,
+    exhausted: bool
+}
+
+impl CachedSolutions {
+    fn covers(&self, count: usize) -> bool {
+        self.exhausted || self.solutions.len() >= count
+    }
+}
+
+/* The routes of the api, kept apart from the http server so they can be called directly.
+  GET /solutions?word_size=5&count=10  the first count squares of that size, a square and its transpose both count
+  GET /daily?date=YYYY-MM-DD           the id and size of the daily puzzle from the solutions file, today without a date
+  GET /guess?id=..&line=r1&word=heart  the feedback for guessing a word for a row or column of a puzzle
+  GET /stream?word_size=5&count=1000   the same squares as /solutions but sent as they are found, one json line each */
+pub struct PuzzleApi {
+    dictionary: HashSet<String>,
+    dictionary_hash: u64,
+    solutions: Vec<Vec<String>>,
+    puzzle_indices: HashMap<String, usize>,
+    cache: Arc<Mutex<HashMap<usize, CachedSolutions>>>,
+    jobs: Sender<GenerateJob>
+}
+
+impl PuzzleApi {
+
+    /* starts the worker that runs generation jobs one at a time, there is a solver for each word size the dictionary has */
+    pub fn new(dictionary: Vec<String>, solvers: HashMap<usize, GridSolver>, solutions: Vec<Vec<String>>) -> PuzzleApi {
+        PuzzleApi::with_time_limit(dictionary, solvers, solutions, GENERATE_TIME_LIMIT)
+    }
+
+    /* the same with each generation job stopped after time_limit rather than the default minute */
+    pub fn with_time_limit(dictionary: Vec<String>, solvers: HashMap<usize, GridSolver>, solutions: Vec<Vec<String>>, time_limit: Duration) -> PuzzleApi {
+
+        let cache: Arc<Mutex<HashMap<usize, CachedSolutions>>> = Arc::new(Mutex::new(HashMap::new()));
+        let (jobs, job_receiver) = crossbeam_channel::unbounded::<GenerateJob>();
+
+        let worker_cache = Arc::clone(&cache);
+        thread::spawn(move || run_generate_jobs(solvers, job_receiver, worker_cache, time_limit));
+
+        PuzzleApi {
+            dictionary_hash: daily::dictionary_hash(&dictionary),
+            dictionary: dictionary.into_iter().collect(),
+            puzzle_indices: solutions.iter().enumerate().map(|(index, rows)| (puzzle::puzzle_id(rows), index)).collect(),
+            solutions,
+            cache,
+            jobs
+        }
+    }
+
+    pub fn handle(&self, method: &Method, url: &str) -> ApiResponse {
+
+        if *method != Method::Get {
+            return ApiResponse::error(405, "only GET requests are supported");
+        }
+
+        let path = url.split('?').next().unwrap_or(url);
+        let params = query_params(url);
+
+        match path {
+            "/solutions" => self.solutions(&params),
+            "/daily" => self.daily(&params),
+            "/guess" => self.guess(&params),
+            "/stream" => ApiResponse::error(400, "stream is sent in chunks, open it with open_stream"),
+            _ => ApiResponse::error(404, "no such route, try /solutions, /daily, /guess or /stream")
+        }
+    }
+
+    fn solutions(&self, params: &HashMap<String, String>) -> ApiResponse {
+
+        let (Some(Ok(word_size)), Some(Ok(count))) = (params.get("word_size").map(|size| size.parse::<usize>()), params.get("count").map(|count| count.parse::<usize>())) else {
+            return ApiResponse::error(400, "solutions needs a word_size and a count");
+        };
+
+        if count > MAX_SOLUTIONS_PER_REQUEST {
+            return ApiResponse::error(400, &format!("count can be at most {}", MAX_SOLUTIONS_PER_REQUEST));
+        }
+
+        let cached = self.cache.lock().expect("cache lock poisoned").get(&word_size).is_some_and(|cached| cached.covers(count));
+
+        if !cached {
+            let (reply, reply_receiver) = crossbeam_channel::bounded(1);
+
+            if self.jobs.send(GenerateJob { word_size, count, reply, stream: None }).is_err() {
+                return ApiResponse::error(500, "the generation worker has stopped");
+            }
+
+            match reply_receiver.recv() {
+                Ok(Ok(())) => (),
+                Ok(Err(message)) => return ApiResponse::error(400, &message),
+                Err(_) => return ApiResponse::error(500, "the generation worker has stopped")
+            }
+        }
+
+        let cache = self.cache.lock().expect("cache lock poisoned");
+        let solutions: Vec<&Vec<String>> = cache.get(&word_size).map(|cached| cached.solutions.iter().take(count).collect()).unwrap_or_default();
+        // fewer than asked for without running out of squares means the job stopped at the time limit
+        let time_limit_reached = !cache.get(&word_size).is_some_and(|cached| cached.covers(count));
+
+        ApiResponse::ok(json!({ "word_size": word_size, "cached": cached, "time_limit_reached": time_limit_reached, "solutions": solutions }))
+    }
+
+    /* Starts streaming the solutions of a word size, straight from the cache when it already has enough.
+      The lines stop coming once the count or the time limit is reached, or when the receiver is dropped */
+    pub fn open_stream(&self, method: &Method, url: &str) -> Result<Receiver<Value>, ApiResponse> {
+
+        if *method != Method::Get {
+            return Err(ApiResponse::error(405, "only GET requests are supported"));
+        }
+
+        let params = query_params(url);
+
+        let (Some(Ok(word_size)), Some(Ok(count))) = (params.get("word_size").map(|size| size.parse::<usize>()), params.get("count").map(|count| count.parse::<usize>())) else {
+            return Err(ApiResponse::error(400, "stream needs a word_size and a count"));
+        };
+
+        if count > MAX_STREAMED_SOLUTIONS {
+            return Err(ApiResponse::error(400, &format!("count can be at most {} when streaming", MAX_STREAMED_SOLUTIONS)));
+        }
+
+        let (stream, lines) = crossbeam_channel::unbounded::<Value>();
+
+        if let Some(cached) = self.cache.lock().expect("cache lock poisoned").get(&word_size).filter(|cached| cached.covers(count)) {
+
+            let solutions: Vec<&Vec<String>> = cached.solutions.iter().take(count).collect();
+
+            for rows in &solutions {
+                let _ = stream.send(json!({ "solution": rows }));
+            }
+
+            let _ = stream.send(json!({ "done": true, "count": solutions.len(), "cached": true, "time_limit_reached": false }));
+
+            return Ok(lines);
+        }
+
+        let (reply, reply_receiver) = crossbeam_channel::bounded(1);
+
+        if self.jobs.send(GenerateJob { word_size, count, reply, stream: Some(stream) }).is_err() {
+            return Err(ApiResponse::error(500, "the generation worker has stopped"));
+        }
+
+        match reply_receiver.recv() {
+            Ok(Ok(())) => Ok(lines),
+            Ok(Err(message)) => Err(ApiResponse::error(400, &message)),
+            Err(_) => Err(ApiResponse::error(500, "the generation worker has stopped"))
+        }
+    }
+
+    fn daily(&self, params: &HashMap<String, String>) -> ApiResponse {
+
+        let date = match params.get("date") {
+            Some(date) => match NaiveDate::parse_from_str(date, "%Y-%m-%d") {
+                Ok(date) => date,
+                Err(_) => return ApiResponse::error(400, "date must be YYYY-MM-DD")
+            },
+            None => chrono::Local::now().date_naive()
+        };
+
+        match daily::pick_daily(&self.solutions, date, self.dictionary_hash) {
+            // the rows and columns stay on the server, guesses are checked against the id
+            Some(puzzle) => ApiResponse::ok(json!({
+                "id": puzzle::puzzle_id(&puzzle.rows),
+                "date": puzzle.date,
+                "word_size": puzzle.word_size
+            })),
+            None => ApiResponse::error(404, "there are no puzzles to pick from")
+        }
+    }
+
+    fn guess(&self, params: &HashMap<String, String>) -> ApiResponse {
+
+        let (Some(id), Some(line), Some(word)) = (params.get("id"), params.get("line"), params.get("word")) else {
+            return ApiResponse::error(400, "guess needs an id, a line like r1 or c3 and a word");
+        };
+
+        let Some(rows) = self.puzzle_indices.get(id).map(|index| &self.solutions[*index]) else {
+            return ApiResponse::error(404, "no puzzle has that id");
+        };
+
+        let Some(line) = play::parse_line(line) else {
+            return ApiResponse::error(400, "line must be a row or column like r1 or c3");
+        };
+
+        let word = word.to_lowercase();
+
+        if !self.dictionary.contains(&word) {
+            return ApiResponse::error(400, &format!("[{}] is not in the dictionary", word));
+        }
+
+        match feedback::guess_feedback(rows, line, &word) {
+            Ok(feedback) => ApiResponse::ok(json!({
+                "correct": feedback.iter().all(|letter_feedback| *letter_feedback == LetterFeedback::Correct),
+                "feedback": feedback
+            })),
+            Err(err) => ApiResponse::error(400, &err.to_string())
+        }
+    }
+}
+
+/* the parameters of the query string, decoded so a word or date sent as %xx escapes is looked up as itself */
+fn query_params(url: &str) -> HashMap<String, String> {
+
+    let query = url.split_once('?').map(|(_, query)| query).unwrap_or("");
+
+    form_urlencoded::parse(query.as_bytes()).into_owned().collect()
+}
+
+/* Stops the solver once the time limit is up unless the job finishes first. The solve callback only
+  runs when a seed has found something, a word size with few squares can search for ages between them,
+  so the search is stopped through its stop flag. Joining it says whether the limit was reached */
+fn start_time_limit(solver: &GridSolver, time_limit: Duration) -> (Sender<()>, thread::JoinHandle<bool>) {
+
+    let stop_flag = solver.stop_flag();
+    let (finished, finished_receiver) = crossbeam_channel::bounded::<()>(0);
+
+    let watchdog = thread::spawn(move || {
+        // the job finishing drops the sender, which wakes this up as disconnected rather than timed out
+        let timed_out = finished_receiver.recv_timeout(time_limit).is_err_and(|err| err.is_timeout());
+
+        if timed_out {
+            stop_flag.store(true, Ordering::Relaxed);
+        }
+
+        timed_out
+    });
+
+    (finished, watchdog)
+}
+
+fn run_generate_jobs(mut solvers: HashMap<usize, GridSolver>, jobs: Receiver<GenerateJob>, cache: Arc<Mutex<HashMap<usize, CachedSolutions>>>, time_limit: Duration) {
+
+    for job in jobs {
+
+        // an earlier job in the queue may have generated these already
+        if cache.lock().expect("cache lock poisoned").get(&job.word_size).is_some_and(|cached| cached.covers(job.count)) {
+            let _ = job.reply.send(Ok(()));
+            continue;
+        }
+
+        let Some(solver) = solvers.get_mut(&job.word_size) else {
+            let _ = job.reply.send(Err(format!("the dictionary has no words of {} letters", job.word_size)));
+            continue;
+        };
+
+        info!("Generating {} solutions of size {}", job.count, job.word_size);
+
+        // the client can start reading straight away, the rest of what happens goes down the stream
+        if job.stream.is_some() {
+            let _ = job.reply.send(Ok(()));
+        }
+
+        let mut solutions: Vec<Vec<String>> = Vec::new();
+        let mut client_gone = false;
+
+        let (finished, watchdog) = start_time_limit(solver, time_limit);
+
+        let generated = parse_partial_grid("", job.word_size).and_then(|fixed_letters| solver.solve_each(fixed_letters, Some(job.count), |found| {
+
+            if let Some(stream) = &job.stream {
+                client_gone = found.iter().any(|rows| stream.send(json!({ "solution": rows })).is_err());
+            }
+
+            solutions.extend(found.into_iter().map(Solution::into_rows));
+
+            !client_gone
+        }));
+
+        // joined before the next job so a late stop can't land on its search
+        drop(finished);
+        let time_limit_reached = watchdog.join().expect("the time limit thread doesn't panic");
+
+        let found_count = solutions.len();
+
+        let result = match generated {
+            Ok(_) => {
+                // a stream that was cut short may have found fewer than is already cached
+                let mut cache = cache.lock().expect("cache lock poisoned");
+                let cached = cache.entry(job.word_size).or_default();
+                let exhausted = !client_gone && !time_limit_reached && solutions.len() < job.count;
+
+                if exhausted || solutions.len() > cached.solutions.len() {
+                    *cached = CachedSolutions { exhausted, solutions };
+                }
+
+                Ok(())
+            },
+            Err(err) => Err(err.to_string())
+        };
+
+        match &job.stream {
+            Some(stream) => {
+                let last_line = match &result {
+                    Ok(()) => json!({ "done": true, "count": found_count, "cached": false, "time_limit_reached": time_limit_reached }),
+                    Err(message) => json!({ "error": message })
+                };
+                let _ = stream.send(last_line);
+            },
+            None => { let _ = job.reply.send(result); }
+        }
+    }
+}
+
+/* Answers requests until the process is stopped, each on its own thread so a long generation
+  doesn't hold up a guess */
+pub fn serve(api: PuzzleApi, address: &str) -> Result<(), SquardleError> {
+
+    let server = Server::http(address).map_err(|err| SquardleError::from(format!("could not listen on {}: {}", address, err).as_str()))?;
+    let api = Arc::new(api);
+
+    info!("Serving puzzles on http://{}", address);
+
+    for request in server.incoming_requests() {
+
+        let api = Arc::clone(&api);
+
+        thread::spawn(move || {
+
+            if request.url().split('?').next() == Some("/stream") {
+
+                match api.open_stream(request.method(), request.url()) {
+                    Ok(lines) => {
+                        info!("{} {} streaming", request.method(), request.url());
+
+                        if let Err(err) = write_stream(request, lines) {
+                            info!("Stream ended early: {err}");
+                        }
+                        return;
+                    },
+                    Err(response) => return respond(request, response)
+                }
+            }
+
+            let response = api.handle(request.method(), request.url());
+            respond(request, response);
+        });
+    }
+
+    Ok(())
+}
+
+fn respond(request: Request, response: ApiResponse) {
+
+    info!("{} {} {}", request.method(), request.url(), response.status);
+
+    let content_type = Header::from_bytes("Content-Type", "application/json").expect("the header is valid");
+    let http_response = Response::from_string(response.body.to_string()).with_status_code(response.status).with_header(content_type);
+
+    if let Err(err) = request.respond(http_response) {
+        error!("Problem responding: {err}");
+    }
+}
+
+/* Writes each line as its own chunk and flushes it so the client sees a solution as soon as it is found,
+  tiny_http would hold on to them until it had a few kilobytes. Writing fails once the client goes away,
+  which drops the lines and lets the worker know to stop */
+fn write_stream(request: Request, lines: Receiver<Value>) -> io::Result<()> {
+
+    let mut writer = request.into_writer();
+
+    write!(writer, "HTTP/1.1 200 OK\r\nContent-Type: application/x-ndjson\r\nTransfer-Encoding: chunked\r\nConnection: close\r\n\r\n")?;
+    writer.flush()?;
+
+    for line in lines {
+        let line = format!("{}\n", line);
+        write!(writer, "{:x}\r\n{}\r\n", line.len(), line)?;
+        writer.flush()?;
+    }
+
+    write!(writer, "0\r\n\r\n")?;
+    writer.flush()
+}
+
+/* serve dictionary_file solutions_file with optional --address host:port */
+#[derive(Debug, PartialEq)]
+pub struct ServeConfig {
+    pub dictionary_file_path: String,
+    pub solutions_file_path: String,
+    pub address: String
+}
+
+impl ServeConfig {
+
+    pub fn build(args: &[String]) -> Result<ServeConfig, SquardleError> {
+
+        let mut address = DEFAULT_ADDRESS.to_string();
+        let mut positional_args: Vec<String> = Vec::with_capacity(2);
+        let mut args_iter = args.iter();
+
+        while let Some(arg) = args_iter.next() {
+
+            match arg.as_str() {
+                "--address" => match args_iter.next() {
+                    Some(next) => address = next.clone(),
+                    None => return Err("Address flag needs a host:port".into())
+                },
+                _ => positional_args.push(arg.clone())
+            }
+        }
+
+        let [dictionary_file_path, solutions_file_path]: [String; 2] = positional_args.try_into()
+            .map_err(|_| SquardleError::from("serve accepts a dictionary file path, a solutions file path and optionally --address host:port"))?;
+
+        Ok(ServeConfig { dictionary_file_path, solutions_file_path, address })
+    }
+}
+
+#[test]
+fn solutions_are_generated_then_cached() {
+
+    let (dictionary, prefix_map) = crate::dictionary::test_dictionary();
+    let solvers = HashMap::from([(3, GridSolver::new(dictionary.clone(), prefix_map).unwrap())]);
+    let api = PuzzleApi::new(dictionary, solvers, vec![vec!["ten".to_string(), "ore".to_string(), "eat".to_string()]]);
+
+    let response = api.handle(&Method::Get, "/solutions?word_size=3&count=1");
+    assert_eq!(200, response.status);
+    assert_eq!(false, response.body["cached"]);
+    assert_eq!(1, response.body["solutions"].as_array().unwrap().len());
+
+    let response = api.handle(&Method::Get, "/solutions?word_size=3&count=1");
+    assert_eq!(true, response.body["cached"]);
+
+    assert_eq!(400, api.handle(&Method::Get, "/solutions?word_size=4&count=1").status);
+    assert_eq!(400, api.handle(&Method::Get, "/solutions?word_size=3&count=100000").status);
+}
+
+#[test]
+fn daily_and_guess_routes() {
+
+    let (dictionary, prefix_map) = crate::dictionary::test_dictionary();
+    let solvers = HashMap::from([(3, GridSolver::new(dictionary.clone(), prefix_map).unwrap())]);
+    let api = PuzzleApi::new(dictionary, solvers, vec![vec!["ten".to_string(), "ore".to_string(), "eat".to_string()]]);
+
+    let daily = api.handle(&Method::Get, "/daily?date=2024-03-01");
+    assert_eq!(200, daily.status);
+    assert_eq!(None, daily.body.get("rows"));
+
+    let id = daily.body["id"].as_str().unwrap();
+
+    let guess = api.handle(&Method::Get, &format!("/guess?id={}&line=c1&word=toe", id));
+    assert_eq!(200, guess.status);
+    assert_eq!(true, guess.body["correct"]);
+
+    let guess = api.handle(&Method::Get, &format!("/guess?id={}&line=r1&word=toe", id));
+    // o isn't in the top row or the column under it, only elsewhere in the square
+    assert_eq!(json!(["correct", "elsewhere_in_grid", "in_row"]), guess.body["feedback"]);
+
+    // escaped values are decoded before they are looked up
+    assert_eq!(daily.body, api.handle(&Method::Get, "/daily?date=2024%2D03%2D01").body);
+    assert_eq!(true, api.handle(&Method::Get, &format!("/guess?id={}&line=c%31&word=%74oe", id)).body["correct"]);
+
+    assert_eq!(400, api.handle(&Method::Get, &format!("/guess?id={}&line=r1&word=zzz", id)).status);
+    assert_eq!(404, api.handle(&Method::Get, "/guess?id=nope&line=r1&word=ten").status);
+    assert_eq!(404, api.handle(&Method::Get, "/nowhere").status);
+    assert_eq!(405, api.handle(&Method::Post, "/daily").status);
+}
+
+#[test]
+fn solutions_are_streamed_as_json_lines() {
+
+    let (dictionary, prefix_map) = crate::dictionary::test_dictionary();
+    let solvers = HashMap::from([(3, GridSolver::new(dictionary.clone(), prefix_map).unwrap())]);
+    let api = PuzzleApi::new(dictionary, solvers, vec![]);
+
+    let lines: Vec<Value> = api.open_stream(&Method::Get, "/stream?word_size=3&count=5").unwrap().iter().collect();
+    assert_eq!(json!(["ten", "ore", "eat"]), lines[0]["solution"]);
+    assert_eq!(json!({ "done": true, "count": lines.len() - 1, "cached": false, "time_limit_reached": false }), lines[lines.len() - 1]);
+
+    // there were fewer than asked for so the cache has all of them
+    let lines: Vec<Value> = api.open_stream(&Method::Get, "/stream?word_size=3&count=5").unwrap().iter().collect();
+    assert_eq!(true, lines[lines.len() - 1]["cached"]);
+    assert_eq!(json!(["ten", "ore", "eat"]), api.handle(&Method::Get, "/solutions?word_size=3&count=1").body["solutions"][0]);
+
+    assert_eq!(400, api.open_stream(&Method::Get, "/stream?word_size=4&count=1").unwrap_err().status);
+    assert_eq!(400, api.open_stream(&Method::Get, "/stream?word_size=3&count=1000000").unwrap_err().status);
+}
+
+#[test]
+fn a_search_with_no_squares_stops_at_the_time_limit() {
+
+    // counting up in base 4 keeps the words sorted. Every word ends in z so the last column would have
+    // to be zzzzz, which isn't a word. The search gets three rows into every seed before finding that
+    // out and never has a solution to report
+    let letters = ['a', 'b', 'c', 'z'];
+    let dictionary: Vec<String> = (0..letters.len().pow(4))
+        .map(|n| (0..4).map(|place| letters[n / letters.len().pow(place) % letters.len()]).rev().chain(['z']).collect::<String>())
+        .filter(|word| word != "zzzzz")
+        .collect();
+
+    let prefix_map = crate::prefix_arena::PrefixArena::new(&dictionary).unwrap();
+    let solvers = HashMap::from([(5, GridSolver::new(dictionary.clone(), prefix_map).unwrap())]);
+    let api = PuzzleApi::with_time_limit(dictionary, solvers, vec![], Duration::from_millis(200));
+
+    let started = std::time::Instant::now();
+    let response = api.handle(&Method::Get, "/solutions?word_size=5&count=1");
+
+    assert_eq!(200, response.status);
+    assert_eq!(true, response.body["time_limit_reached"]);
+    assert!(started.elapsed() < Duration::from_secs(10));
+}
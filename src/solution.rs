@@ -0,0 +1,113 @@
+use std::fmt;
+use std::ops::Deref;
+use serde::{Deserialize, Serialize};
+use crate::solution_generator::construct_potential_transposed_puzzle;
+
+/* A square the generator found, its rows top to bottom. The columns are only ever implied by
+  the rows so they're worked out when asked for. It derefs to the rows so it goes anywhere a
+  slice of words does, and is the rows as a json array, the same as the jsonl output */
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Solution {
+    rows: Vec<String>
+}
+
+impl Solution {
+
+    pub fn new(rows: Vec<String>) -> Solution {
+        Solution { rows }
+    }
+
+    pub fn rows(&self) -> &[String] {
+        &self.rows
+    }
+
+    /* left to right, each read top to bottom */
+    pub fn columns(&self) -> Vec<String> {
+
+        if self.rows.is_empty() {
+            return Vec::new();
+        }
+
+        construct_potential_transposed_puzzle(&self.rows)
+    }
+
+    /* the letter at the row and column counting from 0, none off the edge of the square */
+    pub fn cell(&self, row: usize, column: usize) -> Option<char> {
+        self.rows.get(row).and_then(|word| word.chars().nth(column))
+    }
+
+    /* the same square flipped over the diagonal from the top left, its columns as rows */
+    pub fn transpose(&self) -> Solution {
+        Solution::new(self.columns())
+    }
+
+    pub fn into_rows(self) -> Vec<String> {
+        self.rows
+    }
+}
+
+impl From<Vec<String>> for Solution {
+    fn from(rows: Vec<String>) -> Solution {
+        Solution::new(rows)
+    }
+}
+
+impl Deref for Solution {
+    type Target = [String];
+
+    fn deref(&self) -> &[String] {
+        &self.rows
+    }
+}
+
+impl PartialEq<Vec<String>> for Solution {
+    fn eq(&self, rows: &Vec<String>) -> bool {
+        &self.rows == rows
+    }
+}
+
+impl PartialEq<Solution> for Vec<String> {
+    fn eq(&self, solution: &Solution) -> bool {
+        self == &solution.rows
+    }
+}
+
+impl PartialEq<Solution> for Vec<&str> {
+    fn eq(&self, solution: &Solution) -> bool {
+        self.iter().eq(solution.rows.iter())
+    }
+}
+
+/* the rows separated by commas, a line of the csv output */
+impl fmt::Display for Solution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.rows.join(","))
+    }
+}
+
+#[test]
+fn rows_columns_and_cells() {
+
+    let solution = Solution::from(vec!["abc".to_string(), "def".to_string(), "ghi".to_string()]);
+
+    assert_eq!(["abc", "def", "ghi"], solution.rows());
+    assert_eq!(vec!["adg", "beh", "cfi"], solution.columns());
+    assert_eq!((Some('f'), None), (solution.cell(1, 2), solution.cell(3, 0)));
+
+    assert_eq!(solution.columns(), solution.transpose().rows());
+    assert_eq!(solution, solution.transpose().transpose());
+
+    assert_eq!("abc,def,ghi", solution.to_string());
+    assert_eq!("ghi", solution[2]);
+}
+
+#[test]
+fn solution_as_json() {
+
+    let solution = Solution::from(vec!["abc".to_string(), "def".to_string(), "ghi".to_string()]);
+    let json = serde_json::to_string(&solution).unwrap();
+
+    assert_eq!(r#"["abc","def","ghi"]"#, json);
+    assert_eq!(solution, serde_json::from_str::<Solution>(&json).unwrap());
+}
@@ -1,298 +1,2905 @@
 use std::cmp::Ordering;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::fmt;
-use std::sync::Mutex;
-use std::sync::mpsc::Sender;
-use std::sync::mpsc::Receiver;
+use std::mem;
+use std::ops::Range;
+use std::panic::{self, AssertUnwindSafe};
+use std::any::Any;
 use std::{
-    sync::{mpsc, Arc},
-    thread,
+    sync::{Arc, Mutex, atomic::{AtomicBool, Ordering as AtomicOrdering}},
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
 };
-use tqdm::tqdm;
+use crossbeam_channel::{Receiver, Sender};
+use log::{debug, trace};
+use rayon::prelude::*;
+use serde::Serialize;
+use thiserror::Error;
+use crate::constrained_fill::ConstrainedFill;
+use crate::constraint::{Constraint, Pattern};
+use crate::explain::{Explanation, Step};
+use crate::memo::{FailureMemo, PrefixClasses};
+use crate::naive;
+use crate::solution::Solution;
+use crate::prefix_arena::PrefixArena;
+use crate::screen::{self, AllowedLetters, CandidateLetters};
+use crate::generator_config::{FillOrder, GeneratorConfig, Pruning};
+use crate::error::SquardleError;
+use crate::output::{self, WorkerFile};
+use crate::progress::Progress;
+use crate::sample::SampleRng;
+
+// the length of the prefix shared by the blocks of candidate words a shuffled search reorders
+const SHUFFLED_BLOCK_PREFIX_LENGTH: usize = 1;
+
+const DEFAULT_SOLVE_BATCH_SIZE: usize = 64;
+
+/* the constrained fill's letter bitsets are built once and shared by every worker */
+fn constrained_fill(config: &GeneratorConfig, dictionary: &[String]) -> Option<Arc<ConstrainedFill>> {
+    (config.fill_order == FillOrder::Constrained).then(|| Arc::new(ConstrainedFill::new(dictionary)))
+}
 
+/* the classes of column starts are worked out once, each thread pool worker has its own memo of them */
+fn prefix_classes(config: &GeneratorConfig, prefix_map: &PrefixArena) -> Option<Arc<PrefixClasses>> {
+    config.memo.map(|_| Arc::new(PrefixClasses::new(prefix_map)))
+}
 
-enum ExecuteMessage {
-    Word(String),
-    Terminate,
+fn failure_memo(config: &GeneratorConfig, prefix_classes: &Option<Arc<PrefixClasses>>) -> Option<FailureMemo> {
+    prefix_classes.clone().zip(config.memo).map(|(prefix_classes, capacity)| FailureMemo::new(prefix_classes, capacity))
 }
 
-pub enum ThreadPoolError {
-    ZeroSizedPool,
-    ZeroSizedDictionary,
-    ZeroSizedPrefixMap
+/* a seed word is reported started before any of its solutions are sent, and only reported
+  finished after every one of them has been */
+pub enum GeneratorEvent {
+    SeedStarted(String),
+    Solutions(Vec<Solution>),
+    SeedFinished(String)
 }
 
-impl fmt::Display for ThreadPoolError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match *self {
-            ThreadPoolError::ZeroSizedPool => "Number of threads is less than or equal to 0",
-            ThreadPoolError::ZeroSizedDictionary => "Dictionary is empty",
-            ThreadPoolError::ZeroSizedPrefixMap => "Prefix map is empty",
-        }
-        .fmt(f)
+/* solutions arrive from the workers in batches, iterating flattens them back out into single solutions */
+pub struct SolutionReceiver {
+    event_receiver: Receiver<GeneratorEvent>,
+}
+
+impl SolutionReceiver {
+
+    pub fn iter(&self) -> impl Iterator<Item = Solution> + '_ {
+        self.events().filter_map(|event| match event {
+            GeneratorEvent::Solutions(solutions) => Some(solutions),
+            GeneratorEvent::SeedStarted(_) | GeneratorEvent::SeedFinished(_) => None
+        })
+        .flatten()
+    }
+
+    pub fn events(&self) -> impl Iterator<Item = GeneratorEvent> + '_ {
+        self.event_receiver.iter()
+    }
+
+    /* the same solutions as iter but in the order of the seed words given, each seed word's
+      solutions sorted, so the output is the same however the workers raced */
+    pub fn ordered_iter(&self, seed_order: Vec<String>) -> OrderedSolutions<'_> {
+        OrderedSolutions::new(self.events(), seed_order)
+    }
+
+    /* Throws away whatever is left until every worker has hung up. The channel is bounded so a
+      worker can be stuck sending to a full channel once nobody is reading, after a solution
+      limit say, and would never get to see it has been told to stop */
+    fn drain(&self) {
+        for _ in self.events() {}
     }
 }
 
-pub struct SolutionGeneratorThreadPool {
-    pub solution_receiver: Receiver<Vec<String>>,
+/* Holds back each seed word's solutions until every seed word before it has finished.
+  Seed words that never finish (failed or stopped) hold back the ones after them until
+  the run ends, when whatever is left comes out in seed order */
+pub struct OrderedSolutions<'a> {
+    events: Box<dyn Iterator<Item = GeneratorEvent> + 'a>,
+    seed_order: VecDeque<String>,
+    pending: HashMap<String, Vec<Solution>>,
+    finished_seeds: HashSet<String>,
+    ready: VecDeque<Solution>
 }
 
-impl SolutionGeneratorThreadPool {
+impl<'a> OrderedSolutions<'a> {
 
-    pub fn new(num_threads: usize, dictionary: &Box<Vec<String>>, prefix_map: Box<HashMap<String, Vec<String>>>) -> Result<SolutionGeneratorThreadPool, ThreadPoolError> {
+    fn new<I>(events: I, seed_order: Vec<String>) -> OrderedSolutions<'a>
+        where I: Iterator<Item = GeneratorEvent> + 'a {
 
-        if num_threads <= 0 {
-            return Err(ThreadPoolError::ZeroSizedPool)
+        OrderedSolutions {
+            events: Box::new(events),
+            seed_order: seed_order.into(),
+            pending: HashMap::new(),
+            finished_seeds: HashSet::new(),
+            ready: VecDeque::new()
         }
+    }
 
-        if dictionary.len() == 0 {
-            return Err(ThreadPoolError::ZeroSizedDictionary)
-        }
+    fn release_seed(&mut self, seed: &str) {
 
-        if prefix_map.len() <= 0 {
-            return Err(ThreadPoolError::ZeroSizedPrefixMap)
+        if let Some(mut solutions) = self.pending.remove(seed) {
+            solutions.sort();
+            self.ready.extend(solutions);
         }
+    }
+}
+
+impl Iterator for OrderedSolutions<'_> {
+    type Item = Solution;
 
-        let word_size = dictionary.get(0).unwrap().chars().count();
+    fn next(&mut self) -> Option<Solution> {
 
-        let prefix_map_arc = Arc::new(prefix_map);
+        loop {
 
-        let mut workers: Vec<SolutionGeneratorWorker> = Vec::with_capacity(num_threads);
+            if let Some(solution) = self.ready.pop_front() {
+                return Some(solution);
+            }
 
-        let (word_sender, word_receiver) = mpsc::sync_channel::<ExecuteMessage>(8);
-        let word_receiver = Arc::new(Mutex::new(word_receiver));
+            match self.events.next() {
+                Some(GeneratorEvent::SeedStarted(_)) => (),
+                Some(GeneratorEvent::Solutions(solutions)) => {
+                    for solution in solutions {
+                        self.pending.entry(solution[0].clone()).or_default().push(solution);
+                    }
+                },
+                Some(GeneratorEvent::SeedFinished(seed)) => {
+                    self.finished_seeds.insert(seed);
+
+                    while self.seed_order.front().is_some_and(|seed| self.finished_seeds.contains(seed)) {
+                        let seed = self.seed_order.pop_front().expect("front was just checked");
+                        self.finished_seeds.remove(&seed);
+                        self.release_seed(&seed);
+                    }
+                },
+                None => {
+                    while let Some(seed) = self.seed_order.pop_front() {
+                        self.release_seed(&seed);
+                    }
 
-        let (solution_sender, solution_receiver) = mpsc::channel::<Vec<String>>();
+                    // anything from a seed that was not in the order goes last
+                    let mut unordered_seeds: Vec<String> = self.pending.keys().cloned().collect();
+                    unordered_seeds.sort();
 
-        for _ in 0..num_threads {
+                    for seed in unordered_seeds {
+                        self.release_seed(&seed);
+                    }
 
-            workers.push(SolutionGeneratorWorker::new(
-                dictionary.clone(),
-                Arc::clone(&prefix_map_arc),
-                solution_sender.clone(),
-                word_receiver.clone(),
-                word_size
-            ));
+                    return self.ready.pop_front();
+                }
+            }
         }
+    }
+}
 
-        let dict_clone = dictionary.clone();
+/* something that went wrong inside a worker, reported back instead of the worker just disappearing */
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum GeneratorError {
+    #[error("searching seed word [{seed}] failed: {message}")]
+    SeedFailed { seed: String, message: String },
+    #[error("generator thread panicked: {0}")]
+    ThreadPanicked(String),
+    #[error("generated invalid solution {solution:?}: {reason}")]
+    InvalidSolution { solution: Vec<String>, reason: String },
+    #[error("writing a worker output file failed: {0}")]
+    WorkerOutputFailed(String)
+}
 
-        thread::spawn( move || {
+fn panic_message(payload: &Box<dyn Any + Send>) -> String {
 
-            for word in tqdm(dict_clone.iter()) {
-                word_sender.send(ExecuteMessage::Word(word.to_string())).unwrap();
-            }
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
 
-            for _ in 0..num_threads {
-                word_sender.send(ExecuteMessage::Terminate).unwrap();
-            }
-        });
+/* the letters a to z a word has as bits, the lowest for a */
+pub fn letter_mask(word: &str) -> u32 {
+    word.chars().filter_map(letter_index).fold(0, |mask, index| mask | 1 << index)
+}
 
-        Ok(SolutionGeneratorThreadPool {solution_receiver })
+/* where a letter goes in the per letter counts, none for anything outside a to z */
+pub fn letter_index(letter: char) -> Option<usize> {
+
+    if letter.is_ascii_lowercase() {
+        Some((letter as u8 - b'a') as usize)
+    } else {
+        None
     }
 }
 
-struct SolutionGeneratorWorker {
+/* a letter's bit in a set of letters, none for anything outside a to z */
+fn letter_bit(letter: char) -> u32 {
+    letter_index(letter).map_or(0, |index| 1 << index)
 }
 
-impl SolutionGeneratorWorker {
+/* counters collected by every worker and added together once the run is over,
+  with how long each phase of the run took */
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct RunStats {
+    pub seeds_processed: u64,
+    pub solutions_found: u64,
+    pub backtracks: u64,
+    // how many partial puzzles had words tried against them, backtracks over this is the average branching factor
+    pub nodes_expanded: u64,
+    // partial grids skipped as the memo already knew they go nowhere
+    pub memo_hits: u64,
+    // words checked against the columns, the screen having passed them
+    pub candidate_checks: u64,
+    // starts of a column looked up in the prefix map checking them
+    pub prefix_lookups: u64,
+    // words whose start couldn't start a column, each skipping the run of words after it starting the same way
+    pub bad_start_prunes: u64,
+    // words never tried as they start the same way as one whose letters couldn't start a column
+    pub bad_start_skips: u64,
+    // words that fit but were backed out of straight away as a row below had nothing left, only when forward checking
+    pub forward_check_prunes: u64,
+    // indexed by letter, a to z
+    pub solutions_per_letter: [u64; 26],
+    pub letter_counts: [u64; 26],
+    // how often each word is a row of a solution, not tallied when only counting
+    pub word_counts: HashMap<String, u64>,
+    // only tallied when counting, the seed word a solution starts with and how many there were
+    pub solutions_per_seed: HashMap<String, u64>,
+    pub phase_timings: Vec<(String, Duration)>,
+    pub errors: Vec<GeneratorError>
+}
 
-    fn new(dictionary: Box<Vec<String>>, prefix_map_arc: Arc<Box<HashMap<String, Vec<String>>>>, 
-        solution_sender: Sender<Vec<String>>, word_receiver: Arc<Mutex<Receiver<ExecuteMessage>>>,
-        word_size: usize) -> SolutionGeneratorWorker {
+impl RunStats {
 
-        let mut solution_generator = SolutionGenerator::new(
-            dictionary,
-            prefix_map_arc,
-            solution_sender,
-            word_size
-        );
+    /* the letter and word counts of a solution, the puzzle being every row but the last */
+    pub fn record_solution(&mut self, puzzle: &[String], last_word: &str) {
 
-        thread::spawn(move || 
+        self.record_letters(puzzle, last_word);
 
-            loop {
+        for word in puzzle.iter().map(String::as_str).chain([last_word]) {
+            match self.word_counts.get_mut(word) {
+                Some(count) => *count += 1,
+                None => { self.word_counts.insert(word.to_string(), 1); }
+            }
+        }
+    }
 
-                let execute_message = word_receiver.lock().unwrap().recv().unwrap();
+    /* just the letter counts, which are only array increments where every word is a hash lookup */
+    fn record_letters(&mut self, puzzle: &[String], last_word: &str) {
 
-                match execute_message {
-                    ExecuteMessage::Word(word) => solution_generator.run(word),
-                    ExecuteMessage::Terminate => {
-                        break;
-                    }
-                };
-            }
-        );
+        if let Some(index) = puzzle.first().and_then(|seed| seed.chars().next()).and_then(letter_index) {
+            self.solutions_per_letter[index] += 1;
+        }
 
-        SolutionGeneratorWorker {}
+        for word in puzzle.iter().map(String::as_str).chain([last_word]) {
+            for index in word.chars().filter_map(letter_index) {
+                self.letter_counts[index] += 1;
+            }
+        }
     }
 
-}
+    fn merge(&mut self, other: &RunStats) {
+
+        self.seeds_processed += other.seeds_processed;
+        self.solutions_found += other.solutions_found;
+        self.backtracks += other.backtracks;
+        self.nodes_expanded += other.nodes_expanded;
+        self.memo_hits += other.memo_hits;
+        self.candidate_checks += other.candidate_checks;
+        self.prefix_lookups += other.prefix_lookups;
+        self.bad_start_prunes += other.bad_start_prunes;
+        self.bad_start_skips += other.bad_start_skips;
+        self.forward_check_prunes += other.forward_check_prunes;
+
+        for i in 0..26 {
+            self.solutions_per_letter[i] += other.solutions_per_letter[i];
+            self.letter_counts[i] += other.letter_counts[i];
+        }
 
-struct SolutionGenerator {
-    dictionary: Box<Vec<String>>,
-    last_row_index: usize,
-    prefix_map_arc: Arc<Box<HashMap<String, Vec<String>>>>,
-    solution_sender: Sender<Vec<String>>,
-    word_size: usize
+        for (word, count) in &other.word_counts {
+            *self.word_counts.entry(word.clone()).or_insert(0) += count;
+        }
+
+        for (seed, count) in &other.solutions_per_seed {
+            *self.solutions_per_seed.entry(seed.clone()).or_insert(0) += count;
+        }
+        self.errors.extend(other.errors.iter().cloned());
+    }
 }
 
-impl SolutionGenerator {
+impl fmt::Display for RunStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
 
-    fn new(dictionary: Box<Vec<String>>, prefix_map_arc: Arc<Box<HashMap<String, Vec<String>>>>,
-        solution_sender: Sender<Vec<String>>, word_size: usize) -> SolutionGenerator {
+        writeln!(f, "seeds processed: {}", self.seeds_processed)?;
+        writeln!(f, "solutions found: {}", self.solutions_found)?;
+        writeln!(f, "candidates checked: {}", self.candidate_checks)?;
+        writeln!(f, "prefix lookups: {}", self.prefix_lookups)?;
+        write!(f, "backtracks: {}", self.backtracks)?;
 
-        SolutionGenerator {
-            dictionary,
-            last_row_index: word_size -1,
-            prefix_map_arc,
-            solution_sender,
-            word_size
+        if self.bad_start_prunes > 0 {
+            write!(f, "\nbad starts pruned: {}", self.bad_start_prunes)?;
+        }
+
+        if self.bad_start_skips > 0 {
+            write!(f, "\nwords skipped by bad starts: {}", self.bad_start_skips)?;
+        }
+
+        if self.forward_check_prunes > 0 {
+            write!(f, "\nforward check prunes: {}", self.forward_check_prunes)?;
+        }
+
+        if self.memo_hits > 0 {
+            write!(f, "\nmemo hits: {}", self.memo_hits)?;
+        }
+
+        for (phase, elapsed) in &self.phase_timings {
+            write!(f, "\n{}: {:#?}", phase, elapsed)?;
+        }
+
+        if !self.errors.is_empty() {
+            write!(f, "\nerrors: {}", self.errors.len())?;
         }
+
+        Ok(())
     }
+}
 
-    fn run(&mut self, word: String) {
+/* both backends can be waited on for their run statistics, dropping one
+  before it has finished stops it and waits for its threads to exit */
+pub trait GeneratorPool {
+    fn solution_receiver(&self) -> &SolutionReceiver;
+    fn join(&mut self) -> RunStats;
+}
 
-        let mut initial_puzzle: Vec<String> = Vec::with_capacity(self.word_size);
-        initial_puzzle.push(word);
+/* what both pools check before starting any threads, giving back how long the words are */
+fn checked_word_size(num_threads: usize, dictionary: &[String], prefix_arena: &PrefixArena) -> Result<usize, SquardleError> {
 
-        self.find_solutions(&mut initial_puzzle, 1);
+    if num_threads == 0 {
+        return Err(SquardleError::ZeroSizedPool)
     }
-    
-    fn find_solutions(&self, puzzle: &mut Vec<String>, row_index: usize) {
-    
-        let potential_columns = construct_potential_transposed_puzzle(puzzle);
-    
-        let mut bad_starts = vec![ "".to_string(); self.last_row_index];
-    
-        for word in self.dictionary.iter() {
-    
-            if skip_word(&word, &bad_starts, puzzle) {
-                continue;
-            }
-    
-            let (fit, last_column_index_checked) = 
-                if row_index == self.last_row_index
-                    {self.last_word_fits(puzzle, &word, &potential_columns)} 
-                else 
-                    {self.word_fits(&word, &potential_columns)};
-    
-            if fit {
 
-                // solution found
-                if last_column_index_checked ==  self.last_row_index {
+    let Some(first_word) = dictionary.first() else {
+        return Err(SquardleError::EmptyDictionary)
+    };
+
+    if prefix_arena.is_empty() {
+        return Err(SquardleError::EmptyPrefixMap)
+    }
 
-                    let mut temp_puzzle_solution = puzzle.clone();
-                    temp_puzzle_solution.push(word.clone());
+    Ok(first_word.chars().count())
+}
 
-                    //println!("solution {:?}", temp_puzzle_solution);
-                    self.solution_sender.send(temp_puzzle_solution).expect("Sender should always be able to send");
-                }
+/* the threads behind a pool and the stats they add to as they finish */
+struct PoolThreads {
+    threads: Vec<JoinHandle<()>>,
+    stats: Arc<Mutex<RunStats>>,
+    stop_flag: Arc<AtomicBool>,
+    progress: Arc<Progress>,
+    progress_reporter: Option<JoinHandle<()>>,
+    started: Instant,
+    // the generation phase span, closed once the threads have been joined
+    generation_span: Option<tracing::Span>
+}
 
-                puzzle.push(word.clone());
+impl PoolThreads {
 
-                self.find_solutions(puzzle, row_index + 1);
+    fn new(config: &GeneratorConfig, stop_flag: Arc<AtomicBool>, seeds_total: u64) -> PoolThreads {
 
-                puzzle.pop();
-    
-            } else {
+        let progress = Arc::new(Progress::new(seeds_total));
 
-                // if it failed on the last column index no need to record it as words are unique
-                if last_column_index_checked == self.last_row_index {
-                    continue;
-                }
+        PoolThreads {
+            threads: Vec::new(),
+            stats: Arc::new(Mutex::new(RunStats::default())),
+            stop_flag,
+            progress_reporter: Progress::spawn_reporter(progress.clone(), config.progress_format, config.progress_file_path.as_deref()),
+            progress,
+            started: Instant::now(),
+            generation_span: Some(tracing::info_span!(target: crate::timing::PHASE, "generation"))
+        }
+    }
 
-                let bad_start: String = word.chars().take(last_column_index_checked+1).collect();
+    fn join(&mut self) -> RunStats {
 
-                bad_starts[last_column_index_checked] = bad_start;
+        let mut thread_errors: Vec<GeneratorError> = Vec::new();
+
+        for thread in self.threads.drain(..) {
+            if let Err(payload) = thread.join() {
+                thread_errors.push(GeneratorError::ThreadPanicked(panic_message(&payload)));
             }
         }
-    
-    }
-    
-    fn word_fits(&self, word: &String, potential_columns: &Vec<String>) -> (bool, usize) {
-    
-        let size = word.len();
-        if potential_columns.len() != size {
-            panic!("Potential coulmns [{}] and word [{}] have different lengths", potential_columns.len(), size);
+
+        self.progress.finish();
+
+        if let Some(progress_reporter) = self.progress_reporter.take() {
+            let _ = progress_reporter.join();
         }
-    
-        for i in 0..size {
-    
-            let start_string = potential_columns.get(i).unwrap();
-    
-            let new_potential_column = format!("{}{}", start_string, word.chars().nth(i).unwrap());
-    
-            if !&self.prefix_map_arc.contains_key(&new_potential_column) {
-                return (false, i)
-            }
+
+        let mut stats = match self.stats.lock() {
+            Ok(stats) => stats.clone(),
+            Err(poisoned) => poisoned.into_inner().clone()
+        };
+
+        stats.errors.extend(thread_errors);
+        stats.phase_timings.push(("generation".to_string(), self.started.elapsed()));
+        self.generation_span.take();
+
+        stats
+    }
+}
+
+impl Drop for PoolThreads {
+    fn drop(&mut self) {
+
+        if !self.threads.is_empty() {
+            self.stop_flag.store(true, AtomicOrdering::Relaxed);
+            self.join();
         }
-    
-        (true, size)
     }
-    
-    fn last_word_fits(&self, puzzle: &Vec<String>, word: &String, potential_columns: &Vec<String>) -> (bool, usize) {
-    
-        for i in 0..word.len() {
-    
-            let start_string = potential_columns.get(i).unwrap();
-    
-            let new_potential_column = format!("{}{}", start_string, word.chars().nth(i).unwrap());
-    
-            if i == 0 && would_be_transposed_row(&puzzle.get(0).unwrap(), &new_potential_column) {
-                return (false, i);
-            }
-    
-            if self.does_column_fit(&start_string, &new_potential_column, puzzle) {
+}
 
-                return (false, i)
-            }
+pub struct SolutionGeneratorThreadPool {
+    pub solution_receiver: SolutionReceiver,
+    threads: PoolThreads
+}
+
+impl GeneratorPool for SolutionGeneratorThreadPool {
+
+    fn solution_receiver(&self) -> &SolutionReceiver {
+        &self.solution_receiver
+    }
+
+    fn join(&mut self) -> RunStats {
+        self.solution_receiver.drain();
+        self.threads.join()
+    }
+}
+
+impl SolutionGeneratorThreadPool {
+
+    pub fn new(config: &GeneratorConfig, dictionary: &[String], prefix_arena: PrefixArena,
+        stop_flag: Arc<AtomicBool>, skipped_seeds: HashSet<String>) -> Result<SolutionGeneratorThreadPool, SquardleError> {
+
+        let num_threads = config.num_threads;
+        let word_size = checked_word_size(num_threads, dictionary, &prefix_arena)?;
+
+        let prefix_arena = Arc::new(prefix_arena);
+        let search_words = SearchWords::new(dictionary.to_vec(), word_size);
+        // the chunks are ranges of the words searched rather than the dictionary handed in
+        let dictionary: &[String] = &search_words.dictionary;
+        let skipped_seeds = Arc::new(skipped_seeds);
+        let constrained_fill = constrained_fill(config, dictionary);
+        let prefix_classes = prefix_classes(config, &prefix_arena);
+        let constraints = config.constraints(word_size)?;
+
+        let mut pool_threads = PoolThreads::new(config, stop_flag.clone(), seeds_to_search(dictionary, &skipped_seeds));
+
+        let (chunk_sender, chunk_receiver) = crossbeam_channel::bounded::<Range<usize>>(config.job_queue_capacity);
+
+        // bounded so the workers wait for a slow writer rather than piling up solutions in memory
+        let (solution_sender, solution_receiver) = crossbeam_channel::bounded::<GeneratorEvent>(config.solution_queue_capacity);
+
+        for worker_number in 0..num_threads {
+
+            let worker_file = match (config.worker_output, &config.solutions_dest_file_path) {
+                (true, Some(file_path)) => Some(WorkerFile::create(&output::worker_file_path(file_path, worker_number), config.output_format, config.csv_dialect)?),
+                _ => None
+            };
+
+            let context = WorkerContext {
+                solution_sender: solution_sender.clone(),
+                stop_flag: stop_flag.clone(),
+                skipped_seeds: skipped_seeds.clone(),
+                stats: pool_threads.stats.clone(),
+                progress: pool_threads.progress.clone()
+            };
+
+            let solution_generator = SolutionGenerator::new(search_words.clone(), Arc::clone(&prefix_arena), word_size, config.solution_batch_size, config.count_only, context).with_validation(config.validate).with_shuffled_candidates(shuffle_rng(config, worker_number as u64 + 1))
+                .with_first_per_seed(config.first_per_seed).with_constrained_fill(constrained_fill.clone()).with_pruning(config.pruning)
+                .with_failure_memo(failure_memo(config, &prefix_classes)).with_constraints(constraints.clone());
+
+            pool_threads.threads.push(SolutionGeneratorWorker::spawn(solution_generator, chunk_receiver.clone(), worker_file));
         }
-    
-        (true, word.len() -1)
+
+        // shuffled seed words are handed out one at a time so the order is random throughout, not just between prefixes
+        let chunks = match shuffle_rng(config, 0) {
+            Some(mut rng) => {
+                let mut chunks: Vec<Range<usize>> = (0..dictionary.len()).map(|index| index..index + 1).collect();
+                rng.shuffle(&mut chunks);
+                chunks
+            },
+            None => chunk_by_prefix(dictionary, config.chunk_prefix_length)
+        };
+
+        // dropping the sender at the end closes the channel which stops the workers
+        pool_threads.threads.push(thread::spawn( move || {
+
+            for chunk in chunks {
+
+                if stop_flag.load(AtomicOrdering::Relaxed) {
+                    break;
+                }
+
+                // every worker has gone so there is no one left to send to
+                if chunk_sender.send(chunk).is_err() {
+                    break;
+                }
+            }
+        }));
+
+        Ok(SolutionGeneratorThreadPool { solution_receiver: SolutionReceiver { event_receiver: solution_receiver }, threads: pool_threads })
     }
+}
+
+struct SolutionGeneratorWorker {
+}
+
+impl SolutionGeneratorWorker {
 
-    fn does_column_fit(&self, partial_column: &String, column: &String, puzzle: &Vec<String>) -> bool {
-        !self.prefix_map_arc.contains_key(partial_column)
-        || !self.prefix_map_arc.get(partial_column).unwrap().contains(column) 
-        || puzzle.contains(column)
+    fn spawn(solution_generator: SolutionGenerator, chunk_receiver: Receiver<Range<usize>>, worker_file: Option<WorkerFile>) -> JoinHandle<()> {
+
+        thread::spawn(move || {
+
+            // whatever is left in the batch is flushed when it drops at the end of the thread
+            let mut batch = solution_generator.new_batch();
+            batch.worker_file = worker_file;
+
+            for chunk in chunk_receiver.iter() {
+                solution_generator.run_chunk(chunk, &mut batch);
+            }
+        })
     }
-    
+
 }
 
-/* if the column is alphabetically before the row than the column has already
- been tried at that row previously, so this would result in a transposed solution */
-fn would_be_transposed_row(row: &String, column: &String) -> bool {
-    column.cmp(row) == Ordering::Less
+/* Work stealing alternative to the thread pool, seed words are a parallel iterator
+  and every branch off the second row is spawned as its own task so one prolific seed
+  word gets spread over all of the threads instead of pinning one */
+pub struct SolutionGeneratorRayonPool {
+    pub solution_receiver: SolutionReceiver,
+    threads: PoolThreads
 }
 
-#[test]
-fn is_transposed_row(){
+impl GeneratorPool for SolutionGeneratorRayonPool {
 
-    let row = "bases".to_string();
-    let column = "based".to_string();
+    fn solution_receiver(&self) -> &SolutionReceiver {
+        &self.solution_receiver
+    }
 
-    assert!(would_be_transposed_row(&row, &column))
+    fn join(&mut self) -> RunStats {
+        self.solution_receiver.drain();
+        self.threads.join()
+    }
 }
 
-#[test]
-fn isnt_transposed_row(){
-    
-    let row = "based".to_string();
-    let column = "bases".to_string();
+impl SolutionGeneratorRayonPool {
 
-    assert!(!would_be_transposed_row(&row, &column))
+    pub fn new(config: &GeneratorConfig, dictionary: &[String], prefix_arena: PrefixArena,
+        stop_flag: Arc<AtomicBool>, skipped_seeds: HashSet<String>) -> Result<SolutionGeneratorRayonPool, SquardleError> {
+
+        let num_threads = config.num_threads;
+        let word_size = checked_word_size(num_threads, dictionary, &prefix_arena)?;
+
+        let pool = match rayon::ThreadPoolBuilder::new().num_threads(num_threads).build() {
+            Ok(pool) => pool,
+            Err(error) => return Err(SquardleError::ThreadPoolBuild(error.to_string()))
+        };
+
+        let search_words = SearchWords::new(dictionary.to_vec(), word_size);
+        let dictionary: &[String] = &search_words.dictionary;
+
+        let (solution_sender, solution_receiver) = crossbeam_channel::bounded::<GeneratorEvent>(config.solution_queue_capacity);
+
+        let mut pool_threads = PoolThreads::new(config, stop_flag.clone(), seeds_to_search(dictionary, &skipped_seeds));
+
+        // every task goes through the one generator so the memo is shared by all of the threads
+        let prefix_classes = prefix_classes(config, &prefix_arena);
+
+        let context = WorkerContext {
+            solution_sender,
+            stop_flag,
+            skipped_seeds: Arc::new(skipped_seeds),
+            stats: pool_threads.stats.clone(),
+            progress: pool_threads.progress.clone()
+        };
+
+        let constrained_fill = constrained_fill(config, dictionary);
+
+        // tasks are stolen rather than handed to a worker so all of them share the one shuffled candidate order
+        let mut seed_indexes: Vec<usize> = (0..dictionary.len()).collect();
+
+        if let Some(mut rng) = shuffle_rng(config, 0) {
+            rng.shuffle(&mut seed_indexes);
+        }
+
+        let solution_generator = SolutionGenerator::new(search_words, Arc::new(prefix_arena), word_size, config.solution_batch_size, config.count_only, context).with_validation(config.validate).with_shuffled_candidates(shuffle_rng(config, 1))
+            .with_first_per_seed(config.first_per_seed).with_constrained_fill(constrained_fill).with_pruning(config.pruning)
+            .with_failure_memo(failure_memo(config, &prefix_classes)).with_constraints(config.constraints(word_size)?);
+
+        // the receiver hangs up once the generator and all of its sender clones are dropped
+        pool_threads.threads.push(thread::spawn(move || {
+
+            pool.install(|| {
+                seed_indexes.par_iter().for_each(|index| solution_generator.run_parallel(solution_generator.dictionary[*index].clone()));
+            });
+        }));
+
+        Ok(SolutionGeneratorRayonPool { solution_receiver: SolutionReceiver { event_receiver: solution_receiver }, threads: pool_threads })
+    }
 }
 
+/* The brute force of the naive engine behind the same interface as the other pools, for checking
+  what they find against. It goes through the seed words in dictionary order on one thread, so
+  the solutions come out in the same order every run and nothing can be spread between threads */
+pub struct SolutionGeneratorNaivePool {
+    pub solution_receiver: SolutionReceiver,
+    threads: PoolThreads
+}
+
+impl GeneratorPool for SolutionGeneratorNaivePool {
+
+    fn solution_receiver(&self) -> &SolutionReceiver {
+        &self.solution_receiver
+    }
 
-/* do not process this word as it starts with chars that have been identified as a dead end or are in the puzzle */
-fn skip_word(word: &String, bad_starts: &Vec<String>, puzzle: &Vec<String>) -> bool {
-    bad_starts.iter().filter(|bad_start| !bad_start.is_empty()).any(|bad_start| word.starts_with(bad_start)) || puzzle.contains(word)
+    fn join(&mut self) -> RunStats {
+        self.solution_receiver.drain();
+        self.threads.join()
+    }
 }
 
+impl SolutionGeneratorNaivePool {
+
+    pub fn new(config: &GeneratorConfig, dictionary: &[String], stop_flag: Arc<AtomicBool>, skipped_seeds: HashSet<String>) -> Result<SolutionGeneratorNaivePool, SquardleError> {
+
+        let Some(word_size) = dictionary.first().map(|word| word.chars().count()) else {
+            return Err(SquardleError::EmptyDictionary)
+        };
+
+        // the brute force only checks a square's words are all different, anything more is left to the other engines
+        if !config.constraints(word_size)?.is_empty() {
+            return Err("The naive engine can not be used with constraints".into());
+        }
+
+        let dictionary = distinct_words(dictionary.to_vec());
+
+        let mut pool_threads = PoolThreads::new(config, stop_flag.clone(), seeds_to_search(&dictionary, &skipped_seeds));
+
+        let (solution_sender, solution_receiver) = crossbeam_channel::bounded::<GeneratorEvent>(config.solution_queue_capacity);
+
+        let mut batch = SolutionBatch::new(solution_sender, config.solution_batch_size, config.count_only, pool_threads.stats.clone(), pool_threads.progress.clone());
+        let first_per_seed = config.first_per_seed;
+
+        // the batch is flushed and the receiver hung up on when the thread is done with it
+        pool_threads.threads.push(thread::spawn(move || {
+
+            for seed in dictionary.iter().filter(|seed| !skipped_seeds.contains(*seed)) {
+
+                if stop_flag.load(AtomicOrdering::Relaxed) {
+                    return;
+                }
+
+                batch.seed_started(seed);
+                let squares = naive::brute_force_squares_from(&dictionary, seed);
+
+                // a seed stopped part way through didn't get every square out
+                if stop_flag.load(AtomicOrdering::Relaxed) {
+                    return;
+                }
+
+                for square in squares {
+
+                    let (last_word, puzzle) = square.split_last().expect("a square has rows");
+                    batch.found(puzzle, last_word);
+
+                    if first_per_seed {
+                        break;
+                    }
+                }
+
+                batch.seed_finished(seed.clone());
+            }
+        }));
+
+        Ok(SolutionGeneratorNaivePool { solution_receiver: SolutionReceiver { event_receiver: solution_receiver }, threads: pool_threads })
+    }
+}
+
+/* buffers solutions so the channel is sent one batch at a time instead of every solution,
+  anything still buffered is sent when the batch is dropped. Also counts what the search
+  did while it was in use, adding it to the pool's stats on drop. When only counting
+  nothing is copied or sent, the solutions of the seed being searched are a plain counter
+  that goes into the per seed tallies when the seed changes */
+struct SolutionBatch {
+    solution_sender: Sender<GeneratorEvent>,
+    solutions: Vec<Solution>,
+    batch_size: usize,
+    count_only: bool,
+    stats: RunStats,
+    stats_total: Arc<Mutex<RunStats>>,
+    progress: Arc<Progress>,
+    // only when stopping at the first solution of each seed, set once the seed being searched has one
+    seed_solved: Option<Arc<AtomicBool>>,
+    // only when counting, the seed whose solutions are being counted and how many so far
+    counted_seed: String,
+    counted_seed_solutions: u64,
+    // only with a memo, set once the search below a grid has turned down a word that fits its columns,
+    // for being used already or making a transpose, so finding nothing there doesn't mean the columns can't be finished
+    memo_tainted: bool,
+    // only when each worker writes its own solutions, where they go instead of the channel
+    worker_file: Option<WorkerFile>
+}
+
+impl SolutionBatch {
+
+    fn new(solution_sender: Sender<GeneratorEvent>, batch_size: usize, count_only: bool, stats_total: Arc<Mutex<RunStats>>,
+        progress: Arc<Progress>) -> SolutionBatch {
+
+        SolutionBatch {
+            solution_sender,
+            solutions: Vec::with_capacity(batch_size),
+            batch_size,
+            count_only,
+            stats: RunStats::default(),
+            stats_total,
+            progress,
+            seed_solved: None,
+            counted_seed: String::new(),
+            counted_seed_solutions: 0,
+            memo_tainted: false,
+            worker_file: None
+        }
+    }
+
+    fn seed_is_solved(&self) -> bool {
+        self.seed_solved.as_ref().is_some_and(|seed_solved| seed_solved.load(AtomicOrdering::Relaxed))
+    }
+
+    /* whether a solution can be reported, only the first of a seed can when stopping at the first.
+      Rayon branches of the same seed share the flag so only one of them wins even if both find one */
+    fn claim_solution(&self) -> bool {
+        self.seed_solved.as_ref().is_none_or(|seed_solved| !seed_solved.swap(true, AtomicOrdering::Relaxed))
+    }
+
+    /* the solution is the puzzle with the last word added, only copied out when it is going to be sent */
+    fn found(&mut self, puzzle: &[String], last_word: &str) {
+
+        self.progress.solution_found();
+
+        if !self.count_only {
+            self.stats.record_solution(puzzle, last_word);
+
+            let mut solution = puzzle.to_vec();
+            solution.push(last_word.to_string());
+            self.push(Solution::new(solution));
+            return;
+        }
+
+        self.stats.record_letters(puzzle, last_word);
+        self.stats.solutions_found += 1;
+
+        // a batch only moves onto the next seed once it is done with the last so this rarely copies
+        if self.counted_seed != puzzle[0] {
+            self.add_seed_count();
+            self.counted_seed.clone_from(&puzzle[0]);
+        }
+
+        self.counted_seed_solutions += 1;
+    }
+
+    /* the counted seed's solutions go into the tallies, added to in case a rayon branch counted some of them too */
+    fn add_seed_count(&mut self) {
+
+        if self.counted_seed_solutions == 0 {
+            return;
+        }
+
+        *self.stats.solutions_per_seed.entry(mem::take(&mut self.counted_seed)).or_insert(0) += self.counted_seed_solutions;
+        self.counted_seed_solutions = 0;
+    }
+
+    fn push(&mut self, solution: Solution) {
+
+        self.solutions.push(solution);
+        self.stats.solutions_found += 1;
+
+        if self.solutions.len() >= self.batch_size {
+            self.flush();
+        }
+    }
+
+    fn flush(&mut self) {
+
+        if self.solutions.is_empty() {
+            return;
+        }
+
+        let solutions = mem::replace(&mut self.solutions, Vec::with_capacity(self.batch_size));
+
+        let Some(worker_file) = self.worker_file.as_mut() else {
+            // a failed send means the receiver has hung up and nobody wants the solutions anymore
+            let _ = self.solution_sender.send(GeneratorEvent::Solutions(solutions));
+            return;
+        };
+
+        // the solutions found after a failed write are only counted, the run ends with the error
+        if let Err(err) = worker_file.write_solutions(&solutions) {
+            self.stats.errors.push(GeneratorError::WorkerOutputFailed(err.to_string()));
+            self.worker_file = None;
+            self.count_only = true;
+        }
+    }
+
+    /* only sent once the seed is going to be searched, a skipped seed is never started */
+    fn seed_started(&mut self, seed: &str) {
+        let _ = self.solution_sender.send(GeneratorEvent::SeedStarted(seed.to_string()));
+    }
+
+    /* flushes first so the seed's solutions are always received before it is marked as finished */
+    fn seed_finished(&mut self, seed: String) {
+
+        self.add_seed_count();
+        self.stats.seeds_processed += 1;
+        self.progress.seed_done();
+        self.flush();
+        let _ = self.solution_sender.send(GeneratorEvent::SeedFinished(seed));
+    }
+
+    fn seed_failed(&mut self, seed: String, payload: Box<dyn Any + Send>) {
+        self.progress.seed_done();
+        self.stats.errors.push(GeneratorError::SeedFailed { seed, message: panic_message(&payload) });
+    }
+}
+
+impl Drop for SolutionBatch {
+    fn drop(&mut self) {
+
+        self.flush();
+        self.add_seed_count();
+
+        if let Some(Err(err)) = self.worker_file.as_mut().map(WorkerFile::finish) {
+            self.stats.errors.push(GeneratorError::WorkerOutputFailed(err.to_string()));
+        }
+
+        if let Ok(mut stats_total) = self.stats_total.lock() {
+            stats_total.merge(&self.stats);
+        }
+    }
+}
+
+/* one row of the depth first search, the columns as the rows above leave them and how far through the candidates it has got */
+struct SearchFrame {
+    row_index: usize,
+    potential_columns: Vec<String>,
+    used_positions: Vec<usize>,
+    next_candidate: usize,
+    // the letters each column can take next, with the screen of the block of candidates the search is in
+    allowed_letters: AllowedLetters,
+    screened_block: usize,
+    screened: u16,
+    // only with a memo and a grid it has a key for
+    memo: Option<MemoFrame>
+}
+
+/* what is needed once a row is left to tell whether anything below it worked out */
+struct MemoFrame {
+    key: Vec<u32>,
+    solutions_before: u64,
+    tainted_above: bool
+}
+
+/* The dictionary a pool searches and what is worked out from it for screening candidates,
+  built once by the pool and shared by all of its workers rather than each building its own */
+#[derive(Clone)]
+struct SearchWords {
+    // sorted with each word once, see distinct_words
+    dictionary: Arc<[String]>,
+    // the dictionary's letters for screening candidates a block at a time
+    candidate_letters: Arc<CandidateLetters>
+}
+
+impl SearchWords {
+
+    fn new(dictionary: Vec<String>, word_size: usize) -> SearchWords {
+
+        let dictionary: Arc<[String]> = distinct_words(dictionary).into();
+        let candidate_letters = Arc::new(CandidateLetters::new(&dictionary, word_size));
+
+        SearchWords { dictionary, candidate_letters }
+    }
+}
+
+/* What a generator shares with the pool it works for, where its solutions go, when to stop,
+  which seeds to leave alone and the totals every worker adds to */
+struct WorkerContext {
+    solution_sender: Sender<GeneratorEvent>,
+    stop_flag: Arc<AtomicBool>,
+    skipped_seeds: Arc<HashSet<String>>,
+    stats: Arc<Mutex<RunStats>>,
+    progress: Arc<Progress>
+}
+
+impl WorkerContext {
+
+    /* a generator working on its own, solving a partial grid say, with nothing skipped and nobody to stop it */
+    fn standalone(solution_sender: Sender<GeneratorEvent>) -> WorkerContext {
+
+        WorkerContext {
+            solution_sender,
+            stop_flag: Arc::new(AtomicBool::new(false)),
+            skipped_seeds: Arc::new(HashSet::new()),
+            stats: Arc::new(Mutex::new(RunStats::default())),
+            progress: Arc::new(Progress::new(0))
+        }
+    }
+}
+
+struct SolutionGenerator {
+    // sorted with each word once, see distinct_words
+    dictionary: Arc<[String]>,
+    last_row_index: usize,
+    prefix_arena: Arc<PrefixArena>,
+    solution_sender: Sender<GeneratorEvent>,
+    word_size: usize,
+    solution_batch_size: usize,
+    count_only: bool,
+    stop_flag: Arc<AtomicBool>,
+    // seed words that are not searched, already finished in a checkpoint or belonging to another shard
+    skipped_seeds: Arc<HashSet<String>>,
+    stats: Arc<Mutex<RunStats>>,
+    progress: Arc<Progress>,
+    // what squares have to satisfy besides being made of words, a partial grid's letters say
+    constraints: Vec<Arc<dyn Constraint>>,
+    // a square and its transpose are both found when a constraint can tell them apart
+    keep_transposes: bool,
+    // the columns come from other words than the rows, so a square's transpose is never one as well
+    separate_columns: bool,
+    // only when solving a partial grid, stops once there are this many solutions
+    solution_limit: Option<u64>,
+    validate: bool,
+    // only when shuffling, the dictionary in the order words are tried for the rows after the first
+    shuffled_candidates: Option<Vec<String>>,
+    // only when shuffling, where each dictionary word is in the shuffled candidates
+    shuffled_positions: Vec<usize>,
+    // moves onto the next seed word as soon as the current one has a solution
+    first_per_seed: bool,
+    // only when filling the most constrained slot first rather than row by row
+    constrained_fill: Option<Arc<ConstrainedFill>>,
+    pruning: Pruning,
+    // only with a memo, the partial grids known to go nowhere
+    failure_memo: Option<FailureMemo>,
+    // the candidates' letters for screening them a block at a time, in the order they are tried so a shuffling worker has its own
+    candidate_letters: Arc<CandidateLetters>
+}
+
+impl SolutionGenerator {
+
+    fn new(search_words: SearchWords, prefix_arena: Arc<PrefixArena>, word_size: usize, solution_batch_size: usize, count_only: bool,
+        context: WorkerContext) -> SolutionGenerator {
+
+        let WorkerContext { solution_sender, stop_flag, skipped_seeds, stats, progress } = context;
+        let SearchWords { dictionary, candidate_letters } = search_words;
+        let separate_columns = !dictionary.iter().map(String::as_str).eq(prefix_arena.words().iter());
+
+        SolutionGenerator {
+            dictionary,
+            last_row_index: word_size -1,
+            prefix_arena,
+            solution_sender,
+            word_size,
+            solution_batch_size,
+            count_only,
+            stop_flag,
+            skipped_seeds,
+            stats,
+            progress,
+            constraints: Vec::new(),
+            keep_transposes: separate_columns,
+            separate_columns,
+            solution_limit: None,
+            validate: false,
+            shuffled_candidates: None,
+            shuffled_positions: Vec::new(),
+            first_per_seed: false,
+            constrained_fill: None,
+            pruning: Pruning::BadStarts,
+            failure_memo: None,
+            candidate_letters
+        }
+    }
+
+    fn with_validation(mut self, validate: bool) -> SolutionGenerator {
+        self.validate = validate;
+        self
+    }
+
+    fn with_first_per_seed(mut self, first_per_seed: bool) -> SolutionGenerator {
+        self.first_per_seed = first_per_seed;
+        self
+    }
+
+    fn with_constrained_fill(mut self, constrained_fill: Option<Arc<ConstrainedFill>>) -> SolutionGenerator {
+        self.constrained_fill = constrained_fill;
+        self
+    }
+
+    fn with_pruning(mut self, pruning: Pruning) -> SolutionGenerator {
+        self.pruning = pruning;
+        self
+    }
+
+    fn with_failure_memo(mut self, failure_memo: Option<FailureMemo>) -> SolutionGenerator {
+        self.failure_memo = failure_memo;
+        self
+    }
+
+    fn with_constraints(mut self, constraints: Vec<Arc<dyn Constraint>>) -> SolutionGenerator {
+        self.set_constraints(constraints);
+        self
+    }
+
+    fn set_constraints(&mut self, constraints: Vec<Arc<dyn Constraint>>) {
+        self.keep_transposes = self.separate_columns || constraints.iter().any(|constraint| !constraint.is_symmetric());
+        self.constraints = constraints;
+    }
+
+    /* a fresh flag for each seed word searched when stopping at the first solution of each */
+    fn new_seed_solved(&self) -> Option<Arc<AtomicBool>> {
+        self.first_per_seed.then(|| Arc::new(AtomicBool::new(false)))
+    }
+
+    fn with_shuffled_candidates(mut self, rng: Option<SampleRng>) -> SolutionGenerator {
+
+        // whole blocks of words sharing a first letter are shuffled, each kept sorted, as pruning by bad starts
+        // skips the run of words right after the one that failed, which has to be every word starting the same way
+        if let Some(mut rng) = rng {
+            let mut blocks = chunk_by_prefix(&self.dictionary, SHUFFLED_BLOCK_PREFIX_LENGTH);
+            rng.shuffle(&mut blocks);
+
+            self.shuffled_positions = vec![0; self.dictionary.len()];
+            for (position, index) in blocks.iter().cloned().flatten().enumerate() {
+                self.shuffled_positions[index] = position;
+            }
+
+            let shuffled_candidates: Vec<String> = blocks.into_iter().flat_map(|block| self.dictionary[block].to_vec()).collect();
+            self.candidate_letters = Arc::new(CandidateLetters::new(&shuffled_candidates, self.word_size));
+            self.shuffled_candidates = Some(shuffled_candidates);
+        }
+
+        self
+    }
+
+    /* where a word is in the order candidates are tried, none for a word outside the dictionary */
+    fn candidate_position(&self, word: &String) -> Option<usize> {
+
+        let index = self.dictionary.binary_search(word).ok()?;
+
+        match self.shuffled_candidates {
+            Some(_) => self.shuffled_positions.get(index).copied(),
+            None => Some(index)
+        }
+    }
+
+    /* whether every constraint lets the word go in the row after the puzzle so far */
+    fn allows_word(&self, puzzle: &[String], word: &str) -> bool {
+        self.constraints.iter().all(|constraint| constraint.allows_word(puzzle, word))
+    }
+
+    fn allows_solution(&self, puzzle: &[String], last_word: &str) -> bool {
+
+        if self.constraints.is_empty() {
+            return true;
+        }
+
+        let mut solution = puzzle.to_vec();
+        solution.push(last_word.to_string());
+
+        self.constraints.iter().all(|constraint| constraint.allows_solution(&solution))
+    }
+
+    fn is_stopped(&self) -> bool {
+        self.stop_flag.load(AtomicOrdering::Relaxed)
+    }
+
+    fn new_batch(&self) -> SolutionBatch {
+        SolutionBatch::new(self.solution_sender.clone(), self.solution_batch_size, self.count_only, self.stats.clone(), self.progress.clone())
+    }
+
+    fn run(&self, word: String, batch: &mut SolutionBatch) {
+
+        if self.skipped_seeds.contains(&word) {
+            return;
+        }
+
+        let mut initial_puzzle: Vec<String> = Vec::with_capacity(self.word_size);
+        initial_puzzle.push(word.clone());
+
+        batch.seed_started(&word);
+        batch.seed_solved = self.new_seed_solved();
+
+        // a seed the constraints turn down has nothing to search but still finishes like the others
+        let allowed = self.allows_word(&[], &word);
+
+        // a panic in one seed's search is reported and the worker moves onto the next seed
+        let result = panic::catch_unwind(AssertUnwindSafe(|| match &self.constrained_fill {
+            _ if !allowed => (),
+            Some(constrained_fill) => self.fill_constrained(constrained_fill, &word, batch),
+            None => self.find_solutions(&mut initial_puzzle, 1, batch)
+        }));
+
+        match result {
+            Err(payload) => batch.seed_failed(word, payload),
+            // a stopped search didn't get through every branch of the seed
+            Ok(_) if !self.is_stopped() => {
+                debug!("seed word [{}] finished", word);
+                batch.seed_finished(word)
+            },
+            Ok(_) => ()
+        }
+    }
+
+    /* runs every seed word in the chunk, the chunk being a range of indexes into the sorted dictionary */
+    fn run_chunk(&self, chunk: Range<usize>, batch: &mut SolutionBatch) {
+
+        for index in chunk {
+
+            if self.is_stopped() {
+                return;
+            }
+
+            let word = self.dictionary[index].clone();
+            self.run(word, batch);
+        }
+    }
+
+    /* same as run but each branch of the second row becomes a rayon task that can be stolen */
+    fn run_parallel(&self, word: String) {
+
+        if self.is_stopped() || self.skipped_seeds.contains(&word) {
+            return;
+        }
+
+        // a constrained fill doesn't go a row at a time so there are no second row branches to spread out
+        if self.constrained_fill.is_some() {
+            self.run(word, &mut self.new_batch());
+            return;
+        }
+
+        let mut initial_puzzle: Vec<String> = Vec::with_capacity(self.word_size);
+        initial_puzzle.push(word.clone());
+
+        let mut batch = self.new_batch();
+        batch.seed_started(&word);
+        batch.seed_solved = self.new_seed_solved();
+
+        // the scope passes on a panic from any of its spawned branches once they have all finished
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            rayon::scope(|scope| {
+                self.for_each_fitting_word(&mut initial_puzzle, 1, &mut batch, |puzzle, batch| {
+
+                    let mut branch = puzzle.clone();
+                    let seed_solved = batch.seed_solved.clone();
+
+                    scope.spawn(move |_| {
+                        let mut branch_batch = self.new_batch();
+                        branch_batch.seed_solved = seed_solved;
+                        self.find_solutions(&mut branch, 2, &mut branch_batch)
+                    });
+                });
+            });
+        }));
+
+        // every spawned branch has flushed its own batch by the time the scope ends
+        match result {
+            Err(payload) => batch.seed_failed(word, payload),
+            Ok(_) if !self.is_stopped() => {
+                debug!("seed word [{}] finished", word);
+                batch.seed_finished(word)
+            },
+            Ok(_) => ()
+        }
+    }
+
+    /* the seed word's search filling the most constrained slot first, solutions go through the same checks as the row search's */
+    fn fill_constrained(&self, constrained_fill: &ConstrainedFill, word: &str, batch: &mut SolutionBatch) {
+
+        let Ok(seed_index) = self.dictionary.binary_search_by(|probe| probe.as_str().cmp(word)) else {
+            return;
+        };
+
+        let counts = constrained_fill.search(seed_index, |rows| {
+
+            let (last_word, puzzle) = rows.split_last().expect("a solution has rows");
+            let invalid_reason = if self.validate { self.invalid_solution_reason(puzzle, last_word) } else { None };
+
+            // the constrained fill doesn't go a row at a time so only whole squares are checked against the constraints
+            match invalid_reason {
+                Some(reason) => batch.stats.errors.push(GeneratorError::InvalidSolution { solution: rows.to_vec(), reason }),
+                None if !self.allows_solution(puzzle, last_word) => (),
+                None if batch.claim_solution() => batch.found(puzzle, last_word),
+                None => ()
+            }
+
+            if self.solution_limit.is_some_and(|limit| batch.stats.solutions_found >= limit) {
+                self.stop_flag.store(true, AtomicOrdering::Relaxed);
+            }
+
+            !batch.seed_is_solved()
+        }, || self.is_stopped());
+
+        batch.stats.nodes_expanded += counts.nodes_expanded;
+        batch.stats.backtracks += counts.backtracks;
+    }
+
+    /* Depth first over an explicit stack of rows rather than recursing, so how deep a search goes is
+      only limited by memory and the whole of it can be unwound from the one loop. A row is entered
+      when a word fits the one above and left once every candidate has been tried */
+    fn find_solutions(&self, puzzle: &mut Vec<String>, row_index: usize, batch: &mut SolutionBatch) {
+
+        let mut stack: Vec<SearchFrame> = Vec::with_capacity(self.word_size);
+        stack.extend(self.enter_row(puzzle, row_index, batch));
+
+        while let Some(frame) = stack.last_mut() {
+
+            // unwinds the whole search once a stop has been requested, or just the seed's once it has its solution
+            let unwinding = self.is_stopped() || batch.seed_is_solved();
+
+            if !unwinding && self.push_next_fitting_word(frame, puzzle, batch) {
+
+                // a word fitting the last row was a solution, there is nothing below it to search
+                let below = (frame.row_index < self.last_row_index).then(|| self.enter_row(puzzle, frame.row_index + 1, batch)).flatten();
+
+                match below {
+                    Some(below) => stack.push(below),
+                    None => {
+                        puzzle.pop();
+                        batch.stats.backtracks += 1;
+                    }
+                }
+
+                continue;
+            }
+
+            let frame = stack.pop().expect("the loop only runs with a frame");
+            self.leave_row(frame, batch);
+
+            // takes off the word that led down to the row just left
+            if !stack.is_empty() {
+                puzzle.pop();
+                batch.stats.backtracks += 1;
+            }
+        }
+    }
+
+    /* none when the row isn't worth searching, the search is stopping or the memo knows the grid goes nowhere */
+    fn enter_row(&self, puzzle: &[String], row_index: usize, batch: &mut SolutionBatch) -> Option<SearchFrame> {
+
+        if self.is_stopped() || batch.seed_is_solved() {
+            return None;
+        }
+
+        // only starts of two letters or more are in the prefix map
+        let memo_key = self.failure_memo.as_ref()
+            .filter(|_| row_index >= 2)
+            .and_then(|failure_memo| failure_memo.key(&construct_potential_transposed_puzzle(puzzle)));
+
+        let memo = match (memo_key, &self.failure_memo) {
+            (Some(key), Some(failure_memo)) if failure_memo.has_failed(&key) => {
+                batch.stats.memo_hits += 1;
+                return None;
+            },
+            (Some(key), _) => Some(MemoFrame {
+                key,
+                solutions_before: batch.stats.solutions_found,
+                tainted_above: mem::replace(&mut batch.memo_tainted, false)
+            }),
+            (None, _) => None
+        };
+
+        Some(self.open_frame(puzzle, row_index, memo, batch))
+    }
+
+    fn leave_row(&self, frame: SearchFrame, batch: &mut SolutionBatch) {
+
+        let (Some(memo), Some(failure_memo)) = (frame.memo, &self.failure_memo) else {
+            return;
+        };
+
+        // a stopped search or a solved seed didn't get through every way of finishing the grid
+        if !batch.memo_tainted && batch.stats.solutions_found == memo.solutions_before && !self.is_stopped() && !batch.seed_is_solved() {
+            failure_memo.record_failure(memo.key);
+        }
+
+        batch.memo_tainted |= memo.tainted_above;
+    }
+
+    /* whether a used word turned down at the row index taints the memo, it does if it fits the columns as far as their starts go */
+    fn fits_column_starts(&self, word: &str, potential_columns: &[String], row_index: usize) -> bool {
+
+        if row_index < self.last_row_index {
+            return self.word_fits(word, potential_columns).0;
+        }
+
+        potential_columns.iter().zip(word.chars()).all(|(partial_column, letter)| self.last_column_fits(partial_column, letter))
+    }
+
+    /* the last letter of a column makes a word, ignoring whether it is used already or a transpose */
+    fn last_column_fits(&self, partial_column: &str, letter: char) -> bool {
+        self.prefix_arena.get(partial_column).is_some_and(|words| words.iter().any(|word| word.ends_with(letter)))
+    }
+
+    /* the reasons the last row turns down a column besides there being no such word, without building the column */
+    fn column_is_used_or_transposed(&self, puzzle: &[String], word: &str, potential_columns: &[String], column_index: usize) -> bool {
+
+        let partial_column = potential_columns[column_index].as_str();
+        let letter = word.as_bytes()[column_index] as char;
+
+        let is_column = |word: &String| word.starts_with(partial_column) && word.ends_with(letter) && word.len() == partial_column.len() + letter.len_utf8();
+
+        let transposed = column_index == 0 && !self.keep_transposes
+            && partial_column.chars().chain([letter]).cmp(puzzle[0].chars()) == Ordering::Less;
+
+        transposed || puzzle.iter().any(is_column) || repeats_a_word_of_the_last_row(word, potential_columns, column_index)
+    }
+
+    /* walks every word that fits at the row index, sending any completed solutions and
+       handing the extended puzzle to descend so the caller decides how to search deeper */
+    fn for_each_fitting_word<F>(&self, puzzle: &mut Vec<String>, row_index: usize, batch: &mut SolutionBatch, mut descend: F)
+        where F: FnMut(&mut Vec<String>, &mut SolutionBatch) {
+
+        let mut frame = self.open_frame(puzzle, row_index, None, batch);
+
+        while self.push_next_fitting_word(&mut frame, puzzle, batch) {
+
+            descend(puzzle, batch);
+
+            puzzle.pop();
+            batch.stats.backtracks += 1;
+
+            // only a solution found here or further down can solve the seed so checking after each is enough
+            if batch.seed_is_solved() {
+                return;
+            }
+        }
+    }
+
+    fn open_frame(&self, puzzle: &[String], row_index: usize, memo: Option<MemoFrame>, batch: &mut SolutionBatch) -> SearchFrame {
+
+        let potential_columns = construct_potential_transposed_puzzle(puzzle);
+        batch.stats.nodes_expanded += 1;
+
+        // the rows so far are always skipped, checked once here rather than for every word skipped
+        if self.failure_memo.is_some() && puzzle.iter().any(|used| self.fits_column_starts(used, &potential_columns, row_index)) {
+            batch.memo_tainted = true;
+        }
+
+        // the rows so far by where they are in the candidates, so skipping them compares numbers rather than every candidate's letters
+        let used_positions: Vec<usize> = puzzle.iter().filter_map(|row| self.candidate_position(row)).collect();
+
+        // letters the constraints rule out of the whole row are taken out of every column's
+        let excluded_letters = self.constraints.iter().fold(0, |mask, constraint| mask | constraint.excluded_letters(puzzle));
+
+        let mut column_letters: Vec<u32> = potential_columns.iter()
+            .map(|partial_column| self.prefix_arena.next_letters(partial_column) & !excluded_letters)
+            .collect();
+
+        // and a column the constraints fix the letter of only allows that one
+        for (position, letter) in self.constraints.iter().flat_map(|constraint| constraint.required_letters(puzzle)) {
+            if let (Some(letters), Some(index)) = (column_letters.get_mut(position), letter_index(letter)) {
+                *letters &= 1 << index;
+            }
+        }
+
+        let allowed_letters = AllowedLetters::new(column_letters);
+
+        SearchFrame { row_index, potential_columns, used_positions, next_candidate: 0, allowed_letters, screened_block: usize::MAX, screened: 0, memo }
+    }
+
+    /* Tries candidates from where the frame got to until one fits the row, which is pushed onto the
+      puzzle for the caller to go down into and take off again. A word completing a solution is sent
+      on the way and still pushed. False once there are no candidates left */
+    fn push_next_fitting_word(&self, frame: &mut SearchFrame, puzzle: &mut Vec<String>, batch: &mut SolutionBatch) -> bool {
+
+        let row_index = frame.row_index;
+        let potential_columns = &frame.potential_columns;
+
+        // candidates are sorted, a shuffled search keeps the words sharing a first letter together and sorted
+        let candidates = self.shuffled_candidates.as_deref().unwrap_or(&self.dictionary);
+
+        // kept in a local while looping and written back to the frame before returning
+        let mut index = frame.next_candidate;
+
+        while index < candidates.len() {
+
+            // words with a letter their column can't have next are screened out a block at a time, so only the rest are checked properly
+            let block = index / screen::BLOCK_SIZE;
+
+            if frame.screened_block != block {
+                frame.screened = self.candidate_letters.screen_block(block, &frame.allowed_letters);
+                frame.screened_block = block;
+            }
+
+            let still_to_check = frame.screened >> (index % screen::BLOCK_SIZE);
+
+            if still_to_check == 0 {
+                index = (block + 1) * screen::BLOCK_SIZE;
+                continue;
+            }
+
+            index += still_to_check.trailing_zeros() as usize;
+
+            let word = &candidates[index];
+            index += 1;
+
+            if frame.used_positions.contains(&(index - 1)) {
+                continue;
+            }
+
+            // what a constraint turns down depends on more than the column starts the memo goes by
+            if !self.allows_word(puzzle, word) {
+                batch.memo_tainted |= self.failure_memo.is_some();
+                continue;
+            }
+    
+            let (fit, last_column_index_checked) = 
+                if row_index == self.last_row_index
+                    {self.last_word_fits(puzzle, word, potential_columns, &frame.used_positions)} 
+                else 
+                    {self.word_fits(word, potential_columns)};
+
+            // a column is looked up at a time until one doesn't fit
+            batch.stats.candidate_checks += 1;
+            batch.stats.prefix_lookups += if fit { self.word_size } else { last_column_index_checked + 1 } as u64;
+    
+            if fit {
+
+                // solution found
+                if last_column_index_checked ==  self.last_row_index {
+
+                    let invalid_reason = if self.validate { self.invalid_solution_reason(puzzle, word) } else { None };
+
+                    match invalid_reason {
+                        Some(reason) => {
+                            let mut solution = puzzle.clone();
+                            solution.push(word.clone());
+                            batch.stats.errors.push(GeneratorError::InvalidSolution { solution, reason });
+                            batch.memo_tainted = true;
+                        },
+                        None if !self.allows_solution(puzzle, word) => batch.memo_tainted |= self.failure_memo.is_some(),
+                        None if batch.claim_solution() => batch.found(puzzle, word),
+                        None => ()
+                    }
+
+                    if self.solution_limit.is_some_and(|limit| batch.stats.solutions_found >= limit) {
+                        self.stop_flag.store(true, AtomicOrdering::Relaxed);
+                    }
+                }
+
+                puzzle.push(word.clone());
+
+                // a row further down with nothing left that fits is a dead end however the rows between are filled
+                if self.pruning == Pruning::ForwardCheck && row_index < self.last_row_index && !self.later_rows_have_words(puzzle, true) {
+
+                    if self.failure_memo.is_some() && self.later_rows_have_words(puzzle, false) {
+                        batch.memo_tainted = true;
+                    }
+
+                    puzzle.pop();
+                    batch.stats.forward_check_prunes += 1;
+                    continue;
+                }
+
+                frame.next_candidate = index;
+                return true;
+    
+            } else {
+
+                // the last row also turns words down at a column for making a used word or a transpose, which is then a bad
+                // start for the words after it too. The columns before that one fit so only it can be the reason
+                if self.failure_memo.is_some() && row_index == self.last_row_index {
+
+                    let partial_column = &potential_columns[last_column_index_checked];
+                    let letter = word.chars().nth(last_column_index_checked).expect("the column checked is in the word");
+
+                    if self.column_is_used_or_transposed(puzzle, word, potential_columns, last_column_index_checked) && self.last_column_fits(partial_column, letter) {
+                        batch.memo_tainted = true;
+                    }
+                }
+
+                // if it failed on the last column index no need to record it as words are unique
+                if last_column_index_checked == self.last_row_index || self.pruning != Pruning::BadStarts {
+                    continue;
+                }
+
+                // a last row making itself as a column says nothing about the other words starting the same way
+                if row_index == self.last_row_index && column_is_the_row(word, potential_columns, last_column_index_checked) {
+                    continue;
+                }
+
+                let bad_start: String = word.chars().take(last_column_index_checked+1).collect();
+
+                trace!("pruning words starting [{}] after {:?}, column {} has no words", bad_start, puzzle, last_column_index_checked);
+
+                // Every word with the bad start comes straight after this one so the whole run is skipped at once.
+                // It also means a bad start can never match again once past it, so none are worth keeping
+                let skipped = candidates[index..].partition_point(|candidate| candidate.starts_with(bad_start.as_str()));
+                batch.stats.bad_start_prunes += 1;
+                batch.stats.bad_start_skips += skipped as u64;
+                index += skipped;
+            }
+        }
+
+        frame.next_candidate = index;
+        false
+    }
+    
+    /* Checks a solution from scratch rather than trusting the pruning that found it, every row
+      and column has to be a dictionary word, used once, and the solution can not be the
+      transpose of one the search would find from another seed */
+    fn invalid_solution_reason(&self, puzzle: &[String], last_word: &str) -> Option<String> {
+
+        let mut solution = puzzle.to_vec();
+        solution.push(last_word.to_string());
+
+        if solution.len() != self.word_size || solution.iter().any(|row| row.chars().count() != self.word_size) {
+            return Some("grid is not square".to_string());
+        }
+
+        let columns = construct_potential_transposed_puzzle(&solution);
+        let mut seen_words: HashSet<&String> = HashSet::with_capacity(self.word_size * 2);
+
+        // the columns' words are the prefix map's, the same as the rows' unless they come from another dictionary
+        let is_column_word = |word: &String| self.prefix_arena.get(word).is_some_and(|words| words.contains(word));
+        let column_dictionary = if self.separate_columns { "column dictionary" } else { "dictionary" };
+
+        for (word, is_row) in solution.iter().map(|row| (row, true)).chain(columns.iter().map(|column| (column, false))) {
+
+            if is_row && self.dictionary.binary_search(word).is_err() {
+                return Some(format!("[{}] is not in the dictionary", word));
+            }
+
+            if !is_row && !is_column_word(word) {
+                return Some(format!("[{}] is not in the {}", word, column_dictionary));
+            }
+
+            if !seen_words.insert(word) {
+                return Some(format!("[{}] is used more than once", word));
+            }
+        }
+
+        // a constraint telling a solution and its transpose apart keeps both
+        if !self.keep_transposes && would_be_transposed_row(&solution[0], &columns[0]) {
+            return Some("transpose of a solution found from another seed".to_string());
+        }
+
+        None
+    }
+
+    /* Forward checking, for each row below the puzzle the letters each column can still have there
+      are the ones at that row in the words starting the column so far. Every one of those rows
+      needs an unused word with only allowed letters, a row with none means backing out now
+      rather than once the search gets down to it, or words that are used already too for the
+      memo. Only called with two rows or more as the prefix
+      map has no single letter starts */
+    fn later_rows_have_words(&self, puzzle: &[String], unused_only: bool) -> bool {
+
+        let filled_rows = puzzle.len();
+        let columns = construct_potential_transposed_puzzle(puzzle);
+
+        // by row still to fill then column, a bit for each letter allowed there
+        let mut allowed_letters = vec![vec![0u32; self.word_size]; self.word_size - filled_rows];
+
+        for (column_index, column) in columns.iter().enumerate() {
+
+            let Some(column_words) = self.prefix_arena.get(column) else {
+                return false;
+            };
+
+            for column_word in column_words.iter() {
+                for (letter, allowed) in column_word.chars().skip(filled_rows).zip(allowed_letters.iter_mut()) {
+                    allowed[column_index] |= letter_bit(letter);
+                }
+            }
+        }
+
+        // the rows so far by where they are in the dictionary, like the candidates skip them, so a used word is told by its index rather than its letters
+        let used_indices: Vec<usize> = match unused_only {
+            true => puzzle.iter().filter_map(|row| self.dictionary.binary_search(row).ok()).collect(),
+            false => Vec::new()
+        };
+
+        allowed_letters.iter().all(|allowed| self.dictionary.iter().enumerate().any(|(index, word)| {
+            !used_indices.contains(&index) && word.chars().zip(allowed).all(|(letter, allowed)| allowed & letter_bit(letter) != 0)
+        }))
+    }
+
+    fn word_fits(&self, word: &str, potential_columns: &[String]) -> (bool, usize) {
+    
+        let size = word.len();
+        if potential_columns.len() != size {
+            panic!("Potential coulmns [{}] and word [{}] have different lengths", potential_columns.len(), size);
+        }
+    
+        for i in 0..size {
+    
+            let start_string = potential_columns.get(i).unwrap();
+    
+            let new_potential_column = format!("{}{}", start_string, word.chars().nth(i).unwrap());
+    
+            if !self.prefix_arena.contains_key(&new_potential_column) {
+                return (false, i)
+            }
+        }
+    
+        (true, size)
+    }
+    
+    fn last_word_fits(&self, puzzle: &[String], word: &str, potential_columns: &[String], used_positions: &[usize]) -> (bool, usize) {
+    
+        for i in 0..word.len() {
+    
+            let start_string = potential_columns.get(i).unwrap();
+    
+            let new_potential_column = format!("{}{}", start_string, word.chars().nth(i).unwrap());
+    
+            // a partial grid's transpose doesn't have the same fixed letters so is a different solution
+            if i == 0 && !self.keep_transposes && would_be_transposed_row(&puzzle[0], &new_potential_column) {
+                return (false, i);
+            }
+    
+            if self.does_column_fit(start_string, &new_potential_column, used_positions) || repeats_a_word_of_the_last_row(word, potential_columns, i) {
+
+                return (false, i)
+            }
+        }
+    
+        (true, word.len() -1)
+    }
+
+    /* the steps of every candidate for the row after the puzzle, see GridSolver::explain */
+    fn explain_row(&self, puzzle: &mut Vec<String>) -> Vec<Step> {
+
+        let row = puzzle.len();
+        let potential_columns = construct_potential_transposed_puzzle(puzzle);
+        let column_word = |word: &str, column: usize| format!("{}{}", potential_columns[column], word.chars().nth(column).expect("the column is in the word"));
+        let used_positions: Vec<usize> = puzzle.iter().filter_map(|row| self.candidate_position(row)).collect();
+
+        let mut steps: Vec<Step> = Vec::new();
+        let mut index = 0;
+
+        while index < self.dictionary.len() {
+
+            let word = &self.dictionary[index];
+            index += 1;
+
+            if puzzle.contains(word) {
+                steps.push(Step::Used { row, word: word.clone() });
+                continue;
+            }
+
+            if !self.allows_word(puzzle, word) {
+                steps.push(Step::Constraint { row, word: word.clone() });
+                continue;
+            }
+
+            if row == self.last_row_index {
+
+                let (fit, column) = self.last_word_fits(puzzle, word, &potential_columns, &used_positions);
+
+                steps.push(match fit {
+                    true if self.allows_solution(puzzle, word) => Step::Solution { row, word: word.clone() },
+                    true => Step::Constraint { row, word: word.clone() },
+                    false => {
+                        let column_word = column_word(word, column);
+
+                        if column == 0 && !self.keep_transposes && would_be_transposed_row(&puzzle[0], &column_word) {
+                            Step::Transpose { row, word: word.clone() }
+                        } else if puzzle.contains(&column_word) || repeats_a_word_of_the_last_row(word, &potential_columns, column) {
+                            Step::ColumnUsed { row, word: word.clone(), column, column_word }
+                        } else {
+                            Step::NotAWord { row, word: word.clone(), column, column_word }
+                        }
+                    }
+                });
+
+                continue;
+            }
+
+            let (fit, column) = self.word_fits(word, &potential_columns);
+
+            if !fit {
+
+                // the same run of words the search skips
+                let bad_start: String = word.chars().take(column + 1).collect();
+                let skipped = self.dictionary[index..].partition_point(|candidate| candidate.starts_with(bad_start.as_str()));
+                index += skipped;
+
+                steps.push(Step::BadStart { row, word: word.clone(), column, start: column_word(word, column), skipped });
+                continue;
+            }
+
+            puzzle.push(word.clone());
+            let below = self.explain_row(puzzle);
+            puzzle.pop();
+
+            steps.push(Step::Fits { row, word: word.clone(), solutions: below.iter().map(Step::solutions).sum(), below });
+        }
+
+        steps
+    }
+
+    /* true when the column can't be finished, a column already used as a row is told by its position like the candidates are */
+    fn does_column_fit(&self, partial_column: &str, column: &String, used_positions: &[usize]) -> bool {
+        !self.prefix_arena.contains_key(partial_column)
+        || !self.prefix_arena.get(partial_column).unwrap().contains(column) 
+        || self.candidate_position(column).is_some_and(|position| used_positions.contains(&position))
+    }
+    
+}
+
+/* Whether the column the last row finishes at the index is the last row's own word or the same
+  word as a column before it. The rows above are checked against the columns as they go but
+  these are only words once the last row is in, so a square could have a word twice otherwise */
+fn repeats_a_word_of_the_last_row(word: &str, potential_columns: &[String], column_index: usize) -> bool {
+
+    let letters = word.as_bytes();
+    let (partial_column, letter) = (&potential_columns[column_index], letters[column_index]);
+
+    column_is_the_row(word, potential_columns, column_index)
+        || (0..column_index).any(|earlier| letters[earlier] == letter && potential_columns[earlier] == *partial_column)
+}
+
+/* the column the last row finishes at the index is the row itself, which unlike the other reasons a
+  column is turned down depends on the whole row rather than how it starts */
+fn column_is_the_row(word: &str, potential_columns: &[String], column_index: usize) -> bool {
+
+    let letters = word.as_bytes();
+
+    letters.split_last().is_some_and(|(last, start)| potential_columns[column_index].as_bytes() == start && letters[column_index] == *last)
+}
+
+/* the letters of a partial grid by row, None where the cell is blank */
+pub type FixedLetters = Vec<Vec<Option<char>>>;
+
+/* Reads a partial grid as comma separated rows with . for a blank e.g. cra.e,.....,t..er
+  Rows shorter than the word size, and any missing rows, are blank at the end */
+pub fn parse_partial_grid(grid: &str, word_size: usize) -> Result<FixedLetters, SquardleError> {
+
+    let mut fixed_letters: FixedLetters = Vec::with_capacity(word_size);
+
+    for row in grid.to_lowercase().split(',') {
+
+        if row.chars().count() > word_size {
+            return Err(format!("grid row [{}] is longer than the word size {}", row, word_size).as_str().into());
+        }
+
+        let mut fixed_row: Vec<Option<char>> = row.trim().chars().map(|letter| if letter == '.' { None } else { Some(letter) }).collect();
+        fixed_row.resize(word_size, None);
+        fixed_letters.push(fixed_row);
+    }
+
+    if fixed_letters.len() > word_size {
+        return Err(format!("grid has {} rows but the word size is {}", fixed_letters.len(), word_size).as_str().into());
+    }
+
+    fixed_letters.resize(word_size, vec![None; word_size]);
+
+    Ok(fixed_letters)
+}
+
+/* the mean of the probes is the estimate, give or take a couple of standard errors */
+#[derive(Debug, PartialEq, Serialize)]
+pub struct SolutionCountEstimate {
+    pub probes: usize,
+    // with too few of these the estimate means little, a tree with few solutions needs a lot of probes
+    pub probes_reaching_a_solution: usize,
+    pub solutions: f64,
+    pub standard_error: f64
+}
+
+/* the square with the most different letters a coverage search found */
+#[derive(Debug, PartialEq, Serialize)]
+pub struct LetterCoverage {
+    pub square: Option<Vec<String>>,
+    pub letters: usize,
+    // the search stopped for time before trying or ruling out every grid, so a square with more letters could be out there
+    pub timed_out: bool,
+    pub nodes_expanded: u64
+}
+
+/* where a coverage search has got to */
+struct CoverageSearch {
+    best: Option<Vec<String>>,
+    best_letters: usize,
+    // the search stops at a square with this many letters, as no square could have more or it's enough
+    target: usize,
+    // the different letters in the dictionary, no square can have more
+    available: usize,
+    deadline: Option<Instant>,
+    timed_out: bool
+}
+
+impl CoverageSearch {
+
+    fn is_finished(&self) -> bool {
+        self.timed_out || self.best_letters >= self.target
+    }
+}
+
+/* Completes partial grids on the calling thread, holding onto the dictionary and prefix
+  map so any number of grids can be solved without building them again */
+pub struct GridSolver {
+    generator: SolutionGenerator,
+    solution_receiver: Receiver<GeneratorEvent>,
+    // checked along with the partial grid's letters on every solve
+    constraints: Vec<Arc<dyn Constraint>>
+}
+
+impl GridSolver {
+
+    pub fn new(dictionary: Vec<String>, prefix_arena: PrefixArena) -> Result<GridSolver, SquardleError> {
+
+        let word_size = match dictionary.first() {
+            Some(word) => word.chars().count(),
+            None => return Err(SquardleError::EmptyDictionary)
+        };
+
+        let (solution_sender, solution_receiver) = crossbeam_channel::unbounded::<GeneratorEvent>();
+
+        let search_words = SearchWords::new(dictionary, word_size);
+
+        let generator = SolutionGenerator::new(search_words, Arc::new(prefix_arena), word_size, DEFAULT_SOLVE_BATCH_SIZE, false,
+            WorkerContext::standalone(solution_sender));
+
+        Ok(GridSolver { generator, solution_receiver, constraints: Vec::new() })
+    }
+
+    /* a constraint every completion has to satisfy as well as fitting the grid */
+    pub fn with_constraint(mut self, constraint: Arc<dyn Constraint>) -> GridSolver {
+        self.constraints.push(constraint);
+        self
+    }
+
+    pub fn word_size(&self) -> usize {
+        self.generator.word_size
+    }
+
+    /* Setting it from another thread stops the solve going on, a time limit say. The search checks it
+      as it goes so it stops part way through a seed, not only between them. The next solve clears it */
+    pub fn stop_flag(&self) -> Arc<AtomicBool> {
+        Arc::clone(&self.generator.stop_flag)
+    }
+
+    /* Finds the completions of a partial grid, every one or up to the limit, searching from each
+      seed word that fits the first row. Returns the solutions in dictionary order and the stats of the search */
+    pub fn solve(&mut self, fixed_letters: FixedLetters, solution_limit: Option<usize>) -> Result<(Vec<Solution>, RunStats), SquardleError> {
+
+        let mut solutions: Vec<Solution> = Vec::new();
+
+        let stats = self.solve_each(fixed_letters, solution_limit, |found| {
+            solutions.extend(found);
+            true
+        })?;
+
+        Ok((solutions, stats))
+    }
+
+    /* Same as solve but hands over the solutions of each seed word as soon as it has been searched,
+      in the same order solve returns them. The search stops early when on_solutions returns false */
+    pub fn solve_each<F>(&mut self, fixed_letters: FixedLetters, solution_limit: Option<usize>, mut on_solutions: F) -> Result<RunStats, SquardleError>
+        where F: FnMut(Vec<Solution>) -> bool {
+
+        let pattern: Arc<dyn Constraint> = Arc::new(Pattern { fixed_letters });
+        self.generator.set_constraints([pattern].into_iter().chain(self.constraints.iter().cloned()).collect());
+        self.generator.solution_limit = solution_limit.map(|limit| limit as u64);
+        self.generator.stop_flag.store(false, AtomicOrdering::Relaxed);
+        self.generator.stats = Arc::new(Mutex::new(RunStats::default()));
+
+        let seeds: Vec<String> = self.generator.dictionary.iter().filter(|word| self.generator.allows_word(&[], word)).cloned().collect();
+
+        let mut batch = self.generator.new_batch();
+
+        for seed in seeds {
+
+            if self.generator.is_stopped() {
+                break;
+            }
+
+            self.generator.run(seed, &mut batch);
+            batch.flush();
+
+            let found: Vec<Solution> = self.drain_solutions().collect();
+
+            if !found.is_empty() && !on_solutions(found) {
+                self.generator.stop_flag.store(true, AtomicOrdering::Relaxed);
+            }
+        }
+
+        // the batch adds its stats in when it drops, everything it found has been flushed already
+        drop(batch);
+
+        let stats = self.generator.stats.lock().map(|mut stats| mem::take(&mut *stats)).unwrap_or_default();
+
+        if let Some(err) = stats.errors.first() {
+            return Err(err.clone().into());
+        }
+
+        Ok(stats)
+    }
+
+    /* Goes through the search from one seed word the way generate does, pruning bad starts, but
+      keeps every candidate tried at each row and what became of it rather than only the squares
+      found, to see why a square wasn't. The candidates aren't screened a block at a time first,
+      the ones screening would have passed over show up as the bad starts they are */
+    pub fn explain(&mut self, seed: &str) -> Result<Explanation, SquardleError> {
+
+        if seed.chars().count() != self.generator.word_size {
+            return Err(SquardleError::IncorrectWordSize { word: seed.to_string(), expected: self.generator.word_size, found: seed.chars().count() });
+        }
+
+        if self.generator.dictionary.binary_search_by(|word| word.as_str().cmp(seed)).is_err() {
+            return Err(format!("seed word [{seed}] is not in the dictionary").as_str().into());
+        }
+
+        self.generator.set_constraints(self.constraints.clone());
+
+        let seed_allowed = self.generator.allows_word(&[], seed);
+        let mut puzzle = vec![seed.to_string()];
+
+        let steps = match seed_allowed {
+            true => self.generator.explain_row(&mut puzzle),
+            false => Vec::new()
+        };
+
+        Ok(Explanation { seed: seed.to_string(), seed_allowed, solutions: steps.iter().map(Step::solutions).sum(), steps })
+    }
+
+    /* Beam search for the squares with the least penalty, for when there are too many squares to
+      find them all and rank them. Grids grow a row at a time and only the width of them with the
+      least penalty so far are kept to grow the next, ties going to the earliest in dictionary order.
+      That can miss a better square whose first rows looked worse, so it's a best effort. Returns
+      the squares it finished, least penalty first */
+    pub fn beam_search<P>(&mut self, width: usize, penalty: P) -> Result<(Vec<Solution>, RunStats), SquardleError>
+        where P: Fn(&[String]) -> f64 {
+
+        self.generator.set_constraints(self.constraints.clone());
+        self.generator.solution_limit = None;
+        self.generator.stop_flag.store(false, AtomicOrdering::Relaxed);
+        self.generator.stats = Arc::new(Mutex::new(RunStats::default()));
+
+        // a stable sort so grids with the same penalty stay in the order they were found
+        let keep_best = |grids: Vec<Vec<String>>| -> Vec<Vec<String>> {
+            let mut scored: Vec<(f64, Vec<String>)> = grids.into_iter().map(|grid| (penalty(&grid), grid)).collect();
+            scored.sort_by(|(penalty, _), (other_penalty, _)| penalty.total_cmp(other_penalty));
+            scored.into_iter().take(width).map(|(_, grid)| grid).collect()
+        };
+
+        let mut beam = keep_best(self.generator.dictionary.iter()
+            .filter(|word| self.generator.allows_word(&[], word))
+            .map(|word| vec![word.clone()])
+            .collect());
+
+        let mut batch = self.generator.new_batch();
+
+        for row_index in 1..self.generator.word_size {
+
+            let mut grown: Vec<Vec<String>> = Vec::new();
+
+            // the last row's words are only kept when they finish a square, which is sent rather than grown
+            for mut puzzle in beam {
+                self.generator.for_each_fitting_word(&mut puzzle, row_index, &mut batch, |puzzle, _| {
+                    if row_index < self.generator.last_row_index {
+                        grown.push(puzzle.clone());
+                    }
+                });
+            }
+
+            if row_index == self.generator.last_row_index {
+                batch.flush();
+                grown = self.drain_solutions().map(Solution::into_rows).collect();
+            }
+
+            beam = keep_best(grown);
+        }
+
+        drop(batch);
+
+        let stats = self.generator.stats.lock().map(|mut stats| mem::take(&mut *stats)).unwrap_or_default();
+
+        if let Some(err) = stats.errors.first() {
+            return Err(err.clone().into());
+        }
+
+        Ok((beam.into_iter().map(Solution::new).collect(), stats))
+    }
+
+    /* Searches for the square with the most different letters, stopping at the first with the
+      target if there is one, or when the deadline passes with the best found by then. The letters
+      of the rows so far are kept as a bitmask while going down, and a grid is given up on once
+      even every row left bringing only new letters couldn't beat the best square so far. Seeds
+      with more different letters go first so a good square turns up early to rule the rest out */
+    pub fn most_letters(&mut self, target: Option<usize>, deadline: Option<Instant>) -> Result<LetterCoverage, SquardleError> {
+
+        self.generator.set_constraints(self.constraints.clone());
+        self.generator.solution_limit = None;
+        self.generator.stats = Arc::new(Mutex::new(RunStats::default()));
+
+        // only counted, the best square is kept here
+        let mut batch = SolutionBatch::new(self.generator.solution_sender.clone(), self.generator.solution_batch_size, true,
+            self.generator.stats.clone(), self.generator.progress.clone());
+
+        let available = self.generator.dictionary.iter().fold(0, |mask, word| mask | letter_mask(word)).count_ones() as usize;
+
+        let mut search = CoverageSearch {
+            best: None,
+            best_letters: 0,
+            target: target.unwrap_or(available).min(available),
+            available,
+            deadline,
+            timed_out: false
+        };
+
+        let mut seeds: Vec<&String> = self.generator.dictionary.iter().filter(|word| self.generator.allows_word(&[], word)).collect();
+        seeds.sort_by_key(|word| std::cmp::Reverse(letter_mask(word).count_ones()));
+
+        for seed in seeds {
+
+            if search.is_finished() {
+                break;
+            }
+
+            let mut puzzle = vec![seed.clone()];
+            self.cover_letters(&mut puzzle, letter_mask(seed), &mut search, &mut batch);
+        }
+
+        drop(batch);
+
+        let stats = self.generator.stats.lock().map(|mut stats| mem::take(&mut *stats)).unwrap_or_default();
+
+        if let Some(err) = stats.errors.first() {
+            return Err(err.clone().into());
+        }
+
+        Ok(LetterCoverage { square: search.best, letters: search.best_letters, timed_out: search.timed_out, nodes_expanded: stats.nodes_expanded })
+    }
+
+    fn cover_letters(&self, puzzle: &mut Vec<String>, letters: u32, search: &mut CoverageSearch, batch: &mut SolutionBatch) {
+
+        let row_index = puzzle.len();
+        let rows_left = self.generator.word_size - row_index;
+
+        let most_possible = (letters.count_ones() as usize + rows_left * self.generator.word_size).min(search.available);
+
+        if search.is_finished() || most_possible <= search.best_letters {
+            return;
+        }
+
+        self.generator.for_each_fitting_word(puzzle, row_index, batch, |puzzle, batch| {
+
+            if search.is_finished() {
+                return;
+            }
+
+            if search.deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                search.timed_out = true;
+                return;
+            }
+
+            let letters = letters | letter_mask(&puzzle[row_index]);
+
+            if row_index < self.generator.last_row_index {
+                self.cover_letters(puzzle, letters, search, batch);
+
+            // the last row's word is pushed even when the constraints turn the square down
+            } else if letters.count_ones() as usize > search.best_letters && self.generator.allows_solution(&puzzle[..row_index], &puzzle[row_index]) {
+                search.best = Some(puzzle.clone());
+                search.best_letters = letters.count_ones() as usize;
+            }
+        });
+    }
+
+    /* Knuth's estimate of the size of a search tree, here the number of solutions a full generate run
+      would find. Each probe walks down one random path choosing uniformly between the words that fit
+      each row, and the product of how many there were to choose from is how many solutions it stands
+      for, 0 if it hits a dead end. That is right on average however lopsided the tree is, but a
+      handful of rare paths can hold most of the solutions so the standard error matters */
+    pub fn estimate_solution_count(&mut self, probes: usize, rng: &mut SampleRng) -> SolutionCountEstimate {
+
+        self.generator.set_constraints(self.constraints.clone());
+        self.generator.solution_limit = None;
+
+        // only counted, nothing is sent
+        let mut batch = SolutionBatch::new(self.generator.solution_sender.clone(), self.generator.solution_batch_size, true,
+            self.generator.stats.clone(), self.generator.progress.clone());
+
+        let mut mean = 0.0;
+        let mut squared_deviations = 0.0;
+        let mut probes_reaching_a_solution = 0;
+
+        for probe in 1..=probes {
+
+            let sample = self.probe_solution_count(rng, &mut batch);
+
+            if sample > 0.0 {
+                probes_reaching_a_solution += 1;
+            }
+
+            // Welford's running mean and variance, the samples can be too big to sum their squares
+            let deviation = sample - mean;
+            mean += deviation / probe as f64;
+            squared_deviations += deviation * (sample - mean);
+        }
+
+        let standard_error = if probes > 1 { (squared_deviations / (probes - 1) as f64 / probes as f64).sqrt() } else { f64::INFINITY };
+
+        SolutionCountEstimate { probes, probes_reaching_a_solution, solutions: mean, standard_error }
+    }
+
+    fn probe_solution_count(&self, rng: &mut SampleRng, batch: &mut SolutionBatch) -> f64 {
+
+        let dictionary = &self.generator.dictionary;
+
+        let mut puzzle: Vec<String> = Vec::with_capacity(self.generator.word_size);
+        puzzle.push(dictionary[rng.below(dictionary.len() as u64) as usize].clone());
+
+        let mut paths = dictionary.len() as f64;
+
+        for row_index in 1..self.generator.word_size {
+
+            let mut fitting_words: Vec<String> = Vec::new();
+            self.generator.for_each_fitting_word(&mut puzzle, row_index, batch, |puzzle, _| fitting_words.push(puzzle[row_index].clone()));
+
+            if fitting_words.is_empty() {
+                return 0.0;
+            }
+
+            paths *= fitting_words.len() as f64;
+
+            // every word that fits the last row completes a solution so there is nothing left to choose
+            if row_index < self.generator.last_row_index {
+                puzzle.push(fitting_words.swap_remove(rng.below(fitting_words.len() as u64) as usize));
+            }
+        }
+
+        paths
+    }
+
+    fn drain_solutions(&self) -> impl Iterator<Item = Solution> + '_ {
+        self.solution_receiver.try_iter().filter_map(|event| match event {
+            GeneratorEvent::Solutions(solutions) => Some(solutions),
+            GeneratorEvent::SeedStarted(_) | GeneratorEvent::SeedFinished(_) => None
+        })
+        .flatten()
+    }
+}
+
+/* the solutions of a run with the pool the config picks, in the order they came, and the stats it ended with */
+#[cfg(test)]
+fn pool_solutions(config: &GeneratorConfig, dictionary: &[String]) -> (Vec<Solution>, RunStats) {
+
+    let prefix_map = PrefixArena::new(dictionary).unwrap();
+
+    let mut pool: Box<dyn GeneratorPool> = match config.backend {
+        crate::generator_config::GeneratorBackend::ThreadPool => Box::new(SolutionGeneratorThreadPool::new(config, dictionary, prefix_map, Arc::new(AtomicBool::new(false)), HashSet::new()).unwrap()),
+        crate::generator_config::GeneratorBackend::Rayon => Box::new(SolutionGeneratorRayonPool::new(config, dictionary, prefix_map, Arc::new(AtomicBool::new(false)), HashSet::new()).unwrap())
+    };
+
+    let solutions: Vec<Solution> = pool.solution_receiver().iter().collect();
+    let stats = pool.join();
+
+    (solutions, stats)
+}
+
+/* the same for the config the args build, sorted as the workers find them in any order */
+#[cfg(test)]
+fn solutions_with(args: &[&str], dictionary: &[String]) -> Vec<Solution> {
+
+    let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+    let (mut solutions, _) = pool_solutions(&GeneratorConfig::build(&args).unwrap(), dictionary);
+
+    solutions.sort();
+    solutions
+}
+
+/* a generator on its own over the dictionary, searching squares the size of its first word */
+#[cfg(test)]
+fn generator_for(dictionary: Vec<String>, prefix_arena: PrefixArena, context: WorkerContext) -> SolutionGenerator {
+
+    let word_size = dictionary[0].chars().count();
+
+    SolutionGenerator::new(SearchWords::new(dictionary, word_size), Arc::new(prefix_arena), word_size, 8, false, context)
+}
+
+#[test]
+fn partial_grid_rows_are_padded() {
+
+    let fixed_letters = parse_partial_grid("A.c,,b", 3).unwrap();
+
+    assert_eq!(vec![vec![Some('a'), None, Some('c')], vec![None, None, None], vec![Some('b'), None, None]], fixed_letters);
+    assert!(parse_partial_grid("abcd", 3).is_err());
+    assert!(parse_partial_grid("a,b,c,d", 3).is_err());
+}
+
+#[test]
+fn complete_grid_with_fixed_letters() {
+
+    let (dictionary, prefix_map) = crate::dictionary::test_dictionary();
+    let mut solver = GridSolver::new(dictionary, prefix_map).unwrap();
+
+    let rows = |rows: &[&str]| -> Solution { Solution::from(rows.iter().map(|row| row.to_string()).collect::<Vec<String>>()) };
+
+    // the transpose of ten,ore,eat is toe,era,net which generating would skip as a duplicate
+    let (solutions, stats) = solver.solve(parse_partial_grid("toe,..", 3).unwrap(), None).unwrap();
+    assert_eq!(vec![rows(&["toe", "era", "net"])], solutions);
+    assert!(stats.nodes_expanded > 0);
+
+    // the same solver can be used again
+    let (solutions, _) = solver.solve(parse_partial_grid(",,.a", 3).unwrap(), None).unwrap();
+    assert_eq!(vec![rows(&["ten", "ore", "eat"]), rows(&["ten", "ore", "rat"])], solutions);
+
+    let (solutions, _) = solver.solve(parse_partial_grid(",,.a", 3).unwrap(), Some(1)).unwrap();
+    assert_eq!(vec![rows(&["ten", "ore", "eat"])], solutions);
+
+    // stopping after the first seed's solutions never searches the rest, ten has two and toe is never tried
+    let mut handed_over = Vec::new();
+    solver.solve_each(parse_partial_grid("", 3).unwrap(), None, |found| {
+        handed_over.push(found);
+        false
+    }).unwrap();
+    assert_eq!(vec![vec![rows(&["ten", "ore", "eat"]), rows(&["ten", "ore", "rat"])]], handed_over);
+}
+
+/* if the column is alphabetically before the row than the column has already
+ been tried at that row previously, so this would result in a transposed solution */
+fn would_be_transposed_row(row: &String, column: &String) -> bool {
+    column.cmp(row) == Ordering::Less
+}
+
+#[test]
+fn is_transposed_row(){
+
+    let row = "bases".to_string();
+    let column = "based".to_string();
+
+    assert!(would_be_transposed_row(&row, &column))
+}
+
+#[test]
+fn isnt_transposed_row(){
+    
+    let row = "based".to_string();
+    let column = "bases".to_string();
+
+    assert!(!would_be_transposed_row(&row, &column))
+}
+
+
+#[test]
+fn batch_flushes_when_full_and_on_drop() {
+
+    let (solution_sender, solution_receiver) = crossbeam_channel::unbounded::<GeneratorEvent>();
+
+    let mut batch = SolutionBatch::new(solution_sender, 2, false, Arc::new(Mutex::new(RunStats::default())), Arc::new(Progress::new(0)));
+    let receiver = SolutionReceiver { event_receiver: solution_receiver };
+
+    batch.push(Solution::from(vec!["a".to_string()]));
+    assert!(receiver.event_receiver.try_recv().is_err());
+
+    batch.push(Solution::from(vec!["b".to_string()]));
+    batch.push(Solution::from(vec!["c".to_string()]));
+    drop(batch);
+
+    assert_eq!(vec![vec!["a".to_string()], vec!["b".to_string()], vec!["c".to_string()]], receiver.iter().collect::<Vec<Solution>>());
+}
+
+#[test]
+fn panicking_seed_is_reported_as_an_error() {
+
+    let (solution_sender, _solution_receiver) = crossbeam_channel::unbounded::<GeneratorEvent>();
+    let stats_total = Arc::new(Mutex::new(RunStats::default()));
+
+    // the 2 letter word fails the length check when it is tried against the 3 letter seed, a letter outside a to z keeps it from being screened out first
+    let generator = generator_for(
+        vec!["abc".to_string(), "d-".to_string()],
+        PrefixArena::new(&["ade".to_string()]).unwrap(),
+        WorkerContext { stats: stats_total.clone(), ..WorkerContext::standalone(solution_sender) }
+    );
+
+    let mut batch = generator.new_batch();
+    generator.run("abc".to_string(), &mut batch);
+    drop(batch);
+
+    let stats = stats_total.lock().unwrap();
+
+    assert_eq!(0, stats.seeds_processed);
+    assert_eq!(1, stats.errors.len());
+    assert!(matches!(&stats.errors[0], GeneratorError::SeedFailed { seed, .. } if seed == "abc"));
+}
+
+#[test]
+fn validation_rejects_bad_solutions() {
+
+    let (solution_sender, _solution_receiver) = crossbeam_channel::unbounded::<GeneratorEvent>();
+
+    let dictionary = vec!["ab".to_string(), "ac".to_string(), "bd".to_string(), "cd".to_string()];
+    let prefix_map = PrefixArena::new(&dictionary).unwrap();
+
+    let generator = generator_for(dictionary, prefix_map, WorkerContext::standalone(solution_sender)).with_validation(true);
+
+    assert_eq!(None, generator.invalid_solution_reason(&["ab".to_string()], "cd"));
+    assert_eq!(Some("transpose of a solution found from another seed".to_string()), generator.invalid_solution_reason(&["ac".to_string()], "bd"));
+    assert_eq!(Some("[ab] is used more than once".to_string()), generator.invalid_solution_reason(&["ab".to_string()], "ab"));
+    assert_eq!(Some("[dc] is not in the dictionary".to_string()), generator.invalid_solution_reason(&["ab".to_string()], "dc"));
+    assert_eq!(Some("grid is not square".to_string()), generator.invalid_solution_reason(&["ab".to_string()], "c"));
+}
+
+#[test]
+fn columns_from_another_dictionary() {
+
+    let words = |words: &[&str]| -> Vec<String> { words.iter().map(|word| word.to_string()).collect() };
+
+    // the rows read down are the column words, with one dictionary adg,beh,cfi would be left out for its transpose coming first
+    let rows = words(&["adg", "beh", "cfi"]);
+    let prefix_map = PrefixArena::new(&words(&["abc", "def", "ghi"])).unwrap();
+
+    let mut solver = GridSolver::new(rows.clone(), prefix_map).unwrap();
+    let (solutions, _) = solver.solve(vec![], None).unwrap();
+
+    assert_eq!(vec![rows.clone()], solutions);
+    assert!(solver.generator.separate_columns);
+
+    solver.generator.validate = true;
+    assert_eq!(None, solver.generator.invalid_solution_reason(&rows[..2], "cfi"));
+    assert_eq!(Some("[acb] is not in the column dictionary".to_string()), solver.generator.invalid_solution_reason(&words(&["adg", "cfi"]), "beh"));
+}
+
+#[test]
+fn seed_finished_after_its_solutions() {
+
+    let (solution_sender, solution_receiver) = crossbeam_channel::unbounded::<GeneratorEvent>();
+
+    let stats_total = Arc::new(Mutex::new(RunStats::default()));
+    let mut batch = SolutionBatch::new(solution_sender, 8, false, stats_total.clone(), Arc::new(Progress::new(0)));
+
+    batch.push(Solution::from(vec!["a".to_string()]));
+    batch.seed_finished("a".to_string());
+    drop(batch);
+
+    assert_eq!(1, stats_total.lock().unwrap().seeds_processed);
+    assert_eq!(1, stats_total.lock().unwrap().solutions_found);
+
+    assert!(matches!(solution_receiver.try_recv(), Ok(GeneratorEvent::Solutions(solutions)) if solutions.len() == 1));
+    assert!(matches!(solution_receiver.try_recv(), Ok(GeneratorEvent::SeedFinished(seed)) if seed == "a"));
+}
+
+#[test]
+fn count_only_batch_tallies_by_seed() {
+
+    let (solution_sender, solution_receiver) = crossbeam_channel::unbounded::<GeneratorEvent>();
+    let stats_total = Arc::new(Mutex::new(RunStats::default()));
+    let mut batch = SolutionBatch::new(solution_sender, 1, true, stats_total.clone(), Arc::new(Progress::new(0)));
+
+    batch.found(&["ab".to_string()], "cd");
+    batch.found(&["ab".to_string()], "ce");
+    batch.found(&["ef".to_string()], "gh");
+    batch.seed_finished("ef".to_string());
+    batch.found(&["ab".to_string()], "cf");
+    drop(batch);
+
+    let stats = stats_total.lock().unwrap();
+
+    assert_eq!(4, stats.solutions_found);
+    assert_eq!(HashMap::from([("ab".to_string(), 3), ("ef".to_string(), 1)]), stats.solutions_per_seed);
+    assert_eq!(3, stats.solutions_per_letter[0]);
+    assert!(stats.word_counts.is_empty());
+
+    // only the seed finishing is sent, never a solution
+    assert!(matches!(solution_receiver.try_recv(), Ok(GeneratorEvent::SeedFinished(_))));
+    assert!(solution_receiver.try_recv().is_err());
+}
+
+#[test]
+fn found_solutions_are_counted_by_letter_and_word() {
+
+    let (solution_sender, _solution_receiver) = crossbeam_channel::unbounded::<GeneratorEvent>();
+    let stats_total = Arc::new(Mutex::new(RunStats::default()));
+    let mut batch = SolutionBatch::new(solution_sender, 8, false, stats_total.clone(), Arc::new(Progress::new(0)));
+
+    batch.found(&["ab".to_string()], "ba");
+    batch.found(&["ba".to_string()], "ab");
+    drop(batch);
+
+    let stats = stats_total.lock().unwrap();
+
+    assert_eq!(1, stats.solutions_per_letter[0]);
+    assert_eq!(1, stats.solutions_per_letter[1]);
+    assert_eq!(4, stats.letter_counts[0]);
+    assert_eq!(HashMap::from([("ab".to_string(), 2), ("ba".to_string(), 2)]), stats.word_counts);
+}
+
+#[test]
+fn ordered_solutions_follow_the_seed_order() {
+
+    let solution = |seed: &str, row: &str| Solution::new(vec![seed.to_string(), row.to_string()]);
+
+    let events = vec![
+        GeneratorEvent::Solutions(vec![solution("cd", "zz"), solution("cd", "aa")]),
+        GeneratorEvent::SeedFinished("cd".to_string()),
+        GeneratorEvent::Solutions(vec![solution("ef", "bb")]),
+        GeneratorEvent::Solutions(vec![solution("ab", "cc")]),
+        GeneratorEvent::SeedFinished("ab".to_string()),
+        GeneratorEvent::Solutions(vec![solution("ef", "aa")])
+    ];
+
+    let ordered: Vec<Solution> = OrderedSolutions::new(events.into_iter(), vec!["ab".to_string(), "cd".to_string(), "ef".to_string()]).collect();
+
+    assert_eq!(vec![solution("ab", "cc"), solution("cd", "aa"), solution("cd", "zz"), solution("ef", "aa"), solution("ef", "bb")], ordered);
+}
+
+#[test]
+fn shuffled_search_finds_the_same_solutions() {
+
+    let dictionary: Vec<String> = ["eat", "era", "net", "ore", "ten", "toe"].iter().map(|word| word.to_string()).collect();
+
+    let sorted = solutions_with(&["exec name", "words.csv", "out.csv", "2"], &dictionary);
+    assert_eq!(1, sorted.len());
+
+    assert_eq!(sorted, solutions_with(&["exec name", "words.csv", "out.csv", "2", "--shuffle", "--seed", "42"], &dictionary));
+    assert_eq!(sorted, solutions_with(&["exec name", "words.csv", "out.csv", "2", "--shuffle", "--seed", "7", "--backend", "rayon"], &dictionary));
+}
+
+#[test]
+fn first_solution_per_seed() {
+
+    // ten starts both ten,ore,eat and ten,ore,rat
+    let dictionary: Vec<String> = ["eat", "era", "net", "ore", "rat", "ten", "toe", "tor"].iter().map(|word| word.to_string()).collect();
+
+    let every_solution = solutions_with(&["exec name", "words.csv", "out.csv", "2"], &dictionary);
+    let seeds: HashSet<&String> = every_solution.iter().map(|solution| &solution[0]).collect();
+    assert!(every_solution.len() > seeds.len());
+
+    for backend in ["threads", "rayon"] {
+        let first_solutions = solutions_with(&["exec name", "words.csv", "out.csv", "2", "--first-per-seed", "--backend", backend], &dictionary);
+        let first_seeds: HashSet<&String> = first_solutions.iter().map(|solution| &solution[0]).collect();
+
+        assert_eq!(seeds.len(), first_solutions.len());
+        assert_eq!(seeds, first_seeds);
+    }
+}
+
+#[test]
+fn constrained_fill_order_finds_the_same_solutions() {
+
+    let dictionary: Vec<String> = ["eat", "era", "net", "ore", "rat", "ten", "toe", "tor"].iter().map(|word| word.to_string()).collect();
+
+    let by_rows = solutions_with(&["exec name", "words.csv", "out.csv", "2"], &dictionary);
+    assert_eq!(2, by_rows.len());
+
+    for backend in ["threads", "rayon"] {
+        let args: Vec<String> = ["exec name", "words.csv", "out.csv", "2", "--fill-order", "constrained", "--validate", "--backend", backend].iter().map(|arg| arg.to_string()).collect();
+        let (mut solutions, stats) = pool_solutions(&GeneratorConfig::build(&args).unwrap(), &dictionary);
+
+        solutions.sort();
+        assert_eq!(by_rows, solutions);
+        assert!(stats.errors.is_empty());
+    }
+
+    assert_eq!(1, solutions_with(&["exec name", "words.csv", "out.csv", "2", "--fill-order", "constrained", "--first-per-seed"], &dictionary).len());
+}
+
+#[test]
+fn forward_checking_finds_the_same_solutions() {
+
+    let dictionary: Vec<String> = ["eat", "era", "net", "ore", "rat", "ten", "toe", "tor"].iter().map(|word| word.to_string()).collect();
+
+    let solutions_of = |args: &[&str]| {
+        let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+        let (mut solutions, stats) = pool_solutions(&GeneratorConfig::build(&args).unwrap(), &dictionary);
+
+        solutions.sort();
+        (solutions, stats.nodes_expanded)
+    };
+
+    let (bad_starts_solutions, bad_starts_nodes) = solutions_of(&["exec name", "words.csv", "out.csv", "2"]);
+    let (forward_check_solutions, forward_check_nodes) = solutions_of(&["exec name", "words.csv", "out.csv", "2", "--pruning", "forward-check"]);
+
+    assert_eq!(2, bad_starts_solutions.len());
+    assert_eq!(bad_starts_solutions, forward_check_solutions);
+
+    // skipping bad starts never saves going down a row, with three rows there is only the last to check ahead so nothing is saved here either
+    assert!(forward_check_nodes <= bad_starts_nodes, "{} {}", forward_check_nodes, bad_starts_nodes);
+
+    // under abc,def the columns start ad, be and cf so the last row has to be xyz
+    let later_rows_have_words = |words: &[&str]| {
+        let dictionary: Vec<String> = words.iter().map(|word| word.to_string()).collect();
+        let prefix_map = PrefixArena::new(&dictionary).unwrap();
+        let (solution_sender, _solution_receiver) = crossbeam_channel::unbounded::<GeneratorEvent>();
+
+        let generator = generator_for(dictionary, prefix_map, WorkerContext::standalone(solution_sender)).with_pruning(Pruning::ForwardCheck);
+
+        generator.later_rows_have_words(&["abc".to_string(), "def".to_string()], true)
+    };
+
+    assert!(later_rows_have_words(&["abc", "adx", "bey", "cfz", "def", "xyz"]));
+    assert!(!later_rows_have_words(&["abc", "adx", "bey", "cfz", "def", "xyy"]));
+}
+
+#[test]
+fn constraints_narrow_the_solutions() {
+
+    let dictionary: Vec<String> = ["eat", "era", "net", "ore", "rat", "ten", "toe", "tor"].iter().map(|word| word.to_string()).collect();
+
+    let config = |flags: &[&str]| {
+        let args: Vec<String> = ["exec name", "words.csv", "out.csv", "2"].iter().chain(flags).map(|arg| arg.to_string()).collect();
+        GeneratorConfig::build(&args).unwrap()
+    };
+
+    let solutions_of = |config: GeneratorConfig| {
+        let mut solutions: Vec<String> = pool_solutions(&config, &dictionary).0.iter().map(|solution| solution.join(",")).collect();
+
+        solutions.sort();
+        solutions
+    };
+
+    assert_eq!(vec!["ten,ore,eat", "ten,ore,rat"], solutions_of(config(&[])));
+
+    // toe,era,net is the transpose of ten,ore,eat, a pattern can tell them apart so both are found
+    assert_eq!(vec!["ten,ore,eat", "ten,ore,rat", "toe,era,net", "tor,era,net"], solutions_of(config(&["--pattern", "t.."])));
+
+    assert_eq!(vec!["ten,ore,eat"], solutions_of(config(&["--require", "eat"])));
+    assert!(solutions_of(config(&["--max-letter", "t:1"])).is_empty());
+
+    #[derive(Debug)]
+    struct NoRow(&'static str);
+
+    impl Constraint for NoRow {
+        fn allows_word(&self, _puzzle: &[String], word: &str) -> bool {
+            word != self.0
+        }
+
+        fn is_symmetric(&self) -> bool {
+            true
+        }
+    }
+
+    assert_eq!(vec!["ten,ore,eat"], solutions_of(config(&[]).with_constraint(Arc::new(NoRow("rat")))));
+}
+
+#[test]
+fn explain_finds_what_the_search_does() {
+
+    let dictionary: Vec<String> = ["abc", "adc", "adf", "adg", "beb", "bed", "beh", "cba", "cda", "cfi", "deb", "ded", "def", "fda", "ghi"].iter().map(|word| word.to_string()).collect();
+    let prefix_map = PrefixArena::new(&dictionary).unwrap();
+
+    let mut solver = GridSolver::new(dictionary.clone(), prefix_map).unwrap();
+    let explanation = solver.explain("abc").unwrap();
+
+    // the squares generate finds from abc
+    let args: Vec<String> = ["exec name", "words.csv", "out.csv", "1"].iter().map(|arg| arg.to_string()).collect();
+    let mut pool = SolutionGeneratorThreadPool::new(&GeneratorConfig::build(&args).unwrap(), &dictionary,
+        PrefixArena::new(&dictionary).unwrap(), Arc::new(AtomicBool::new(false)), HashSet::new()).unwrap();
+
+    let mut found: Vec<Solution> = pool.solution_receiver().iter().filter(|solution| solution[0] == "abc").collect();
+    pool.join();
+    found.sort();
+
+    assert_eq!(found.len(), explanation.solutions);
+    assert!(explanation.solutions > 0);
+
+    let mut solutions: Vec<Solution> = vec![];
+    let mut steps: Vec<(&Step, Vec<String>)> = explanation.steps.iter().map(|step| (step, vec!["abc".to_string()])).collect();
+
+    while let Some((step, mut rows)) = steps.pop() {
+        match step {
+            Step::Fits { word, below, .. } => {
+                rows.push(word.clone());
+                steps.extend(below.iter().map(|below| (below, rows.clone())));
+            },
+            Step::Solution { word, .. } => {
+                rows.push(word.clone());
+                solutions.push(Solution::new(rows));
+            },
+            _ => ()
+        }
+    }
+
+    solutions.sort();
+    assert_eq!(found, solutions);
+
+    // abc is used already, and af starts no word so adc, adf and adg go together
+    assert_eq!(Step::Used { row: 1, word: "abc".to_string() }, explanation.steps[0]);
+    assert!(explanation.steps.contains(&Step::BadStart { row: 1, word: "adc".to_string(), column: 0, start: "aa".to_string(), skipped: 2 }));
+
+    assert!(solver.explain("xyz").is_err());
+}
+
+#[test]
+fn beam_search_keeps_the_least_penalty() {
+
+    let dictionary: Vec<String> = ["eat", "era", "net", "ore", "rat", "ten", "toe", "tor"].iter().map(|word| word.to_string()).collect();
+    let prefix_map = PrefixArena::new(&dictionary).unwrap();
+
+    let mut solver = GridSolver::new(dictionary, prefix_map).unwrap();
+
+    let soft_constraints = vec![crate::constraint::SoftConstraint { constraint: Arc::new(crate::constraint::ForbiddenWords { words: HashSet::from(["eat".to_string()]) }), weight: 1.0 }];
+    let penalty = |puzzle: &[String]| crate::constraint::penalty(&soft_constraints, puzzle);
+
+    let (solutions, stats) = solver.beam_search(8, penalty).unwrap();
+    assert_eq!(vec![vec!["ten", "ore", "rat"], vec!["ten", "ore", "eat"]], solutions);
+    assert!(stats.nodes_expanded > 0);
+
+    // era is the first seed without a penalty but no square starts with it
+    let (solutions, _) = solver.beam_search(1, penalty).unwrap();
+    assert!(solutions.is_empty());
+}
+
+#[test]
+fn most_letters_finds_the_best_covered_square() {
+
+    let dictionary = crate::dictionary::dictionary_from_bytes(include_bytes!("../resources/dictionaries/words_small.csv")).unwrap();
+    let prefix_map = PrefixArena::new(&dictionary).unwrap();
+
+    let mut solver = GridSolver::new(dictionary, prefix_map).unwrap();
+
+    // the only square, every letter but f, h, j, k, m, o, p, q, v, w, x and z
+    let coverage = solver.most_letters(None, None).unwrap();
+    assert_eq!(Some("based,ulcer,diary,genie,enter".to_string()), coverage.square.map(|square| square.join(",")));
+    assert_eq!((14, false), (coverage.letters, coverage.timed_out));
+
+    // out of time before trying anything
+    let coverage = solver.most_letters(Some(5), Some(Instant::now())).unwrap();
+    assert!(coverage.timed_out && coverage.square.is_none());
+
+    assert_eq!(0b1011, letter_mask("abd"));
+}
+
+#[test]
+fn all_letters_different_in_the_search() {
+
+    // abc,def,ghi and abc,def,gha are squares, only the first without a letter twice
+    let dictionary: Vec<String> = ["abc", "adg", "beh", "cfa", "cfi", "def", "gha", "ghi"].iter().map(|word| word.to_string()).collect();
+
+    assert_eq!(vec![vec!["abc", "def", "ghi"]], solutions_with(&["exec name", "words.csv", "out.csv", "--all-letters-different"], &dictionary));
+}
+
+#[test]
+fn symmetric_and_diagonal_squares_in_the_search() {
+
+    let dictionary: Vec<String> = ["abc", "adc", "adf", "adg", "beb", "bed", "beh", "cba", "cda", "cfi", "deb", "ded", "def", "fda", "ghi"]
+        .iter().map(|word| word.to_string()).collect();
+
+    let solutions_of = |flags: &[&str]| {
+        let args: Vec<&str> = ["exec name", "words.csv", "out.csv"].into_iter().chain(flags.iter().copied()).collect();
+        solutions_with(&args, &dictionary).iter().map(|solution| solution.join(",")).collect::<Vec<String>>()
+    };
+
+    // of the eight squares
+    assert_eq!(vec!["abc,ded,cba", "cba,ded,abc"], solutions_of(&["--symmetry", "palindromic"]));
+    assert_eq!(vec!["abc,deb,cda", "abc,deb,fda", "cba,deb,adc"], solutions_of(&["--symmetry", "anti-diagonal"]));
+
+    // the only one with a, e then i down the diagonal, its transpose adg,beh,cfi is left out as usual
+    assert_eq!(vec!["abc,def,ghi"], solutions_of(&["--diagonal", "aei"]));
+    assert_eq!(2, solutions_of(&["--diagonal", "cec"]).len());
+}
+
+#[test]
+fn stopping_unwinds_the_search_stack() {
+
+    let dictionary: Vec<String> = ["eat", "era", "net", "ore", "rat", "ten", "toe", "tor"].iter().map(|word| word.to_string()).collect();
+    let prefix_map = PrefixArena::new(&dictionary).unwrap();
+    let (solution_sender, solution_receiver) = crossbeam_channel::unbounded::<GeneratorEvent>();
+
+    let mut generator = generator_for(dictionary, prefix_map, WorkerContext::standalone(solution_sender));
+
+    // ten has two solutions, the limit stops the search in the middle of the last row
+    generator.solution_limit = Some(1);
+
+    let mut puzzle = vec!["ten".to_string()];
+    let mut batch = generator.new_batch();
+    generator.find_solutions(&mut puzzle, 1, &mut batch);
+
+    assert_eq!(vec!["ten".to_string()], puzzle);
+    assert_eq!(batch.stats.nodes_expanded, batch.stats.backtracks);
+
+    drop(batch);
+    drop(generator);
+
+    let solutions = SolutionReceiver { event_receiver: solution_receiver }.iter().collect::<Vec<Solution>>();
+    assert_eq!(vec![vec!["ten".to_string(), "ore".to_string(), "eat".to_string()]], solutions);
+}
+
+#[test]
+fn candidate_positions_follow_the_shuffle() {
+
+    let dictionary: Vec<String> = ["eat", "era", "net", "ore", "rat", "ten", "toe", "tor"].iter().map(|word| word.to_string()).collect();
+    let (solution_sender, _solution_receiver) = crossbeam_channel::unbounded::<GeneratorEvent>();
+
+    let generator = generator_for(dictionary.clone(), PrefixArena::new(&dictionary).unwrap(), WorkerContext::standalone(solution_sender));
+
+    assert_eq!(Some(5), generator.candidate_position(&"ten".to_string()));
+    assert_eq!(None, generator.candidate_position(&"tan".to_string()));
+
+    let generator = generator.with_shuffled_candidates(Some(SampleRng::new(3)));
+    let candidates = generator.shuffled_candidates.clone().unwrap();
+
+    for word in &dictionary {
+        assert_eq!(Some(word), generator.candidate_position(word).map(|position| &candidates[position]));
+    }
+}
+
+#[test]
+fn a_word_in_the_dictionary_twice_still_goes_in_a_square_once() {
+
+    // bac,bac,xyz has the columns bbx,aay,ccz, so it is a square but for bac being in it twice
+    let dictionary: Vec<String> = ["aay", "bac", "bac", "bbx", "ccz", "xyz"].iter().map(|word| word.to_string()).collect();
+
+    assert!(solutions_with(&["exec name", "words.csv"], &dictionary).is_empty());
+    assert!(solutions_with(&["exec name", "words.csv", "--backend", "rayon"], &dictionary).is_empty());
+    assert!(solutions_with(&["exec name", "words.csv", "--shuffle", "--seed", "3"], &dictionary).is_empty());
+
+    let prefix_map = PrefixArena::new(&dictionary).unwrap();
+    let mut solver = GridSolver::new(dictionary, prefix_map).unwrap();
+
+    assert!(solver.solve(parse_partial_grid("", 3).unwrap(), None).unwrap().0.is_empty());
+}
+
+#[test]
+fn join_drains_a_full_solution_queue() {
+
+    let dictionary: Vec<String> = ["eat", "era", "net", "ore", "rat", "ten", "toe", "tor"].iter().map(|word| word.to_string()).collect();
+    let prefix_map = PrefixArena::new(&dictionary).unwrap();
+
+    let args: Vec<String> = ["exec name", "words.csv", "out.csv", "2", "--batch-size", "1", "--solution-queue", "1"].iter().map(|arg| arg.to_string()).collect();
+    let config = GeneratorConfig::build(&args).unwrap();
+
+    // every seed finishing is an event too so the queue of one is full long before the workers are done
+    let mut pool = SolutionGeneratorThreadPool::new(&config, &dictionary, prefix_map, Arc::new(AtomicBool::new(false)), HashSet::new()).unwrap();
+    let stats = pool.join();
+
+    assert_eq!(2, stats.solutions_found);
+    assert_eq!(8, stats.seeds_processed);
+}
+
+#[test]
+fn bad_starts_are_counted() {
+
+    let dictionary: Vec<String> = ["eat", "era", "net", "ore", "rat", "tea", "ten", "toe", "tor", "tot"].iter().map(|word| word.to_string()).collect();
+
+    let args: Vec<String> = ["exec name", "words.csv", "out.csv", "1"].iter().map(|arg| arg.to_string()).collect();
+    let (solutions, stats) = pool_solutions(&GeneratorConfig::build(&args).unwrap(), &dictionary);
+
+    assert_eq!(2, solutions.len());
+
+    // Words that can't fit a column never get past the screen, so the bad starts come from the last row. Under tot,ore
+    // the t of tea makes tot again and every t word after it is skipped without trying them, which doesn't change what is found
+    assert_eq!(4, stats.bad_start_skips);
+    assert!(stats.to_string().contains("words skipped by bad starts: "));
+
+    // every candidate the screen passed was checked against at least its first column
+    assert!(stats.bad_start_prunes > 0 && stats.bad_start_prunes <= stats.candidate_checks);
+    assert!(stats.prefix_lookups >= stats.candidate_checks);
+    assert!(stats.to_string().contains(&format!("candidates checked: {}", stats.candidate_checks)));
+}
+
+#[test]
+fn memo_finds_the_same_solutions() {
+
+    // the used words and transposes turned down at the last row are what the memo has to notice
+    let dictionary: Vec<String> = ["eat", "era", "net", "ore", "rat", "ten", "toe", "tor"].iter().map(|word| word.to_string()).collect();
+
+    let without_memo = solutions_with(&["exec name", "words.csv", "out.csv", "2"], &dictionary);
+    assert_eq!(2, without_memo.len());
+
+    for args in [vec!["--memo", "1"], vec!["--memo", "100", "--backend", "rayon"], vec!["--memo", "100", "--pruning", "forward-check"]] {
+        let args: Vec<&str> = ["exec name", "words.csv", "out.csv", "1"].into_iter().chain(args).collect();
+        assert_eq!(without_memo, solutions_with(&args, &dictionary));
+    }
+}
+
+#[test]
+fn naive_engine_finds_the_same_solutions_in_order() {
+
+    let dictionary: Vec<String> = ["abc", "adc", "adf", "adg", "beb", "bed", "beh", "cba", "cda", "cfi", "deb", "ded", "def", "fda", "ghi"]
+        .iter().map(|word| word.to_string()).collect();
+    let prefix_map = PrefixArena::new(&dictionary).unwrap();
+
+    let config_of = |flags: &[&str]| {
+        let args: Vec<String> = ["exec name", "words.csv", "out.csv"].iter().chain(flags).map(|arg| arg.to_string()).collect();
+        GeneratorConfig::build(&args).unwrap()
+    };
+
+    let mut pool = SolutionGeneratorNaivePool::new(&config_of(&["--engine", "naive"]), &dictionary, Arc::new(AtomicBool::new(false)), HashSet::from(["cba".to_string()])).unwrap();
+    let naive: Vec<Solution> = pool.solution_receiver().iter().collect();
+    let stats = pool.join();
+
+    // in seed order already, and the one seed skipped is left out the same as it would be for the thread pool
+    let config = config_of(&["--deterministic"]);
+    let mut pool = SolutionGeneratorThreadPool::new(&config, &dictionary, prefix_map, Arc::new(AtomicBool::new(false)), HashSet::from(["cba".to_string()])).unwrap();
+    let seed_order: Vec<String> = dictionary.iter().filter(|word| *word != "cba").cloned().collect();
+    let prefix_engine: Vec<Solution> = pool.solution_receiver().ordered_iter(seed_order).collect();
+    pool.join();
+
+    assert_eq!(prefix_engine, naive);
+    assert_eq!(naive.len() as u64, stats.solutions_found);
+    assert_eq!(14, stats.seeds_processed);
+
+    let with_constraint = config_of(&["--engine", "naive", "--require", "ghi"]);
+    assert!(SolutionGeneratorNaivePool::new(&with_constraint, &dictionary, Arc::new(AtomicBool::new(false)), HashSet::new()).is_err());
+}
+
+#[test]
+fn estimate_is_close_to_the_exact_count() {
+
+    let dictionary: Vec<String> = ["eat", "era", "net", "ore", "rat", "ten", "toe", "tor"].iter().map(|word| word.to_string()).collect();
+    let prefix_map = PrefixArena::new(&dictionary).unwrap();
+
+    let mut solver = GridSolver::new(dictionary, prefix_map).unwrap();
+    let estimate = solver.estimate_solution_count(20_000, &mut SampleRng::new(3));
+
+    // ten,ore,eat and ten,ore,rat, their transposes are the same solutions
+    assert_eq!(20_000, estimate.probes);
+    assert!((estimate.solutions - 2.0).abs() < 0.2, "{:?}", estimate);
+    assert!(estimate.standard_error < 0.1, "{:?}", estimate);
+}
+
+/* the random order of one part of a shuffled search, the seed order being stream 0 and each worker its own
+  stream after that. The same seed gives the same orders again, though with more than one thread which
+  worker picks up which seed word still comes down to timing */
+fn shuffle_rng(config: &GeneratorConfig, stream: u64) -> Option<SampleRng> {
+    config.shuffle.then(|| SampleRng::new(config.seed.unwrap_or_default().wrapping_add(stream)))
+}
+
+/* The sorted dictionary with each word once. Rows are skipped as already used by where they are in
+  it, so a word in it twice could go in a square twice and would be searched from twice as a seed */
+fn distinct_words(mut dictionary: Vec<String>) -> Vec<String> {
+    dictionary.dedup();
+    dictionary
+}
+
+/* how many seed words a pool is going to search, for the progress line */
+fn seeds_to_search(dictionary: &[String], skipped_seeds: &HashSet<String>) -> u64 {
+    dictionary.iter().filter(|word| !skipped_seeds.contains(*word)).count() as u64
+}
+
+/* splits the sorted dictionary into ranges of words that share the same starting letters,
+  so each job a worker picks up is a contiguous slice of the dictionary */
+fn chunk_by_prefix(dictionary: &[String], prefix_length: usize) -> Vec<Range<usize>> {
+
+    let mut chunks: Vec<Range<usize>> = Vec::new();
+    let mut chunk_start = 0;
+
+    for i in 1..=dictionary.len() {
+
+        let chunk_ended = i == dictionary.len()
+            || dictionary[i].chars().take(prefix_length).ne(dictionary[chunk_start].chars().take(prefix_length));
+
+        if chunk_ended {
+            chunks.push(chunk_start..i);
+            chunk_start = i;
+        }
+    }
+
+    chunks
+}
+
+#[test]
+fn chunk_dictionary_by_prefix() {
+
+    let dictionary = vec!["based".to_string(), "bases".to_string(), "baton".to_string(), "belly".to_string(), "elses".to_string()];
+
+    assert_eq!(vec![0..3, 3..4, 4..5], chunk_by_prefix(&dictionary, 2));
+    assert_eq!(vec![0..2, 2..3, 3..4, 4..5], chunk_by_prefix(&dictionary, 4));
+}
+
+#[test]
+fn chunk_empty_dictionary() {
+
+    let dictionary: Vec<String> = Vec::new();
+
+    assert!(chunk_by_prefix(&dictionary, 2).is_empty());
+}
+
+
 /* transposes the rows of a puzzle into columns to be used in determining if the columns of a puzzle will be valid */
-fn construct_potential_transposed_puzzle(puzzle: &Vec<String>) -> Vec<String> {
+pub fn construct_potential_transposed_puzzle(puzzle: &[String]) -> Vec<String> {
     
-    let mut potential_transposed_puzzle = vec!["".to_string() ; puzzle.get(0).expect("puzzle to have the first row populated").len()];
+    let mut potential_transposed_puzzle = vec!["".to_string() ; puzzle.first().expect("puzzle to have the first row populated").len()];
 
     for word in puzzle {
         for (i, ch) in word.chars().enumerate() {
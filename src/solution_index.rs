@@ -0,0 +1,162 @@
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, BufWriter, Seek, SeekFrom, Write};
+use serde::{Deserialize, Serialize};
+use crate::error::SquardleError;
+use crate::generator_config::Compression;
+use crate::output;
+use crate::solution_generator::construct_potential_transposed_puzzle;
+
+/* Which solutions of a csv solutions file have each word in a row or column, so finding the
+  squares with some words in them reads just those lines instead of the whole file. A solution's
+  id is its position in the file, the byte offset of its line is kept to seek straight to it */
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct SolutionIndex {
+    // the length of the solutions file when it was indexed, a different length means it has changed since
+    pub solutions_file_length: u64,
+    offsets: Vec<u64>,
+    words: HashMap<String, Vec<u32>>
+}
+
+impl SolutionIndex {
+
+    /* the file is read line by line so the offsets are exact, it can't be compressed as then it couldn't be seeked into */
+    pub fn build(solutions_file_path: &str) -> Result<SolutionIndex, SquardleError> {
+
+        if Compression::from_file_path(solutions_file_path) != Compression::None {
+            return Err("Only an uncompressed csv solutions file can be indexed".into());
+        }
+
+        let mut reader = BufReader::new(File::open(solutions_file_path)?);
+        let mut offsets: Vec<u64> = Vec::new();
+        let mut words: HashMap<String, Vec<u32>> = HashMap::new();
+
+        let mut line = String::new();
+        let mut offset: u64 = 0;
+
+        loop {
+            line.clear();
+            let length = reader.read_line(&mut line)?;
+
+            if length == 0 {
+                break;
+            }
+
+            let rows = output::rows_of_line(&line);
+
+            if !rows.is_empty() {
+                let id = offsets.len() as u32;
+                offsets.push(offset);
+
+                for word in rows.iter().chain(construct_potential_transposed_puzzle(&rows).iter()) {
+                    let ids = words.entry(word.clone()).or_default();
+
+                    // a word can be both a row and a column of the same square
+                    if ids.last() != Some(&id) {
+                        ids.push(id);
+                    }
+                }
+            }
+
+            offset += length as u64;
+        }
+
+        Ok(SolutionIndex { solutions_file_length: offset, offsets, words })
+    }
+
+    pub fn solution_count(&self) -> usize {
+        self.offsets.len()
+    }
+
+    /* writes to a temp file first like a checkpoint so a crash never leaves half an index */
+    pub fn save(&self, file_path: &str) -> Result<(), SquardleError> {
+
+        let temp_file_path = format!("{}.tmp", file_path);
+
+        let mut file = BufWriter::new(File::create(&temp_file_path)?);
+        bincode::serialize_into(&mut file, self)?;
+        file.flush()?;
+        drop(file);
+
+        fs::rename(&temp_file_path, file_path)?;
+
+        Ok(())
+    }
+
+    pub fn load(file_path: &str) -> Result<SolutionIndex, SquardleError> {
+        Ok(bincode::deserialize_from(BufReader::new(File::open(file_path)?))?)
+    }
+
+    /* the ids of the solutions that have every one of the words, in file order */
+    pub fn solutions_with(&self, words: &[String]) -> Vec<u32> {
+
+        let mut id_lists: Vec<&Vec<u32>> = Vec::with_capacity(words.len());
+
+        for word in words {
+            match self.words.get(word) {
+                Some(ids) => id_lists.push(ids),
+                None => return Vec::new()
+            }
+        }
+
+        // starting from the shortest list keeps the intersection small from the start
+        id_lists.sort_by_key(|ids| ids.len());
+
+        let Some((shortest, others)) = id_lists.split_first() else {
+            return Vec::new();
+        };
+
+        shortest.iter().filter(|id| others.iter().all(|ids| ids.binary_search(id).is_ok())).copied().collect()
+    }
+
+    /* the lines of the solutions with the ids, read from the file the index was built from */
+    pub fn read_solutions(&self, solutions_file_path: &str, ids: &[u32]) -> Result<Vec<String>, SquardleError> {
+
+        let mut reader = BufReader::new(File::open(solutions_file_path)?);
+
+        if reader.get_ref().metadata()?.len() != self.solutions_file_length {
+            return Err(format!("{} has changed since it was indexed, index it again", solutions_file_path).as_str().into());
+        }
+
+        let mut lines = Vec::with_capacity(ids.len());
+
+        for id in ids {
+            let offset = *self.offsets.get(*id as usize).ok_or(SquardleError::from("solution id is not in the index"))?;
+            reader.seek(SeekFrom::Start(offset))?;
+
+            let mut line = String::new();
+            reader.read_line(&mut line)?;
+            lines.push(line.trim_end().to_string());
+        }
+
+        Ok(lines)
+    }
+}
+
+#[test]
+fn index_and_query_solutions() {
+
+    let file_path = "test_index_solutions.csv";
+    fs::write(file_path, "ten,ore,eat\ntoe,era,net,1.250\n\nwon,era,tee\n").unwrap();
+
+    let index = SolutionIndex::build(file_path).unwrap();
+    assert_eq!(3, index.solution_count());
+
+    // era is a row of the second and third and a column of the first
+    assert_eq!(vec![0, 1, 2], index.solutions_with(&["era".to_string()]));
+    assert_eq!(vec![0, 1], index.solutions_with(&["era".to_string(), "ten".to_string()]));
+    assert!(index.solutions_with(&["era".to_string(), "zzz".to_string()]).is_empty());
+
+    index.save("test_index_solutions.index").unwrap();
+    let loaded = SolutionIndex::load("test_index_solutions.index").unwrap();
+    fs::remove_file("test_index_solutions.index").unwrap();
+
+    assert_eq!(index, loaded);
+    assert_eq!(vec!["toe,era,net,1.250".to_string(), "won,era,tee".to_string()], loaded.read_solutions(file_path, &[1, 2]).unwrap());
+
+    fs::write(file_path, "ten,ore,eat\n").unwrap();
+    let changed = loaded.read_solutions(file_path, &[0]);
+    fs::remove_file(file_path).unwrap();
+
+    assert!(changed.is_err());
+}
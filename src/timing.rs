@@ -0,0 +1,104 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::span::{Attributes, Id};
+use tracing::Subscriber;
+use tracing_subscriber::layer::{Context, Layer};
+use tracing_subscriber::registry::LookupSpan;
+
+/* the target phase spans are made under, info_span!(target: PHASE, "output") say, so the
+  timings only pick those up and not whatever else is instrumented */
+pub const PHASE: &str = "squardle_rust::phase";
+
+/* A tracing layer timing each phase span from being made to being closed rather than only while
+  it's entered, as generation goes on in other threads while the output span is open on the main
+  one. The phases come back in the order they started, which is why they can add up to more
+  than the run took */
+#[derive(Clone, Default)]
+pub struct PhaseTimings {
+    finished: Arc<Mutex<Vec<(Instant, String, Duration)>>>
+}
+
+impl PhaseTimings {
+
+    pub fn new() -> PhaseTimings {
+        PhaseTimings::default()
+    }
+
+    /* the phases closed so far, by when they started */
+    pub fn take(&self) -> Vec<(String, Duration)> {
+
+        let mut finished = match self.finished.lock() {
+            Ok(mut finished) => std::mem::take(&mut *finished),
+            Err(poisoned) => std::mem::take(&mut *poisoned.into_inner())
+        };
+
+        finished.sort_by_key(|(started, _, _)| *started);
+
+        finished.into_iter().map(|(_, phase, elapsed)| (phase, elapsed)).collect()
+    }
+}
+
+// when a phase span was made, kept with the span until it closes
+struct PhaseStarted(Instant);
+
+impl<S> Layer<S> for PhaseTimings where S: Subscriber + for<'lookup> LookupSpan<'lookup> {
+
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+
+        if attrs.metadata().target() != PHASE {
+            return;
+        }
+
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut().insert(PhaseStarted(Instant::now()));
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+
+        let Some(span) = ctx.span(&id) else {
+            return;
+        };
+
+        let Some(started) = span.extensions().get::<PhaseStarted>().map(|started| started.0) else {
+            return;
+        };
+
+        let finished = (started, span.name().to_string(), started.elapsed());
+
+        match self.finished.lock() {
+            Ok(mut timings) => timings.push(finished),
+            Err(poisoned) => poisoned.into_inner().push(finished)
+        }
+    }
+}
+
+#[test]
+fn phases_are_timed_in_the_order_they_started() {
+
+    use tracing_subscriber::layer::SubscriberExt;
+
+    let timings = PhaseTimings::new();
+    let subscriber = tracing_subscriber::registry().with(timings.clone());
+
+    tracing::subscriber::with_default(subscriber, || {
+
+        let outer = tracing::info_span!(target: PHASE, "outer");
+
+        {
+            let _inner = tracing::info_span!(target: PHASE, "inner").entered();
+            std::thread::sleep(Duration::from_millis(5));
+        }
+
+        // not a phase so not timed
+        let _other = tracing::info_span!("other").entered();
+
+        drop(outer);
+    });
+
+    let timings = timings.take();
+
+    assert_eq!(vec!["outer", "inner"], timings.iter().map(|(phase, _)| phase.as_str()).collect::<Vec<&str>>());
+    assert!(timings[0].1 >= timings[1].1);
+    assert!(timings[1].1 >= Duration::from_millis(5));
+}
@@ -0,0 +1,60 @@
+use crate::error::SquardleError;
+use crate::layout::{GridLayout, LayoutSolver};
+use crate::solution_generator::FixedLetters;
+
+/* Whether the revealed letters of a partial grid can only be completed one way. The filler
+  stops at a second completion so this never searches further than it has to. Returns the
+  completions found, one when it is unique */
+pub fn unique_completion(fixed_letters: &FixedLetters, dictionaries: &[Vec<String>]) -> Result<(bool, Vec<Vec<String>>), SquardleError> {
+
+    let layout = GridLayout::from_fixed_letters(fixed_letters)?;
+    let solutions = LayoutSolver::new(&layout, dictionaries).solve(Some(2));
+
+    Ok((solutions.len() == 1, solutions))
+}
+
+/* Starts from the whole square revealed and hides one cell at a time, keeping it hidden if
+  the square can still only be completed one way. What is left revealed can't lose any
+  letter without becoming ambiguous, though a smaller set may exist that hides other cells */
+pub fn minimal_reveal_set(square: &[String], dictionaries: &[Vec<String>]) -> Result<FixedLetters, SquardleError> {
+
+    let mut fixed_letters: FixedLetters = square.iter().map(|row| row.chars().map(Some).collect()).collect();
+
+    for i in 0..square.len() {
+        for j in 0..square.len() {
+
+            let letter = fixed_letters[i][j].take();
+
+            if !unique_completion(&fixed_letters, dictionaries)?.0 {
+                fixed_letters[i][j] = letter;
+            }
+        }
+    }
+
+    Ok(fixed_letters)
+}
+
+/* the partial grid as solve takes it, rows separated by commas with . for a blank */
+pub fn format_partial_grid(fixed_letters: &FixedLetters) -> String {
+    fixed_letters.iter().map(|row| row.iter().map(|letter| letter.unwrap_or('.')).collect::<String>()).collect::<Vec<String>>().join(",")
+}
+
+#[test]
+fn uniqueness_of_partial_grids() {
+
+    use crate::solution_generator::parse_partial_grid;
+
+    let (dictionary, _) = crate::dictionary::test_dictionary();
+    let dictionaries = vec![dictionary];
+    let square: Vec<String> = ["ten", "ore", "eat"].iter().map(|row| row.to_string()).collect();
+
+    // ten,ore,eat and ten,ore,rat and their transposes all end in t
+    assert!(!unique_completion(&parse_partial_grid(",,..t", 3).unwrap(), &dictionaries).unwrap().0);
+    assert_eq!((true, vec![square.clone()]), unique_completion(&parse_partial_grid("..n,,e", 3).unwrap(), &dictionaries).unwrap());
+
+    let fixed_letters = minimal_reveal_set(&square, &dictionaries).unwrap();
+
+    // the other three squares have r or n where ten,ore,eat has the e of eat, it alone tells them apart
+    assert_eq!("...,...,e..", format_partial_grid(&fixed_letters));
+    assert!(unique_completion(&fixed_letters, &dictionaries).unwrap().0);
+}
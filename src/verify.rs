@@ -0,0 +1,94 @@
+use std::collections::HashSet;
+use std::io::BufRead;
+use thiserror::Error;
+use crate::error::SquardleError;
+use crate::generator_config::CsvDialect;
+use crate::output;
+use crate::solution_generator::construct_potential_transposed_puzzle;
+
+/* what is wrong with one solution in a solutions file, line numbers start at 1 */
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum VerifyProblem {
+    #[error("line {line_number}: grid has {rows} rows of lengths {lengths:?}, needs to be square")]
+    NotSquare { line_number: usize, rows: usize, lengths: Vec<usize> },
+    #[error("line {line_number}: row {position} [{word}] is not in the dictionary")]
+    RowNotAWord { line_number: usize, position: usize, word: String },
+    #[error("line {line_number}: column {position} [{word}] is not in the dictionary")]
+    ColumnNotAWord { line_number: usize, position: usize, word: String }
+}
+
+/* Checks every solution in a csv solutions file is a square grid whose rows and columns
+  are all dictionary words, a header row is skipped. Returns the number of solutions checked and every problem found */
+pub fn verify_solution_file(file_path: &str, dictionary: &HashSet<String>, csv_dialect: &CsvDialect) -> Result<(usize, Vec<VerifyProblem>), SquardleError> {
+
+    let reader = output::open_solution_file(file_path)?;
+
+    let mut solution_count = 0;
+    let mut problems: Vec<VerifyProblem> = Vec::new();
+
+    for (i, line) in reader.lines().enumerate() {
+        let rows = output::rows_of_csv_line(&line?, csv_dialect);
+
+        if rows.is_empty() {
+            continue;
+        }
+
+        solution_count += 1;
+        problems.extend(verify_solution(i + 1, &rows, dictionary));
+    }
+
+    Ok((solution_count, problems))
+}
+
+fn verify_solution(line_number: usize, rows: &[String], dictionary: &HashSet<String>) -> Vec<VerifyProblem> {
+
+    let lengths: Vec<usize> = rows.iter().map(|row| row.chars().count()).collect();
+
+    // the columns of a grid that isn't square don't mean anything
+    if lengths.iter().any(|length| *length != rows.len()) {
+        return vec![VerifyProblem::NotSquare { line_number, rows: rows.len(), lengths }];
+    }
+
+    let row_problems = rows.iter().enumerate()
+        .filter(|(_, word)| !dictionary.contains(*word))
+        .map(|(position, word)| VerifyProblem::RowNotAWord { line_number, position: position + 1, word: word.clone() });
+
+    let column_problems = construct_potential_transposed_puzzle(rows).into_iter().enumerate()
+        .filter(|(_, word)| !dictionary.contains(word))
+        .map(|(position, word)| VerifyProblem::ColumnNotAWord { line_number, position: position + 1, word });
+
+    row_problems.chain(column_problems).collect()
+}
+
+#[test]
+fn verify_finds_bad_words_and_grids() {
+
+    let dictionary: HashSet<String> = ["ab", "cd", "ac", "bd"].iter().map(|word| word.to_string()).collect();
+
+    assert!(verify_solution(1, &["ab".to_string(), "cd".to_string()], &dictionary).is_empty());
+
+    assert_eq!(
+        vec![VerifyProblem::RowNotAWord { line_number: 2, position: 2, word: "cb".to_string() }, VerifyProblem::ColumnNotAWord { line_number: 2, position: 2, word: "bb".to_string() }],
+        verify_solution(2, &["ab".to_string(), "cb".to_string()], &dictionary)
+    );
+
+    assert_eq!(
+        vec![VerifyProblem::NotSquare { line_number: 3, rows: 2, lengths: vec![2, 3] }],
+        verify_solution(3, &["ab".to_string(), "cde".to_string()], &dictionary)
+    );
+}
+
+#[test]
+fn verify_reports_line_numbers() {
+
+    let file_path = "test_verify.csv";
+    std::fs::write(file_path, "row_1,row_2\nab,cd\n\nab,cb\n").unwrap();
+
+    let dictionary: HashSet<String> = ["ab", "cd", "ac", "bd"].iter().map(|word| word.to_string()).collect();
+    let (solution_count, problems) = verify_solution_file(file_path, &dictionary, &CsvDialect::default()).unwrap();
+    std::fs::remove_file(file_path).unwrap();
+
+    assert_eq!(2, solution_count);
+    assert_eq!(2, problems.len());
+    assert_eq!("line 4: row 2 [cb] is not in the dictionary", problems[0].to_string());
+}
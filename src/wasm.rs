@@ -0,0 +1,69 @@
+use wasm_bindgen::prelude::*;
+use crate::feedback;
+use crate::play::parse_line;
+use crate::prefix_arena::PrefixArena;
+use crate::solution_generator::{parse_partial_grid, GridSolver};
+
+/* The browser interface, the same calls as the C one in src/ffi.rs. A failure comes back to
+  javascript as a thrown string with the same message the command line would print */
+
+/** the words of a dictionary file, separated by commas or new lines, sorted and lowercase */
+#[wasm_bindgen]
+pub fn dictionary_from_bytes(bytes: &[u8]) -> Result<Vec<String>, String> {
+    crate::dictionary::dictionary_from_bytes(bytes).map_err(|err| err.to_string())
+}
+
+#[wasm_bindgen]
+pub struct Solver {
+    solver: GridSolver
+}
+
+#[wasm_bindgen]
+impl Solver {
+
+    /** a solver for the words of a dictionary file, they all have to be the same size */
+    #[wasm_bindgen(constructor)]
+    pub fn new(bytes: &[u8]) -> Result<Solver, String> {
+
+        let dictionary = crate::dictionary::dictionary_from_bytes(bytes).map_err(|err| err.to_string())?;
+        let prefix_map = PrefixArena::new(&dictionary).map_err(|err| err.to_string())?;
+
+        GridSolver::new(dictionary, prefix_map).map(|solver| Solver { solver }).map_err(|err| err.to_string())
+    }
+
+    /** The completions of a partial grid written the way the solve command takes it, like "h...t,,e",
+      each as its rows separated by commas. A limit of 0 finds every completion, which can take a long time */
+    pub fn solve(&mut self, partial_grid: &str, limit: usize) -> Result<Vec<String>, String> {
+
+        let fixed_letters = parse_partial_grid(partial_grid, self.solver.word_size()).map_err(|err| err.to_string())?;
+        let (solutions, _) = self.solver.solve(fixed_letters, (limit > 0).then_some(limit)).map_err(|err| err.to_string())?;
+
+        Ok(solutions.iter().map(|solution| solution.to_string()).collect())
+    }
+}
+
+/** The colour of each letter of a guess at a line of the hidden square, a line being r1 or c3 say.
+  Named the way the server sends them, correct, in_row, in_column, elsewhere_in_grid or absent */
+#[wasm_bindgen]
+pub fn guess_feedback(square: Vec<String>, line: &str, guess: &str) -> Result<Vec<String>, String> {
+
+    let line = parse_line(line).ok_or(format!("[{}] is not a line, use r1 or c3 say", line))?;
+    let feedback = feedback::guess_feedback(&square, line, &guess.to_lowercase()).map_err(|err| err.to_string())?;
+
+    Ok(feedback.iter().map(|letter_feedback| letter_feedback.name().to_string()).collect())
+}
+
+#[test]
+fn solve_through_the_wasm_interface() {
+
+    let words = crate::dictionary::test_dictionary_file();
+
+    assert_eq!(crate::dictionary::test_dictionary().0, dictionary_from_bytes(&words).unwrap());
+
+    let mut solver = Solver::new(&words).unwrap();
+    assert_eq!(vec!["toe,era,net"], solver.solve("toe", 0).unwrap());
+    assert!(solver.solve("abcd", 0).is_err());
+
+    assert_eq!(vec!["correct", "elsewhere_in_grid", "in_row"], guess_feedback(vec!["ten".into(), "ore".into(), "eat".into()], "r1", "toe").unwrap());
+    assert!(guess_feedback(Vec::new(), "x1", "toe").is_err());
+}
@@ -0,0 +1,242 @@
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufReader, Read};
+use std::mem;
+use std::str;
+use std::sync::Arc;
+use memmap2::Mmap;
+use crate::dictionary::{dictionary_from_csv, STDIN_FILE_PATH};
+use crate::error::SquardleError;
+use crate::generator_config::CsvDialect;
+
+/* Sorted words, each once, held as spans of text rather than a String each. Read from a dictionary
+  file the text is the file memory mapped, so a word is borrowed straight from the file rather than
+  copied out of it. A word the file doesn't have as it is, with capitals or in quotes say, is
+  lowercased into an owned tail and its span goes past the end of the mapping into there */
+#[derive(Clone, Default)]
+pub struct WordList {
+    mapped: Option<Arc<Mmap>>,
+    owned: String,
+    spans: Vec<WordSpan>
+}
+
+#[derive(Clone, Copy)]
+struct WordSpan {
+    start: usize,
+    end: usize
+}
+
+impl WordList {
+
+    /* the words copied into one block of text, for words that didn't come from a file */
+    pub fn from_words(words: &[String]) -> WordList {
+
+        let mut owned = String::new();
+        let mut spans: Vec<WordSpan> = Vec::with_capacity(words.len());
+
+        for word in words {
+            spans.push(WordSpan { start: owned.len(), end: owned.len() + word.len() });
+            owned.push_str(word);
+        }
+
+        WordList::sorted(None, owned, spans)
+    }
+
+    /* The words of a dictionary file split the same as read_dictionary_with_dialect splits them. Stdin
+      and anything else that isn't a plain file, a pipe say, can't be mapped so is read in and owned */
+    pub fn read(file_path: &str, csv_dialect: &CsvDialect) -> Result<WordList, SquardleError> {
+
+        let mut bytes: Vec<u8> = Vec::new();
+
+        if file_path == STDIN_FILE_PATH {
+            io::stdin().lock().read_to_end(&mut bytes)?;
+            return Ok(WordList::from_words(&dictionary_from_csv(&bytes, csv_dialect)?));
+        }
+
+        let file = File::open(file_path)?;
+        let metadata = file.metadata()?;
+
+        // an empty file can't be mapped, and has no words anyway
+        if !metadata.is_file() || metadata.len() == 0 {
+            BufReader::new(file).read_to_end(&mut bytes)?;
+            return Ok(WordList::from_words(&dictionary_from_csv(&bytes, csv_dialect)?));
+        }
+
+        // SAFETY: the file changing while it is mapped is undefined behaviour, a dictionary isn't written to while it is being used
+        let mapped = unsafe { Mmap::map(&file)? };
+
+        let mut reader = csv::ReaderBuilder::new()
+            .delimiter(csv_dialect.delimiter)
+            .has_headers(csv_dialect.dictionary_header)
+            .flexible(true)
+            .trim(csv::Trim::All)
+            .from_reader(&mapped[..]);
+
+        let mut record = csv::StringRecord::new();
+        let mut owned = String::new();
+        let mut spans: Vec<WordSpan> = Vec::new();
+
+        while reader.read_record(&mut record)? {
+
+            // each field is looked for in what is left of its record, the fields come in the order they are written
+            let mut from = record.position().map_or(0, |position| position.byte() as usize);
+            let record_end = reader.position().byte() as usize;
+
+            for field in record.iter().filter(|field| !field.is_empty()) {
+
+                let word = field.to_lowercase();
+                let found = (word == field).then(|| find(&mapped[from..record_end], field.as_bytes())).flatten();
+
+                match found {
+                    Some(offset) => {
+                        spans.push(WordSpan { start: from + offset, end: from + offset + field.len() });
+                        from += offset + field.len();
+                    },
+                    None => {
+                        let start = mapped.len() + owned.len();
+                        spans.push(WordSpan { start, end: start + word.len() });
+                        owned.push_str(&word);
+                    }
+                }
+            }
+        }
+
+        Ok(WordList::sorted(Some(Arc::new(mapped)), owned, spans))
+    }
+
+    fn sorted(mapped: Option<Arc<Mmap>>, owned: String, mut spans: Vec<WordSpan>) -> WordList {
+
+        let mapped_bytes = mapped.as_deref().map_or(&[][..], |mapped| &mapped[..]);
+
+        spans.sort_unstable_by(|a, b| span_text(mapped_bytes, &owned, a).cmp(span_text(mapped_bytes, &owned, b)));
+        spans.dedup_by(|a, b| span_text(mapped_bytes, &owned, a) == span_text(mapped_bytes, &owned, b));
+
+        WordList { mapped, owned, spans }
+    }
+
+    pub fn len(&self) -> usize {
+        self.spans.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.spans.is_empty()
+    }
+
+    pub fn get(&self, index: usize) -> &str {
+        self.text(&self.spans[index])
+    }
+
+    fn text(&self, span: &WordSpan) -> &str {
+        span_text(self.mapped.as_deref().map_or(&[][..], |mapped| &mapped[..]), &self.owned, span)
+    }
+
+    /* every word, sorted */
+    pub fn words(&self) -> Words<'_> {
+        Words { list: self, spans: &self.spans }
+    }
+
+    /* the words from the start up to the end, in the same order */
+    pub fn slice(&self, start: usize, end: usize) -> Words<'_> {
+        Words { list: self, spans: &self.spans[start..end] }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &str> + '_ {
+        self.words().iter()
+    }
+
+    /* only the words the filter keeps, still borrowed from the same text */
+    pub fn retain<F>(&mut self, mut keep: F) where F: FnMut(&str) -> bool {
+
+        let mapped_bytes = self.mapped.as_deref().map_or(&[][..], |mapped| &mapped[..]);
+        let owned = &self.owned;
+
+        self.spans.retain(|span| keep(span_text(mapped_bytes, owned, span)));
+    }
+
+    /* the words copied out into owned strings */
+    pub fn to_words(&self) -> Vec<String> {
+        self.iter().map(str::to_string).collect()
+    }
+
+    /* roughly the heap it takes up, the spans and the owned tail, the mapping being the file's pages rather than the heap */
+    pub fn estimated_bytes(&self) -> usize {
+        self.spans.capacity() * mem::size_of::<WordSpan>() + self.owned.capacity()
+    }
+}
+
+impl fmt::Debug for WordList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl PartialEq for WordList {
+    fn eq(&self, other: &WordList) -> bool {
+        self.iter().eq(other.iter())
+    }
+}
+
+impl Eq for WordList {}
+
+/* some of the words of a list, a prefix's run say */
+#[derive(Clone, Copy)]
+pub struct Words<'a> {
+    list: &'a WordList,
+    spans: &'a [WordSpan]
+}
+
+impl<'a> Words<'a> {
+
+    pub fn len(&self) -> usize {
+        self.spans.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.spans.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &'a str> + 'a {
+        let list = self.list;
+        self.spans.iter().map(move |span| list.text(span))
+    }
+
+    pub fn contains(&self, word: &str) -> bool {
+        self.spans.binary_search_by(|span| self.list.text(span).cmp(word)).is_ok()
+    }
+}
+
+fn span_text<'a>(mapped: &'a [u8], owned: &'a str, span: &WordSpan) -> &'a str {
+
+    if span.start >= mapped.len() {
+        return &owned[span.start - mapped.len()..span.end - mapped.len()];
+    }
+
+    // SAFETY: a span in the mapping is where the bytes of a field csv read as a string were found, so they are utf-8
+    unsafe { str::from_utf8_unchecked(&mapped[span.start..span.end]) }
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|window| window == needle)
+}
+
+#[test]
+fn words_are_borrowed_from_the_mapped_file() {
+
+    std::fs::write("test_word_list.csv", "toe,Era\nnet,\"ore\"\n  ten  ,toe\n").unwrap();
+    let words = WordList::read("test_word_list.csv", &CsvDialect::default());
+    std::fs::remove_file("test_word_list.csv").unwrap();
+
+    let words = words.unwrap();
+
+    assert_eq!(vec!["era", "net", "ore", "ten", "toe"], words.iter().collect::<Vec<&str>>());
+    assert_eq!(crate::dictionary::dictionary_from_bytes(b"era,net,ore,ten,toe").unwrap(), words.to_words());
+
+    // only era had to be changed, the quotes around ore are taken off by csv and the letters found after them
+    assert_eq!("era".len(), words.owned.len());
+    assert!(words.words().contains("ten"));
+    assert!(!words.slice(0, 2).contains("ten"));
+
+    let mut words = words;
+    words.retain(|word| word.starts_with('t'));
+    assert_eq!(WordList::from_words(&["toe".to_string(), "ten".to_string()]), words);
+}
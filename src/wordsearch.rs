@@ -0,0 +1,214 @@
+use std::collections::BTreeSet;
+use crate::error::SquardleError;
+use crate::solution_generator::construct_potential_transposed_puzzle;
+
+/* what to search, one grid or every square of a solutions file */
+#[derive(Debug, PartialEq)]
+pub enum SearchSource {
+    Grid(Vec<String>),
+    Solutions(String)
+}
+
+/* wordsearch dictionary_file with a grid of comma separated rows e.g. cat,are,tea or --solutions file,
+  and --min-length n */
+#[derive(Debug, PartialEq)]
+pub struct WordSearchConfig {
+    pub dictionary_file_path: String,
+    pub source: SearchSource,
+    pub min_length: usize
+}
+
+// the shortest word squaredle counts
+const DEFAULT_MIN_LENGTH: usize = 4;
+
+impl WordSearchConfig {
+
+    pub fn build(args: &[String]) -> Result<WordSearchConfig, SquardleError> {
+
+        let mut solutions_file_path: Option<String> = None;
+        let mut min_length = DEFAULT_MIN_LENGTH;
+        let mut positional_args: Vec<String> = Vec::with_capacity(2);
+        let mut args_iter = args.iter();
+
+        while let Some(arg) = args_iter.next() {
+
+            match arg.as_str() {
+                "--solutions" => match args_iter.next() {
+                    Some(file_path) => solutions_file_path = Some(file_path.clone()),
+                    None => return Err("Solutions flag needs a file path".into())
+                },
+                "--min-length" => min_length = match args_iter.next().map(|length| length.parse::<usize>()) {
+                    Some(Ok(length)) if length > 0 => length,
+                    _ => return Err("Min length must be a number greater than 0".into())
+                },
+                _ => positional_args.push(arg.clone())
+            }
+        }
+
+        let source = match (positional_args.len(), solutions_file_path) {
+            (1, Some(solutions_file_path)) => SearchSource::Solutions(solutions_file_path),
+            (2, None) => SearchSource::Grid(parse_grid(&positional_args.pop().expect("there are two"))?),
+            _ => return Err("wordsearch accepts a dictionary file path and either a grid of comma separated rows e.g. cat,are,tea or --solutions file path, and optionally --min-length n".into())
+        };
+
+        let dictionary_file_path = positional_args.swap_remove(0);
+
+        Ok(WordSearchConfig { dictionary_file_path, source, min_length })
+    }
+}
+
+/* rows of letters all as long as each other, they don't have to make a square */
+pub fn parse_grid(grid: &str) -> Result<Vec<String>, SquardleError> {
+
+    let rows: Vec<String> = grid.split(',').map(|row| row.trim().to_lowercase()).collect();
+    let width = rows[0].chars().count();
+
+    if width == 0 || rows.iter().any(|row| row.chars().count() != width || !row.chars().all(|letter| letter.is_ascii_lowercase())) {
+        return Err(format!("grid [{grid}] needs rows of letters all the same length").as_str().into());
+    }
+
+    Ok(rows)
+}
+
+/* Finds the words a grid spells the way squaredle and boggle have them, going from cell to any of
+  the eight around it without using a cell twice. The dictionary is kept sorted like the prefix
+  arena's words, so the words starting with a path are a run of them and the path is only gone on
+  with while the first word at or after it in the list starts with it */
+pub struct WordSearcher {
+    words: Vec<String>,
+    min_length: usize
+}
+
+impl WordSearcher {
+
+    /* the words can be any length, unlike the dictionary of a square */
+    pub fn new(dictionary: &[String], min_length: usize) -> WordSearcher {
+
+        let mut words: Vec<String> = dictionary.iter().filter(|word| word.chars().count() >= min_length).cloned().collect();
+        words.sort_unstable();
+        words.dedup();
+
+        WordSearcher { words, min_length }
+    }
+
+    /* every word in the grid once, longest first then alphabetically */
+    pub fn find(&self, rows: &[String]) -> Vec<String> {
+
+        let cells: Vec<Vec<char>> = rows.iter().map(|row| row.chars().collect()).collect();
+        let mut search = PathSearch {
+            searcher: self,
+            cells: &cells,
+            used: cells.iter().map(|row| vec![false; row.len()]).collect(),
+            path: String::new(),
+            found: BTreeSet::new()
+        };
+
+        for (row, letters) in cells.iter().enumerate() {
+            for column in 0..letters.len() {
+                search.extend(row, column);
+            }
+        }
+
+        let mut found: Vec<String> = search.found.into_iter().collect();
+        found.sort_by(|first, second| second.len().cmp(&first.len()).then_with(|| first.cmp(second)));
+
+        found
+    }
+
+    fn starts_a_word(&self, start: &str) -> bool {
+
+        let first = self.words.partition_point(|word| word.as_str() < start);
+        self.words.get(first).is_some_and(|word| word.starts_with(start))
+    }
+
+    fn is_word(&self, path: &str) -> bool {
+        path.chars().count() >= self.min_length && self.words.binary_search_by(|word| word.as_str().cmp(path)).is_ok()
+    }
+}
+
+struct PathSearch<'a> {
+    searcher: &'a WordSearcher,
+    cells: &'a [Vec<char>],
+    used: Vec<Vec<bool>>,
+    path: String,
+    found: BTreeSet<String>
+}
+
+impl PathSearch<'_> {
+
+    fn extend(&mut self, row: usize, column: usize) {
+
+        self.path.push(self.cells[row][column]);
+
+        if self.searcher.starts_a_word(&self.path) {
+
+            if self.searcher.is_word(&self.path) {
+                self.found.insert(self.path.clone());
+            }
+
+            self.used[row][column] = true;
+
+            for next_row in row.saturating_sub(1)..(row + 2).min(self.cells.len()) {
+                for next_column in column.saturating_sub(1)..(column + 2).min(self.cells[next_row].len()) {
+                    if !self.used[next_row][next_column] {
+                        self.extend(next_row, next_column);
+                    }
+                }
+            }
+
+            self.used[row][column] = false;
+        }
+
+        self.path.pop();
+    }
+}
+
+/* the words found that aren't read straight along a row or down a column of the square, its bonus words */
+pub fn bonus_words(rows: &[String], found: Vec<String>) -> Vec<String> {
+
+    let columns = construct_potential_transposed_puzzle(rows);
+
+    found.into_iter().filter(|word| !rows.contains(word) && !columns.contains(word)).collect()
+}
+
+#[test]
+fn build_word_search_config() {
+
+    let args = |args: &[&str]| -> Vec<String> { args.iter().map(|arg| arg.to_string()).collect() };
+
+    assert_eq!(WordSearchConfig { dictionary_file_path: "words.csv".to_string(), source: SearchSource::Grid(args(&["cat", "are", "tea"])), min_length: 3 },
+        WordSearchConfig::build(&args(&["words.csv", "CAT,are,tea", "--min-length", "3"])).unwrap());
+
+    assert_eq!(SearchSource::Solutions("out.csv".to_string()), WordSearchConfig::build(&args(&["words.csv", "--solutions", "out.csv"])).unwrap().source);
+
+    // a grid and solutions, neither, and a ragged grid
+    assert!(WordSearchConfig::build(&args(&["words.csv", "cat,are", "--solutions", "out.csv"])).is_err());
+    assert!(WordSearchConfig::build(&args(&["words.csv"])).is_err());
+    assert!(WordSearchConfig::build(&args(&["words.csv", "cat,ar"])).is_err());
+}
+
+#[test]
+fn words_follow_paths_of_neighbouring_cells() {
+
+    let dictionary: Vec<String> = ["cat", "care", "cater", "crate", "tat", "tear", "trace", "aa", "cab"].iter().map(|word| word.to_string()).collect();
+    let rows: Vec<String> = ["cat", "are", "tea"].iter().map(|row| row.to_string()).collect();
+
+    let searcher = WordSearcher::new(&dictionary, 3);
+
+    // tat needs a t twice, no e is next to a c for trace, there's no b for cab and aa is too short
+    assert_eq!(vec!["cater", "crate", "care", "tear", "cat"], searcher.find(&rows));
+
+    assert_eq!(vec!["cater", "crate", "care", "tear"], WordSearcher::new(&dictionary, 4).find(&rows));
+}
+
+#[test]
+fn bonus_words_leave_out_the_rows_and_columns() {
+
+    let dictionary: Vec<String> = ["abc", "adg", "beh", "cfi", "def", "ghi", "aei", "bad", "head"].iter().map(|word| word.to_string()).collect();
+    let rows: Vec<String> = ["abc", "def", "ghi"].iter().map(|row| row.to_string()).collect();
+
+    let found = WordSearcher::new(&dictionary, 3).find(&rows);
+
+    assert_eq!(9, found.len());
+    assert_eq!(vec!["head", "aei", "bad"], bonus_words(&rows, found));
+}